@@ -0,0 +1,81 @@
+import assert from 'node:assert/strict';
+import { mkdtempSync, writeFileSync, existsSync } from 'node:fs';
+import { createRequire } from 'node:module';
+import { tmpdir } from 'node:os';
+import path from 'node:path';
+import { test } from 'node:test';
+
+const require = createRequire(import.meta.url);
+const { run, check, parseSources } = require('../index.js');
+
+function createProject() {
+  const dir = mkdtempSync(path.join(tmpdir(), 'ts-to-luban-'));
+  writeFileSync(path.join(dir, 'tsconfig.json'), '{"compilerOptions": {}}');
+  writeFileSync(
+    path.join(dir, 'item.ts'),
+    `export enum Quality { Common = 1, Rare = 2 }
+
+export class ItemConfig {
+    public id: number;
+    public quality: Quality;
+    public tags?: string[];
+}
+`,
+  );
+  writeFileSync(
+    path.join(dir, 'luban.config.toml'),
+    `[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+cache_file = ".cache.json"
+
+[[sources]]
+type = "file"
+path = "item.ts"
+`,
+  );
+  return dir;
+}
+
+test('parseSources returns classes and enums', () => {
+  const parsed = parseSources({ 'item.ts': 'export class Item { public id: number; public name?: string; }' });
+  assert.equal(parsed.classes.length, 1);
+  assert.equal(parsed.classes[0].name, 'Item');
+  assert.deepEqual(
+    parsed.classes[0].fields.map((f) => [f.name, f.fieldType, f.optional]),
+    [
+      ['id', 'double', false],
+      ['name', 'string', true],
+    ],
+  );
+  assert.deepEqual(parsed.enums, []);
+});
+
+test('check validates without writing', () => {
+  const dir = createProject();
+  const report = check(path.join(dir, 'luban.config.toml'));
+  assert.equal(report.beans, 1);
+  assert.equal(report.enums, 1);
+  assert.deepEqual(report.files, [path.join(dir, 'output.xml')]);
+  assert.ok(!existsSync(path.join(dir, 'output.xml')));
+});
+
+test('run writes outputs and returns the report', () => {
+  const dir = createProject();
+  const configPath = path.join(dir, 'luban.config.toml');
+
+  const report = run(configPath);
+  assert.equal(report.beans, 1);
+  assert.equal(report.updated, 2);
+  assert.deepEqual(report.written, [path.join(dir, 'output.xml')]);
+
+  const again = run(configPath, { force: false });
+  assert.equal(again.updated, 0);
+  assert.deepEqual(again.unchanged, [path.join(dir, 'output.xml')]);
+});
+
+test('errors are thrown as JS exceptions', () => {
+  assert.throws(() => run(path.join(tmpdir(), 'missing', 'luban.config.toml')), /Failed to load config/);
+});
@@ -0,0 +1,52 @@
+// Builds the addon as ts-to-luban.<platform>.node.
+//
+// The crate is an rlib only, so that `cargo build` and crates depending on luban-gen
+// don't link a cdylib; the addon is built with `cargo rustc --crate-type cdylib` instead.
+//
+//   node build.js [--release] [--target <rust triple>]
+const { execFileSync } = require('child_process');
+const { copyFileSync } = require('fs');
+const path = require('path');
+
+// Rust target triple -> platform suffix of the .node file (see index.js)
+const TARGETS = {
+  'x86_64-pc-windows-msvc': 'win32-x64-msvc',
+  'x86_64-unknown-linux-gnu': 'linux-x64-gnu',
+  'x86_64-apple-darwin': 'darwin-x64',
+  'aarch64-apple-darwin': 'darwin-arm64',
+};
+const HOST_TARGETS = {
+  'win32-x64': 'x86_64-pc-windows-msvc',
+  'linux-x64': 'x86_64-unknown-linux-gnu',
+  'darwin-x64': 'x86_64-apple-darwin',
+  'darwin-arm64': 'aarch64-apple-darwin',
+};
+
+const args = process.argv.slice(2);
+const release = args.includes('--release');
+const targetIndex = args.indexOf('--target');
+const target = targetIndex >= 0 ? args[targetIndex + 1] : undefined;
+
+const rustTarget = target ?? HOST_TARGETS[`${process.platform}-${process.arch}`];
+const platform = TARGETS[rustTarget];
+if (!platform) {
+  throw new Error(`Unsupported target: ${rustTarget}. Supported: ${Object.keys(TARGETS).join(', ')}`);
+}
+
+const root = path.join(__dirname, '..');
+const cargoArgs = ['rustc', '--lib', '--features', 'napi', '--crate-type', 'cdylib'];
+if (release) {
+  cargoArgs.push('--release');
+}
+if (target) {
+  cargoArgs.push('--target', target);
+}
+execFileSync('cargo', cargoArgs, { cwd: root, stdio: 'inherit' });
+
+const library = rustTarget.includes('windows')
+  ? 'luban_gen.dll'
+  : rustTarget.includes('apple')
+    ? 'libluban_gen.dylib'
+    : 'libluban_gen.so';
+const outDir = path.join(root, 'target', ...(target ? [target] : []), release ? 'release' : 'debug');
+copyFileSync(path.join(outDir, library), path.join(__dirname, `ts-to-luban.${platform}.node`));
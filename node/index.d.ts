@@ -0,0 +1,91 @@
+export interface RunOptions {
+  /** Ignore the cache and treat every bean as updated */
+  force?: boolean;
+  /** Print progress, per-bean lines and warnings to the console (silent by default) */
+  verbose?: boolean;
+  /** Only generate beans/enums whose name matches one of these regexes */
+  filter?: string[];
+  /** Only declare tables of these groups (a [groups] name or Luban group) in the TypeScript outputs */
+  group?: string[];
+  /** Throw instead of generating when any warning is reported */
+  denyWarnings?: boolean;
+}
+
+export interface RunReport {
+  beans: number;
+  enums: number;
+  cached: number;
+  updated: number;
+  /** Beans/enums whose source changed only in comments or formatting */
+  formattingOnly: number;
+  written: string[];
+  unchanged: string[];
+  warnings: string[];
+}
+
+export interface CheckReport {
+  beans: number;
+  enums: number;
+  /** Files that `run` would generate (XML and TypeScript) */
+  files: string[];
+}
+
+export interface Field {
+  name: string;
+  /** Luban type before type mappings, e.g. `list,int` */
+  fieldType: string;
+  originalType: string;
+  /** May be absent (`name?: T`) */
+  optional: boolean;
+  /** Present but may be null/undefined (`name: T | null`) */
+  nullable: boolean;
+  /** Declared `readonly`, typed `readonly T[]` / `ReadonlyMap` etc., or a getter read with defaults.include_getters */
+  readonly: boolean;
+  comment?: string;
+  alias?: string;
+  /** `@example` blocks from the JSDoc, verbatim */
+  examples: string[];
+}
+
+export interface Class {
+  name: string;
+  comment?: string;
+  alias?: string;
+  extends?: string;
+  implements: string[];
+  isInterface: boolean;
+  sourceFile: string;
+  fields: Field[];
+  examples: string[];
+}
+
+export interface EnumVariant {
+  name: string;
+  value: string;
+  alias?: string;
+  comment?: string;
+}
+
+export interface Enum {
+  name: string;
+  comment?: string;
+  alias?: string;
+  isStringEnum: boolean;
+  isFlags: boolean;
+  sourceFile: string;
+  variants: EnumVariant[];
+}
+
+export interface ParsedSources {
+  classes: Class[];
+  enums: Enum[];
+}
+
+/** Run the full generation for a config file, like the CLI */
+export function run(configPath: string, options?: RunOptions): RunReport;
+
+/** Parse and validate without writing anything; throws on the same errors as `run` */
+export function check(configPath: string, options?: RunOptions): CheckReport;
+
+/** Parse in-memory TypeScript sources (file name → content) without a config */
+export function parseSources(sources: Record<string, string>): ParsedSources;
@@ -0,0 +1,30 @@
+const { existsSync } = require('fs');
+const path = require('path');
+
+const TRIPLES = {
+  'win32-x64': 'win32-x64-msvc',
+  'linux-x64': 'linux-x64-gnu',
+  'darwin-x64': 'darwin-x64',
+  'darwin-arm64': 'darwin-arm64',
+};
+
+function loadBinding() {
+  const platformKey = `${process.platform}-${process.arch}`;
+  const triple = TRIPLES[platformKey];
+  if (!triple) {
+    throw new Error(`Unsupported platform: ${platformKey}. Supported: ${Object.keys(TRIPLES).join(', ')}`);
+  }
+
+  // Local build (`npm run build`) first, then the published platform package
+  const localPath = path.join(__dirname, `ts-to-luban.${triple}.node`);
+  if (existsSync(localPath)) {
+    return require(localPath);
+  }
+  return require(`@white-dragon-bevy/ts-to-luban-native-${triple}`);
+}
+
+const binding = loadBinding();
+
+module.exports.run = binding.run;
+module.exports.check = binding.check;
+module.exports.parseSources = binding.parseSources;
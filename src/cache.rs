@@ -1,48 +1,219 @@
+use crate::changelog::SchemaSnapshot;
+use crate::module_stats::ModuleCounts;
+use crate::parser::{ClassInfo, ClassWarning, EnumInfo, ImportMap};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Layout version of the cache file, independent of the package version. Caches in other
+/// formats are upgraded by `Cache::migrate` when possible, otherwise discarded
+pub const CACHE_FORMAT: u32 = 1;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Cache {
+    /// Format of this cache (see CACHE_FORMAT); 0 for caches written before it was recorded
+    #[serde(default)]
+    pub cache_format: u32,
+    /// Package version that last wrote the cache (informational)
     pub version: String,
     pub generated_at: DateTime<Utc>,
     pub entries: HashMap<String, CacheEntry>,
+    /// Schema of the last run, diffed by `--changelog`; absent in older caches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<SchemaSnapshot>,
+    /// Parse results of each source file in scan order, kept with output.per_file_cache so
+    /// `--stdin-filelist` only re-parses the listed files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<CachedFile>,
+    /// Per-module counts of the last run, the baseline of `--stats-by-module` deltas
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub modules: BTreeMap<String, ModuleCounts>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheEntry {
     pub source: String,
+    /// Hash of the source file
+    pub hash: String,
+    /// Structural hash of the bean or enum (`schema_version::structure_hash` / `enum_hash`);
+    /// absent in older caches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structure: Option<String>,
+}
+
+/// What parsing one source file produced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedFile {
+    /// Source file relative to the project root (like `ClassInfo::source_file`)
+    pub path: String,
+    /// Hash of the source file
     pub hash: String,
+    pub classes: Vec<CachedClass>,
+    pub enums: Vec<EnumInfo>,
+    /// Parse failures, reported again whenever the entry is reused
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parse_errors: Vec<String>,
+    /// Unsupported-syntax warnings, reported again whenever the entry is reused
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Declaration warnings (`TsParser::take_class_warnings`), reported again whenever the
+    /// entry is reused
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub class_warnings: Vec<ClassWarning>,
+    /// Not valid UTF-8 (see `FileStats::lossy_utf8`)
+    #[serde(default)]
+    pub lossy_utf8: bool,
+}
+
+/// A parsed class with the parts its serialized form leaves out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedClass {
+    pub class: ClassInfo,
+    pub imports: ImportMap,
+    /// `FieldInfo::order` of each field
+    pub field_order: Vec<u32>,
+}
+
+impl CachedClass {
+    pub fn new(class: &ClassInfo) -> Self {
+        Self {
+            class: class.clone(),
+            imports: class.imports.clone(),
+            field_order: class.fields.iter().map(|f| f.order).collect(),
+        }
+    }
+
+    /// The class as the parser returned it; `source_path` is where the file is read from
+    pub fn restore(self, source_path: &Path, hash: &str) -> ClassInfo {
+        let mut class = self.class;
+        class.source_path = source_path.to_path_buf();
+        class.file_hash = hash.to_string();
+        class.imports = self.imports;
+        for (field, order) in class.fields.iter_mut().zip(self.field_order) {
+            field.order = order;
+        }
+        class
+    }
+}
+
+impl CachedFile {
+    /// Classes and enums of the file, with `source_path` and `file_hash` filled in again
+    pub fn restore(self, source_path: &Path) -> (Vec<ClassInfo>, Vec<EnumInfo>) {
+        let hash = self.hash;
+        let classes = self
+            .classes
+            .into_iter()
+            .map(|class| class.restore(source_path, &hash))
+            .collect();
+        let enums = self
+            .enums
+            .into_iter()
+            .map(|mut enum_info| {
+                enum_info.source_path = source_path.to_path_buf();
+                enum_info.file_hash = hash.clone();
+                enum_info
+            })
+            .collect();
+        (classes, enums)
+    }
+}
+
+/// How a bean or enum compares with its cache entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// The source file is unchanged
+    Cached,
+    /// The source file changed but the structure didn't (comment or formatting edits)
+    FormattingOnly,
+    /// New, structurally changed, or cached without a structural hash
+    Updated,
 }
 
 impl Cache {
     pub fn new() -> Self {
         Self {
+            cache_format: CACHE_FORMAT,
             version: env!("CARGO_PKG_VERSION").to_string(),
             generated_at: Utc::now(),
             entries: HashMap::new(),
+            schema: None,
+            files: Vec::new(),
+            modules: BTreeMap::new(),
         }
     }
 
-    pub fn load(path: &Path) -> anyhow::Result<Self> {
+    /// Load the cache at `path`. A missing file gives a fresh cache; a primary that can't be
+    /// read or parsed (e.g. left empty by a crash) falls back to the backup kept by `save`,
+    /// and to a fresh cache when that isn't usable either. Also returns a warning naming
+    /// the fallback taken, if any
+    pub fn load(path: &Path) -> (Self, Option<String>) {
         if !path.exists() {
-            return Ok(Self::new());
+            return (Self::new(), None);
         }
+        let error = match Self::read(path) {
+            Ok(cache) => return (cache, None),
+            Err(e) => e,
+        };
+        let backup = backup_path(path);
+        match Self::read(&backup) {
+            Ok(cache) => (
+                cache,
+                Some(format!(
+                    "{}: cache is unreadable ({}), using the backup {}",
+                    path.display(),
+                    error,
+                    backup.display()
+                )),
+            ),
+            Err(_) => (
+                Self::new(),
+                Some(format!(
+                    "{}: cache is unreadable ({}) and has no usable backup, starting fresh",
+                    path.display(),
+                    error
+                )),
+            ),
+        }
+    }
+
+    /// Read and migrate one cache file. Caches in unknown formats are discarded (a fresh
+    /// cache); only unreadable files and invalid JSON are errors
+    fn read(path: &Path) -> anyhow::Result<Self> {
         let content = std::fs::read_to_string(path)?;
-        let cache = Self::from_json(&content)?;
+        let json = serde_json::from_str::<serde_json::Value>(&content)?;
+        Ok(Self::migrate(json).unwrap_or_default())
+    }
 
-        // Invalidate cache if version mismatch
-        if cache.version != env!("CARGO_PKG_VERSION") {
-            return Ok(Self::new());
+    /// Bring a cache in any known format up to CACHE_FORMAT.
+    /// Returns None for unknown formats or data that doesn't match its format
+    pub fn migrate(old_json: serde_json::Value) -> Option<Cache> {
+        let format = old_json
+            .get("cache_format")
+            .and_then(|f| f.as_u64())
+            .unwrap_or(0);
+        match format {
+            // Format 0 (invalidated by package version) only lacks `cache_format`
+            0 => {
+                let mut cache: Cache = serde_json::from_value(old_json).ok()?;
+                cache.cache_format = CACHE_FORMAT;
+                Some(cache)
+            }
+            f if f == u64::from(CACHE_FORMAT) => serde_json::from_value(old_json).ok(),
+            _ => None,
         }
-
-        Ok(cache)
     }
 
+    /// Write the cache atomically, so a failed write (e.g. a full disk) leaves the previous
+    /// file intact. A previous cache that is still valid JSON is first kept as the backup
     pub fn save(&self, path: &Path) -> anyhow::Result<()> {
         let json = self.to_json()?;
-        std::fs::write(path, json)?;
+        if let Ok(previous) = std::fs::read_to_string(path) {
+            if serde_json::from_str::<serde_json::Value>(&previous).is_ok() {
+                crate::output::write_atomic(&backup_path(path), previous.as_bytes())?;
+            }
+        }
+        crate::output::write_atomic(path, json.as_bytes())?;
         Ok(())
     }
 
@@ -56,21 +227,32 @@ impl Cache {
         Ok(json)
     }
 
-    #[allow(dead_code)]
     pub fn get_entry(&self, class_name: &str) -> Option<&CacheEntry> {
         self.entries.get(class_name)
     }
 
-    pub fn set_entry(&mut self, class_name: &str, source: &str, hash: &str) {
+    pub fn set_entry(&mut self, class_name: &str, source: &str, hash: &str, structure: &str) {
         self.entries.insert(
             class_name.to_string(),
             CacheEntry {
                 source: source.to_string(),
                 hash: hash.to_string(),
+                structure: Some(structure.to_string()),
             },
         );
     }
 
+    /// Compare a bean or enum's file hash, then its structural hash, with the cache
+    pub fn status(&self, class_name: &str, current_hash: &str, structure: &str) -> CacheStatus {
+        match self.entries.get(class_name) {
+            Some(entry) if entry.hash == current_hash => CacheStatus::Cached,
+            Some(entry) if entry.structure.as_deref() == Some(structure) => {
+                CacheStatus::FormattingOnly
+            }
+            _ => CacheStatus::Updated,
+        }
+    }
+
     pub fn is_valid(&self, class_name: &str, current_hash: &str) -> bool {
         self.entries
             .get(class_name)
@@ -85,6 +267,13 @@ impl Cache {
     }
 }
 
+/// The single backup `Cache::save` keeps of the previous cache: `<path>.bak`
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    PathBuf::from(backup)
+}
+
 impl Default for Cache {
     fn default() -> Self {
         Self::new()
@@ -98,7 +287,7 @@ mod tests {
     #[test]
     fn test_cache_roundtrip() {
         let mut cache = Cache::new();
-        cache.set_entry("MyClass", "test.ts", "abc123");
+        cache.set_entry("MyClass", "test.ts", "abc123", "structure");
 
         let json = cache.to_json().unwrap();
         let loaded = Cache::from_json(&json).unwrap();
@@ -111,16 +300,179 @@ mod tests {
     #[test]
     fn test_is_valid() {
         let mut cache = Cache::new();
-        cache.set_entry("MyClass", "test.ts", "abc123");
+        cache.set_entry("MyClass", "test.ts", "abc123", "structure");
 
         assert!(cache.is_valid("MyClass", "abc123"));
         assert!(!cache.is_valid("MyClass", "different"));
         assert!(!cache.is_valid("OtherClass", "abc123"));
     }
 
+    #[test]
+    fn test_status() {
+        let mut cache = Cache::new();
+        cache.set_entry("MyClass", "test.ts", "abc123", "s1");
+
+        assert_eq!(cache.status("MyClass", "abc123", "s1"), CacheStatus::Cached);
+        assert_eq!(
+            cache.status("MyClass", "def456", "s1"),
+            CacheStatus::FormattingOnly
+        );
+        assert_eq!(
+            cache.status("MyClass", "def456", "s2"),
+            CacheStatus::Updated
+        );
+        assert_eq!(
+            cache.status("OtherClass", "abc123", "s1"),
+            CacheStatus::Updated
+        );
+
+        // Entries from caches without structural hashes only match by file hash
+        let old = load_json(
+            r#"{"cache_format": 1, "version": "0.1.0", "generated_at": "2024-01-01T00:00:00Z", "entries": {"MyClass": {"source": "test.ts", "hash": "abc123"}}}"#,
+        );
+        assert_eq!(old.status("MyClass", "abc123", "s1"), CacheStatus::Cached);
+        assert_eq!(old.status("MyClass", "def456", "s1"), CacheStatus::Updated);
+    }
+
+    #[test]
+    fn test_cached_file_roundtrip() {
+        let parser = crate::parser::TsParser::new();
+        let source = "export enum Kind {\n    A = 1,\n}\n\nexport class Item {\n    constructor(public count: number) {}\n    id: number;\n}\n";
+        let classes = parser.parse_source("src/item.ts", source).unwrap();
+        let enums = parser.parse_enums_source("src/item.ts", source).unwrap();
+        let mut cache = Cache::new();
+        cache.files.push(CachedFile {
+            path: "src/item.ts".to_string(),
+            hash: classes[0].file_hash.clone(),
+            classes: classes.iter().map(CachedClass::new).collect(),
+            enums,
+            parse_errors: Vec::new(),
+            warnings: Vec::new(),
+            class_warnings: Vec::new(),
+            lossy_utf8: false,
+        });
+
+        let mut loaded = Cache::from_json(&cache.to_json().unwrap()).unwrap();
+        let file = loaded.files.remove(0);
+        assert_eq!(file.path, "src/item.ts");
+        let (restored, enums) = file.restore(Path::new("/p/src/item.ts"));
+        assert_eq!(restored[0].file_hash, classes[0].file_hash);
+        assert_eq!(restored[0].source_path, Path::new("/p/src/item.ts"));
+        let order = |class: &ClassInfo| -> Vec<(String, u32)> {
+            class
+                .fields
+                .iter()
+                .map(|f| (f.name.clone(), f.order))
+                .collect()
+        };
+        assert_eq!(order(&restored[0]), order(&classes[0]));
+        assert_eq!(enums[0].name, "Kind");
+        assert_eq!(enums[0].file_hash, classes[0].file_hash);
+
+        // Without per-file entries the cache file looks as before
+        assert!(Cache::new().to_json().unwrap().find("\"files\"").is_none());
+    }
+
     #[test]
     fn test_load_missing_file() {
-        let cache = Cache::load(Path::new("/nonexistent/path.json")).unwrap();
+        let (cache, fallback) = Cache::load(Path::new("/nonexistent/path.json"));
+        assert!(cache.entries.is_empty());
+        assert_eq!(fallback, None);
+    }
+
+    fn load_json(json: &str) -> Cache {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache.json");
+        std::fs::write(&path, json).unwrap();
+        Cache::load(&path).0
+    }
+
+    #[test]
+    fn test_load_keeps_cache_across_versions() {
+        let mut cache = Cache::new();
+        cache.version = "0.0.1".to_string();
+        cache.set_entry("MyClass", "test.ts", "abc123", "structure");
+
+        let loaded = load_json(&cache.to_json().unwrap());
+        assert!(loaded.is_valid("MyClass", "abc123"));
+    }
+
+    #[test]
+    fn test_load_discards_other_format() {
+        let mut cache = Cache::new();
+        cache.cache_format = CACHE_FORMAT + 1;
+        cache.set_entry("MyClass", "test.ts", "abc123", "structure");
+
+        let loaded = load_json(&cache.to_json().unwrap());
+        assert!(loaded.entries.is_empty());
+        assert_eq!(loaded.cache_format, CACHE_FORMAT);
+    }
+
+    #[test]
+    fn test_migrate_format_0() {
+        let json = r#"{
+            "version": "0.4.0",
+            "generated_at": "2024-01-01T00:00:00Z",
+            "entries": { "MyClass": { "source": "test.ts", "hash": "abc123" } }
+        }"#;
+
+        let loaded = load_json(json);
+        assert_eq!(loaded.cache_format, CACHE_FORMAT);
+        assert!(loaded.is_valid("MyClass", "abc123"));
+    }
+
+    #[test]
+    fn test_load_corrupted_file_starts_fresh() {
+        let loaded = load_json("{ not json");
+        assert!(loaded.entries.is_empty());
+    }
+
+    #[test]
+    fn test_save_keeps_backup_of_previous_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".luban-cache.json");
+        let mut cache = Cache::new();
+        cache.set_entry("MyClass", "test.ts", "v1", "structure");
+        cache.save(&path).unwrap();
+        assert!(!backup_path(&path).exists());
+
+        cache.set_entry("MyClass", "test.ts", "v2", "structure");
+        cache.save(&path).unwrap();
+        assert_eq!(backup_path(&path), dir.path().join(".luban-cache.json.bak"));
+        let backup = Cache::from_json(&std::fs::read_to_string(backup_path(&path)).unwrap());
+        assert!(backup.unwrap().is_valid("MyClass", "v1"));
+
+        // A corrupt primary is not rotated over the good backup
+        std::fs::write(&path, "").unwrap();
+        cache.set_entry("MyClass", "test.ts", "v3", "structure");
+        cache.save(&path).unwrap();
+        let backup = Cache::from_json(&std::fs::read_to_string(backup_path(&path)).unwrap());
+        assert!(backup.unwrap().is_valid("MyClass", "v1"));
+        assert!(Cache::load(&path).0.is_valid("MyClass", "v3"));
+    }
+
+    #[test]
+    fn test_load_falls_back_to_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".luban-cache.json");
+        let mut cache = Cache::new();
+        cache.set_entry("MyClass", "test.ts", "abc123", "structure");
+        std::fs::write(backup_path(&path), cache.to_json().unwrap()).unwrap();
+
+        // Truncated by a crash mid-write, or left empty
+        for corrupt in ["{ \"cache_format\": 1, \"entr", ""] {
+            std::fs::write(&path, corrupt).unwrap();
+            let (cache, fallback) = Cache::load(&path);
+            assert!(cache.is_valid("MyClass", "abc123"));
+            assert!(fallback.unwrap().contains("cache is unreadable"));
+        }
+
+        // No usable backup either: start fresh
+        std::fs::write(backup_path(&path), "not json").unwrap();
+        let (cache, fallback) = Cache::load(&path);
         assert!(cache.entries.is_empty());
+        assert!(fallback
+            .unwrap()
+            .ends_with("and has no usable backup, starting fresh"));
     }
 }
@@ -0,0 +1,438 @@
+//! Schema changelog between runs. The cache keeps a snapshot of the previous schema
+//! (bean fields with their Luban types, and table configs); `--changelog` diffs it against
+//! the current one and appends a dated Markdown section describing the changes.
+
+use crate::parser::ClassInfo;
+use crate::schema_version::field_luban_type;
+use crate::table_registry::TableRegistry;
+use crate::type_mapper::TypeMapper;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The parts of a schema the changelog reports on, keyed by name for a deterministic diff
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaSnapshot {
+    /// Bean (`module.Name`) -> schema field name -> Luban type (`?` when optional)
+    pub beans: BTreeMap<String, BTreeMap<String, String>>,
+    /// Table (`module.TableName`) -> summary of its config
+    pub tables: BTreeMap<String, String>,
+}
+
+impl SchemaSnapshot {
+    /// Snapshot of the parsed model. Classes without a module use `default_module`
+    pub fn capture(
+        classes: &[ClassInfo],
+        type_mapper: &TypeMapper,
+        table_registry: &TableRegistry,
+        default_module: &str,
+    ) -> Self {
+        let mut snapshot = Self::default();
+        for class in classes {
+            let fields = class
+                .fields
+                .iter()
+                .map(|field| {
+                    (
+                        field.schema_name().to_string(),
+                        field_luban_type(field, type_mapper),
+                    )
+                })
+                .collect();
+            snapshot
+                .beans
+                .insert(bean_key(class, default_module), fields);
+        }
+        for table in table_registry.all_tables() {
+            let mut summary = format!(
+                "mode={} index={} input={}",
+                table.mode, table.index, table.input
+            );
+            if !table.secondary_indexes.is_empty() {
+                summary.push_str(&format!(" indexes={}", table.secondary_indexes.join(",")));
+            }
+            if let Some(transform) = &table.transform {
+                summary.push_str(&format!(" transform={}", transform));
+            }
+            if !table.groups.is_empty() {
+                summary.push_str(&format!(" groups={}", table.groups.join(",")));
+            }
+            snapshot
+                .tables
+                .insert(qualified(&table.module, &table.name), summary);
+        }
+        snapshot
+    }
+}
+
+/// Key of a class in `SchemaSnapshot::beans`
+pub fn bean_key(class: &ClassInfo, default_module: &str) -> String {
+    let module = class.module_name.as_deref().unwrap_or(default_module);
+    qualified(module, &class.name)
+}
+
+fn qualified(module: &str, name: &str) -> String {
+    if module.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", module, name)
+    }
+}
+
+/// Change of one schema field between two snapshots of a bean
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FieldChange {
+    Added {
+        field: String,
+        luban_type: String,
+    },
+    Removed {
+        field: String,
+        luban_type: String,
+    },
+    Changed {
+        field: String,
+        before: String,
+        after: String,
+    },
+}
+
+impl std::fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldChange::Added { field, luban_type } => write!(f, "+ {}: {}", field, luban_type),
+            FieldChange::Removed { field, .. } => write!(f, "- {}", field),
+            FieldChange::Changed {
+                field,
+                before,
+                after,
+            } => write!(f, "~ {}: {} -> {}", field, before, after),
+        }
+    }
+}
+
+/// Field changes from `before` to `after` (schema field name -> Luban type): additions and
+/// type changes in field order, then removals
+pub fn diff_fields(
+    before: &BTreeMap<String, String>,
+    after: &BTreeMap<String, String>,
+) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    for (field, after_type) in after {
+        match before.get(field) {
+            None => changes.push(FieldChange::Added {
+                field: field.clone(),
+                luban_type: after_type.clone(),
+            }),
+            Some(before_type) if before_type != after_type => changes.push(FieldChange::Changed {
+                field: field.clone(),
+                before: before_type.clone(),
+                after: after_type.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (field, before_type) in before {
+        if !after.contains_key(field) {
+            changes.push(FieldChange::Removed {
+                field: field.clone(),
+                luban_type: before_type.clone(),
+            });
+        }
+    }
+    changes
+}
+
+/// What changed in one regenerated bean since the previous snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BeanDelta {
+    /// `module.Name`
+    pub bean: String,
+    /// The bean wasn't in the previous snapshot
+    pub added: bool,
+    /// Field changes; empty when only comments or other code changed
+    pub fields: Vec<FieldChange>,
+}
+
+impl BeanDelta {
+    /// Delta of `bean` between two snapshots; None when the current one doesn't have it
+    pub fn between(before: &SchemaSnapshot, after: &SchemaSnapshot, bean: &str) -> Option<Self> {
+        let after_fields = after.beans.get(bean)?;
+        Some(match before.beans.get(bean) {
+            Some(before_fields) => Self {
+                bean: bean.to_string(),
+                added: false,
+                fields: diff_fields(before_fields, after_fields),
+            },
+            None => Self {
+                bean: bean.to_string(),
+                added: true,
+                fields: Vec::new(),
+            },
+        })
+    }
+
+    /// One line per field change, or a single line for new beans and unchanged fields
+    pub fn lines(&self) -> Vec<String> {
+        if self.added {
+            vec!["new bean".to_string()]
+        } else if self.fields.is_empty() {
+            vec!["comment-only change (no field changes)".to_string()]
+        } else {
+            self.fields
+                .iter()
+                .map(|change| change.to_string())
+                .collect()
+        }
+    }
+}
+
+/// Markdown section describing the changes from `before` to `after`, headed by `date`.
+/// None when nothing changed. Fields of added or removed beans are not listed
+pub fn render_changes(
+    before: &SchemaSnapshot,
+    after: &SchemaSnapshot,
+    date: &str,
+) -> Option<String> {
+    let mut beans = Vec::new();
+    let mut fields = Vec::new();
+    for (bean, after_fields) in &after.beans {
+        let Some(before_fields) = before.beans.get(bean) else {
+            beans.push(format!("- Added `{}`", bean));
+            continue;
+        };
+        for change in diff_fields(before_fields, after_fields) {
+            fields.push(match change {
+                FieldChange::Added { field, luban_type } => {
+                    format!("- `{}`: added `{}` (`{}`)", bean, field, luban_type)
+                }
+                FieldChange::Changed {
+                    field,
+                    before,
+                    after,
+                } => format!("- `{}`: `{}` `{}` → `{}`", bean, field, before, after),
+                FieldChange::Removed { field, luban_type } => {
+                    format!("- `{}`: removed `{}` (`{}`)", bean, field, luban_type)
+                }
+            });
+        }
+    }
+    for bean in before.beans.keys() {
+        if !after.beans.contains_key(bean) {
+            beans.push(format!("- Removed `{}`", bean));
+        }
+    }
+
+    let mut tables = Vec::new();
+    for (table, after_config) in &after.tables {
+        match before.tables.get(table) {
+            None => tables.push(format!("- Added `{}` ({})", table, after_config)),
+            Some(before_config) if before_config != after_config => tables.push(format!(
+                "- `{}`: {} → {}",
+                table, before_config, after_config
+            )),
+            Some(_) => {}
+        }
+    }
+    for (table, before_config) in &before.tables {
+        if !after.tables.contains_key(table) {
+            tables.push(format!("- Removed `{}` ({})", table, before_config));
+        }
+    }
+
+    if beans.is_empty() && fields.is_empty() && tables.is_empty() {
+        return None;
+    }
+    let mut lines = vec![format!("## {}", date)];
+    for (heading, entries) in [("Beans", beans), ("Fields", fields), ("Tables", tables)] {
+        if !entries.is_empty() {
+            lines.push(String::new());
+            lines.push(format!("### {}", heading));
+            lines.push(String::new());
+            lines.extend(entries);
+        }
+    }
+    Some(lines.join("\n"))
+}
+
+/// Append a section to the changelog file, separated from earlier sections by a blank line
+pub fn append_section(path: &Path, section: &str) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let existing = std::fs::read_to_string(path).unwrap_or_default();
+    let separator = if existing.trim().is_empty() || existing.ends_with("\n\n") {
+        ""
+    } else if existing.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}{}", separator, section)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(beans: &[(&str, &[(&str, &str)])], tables: &[(&str, &str)]) -> SchemaSnapshot {
+        SchemaSnapshot {
+            beans: beans
+                .iter()
+                .map(|(bean, fields)| {
+                    let fields = fields
+                        .iter()
+                        .map(|(name, ty)| (name.to_string(), ty.to_string()))
+                        .collect();
+                    (bean.to_string(), fields)
+                })
+                .collect(),
+            tables: tables
+                .iter()
+                .map(|(name, config)| (name.to_string(), config.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_render_changes() {
+        let before = snapshot(
+            &[
+                (
+                    "role.RoleConfig",
+                    &[("id", "int"), ("name", "string"), ("hp", "int")],
+                ),
+                ("role.OldBuff", &[("id", "int")]),
+            ],
+            &[
+                (
+                    "role.RoleConfigTable",
+                    "mode=map index=id input=../datas/role",
+                ),
+                ("role.OldBuffTable", "mode=map index=id input=../datas/buff"),
+            ],
+        );
+        let after = snapshot(
+            &[
+                (
+                    "role.RoleConfig",
+                    &[("id", "int"), ("hp", "double"), ("speed", "float?")],
+                ),
+                ("skill.SkillConfig", &[("id", "int")]),
+            ],
+            &[
+                (
+                    "role.RoleConfigTable",
+                    "mode=list index=id input=../datas/role",
+                ),
+                (
+                    "skill.SkillConfigTable",
+                    "mode=map index=id input=../datas/skill",
+                ),
+            ],
+        );
+
+        assert_eq!(
+            render_changes(&before, &after, "2026-10-16").unwrap(),
+            "## 2026-10-16
+
+### Beans
+
+- Added `skill.SkillConfig`
+- Removed `role.OldBuff`
+
+### Fields
+
+- `role.RoleConfig`: `hp` `int` → `double`
+- `role.RoleConfig`: added `speed` (`float?`)
+- `role.RoleConfig`: removed `name` (`string`)
+
+### Tables
+
+- `role.RoleConfigTable`: mode=map index=id input=../datas/role → mode=list index=id input=../datas/role
+- Added `skill.SkillConfigTable` (mode=map index=id input=../datas/skill)
+- Removed `role.OldBuffTable` (mode=map index=id input=../datas/buff)"
+        );
+    }
+
+    #[test]
+    fn test_render_changes_only_present_sections() {
+        let before = snapshot(&[("Item", &[("id", "int")])], &[]);
+        let mut after = before.clone();
+        assert_eq!(render_changes(&before, &after, "2026-10-16"), None);
+
+        after
+            .beans
+            .get_mut("Item")
+            .unwrap()
+            .insert("count".to_string(), "int".to_string());
+        assert_eq!(
+            render_changes(&before, &after, "2026-10-16").unwrap(),
+            "## 2026-10-16\n\n### Fields\n\n- `Item`: added `count` (`int`)"
+        );
+    }
+
+    #[test]
+    fn test_diff_fields() {
+        let fields = |entries: &[(&str, &str)]| -> BTreeMap<String, String> {
+            entries
+                .iter()
+                .map(|(name, ty)| (name.to_string(), ty.to_string()))
+                .collect()
+        };
+        let before = fields(&[("id", "int"), ("damage", "int"), ("oldField", "string")]);
+        let after = fields(&[("id", "int"), ("damage", "double"), ("newField", "int")]);
+
+        let changes = diff_fields(&before, &after);
+        let lines: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            lines,
+            vec!["~ damage: int -> double", "+ newField: int", "- oldField"]
+        );
+        assert_eq!(
+            changes[2],
+            FieldChange::Removed {
+                field: "oldField".to_string(),
+                luban_type: "string".to_string(),
+            }
+        );
+        assert!(diff_fields(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_bean_delta_lines() {
+        let before = snapshot(&[("Item", &[("id", "int")])], &[]);
+        let after = snapshot(
+            &[("Item", &[("id", "int")]), ("Shop", &[("id", "int")])],
+            &[],
+        );
+        let lines = |bean: &str| BeanDelta::between(&before, &after, bean).unwrap().lines();
+        assert_eq!(
+            lines("Item"),
+            vec!["comment-only change (no field changes)"]
+        );
+        assert_eq!(lines("Shop"), vec!["new bean"]);
+        assert!(BeanDelta::between(&before, &after, "Missing").is_none());
+    }
+
+    #[test]
+    fn test_append_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("docs/SCHEMA_CHANGES.md");
+
+        append_section(&path, "## 2026-10-15\n\n### Beans\n\n- Added `Item`").unwrap();
+        append_section(&path, "## 2026-10-16\n\n### Beans\n\n- Removed `Item`").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "## 2026-10-15\n\n### Beans\n\n- Added `Item`\n\n## 2026-10-16\n\n### Beans\n\n- Removed `Item`\n"
+        );
+    }
+}
@@ -1,9 +1,14 @@
-use serde::Deserialize;
+pub mod validate;
+
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// Table configuration - supports both simple string format and full object format
 /// Simple: "module.ClassName" = "../datas/path"
 /// Full: "module.ClassName" = { input = "../datas/path", mode = "one", index = "id", name = "TbCustom" }
+/// List tables may add `secondary_indexes = ["skillId", "ownerId"]`
+/// and any table a row `transform = "./transforms/role-config"`; one/singleton tables
+/// may be marked `required = false`
 #[derive(Debug, Deserialize, Clone)]
 #[serde(untagged)]
 pub enum TableConfig {
@@ -18,6 +23,20 @@ pub enum TableConfig {
         mode: Option<String>,
         #[serde(default)]
         index: Option<String>,
+        /// Lookup fields of a `mode = "list"` table (typed index maps in tables.d.ts)
+        #[serde(default)]
+        secondary_indexes: Vec<String>,
+        /// Module (relative to the config file, or a package) whose default export
+        /// post-processes the loaded rows; its signature is emitted in tables.d.ts
+        #[serde(default)]
+        transform: Option<String>,
+        /// Whether a `mode = "one"` / `"singleton"` table must hold its row; `false` types
+        /// the tables.d.ts getter `T | undefined` instead of throwing on an empty table
+        #[serde(default)]
+        required: Option<bool>,
+        /// Luban groups the table belongs to (names from [groups]); none means every group
+        #[serde(default)]
+        groups: Vec<String>,
     },
 }
 
@@ -53,6 +72,40 @@ impl TableConfig {
             TableConfig::Full { index, .. } => index.as_deref().unwrap_or("id"),
         }
     }
+
+    /// Get the secondary index fields (default: none)
+    pub fn secondary_indexes(&self) -> &[String] {
+        match self {
+            TableConfig::Simple(_) => &[],
+            TableConfig::Full {
+                secondary_indexes, ..
+            } => secondary_indexes,
+        }
+    }
+
+    /// Get the row transform module (default: none)
+    pub fn transform(&self) -> Option<&str> {
+        match self {
+            TableConfig::Simple(_) => None,
+            TableConfig::Full { transform, .. } => transform.as_deref(),
+        }
+    }
+
+    /// Whether a one/singleton table must hold its row (default: true)
+    pub fn required(&self) -> bool {
+        match self {
+            TableConfig::Simple(_) => true,
+            TableConfig::Full { required, .. } => required.unwrap_or(true),
+        }
+    }
+
+    /// Get the table groups (default: none, i.e. every group)
+    pub fn groups(&self) -> &[String] {
+        match self {
+            TableConfig::Simple(_) => &[],
+            TableConfig::Full { groups, .. } => groups,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -72,11 +125,51 @@ pub struct Config {
     /// New [tables] configuration - maps "module.ClassName" to table config
     #[serde(default)]
     pub tables: std::collections::HashMap<String, TableConfig>,
+    /// [[virtual_fields]] configuration - extra fields injected into parsed classes
+    #[serde(default)]
+    pub virtual_fields: Vec<VirtualFieldsConfig>,
+    /// [[virtual_beans]] configuration - beans defined entirely in config
+    #[serde(default)]
+    pub virtual_beans: Vec<VirtualBeanConfig>,
+    /// [[output_routes]] configuration - named XML outputs for selected beans
+    #[serde(default)]
+    pub output_routes: Vec<OutputRoute>,
+    /// [external_beans] configuration - bean names defined outside the parsed sources
+    #[serde(default)]
+    pub external_beans: ExternalBeansConfig,
+    /// [beans] configuration - classes left out of beans.ts
+    #[serde(default)]
+    pub beans: BeansConfig,
+    /// [forbidden_types] configuration - extra runtime types that config fields may not use
+    #[serde(default)]
+    pub forbidden_types: ForbiddenTypesConfig,
+    /// [ts_type_overrides] configuration - TypeScript types of Luban types in generated
+    /// declarations, keyed by Luban type (`text`, `datetime`)
+    #[serde(default)]
+    pub ts_type_overrides: std::collections::HashMap<String, TsTypeOverride>,
+    /// [parent_fields] configuration - fields of parent beans defined in hand-written XML,
+    /// keyed by parent name, so re-declaring one is reported before Luban runs
+    #[serde(default)]
+    pub parent_fields: std::collections::HashMap<String, Vec<String>>,
+    /// [limits] configuration - per-file size and parse time thresholds
+    #[serde(default)]
+    pub limits: LimitsConfig,
+    /// [groups] configuration - Luban group name (as written in luban.conf) of each table
+    /// group, e.g. `client = "c"`
+    #[serde(default)]
+    pub groups: std::collections::HashMap<String, String>,
+    /// [lint] configuration - naming conventions for beans, fields and table names
+    #[serde(default)]
+    pub lint: LintConfig,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ProjectConfig {
     pub tsconfig: PathBuf,
+    /// Build flavor (e.g. `lite`): classes and fields marked @OnlyIn / @ExcludeFrom for
+    /// other flavors are left out. Overridden by `--flavor`
+    #[serde(default)]
+    pub flavor: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -84,6 +177,10 @@ pub struct OutputConfig {
     pub path: PathBuf,
     #[serde(default = "default_cache_file")]
     pub cache_file: PathBuf,
+    /// Also keep each source file's parse results in the cache, so `--stdin-filelist` can
+    /// re-parse only the listed files
+    #[serde(default)]
+    pub per_file_cache: bool,
     #[serde(default)]
     pub module_name: String,
     /// Path to output enum XML file (optional, defaults to {path}_enums.xml)
@@ -95,12 +192,166 @@ pub struct OutputConfig {
     /// Path to output TypeScript table code
     #[serde(default)]
     pub table_output_path: Option<PathBuf>,
+    /// Emit child beans nested inside their parent <bean> element instead of a flat list
+    #[serde(default)]
+    pub nested_children: bool,
+    /// Write each module without its own output_path to `beans_<module>.xml` next to
+    /// `path`, which keeps the root module and imports the module files
+    #[serde(default)]
+    pub split_by_module: bool,
+    /// Line ending used for every generated file
+    #[serde(default)]
+    pub newline: Newline,
+    /// Output file for class constants (`static readonly NAME = <literal>`); not generated if unset
+    #[serde(default)]
+    pub constants_path: Option<PathBuf>,
+    /// Format of the constants output
+    #[serde(default)]
+    pub constants_format: ConstantsFormat,
+    /// Output file mapping each Beans key to its structural schema hash
+    /// (`export const SchemaVersions`); not generated if unset
+    #[serde(default)]
+    pub schema_versions_path: Option<PathBuf>,
+    /// Emit secondary indexes of list tables as `index="a,b"` (multi-index list tables,
+    /// needs a Luban version that supports them)
+    #[serde(default)]
+    pub list_table_indexes: bool,
+    /// How a one-sided @Range is written in the `#range=` validator
+    #[serde(default)]
+    pub open_range_format: OpenRangeFormat,
+    /// How a container nested in another (`number[][]`) is written in field types
+    #[serde(default)]
+    pub nested_containers: NestedContainers,
+    /// Import bindings per beans_N.ts chunk, kept well under Luau's 200-register limit
+    #[serde(default = "default_max_imports_per_file")]
+    pub max_imports_per_file: usize,
+    /// Warn when a generated .ts file's estimated register count comes within this many
+    /// registers of the limit (over the limit is an error)
+    #[serde(default = "default_register_margin")]
+    pub register_margin: usize,
+    /// Shape of the Beans export in beans.ts
+    #[serde(default)]
+    pub beans_export: BeansExport,
+    /// Template for Beans keys: `{module}`, `{name}`, literal text and `{module?sep}`
+    /// (module + sep, or nothing when the module is empty)
+    #[serde(default = "default_bean_key_format")]
+    pub bean_key_format: String,
+    /// Formatting of every generated XML file
+    #[serde(default)]
+    pub xml_style: XmlStyle,
+}
+
+/// Formatting of generated XML (output.xml_style)
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct XmlStyle {
+    /// Spaces per nesting level
+    pub indent: usize,
+    /// Order of the attributes on each element
+    pub attribute_order: AttributeOrder,
+    /// Write beans without fields (or nested children) as `<bean .../>`
+    pub self_closing: bool,
+}
+
+impl Default for XmlStyle {
+    fn default() -> Self {
+        Self {
+            indent: 4,
+            attribute_order: AttributeOrder::default(),
+            self_closing: true,
+        }
+    }
+}
+
+/// Attribute order of generated XML elements (output.xml_style.attribute_order)
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeOrder {
+    /// One fixed order for every element (name, type, parent, alias, value, flags, mode,
+    /// index, input, comment, tags), so adding an attribute never moves the others
+    #[default]
+    Canonical,
+    /// The per-element order of earlier versions (bean alias before parent, enum tags
+    /// before comment)
+    Legacy,
+}
+
+/// Shape of the Beans export (output.beans_export)
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BeansExport {
+    /// `Beans`: an object keyed by `module.Name`
+    #[default]
+    Object,
+    /// `BeansMap`: a ReadonlyMap filled from the chunk objects at runtime
+    Map,
+    /// Both exports, sharing the same chunk files
+    Both,
+}
+
+/// Syntax of a one-sided range validator (output.open_range_format)
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenRangeFormat {
+    /// Leave the missing bound empty: `[0,]` / `[,59]`
+    #[default]
+    Open,
+    /// Fill the missing bound with the limit of the field's numeric type, for Luban
+    /// versions that only accept `[min,max]`
+    Bounded,
+}
+
+/// Syntax of a container nested in another (output.nested_containers)
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NestedContainers {
+    /// Parenthesize the inner container: `list,(list,int)`, `map,string,(list,Item)`
+    #[default]
+    Parenthesized,
+    /// Write the types one after another: `list,list,int`, for older Luban versions
+    Flat,
+}
+
+/// Format of the class constants output (output.constants_format)
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConstantsFormat {
+    /// A `{Class}Constants` bean per class with a singleton (mode="one") table
+    #[default]
+    Xml,
+    /// A TypeScript module exporting `{Class}Constants` objects
+    Ts,
+}
+
+/// Line ending for generated files (output.newline)
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Newline {
+    #[default]
+    Lf,
+    Crlf,
 }
 
 fn default_cache_file() -> PathBuf {
     PathBuf::from(".luban-cache.json")
 }
 
+fn default_max_imports_per_file() -> usize {
+    100
+}
+
+fn default_register_margin() -> usize {
+    20
+}
+
+fn default_bean_key_format() -> String {
+    "{module?.}{name}".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum SourceConfig {
@@ -134,20 +385,412 @@ pub enum SourceConfig {
         #[serde(default)]
         module_name: Option<String>,
     },
+    /// A registration file: the sources are the files it reaches through `import` and
+    /// `export ... from`, followed transitively
     Registration {
         path: PathBuf,
+        #[serde(default)]
+        output_path: Option<PathBuf>,
+        #[serde(default)]
+        module_name: Option<String>,
     },
 }
 
 #[derive(Debug, Deserialize, Default)]
-pub struct DefaultsConfig {}
+pub struct DefaultsConfig {
+    /// Module of classes and enums without a [[sources]] module_name or @Module; takes
+    /// precedence over output.module_name and is overridden by `--module`
+    #[serde(default)]
+    pub module: Option<String>,
+    /// How `?` on list/map/set fields is handled
+    #[serde(default)]
+    pub optional_containers: OptionalContainers,
+    /// Drop ambient classes (`declare class`, classes in .d.ts files) instead of emitting beans
+    #[serde(default)]
+    pub skip_ambient: bool,
+    /// Fail on field types that aren't parsed, mapped, builtin or listed in [external_beans]
+    #[serde(default)]
+    pub strict_types: bool,
+    /// Emit nullable list elements / map values (`list,double?`) for `T | null` element types
+    /// instead of stripping the null with a warning
+    #[serde(default)]
+    pub nullable_elements: bool,
+    /// How much of each JSDoc description becomes the XML/TS comment
+    #[serde(default)]
+    pub comment_style: CommentStyle,
+    /// Truncate comments longer than this many characters, ending them with `…`
+    #[serde(default)]
+    pub comment_max_length: Option<usize>,
+    /// JSDoc tags (without `@`) kept in comments, e.g. `["deprecated"]`; other tag lines are dropped
+    #[serde(default)]
+    pub comment_keep_tags: Vec<String>,
+    /// Extract public getters with an explicit return type as fields tagged `computed=true`
+    /// (columns filled by the data pipeline); setters are always ignored
+    #[serde(default)]
+    pub include_getters: bool,
+    /// Luban type that fields typed `any`/`unknown` fall back to, which also silences their
+    /// report. Unset, such fields become string with a warning (an error with strict_types)
+    #[serde(default)]
+    pub any_type: Option<String>,
+    /// Generate classes declared without `export` when an exported class's field uses them
+    /// (left out of beans.ts, which can't import them) instead of failing
+    #[serde(default)]
+    pub include_private_referenced: bool,
+    /// How disagreements between tables declared in code (@LubanTable / `@table`) and
+    /// [tables] entries are reported
+    #[serde(default)]
+    pub table_reconcile: TableReconcile,
+    /// Pass [tables] modes other than map/list/one/singleton through to the XML (for Luban
+    /// forks with extra modes) instead of rejecting them; tables.d.ts types such tables `unknown`
+    #[serde(default)]
+    pub allow_custom_modes: bool,
+    /// What to do with source files that aren't valid UTF-8 (e.g. GBK-encoded legacy files)
+    #[serde(default)]
+    pub invalid_utf8: InvalidUtf8,
+    /// Which parent a class gets when it extends a bean but also implements a bean
+    /// interface outside the base's hierarchy; @Parent overrides either
+    #[serde(default)]
+    pub parent_precedence: ParentPrecedence,
+}
+
+/// Beans defined in hand-written XML that parsed classes may reference (`[external_beans]`)
+#[derive(Debug, Deserialize, Default)]
+pub struct ExternalBeansConfig {
+    /// Bean names, optionally module-qualified (`shared.LegacyBuff`)
+    #[serde(default)]
+    pub names: Vec<String>,
+}
+
+/// beans.ts contents (`[beans]`)
+#[derive(Debug, Deserialize, Default)]
+pub struct BeansConfig {
+    /// Regexes matched against class names; matching classes keep their XML bean and
+    /// tables.d.ts typing but aren't imported into beans.ts (same as @NoRuntime())
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// TypeScript type the generated declarations use for a Luban type:
+/// Simple: `datetime = "number"`
+/// Full: `text = { type = "LocalizedString", import = "./shared/localization" }`
+/// (`import` is relative to the config file, or a package name)
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum TsTypeOverride {
+    Simple(String),
+    Full {
+        #[serde(rename = "type")]
+        ts_type: String,
+        #[serde(default)]
+        import: Option<String>,
+    },
+}
+
+impl TsTypeOverride {
+    /// The TypeScript type name
+    pub fn ts_type(&self) -> &str {
+        match self {
+            TsTypeOverride::Simple(ts_type) => ts_type,
+            TsTypeOverride::Full { ts_type, .. } => ts_type,
+        }
+    }
+
+    /// Module the type is imported from (None for global types)
+    pub fn import(&self) -> Option<&str> {
+        match self {
+            TsTypeOverride::Simple(_) => None,
+            TsTypeOverride::Full { import, .. } => import.as_deref(),
+        }
+    }
+}
+
+/// Runtime-only types (in addition to the built-in Roblox Instance/async list) that are
+/// reported when a field references them (`[forbidden_types]`)
+#[derive(Debug, Deserialize, Default)]
+pub struct ForbiddenTypesConfig {
+    #[serde(default)]
+    pub names: Vec<String>,
+}
 
+/// Naming conventions checked after parsing and table resolution (`[lint]`). Nothing is
+/// checked unless a preset or a rule is set
 #[derive(Debug, Deserialize, Default, Clone)]
+pub struct LintConfig {
+    /// Built-in rules for the categories not configured below
+    #[serde(default)]
+    pub preset: Option<LintPreset>,
+    /// Severity of the preset's rules
+    #[serde(default)]
+    pub severity: LintSeverity,
+    /// Bean names (classes, interfaces and virtual beans)
+    #[serde(default)]
+    pub beans: Option<LintRule>,
+    /// Field names as written to the schema (after @Name)
+    #[serde(default)]
+    pub fields: Option<LintRule>,
+    /// Custom table names, i.e. other than the default `{Class}Table`
+    #[serde(default)]
+    pub tables: Option<LintRule>,
+}
+
+/// Built-in [lint] rules (lint.preset)
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintPreset {
+    /// PascalCase beans, camelCase fields and `Tb`-prefixed custom table names
+    Default,
+}
+
+/// One [lint] rule: names must match `pattern`
+#[derive(Debug, Deserialize, Clone)]
+pub struct LintRule {
+    pub pattern: String,
+    #[serde(default)]
+    pub severity: LintSeverity,
+}
+
+/// What a [lint] violation does
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    /// Report a warning (an error with --deny-warnings)
+    #[default]
+    Warn,
+    /// Fail validation
+    Error,
+}
+
+/// Part of a comment kept by comment normalization (defaults.comment_style)
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommentStyle {
+    /// The whole description, whitespace collapsed
+    Full,
+    /// Only the first paragraph (up to the first blank line), plus kept tag lines
+    #[default]
+    FirstParagraph,
+    /// Only the first non-empty line
+    FirstLine,
+    /// Only the first sentence of the first paragraph
+    FirstSentence,
+}
+
+/// Handling of optional container fields (defaults.optional_containers)
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionalContainers {
+    /// Silently emit the container as required
+    Drop,
+    /// Emit the container as required and warn about each affected field
+    #[default]
+    Warn,
+    /// Emit a nullable container type (`list?,T`) for Luban versions that support it
+    Nullable,
+}
+
+/// Reporting of tables declared in code but missing from [tables], and vice versa
+/// (defaults.table_reconcile)
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TableReconcile {
+    /// Don't compare
+    Off,
+    /// Warn about each mismatch
+    #[default]
+    Warn,
+    /// Fail on any mismatch
+    Error,
+}
+
+/// Handling of source files that aren't valid UTF-8 (defaults.invalid_utf8)
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InvalidUtf8 {
+    /// Replace invalid bytes with U+FFFD, parse the file and warn
+    #[default]
+    Warn,
+    /// Fail the run
+    Error,
+}
+
+/// Parent of a class whose extends and implements name unrelated beans
+/// (defaults.parent_precedence)
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ParentPrecedence {
+    /// The extended class, keeping the TypeScript inheritance
+    #[default]
+    Extends,
+    /// The implemented interface, when exactly one conflicts with the base
+    Implements,
+}
+
+/// A [[virtual_fields]] block: fields injected into an existing class
+#[derive(Debug, Deserialize, Clone)]
+pub struct VirtualFieldsConfig {
+    /// Target class name
+    pub class: String,
+    #[serde(default)]
+    pub fields: Vec<VirtualField>,
+}
+
+/// An [[output_routes]] entry: beans matching `patterns` (or marked @Output("name"))
+/// are written to `path` instead of their source's output
+#[derive(Debug, Deserialize, Clone)]
+pub struct OutputRoute {
+    pub name: String,
+    pub path: PathBuf,
+    /// Regexes matched against class names; the first matching route wins
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+/// A [[virtual_beans]] block: a bean with no TypeScript counterpart
+#[derive(Debug, Deserialize, Clone)]
+pub struct VirtualBeanConfig {
+    pub name: String,
+    /// Module name (defaults to output.module_name)
+    #[serde(default)]
+    pub module: Option<String>,
+    /// Parent bean name
+    #[serde(default)]
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub comment: Option<String>,
+    /// Luban alias of the bean, like @alias on a parsed class
+    #[serde(default)]
+    pub alias: Option<String>,
+    /// Custom output path for this bean (overrides default output)
+    #[serde(default)]
+    pub output_path: Option<PathBuf>,
+    #[serde(default)]
+    pub fields: Vec<VirtualField>,
+}
+
+/// A field defined in config rather than in TypeScript
+#[derive(Debug, Deserialize, Clone)]
+pub struct VirtualField {
+    pub name: String,
+    /// Luban type of the field (e.g. "ScalingStat", "list,int"); not needed for mode = "remove"
+    #[serde(rename = "type", default)]
+    pub field_type: String,
+    #[serde(default)]
+    pub comment: Option<String>,
+    /// Luban alias of the field, like @alias on a parsed field
+    #[serde(default)]
+    pub alias: Option<String>,
+    #[serde(default)]
+    pub is_optional: bool,
+    #[serde(default)]
+    pub relocate_to: Option<RelocateTo>,
+    #[serde(default)]
+    pub validators: VirtualFieldValidators,
+    /// Insertion position: "start", "end" (default), "before:<field>" or "after:<field>"
+    #[serde(default)]
+    pub position: Option<String>,
+    /// How the field is applied to the target class
+    #[serde(default)]
+    pub mode: VirtualFieldMode,
+}
+
+/// Virtual field application mode
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VirtualFieldMode {
+    /// Add a new field (error if a field with the same name exists)
+    #[default]
+    Add,
+    /// Replace type/validators/comment of an existing field, keeping its position
+    Override,
+    /// Remove an existing field from the schema
+    Remove,
+}
+
+/// relocateTo tag configuration for a virtual field
+#[derive(Debug, Deserialize, Clone)]
+pub struct RelocateTo {
+    /// Target table name
+    pub target: String,
+    /// Key suffix
+    pub prefix: String,
+    /// Target bean type (optional, used for automatic conversion)
+    #[serde(default)]
+    pub target_bean: Option<String>,
+}
+
+/// Validators for a virtual field, mirroring the field decorators
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct VirtualFieldValidators {
+    #[serde(default, rename = "ref")]
+    pub has_ref: bool,
+    #[serde(default)]
+    pub range: Option<(f64, f64)>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub size: Option<usize>,
+    #[serde(default)]
+    pub set: Vec<String>,
+    #[serde(default)]
+    pub index: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
 pub struct ScanOptions {
     #[serde(default)]
     pub include_dts: bool,
     #[serde(default)]
     pub include_node_modules: bool,
+    /// Directory levels to descend below the source directory (0 = only its own files)
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+    /// Skip dot-directories such as `.yarn`, `.turbo` or `.cache`
+    #[serde(default)]
+    pub skip_hidden: bool,
+    /// Skip the built-in list of build/VCS directories (`dist`, `out`, `.git`)
+    #[serde(default = "default_true")]
+    pub skip_common_dirs: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            include_dts: false,
+            include_node_modules: false,
+            max_depth: None,
+            skip_hidden: false,
+            skip_common_dirs: true,
+        }
+    }
+}
+
+/// Per-source-file thresholds (`[limits]`); 0 disables a check
+#[derive(Debug, Deserialize, Clone)]
+pub struct LimitsConfig {
+    /// Files larger than this many bytes are skipped with a warning
+    #[serde(default = "default_max_file_size")]
+    pub max_file_size: u64,
+    /// Files taking longer than this many milliseconds to parse are reported (the parse
+    /// itself can't be interrupted)
+    #[serde(default = "default_parse_budget_ms")]
+    pub parse_budget_ms: u64,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_file_size: default_max_file_size(),
+            parse_budget_ms: default_parse_budget_ms(),
+        }
+    }
+}
+
+fn default_max_file_size() -> u64 {
+    2 * 1024 * 1024
+}
+
+fn default_parse_budget_ms() -> u64 {
+    2000
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -168,6 +811,7 @@ impl Config {
     pub fn load(path: &std::path::Path) -> anyhow::Result<Self> {
         let content = std::fs::read_to_string(path)?;
         let config: Config = toml::from_str(&content)?;
+        validate::validate(&config)?;
         Ok(config)
     }
 
@@ -256,13 +900,21 @@ impl Config {
                     module_name,
                 }
             }
-            SourceConfig::Registration { path } => {
+            SourceConfig::Registration {
+                path,
+                output_path,
+                module_name,
+            } => {
                 let resolved = if path.is_absolute() {
                     path
                 } else {
                     base_dir.join(&path)
                 };
-                SourceConfig::Registration { path: resolved }
+                SourceConfig::Registration {
+                    path: resolved,
+                    output_path,
+                    module_name,
+                }
             }
             SourceConfig::Glob {
                 pattern,
@@ -360,6 +1012,32 @@ path = "../another-pkg/ts-luban.config.toml"
         );
     }
 
+    #[test]
+    fn test_load_rejects_malformed_container_mapping() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("luban.config.toml");
+        let write = |mapping: &str| {
+            std::fs::write(
+                &config_path,
+                format!(
+                    "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[type_mappings]\n{}\n",
+                    mapping
+                ),
+            )
+            .unwrap();
+        };
+
+        write("IntPair = \"list,int\"");
+        assert!(Config::load(&config_path).is_ok());
+
+        write("IntPair = \"map,int\"");
+        let err = Config::load(&config_path).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Invalid configuration:\n  [type_mappings]\n    \"IntPair\" = \"map,int\": expected map,K,V"
+        );
+    }
+
     #[test]
     fn test_load_with_refs_merges_sources() {
         let config_path =
@@ -603,6 +1281,38 @@ output = "{name}"
         assert_eq!(config.table_mappings[0].pattern, "Tb.*");
     }
 
+    #[test]
+    fn test_parse_output_routes() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+
+[[output_routes]]
+name = "editor"
+path = "output/editor.xml"
+patterns = ["^Debug", "EditorConfig$"]
+
+[[output_routes]]
+name = "server"
+path = "output/server.xml"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.output_routes.len(), 2);
+        assert_eq!(config.output_routes[0].name, "editor");
+        assert_eq!(
+            config.output_routes[0].path,
+            PathBuf::from("output/editor.xml")
+        );
+        assert_eq!(
+            config.output_routes[0].patterns,
+            vec!["^Debug", "EditorConfig$"]
+        );
+        assert!(config.output_routes[1].patterns.is_empty());
+    }
+
     #[test]
     fn test_parse_table_output_path() {
         let toml_str = r#"
@@ -620,6 +1330,141 @@ table_output_path = "out/tables"
         );
     }
 
+    #[test]
+    fn test_parse_nested_children() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+nested_children = true
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(config.output.nested_children);
+
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(!config.output.nested_children);
+    }
+
+    #[test]
+    fn test_parse_newline() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+newline = "crlf"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.output.newline, Newline::Crlf);
+
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.output.newline, Newline::Lf);
+    }
+
+    #[test]
+    fn test_parse_bean_key_format() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+bean_key_format = "{module?__}{name}"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.output.bean_key_format, "{module?__}{name}");
+
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.output.bean_key_format, "{module?.}{name}");
+    }
+
+    #[test]
+    fn test_parse_optional_containers() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+
+[defaults]
+optional_containers = "nullable"
+nullable_elements = true
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.defaults.optional_containers,
+            OptionalContainers::Nullable
+        );
+        assert!(config.defaults.nullable_elements);
+
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.defaults.optional_containers, OptionalContainers::Warn);
+        assert!(!config.defaults.nullable_elements);
+    }
+
+    #[test]
+    fn test_parse_comment_policy() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+
+[defaults]
+comment_style = "first-sentence"
+comment_max_length = 80
+comment_keep_tags = ["deprecated"]
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.defaults.comment_style, CommentStyle::FirstSentence);
+        assert_eq!(config.defaults.comment_max_length, Some(80));
+        assert_eq!(config.defaults.comment_keep_tags, vec!["deprecated"]);
+
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.defaults.comment_style, CommentStyle::FirstParagraph);
+        assert_eq!(config.defaults.comment_max_length, None);
+    }
+
     #[test]
     fn test_parse_tables_simple_format() {
         let toml_str = r#"
@@ -700,6 +1545,130 @@ path = "output.xml"
         assert_eq!(skill.mode(), "list");
     }
 
+    #[test]
+    fn test_parse_tables_secondary_indexes() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+list_table_indexes = true
+
+[tables]
+"skill.SkillBinding" = { input = "../datas/skill-binding", mode = "list", secondary_indexes = ["skillId", "ownerId"] }
+"role.RoleConfig" = "../datas/role"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(config.output.list_table_indexes);
+
+        let binding = config.tables.get("skill.SkillBinding").unwrap();
+        assert_eq!(binding.secondary_indexes(), ["skillId", "ownerId"]);
+
+        let role = config.tables.get("role.RoleConfig").unwrap();
+        assert!(role.secondary_indexes().is_empty());
+    }
+
+    #[test]
+    fn test_parse_tables_transform() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+
+[tables]
+"role.RoleConfig" = { input = "../datas/role", transform = "./transforms/role-config" }
+"item.ItemConfig" = "../datas/item"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let role = config.tables.get("role.RoleConfig").unwrap();
+        assert_eq!(role.transform(), Some("./transforms/role-config"));
+        let item = config.tables.get("item.ItemConfig").unwrap();
+        assert_eq!(item.transform(), None);
+    }
+
+    #[test]
+    fn test_parse_external_beans() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+
+[defaults]
+strict_types = true
+
+[external_beans]
+names = ["LegacyBuff", "shared.Vector"]
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(config.defaults.strict_types);
+        assert_eq!(
+            config.external_beans.names,
+            vec!["LegacyBuff", "shared.Vector"]
+        );
+    }
+
+    #[test]
+    fn test_parse_forbidden_types() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+
+[forbidden_types]
+names = ["Janitor", "Signal"]
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.forbidden_types.names, vec!["Janitor", "Signal"]);
+    }
+
+    #[test]
+    fn test_parse_parent_fields() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+
+[parent_fields]
+TsTriggerClass = ["priority", "enabled"]
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.parent_fields.get("TsTriggerClass"),
+            Some(&vec!["priority".to_string(), "enabled".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_parse_ts_type_overrides() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+
+[ts_type_overrides]
+datetime = "number"
+text = { type = "LocalizedString", import = "./shared/localization" }
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let datetime = &config.ts_type_overrides["datetime"];
+        assert_eq!(datetime.ts_type(), "number");
+        assert_eq!(datetime.import(), None);
+        let text = &config.ts_type_overrides["text"];
+        assert_eq!(text.ts_type(), "LocalizedString");
+        assert_eq!(text.import(), Some("./shared/localization"));
+    }
+
     #[test]
     fn test_parse_tables_empty() {
         let toml_str = r#"
@@ -712,4 +1681,95 @@ path = "output.xml"
         let config: Config = toml::from_str(toml_str).unwrap();
         assert_eq!(config.tables.len(), 0);
     }
+
+    #[test]
+    fn test_parse_virtual_fields() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+
+[[virtual_fields]]
+class = "WeaponConfig"
+fields = [
+    { name = "mainStat", type = "ScalingStat", comment = "主属性", relocate_to = { target = "TScalingStat", prefix = "_main" }, position = "after:id" },
+    { name = "level", type = "int", validators = { range = [1, 100], required = true } },
+]
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.virtual_fields.len(), 1);
+
+        let block = &config.virtual_fields[0];
+        assert_eq!(block.class, "WeaponConfig");
+        assert_eq!(block.fields.len(), 2);
+
+        let main_stat = &block.fields[0];
+        assert_eq!(main_stat.field_type, "ScalingStat");
+        assert_eq!(main_stat.comment.as_deref(), Some("主属性"));
+        assert_eq!(main_stat.position.as_deref(), Some("after:id"));
+        let relocate = main_stat.relocate_to.as_ref().unwrap();
+        assert_eq!(relocate.target, "TScalingStat");
+        assert_eq!(relocate.prefix, "_main");
+        assert!(relocate.target_bean.is_none());
+
+        let level = &block.fields[1];
+        assert_eq!(level.validators.range, Some((1.0, 100.0)));
+        assert!(level.validators.required);
+        assert!(level.position.is_none());
+        assert_eq!(level.mode, VirtualFieldMode::Add);
+    }
+
+    #[test]
+    fn test_parse_virtual_field_modes() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+
+[[virtual_fields]]
+class = "ShopItem"
+fields = [
+    { name = "cost", type = "CostBundle", mode = "override" },
+    { name = "debugNote", mode = "remove" },
+]
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let fields = &config.virtual_fields[0].fields;
+        assert_eq!(fields[0].mode, VirtualFieldMode::Override);
+        assert_eq!(fields[1].mode, VirtualFieldMode::Remove);
+        assert!(fields[1].field_type.is_empty());
+    }
+
+    #[test]
+    fn test_parse_virtual_beans() {
+        let toml_str = r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+
+[[virtual_beans]]
+name = "TScalingStat"
+module = "stats"
+parent = "StatBase"
+fields = [
+    { name = "id", type = "int" },
+    { name = "value", type = "double", validators = { range = [0, 1] } },
+]
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.virtual_beans.len(), 1);
+
+        let bean = &config.virtual_beans[0];
+        assert_eq!(bean.name, "TScalingStat");
+        assert_eq!(bean.module.as_deref(), Some("stats"));
+        assert_eq!(bean.parent.as_deref(), Some("StatBase"));
+        assert_eq!(bean.fields.len(), 2);
+        assert_eq!(bean.fields[1].validators.range, Some((0.0, 1.0)));
+    }
 }
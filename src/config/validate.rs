@@ -0,0 +1,437 @@
+//! Config-level checks run once after loading, so every conflicting setting is reported
+//! together (grouped by section) instead of one failure per run.
+
+use super::{Config, LintConfig, OutputConfig, OutputRoute, TableConfig};
+use crate::error::GenError;
+use crate::lint::Linter;
+use crate::table_registry::{unknown_mode_error, TABLE_MODES};
+use crate::ts_generator::BeanKeyFormat;
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Run every config rule and fail with all violations, grouped by config section
+pub fn validate(config: &Config) -> Result<()> {
+    let sections = [
+        (
+            "[tables]",
+            check_tables(
+                &config.tables,
+                config.defaults.allow_custom_modes,
+                &config.groups,
+            ),
+        ),
+        (
+            "[[output_routes]]",
+            check_output_routes(&config.output_routes),
+        ),
+        ("[output]", check_bean_key_format(&config.output)),
+        (
+            "[type_mappings]",
+            check_type_mappings(&config.type_mappings),
+        ),
+        (
+            "[external_beans]",
+            check_external_beans(&config.external_beans.names, &config.type_mappings),
+        ),
+        ("[lint]", check_lint(&config.lint)),
+    ];
+    let report: Vec<String> = sections
+        .into_iter()
+        .filter(|(_, errors)| !errors.is_empty())
+        .map(|(section, errors)| format!("  {}\n    {}", section, errors.join("\n    ")))
+        .collect();
+    if !report.is_empty() {
+        anyhow::bail!(GenError::Validation(format!(
+            "Invalid configuration:\n{}",
+            report.join("\n")
+        )));
+    }
+    Ok(())
+}
+
+/// Unknown modes (unless `allow_custom_modes`), secondary indexes outside list tables,
+/// `required` outside one/singleton tables, groups missing from a non-empty [groups] and
+/// entries that resolve to the same table (`module.TableName`)
+fn check_tables(
+    tables: &HashMap<String, TableConfig>,
+    allow_custom_modes: bool,
+    groups: &HashMap<String, String>,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut group_names: Vec<&str> = groups.keys().map(String::as_str).collect();
+    group_names.sort();
+    let mut by_table: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    let mut keys: Vec<&String> = tables.keys().collect();
+    keys.sort();
+    for key in keys {
+        let table = &tables[key];
+        if !TABLE_MODES.contains(&table.mode()) && !allow_custom_modes {
+            errors.push(unknown_mode_error(key, table.mode()));
+        }
+        if !table.secondary_indexes().is_empty() && table.mode() != "list" {
+            errors.push(format!(
+                "\"{}\": secondary_indexes need mode = \"list\", not \"{}\"",
+                key,
+                table.mode()
+            ));
+        }
+        if let TableConfig::Full {
+            required: Some(_), ..
+        } = table
+        {
+            if !matches!(table.mode(), "one" | "singleton") {
+                errors.push(format!(
+                    "\"{}\": required only applies to mode = \"one\" or \"singleton\", not \"{}\"",
+                    key,
+                    table.mode()
+                ));
+            }
+        }
+        if !groups.is_empty() {
+            for group in table.groups().iter().filter(|g| !groups.contains_key(*g)) {
+                errors.push(format!(
+                    "\"{}\": unknown group \"{}\" (expected one of [groups]: {})",
+                    key,
+                    group,
+                    group_names.join(", ")
+                ));
+            }
+        }
+        // Same naming as TableRegistry::from_config
+        let (module, class_name) = key.rsplit_once('.').unwrap_or(("", key));
+        let name = table
+            .name()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}Table", class_name));
+        let table_ref = if module.is_empty() {
+            name
+        } else {
+            format!("{}.{}", module, name)
+        };
+        by_table.entry(table_ref).or_default().push(key);
+    }
+    for (table_ref, entries) in by_table {
+        if entries.len() > 1 {
+            errors.push(format!(
+                "{} all generate table \"{}\"",
+                quoted(&entries),
+                table_ref
+            ));
+        }
+    }
+    errors
+}
+
+/// Duplicate route names, invalid patterns, a pattern another route already claims (the
+/// first matching route wins, so the later one never applies) and routes sharing a path
+fn check_output_routes(routes: &[OutputRoute]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut names: HashSet<&str> = HashSet::new();
+    let mut patterns: HashMap<&str, &str> = HashMap::new();
+    let mut paths: HashMap<&std::path::Path, &str> = HashMap::new();
+    for route in routes {
+        if !names.insert(route.name.as_str()) {
+            errors.push(format!("\"{}\": duplicate route name", route.name));
+        }
+        for pattern in &route.patterns {
+            if let Err(e) = regex::Regex::new(pattern) {
+                errors.push(format!(
+                    "\"{}\": invalid pattern {:?}: {}",
+                    route.name, pattern, e
+                ));
+            }
+            match patterns.get(pattern.as_str()) {
+                Some(first) if *first != route.name => errors.push(format!(
+                    "\"{}\": pattern {:?} is already matched by route \"{}\"",
+                    route.name, pattern, first
+                )),
+                Some(_) => {}
+                None => {
+                    patterns.insert(pattern, &route.name);
+                }
+            }
+        }
+        match paths.get(route.path.as_path()) {
+            Some(first) if *first != route.name => errors.push(format!(
+                "\"{}\": path {:?} is also the path of route \"{}\"",
+                route.name, route.path, first
+            )),
+            Some(_) => {}
+            None => {
+                paths.insert(route.path.as_path(), &route.name);
+            }
+        }
+    }
+    errors
+}
+
+/// output.bean_key_format must parse and keep bean names apart
+fn check_bean_key_format(output: &OutputConfig) -> Vec<String> {
+    match BeanKeyFormat::parse(&output.bean_key_format) {
+        Err(e) => vec![e.to_string()],
+        Ok(format) if format.format("m", "A") == format.format("m", "B") => vec![format!(
+            "bean_key_format \"{}\" has no {{name}}, every bean of a module would get the same key",
+            output.bean_key_format
+        )],
+        Ok(_) => Vec::new(),
+    }
+}
+
+/// Container targets must be well-formed (see `type_mapper::check_mapping_target`)
+fn check_type_mappings(type_mappings: &HashMap<String, String>) -> Vec<String> {
+    let mut mappings: Vec<(&String, &String)> = type_mappings.iter().collect();
+    mappings.sort();
+    mappings
+        .into_iter()
+        .filter_map(|(name, target)| {
+            crate::type_mapper::check_mapping_target(target)
+                .err()
+                .map(|e| format!("\"{}\" = {:?}: {}", name, target, e))
+        })
+        .collect()
+}
+
+/// Names must be (optionally module-qualified) identifiers, listed once, and not also
+/// type-mapped (the mapping would replace every reference to the external bean)
+fn check_external_beans(names: &[String], type_mappings: &HashMap<String, String>) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut seen: HashSet<&str> = HashSet::new();
+    for name in names {
+        let valid = !name.is_empty()
+            && name.split('.').all(|part| {
+                !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_')
+            });
+        if !valid {
+            errors.push(format!("\"{}\": not a bean name", name));
+            continue;
+        }
+        if !seen.insert(name.as_str()) {
+            errors.push(format!("\"{}\": listed more than once", name));
+        }
+        let short_name = name.rsplit('.').next().unwrap_or(name);
+        if let Some(target) = type_mappings.get(short_name) {
+            errors.push(format!(
+                "\"{}\": also mapped by [type_mappings] {} = {:?}",
+                name, short_name, target
+            ));
+        }
+    }
+    errors
+}
+
+fn quoted(keys: &[&str]) -> String {
+    keys.iter()
+        .map(|key| format!("\"{}\"", key))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Rule patterns must be valid regexes
+fn check_lint(lint: &LintConfig) -> Vec<String> {
+    Linter::new(lint).err().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(toml_text: &str) -> Config {
+        toml::from_str(&format!(
+            "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n{}",
+            toml_text
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_tables() {
+        let config = config(
+            r#"
+[tables]
+"item.Item" = { input = "../datas/item", name = "TbItem" }
+"item.ItemV2" = { input = "../datas/item2", name = "TbItem" }
+"role.Role" = { input = "../datas/role", mode = "dict" }
+"skill.Skill" = { input = "../datas/skill", secondary_indexes = ["ownerId"] }
+"shop.Shop" = "../datas/shop"
+"game.Global" = { input = "../datas/global", mode = "one", required = false }
+"game.Event" = { input = "../datas/event", required = true }
+"#,
+        );
+        assert_eq!(
+            check_tables(&config.tables, false, &config.groups),
+            vec![
+                "\"game.Event\": required only applies to mode = \"one\" or \"singleton\", not \"map\"",
+                "\"role.Role\": unknown mode \"dict\" (expected map, list, one, singleton; set defaults.allow_custom_modes = true to pass custom modes through)",
+                "\"skill.Skill\": secondary_indexes need mode = \"list\", not \"map\"",
+                "\"item.Item\", \"item.ItemV2\" all generate table \"item.TbItem\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_tables_groups() {
+        let config = config(
+            r#"
+[groups]
+client = "c"
+server = "s"
+
+[tables]
+"item.Item" = { input = "../datas/item", groups = ["client", "server"] }
+"item.Drop" = { input = "../datas/drop", groups = ["sever"] }
+"#,
+        );
+        assert_eq!(
+            check_tables(&config.tables, false, &config.groups),
+            vec![
+                "\"item.Drop\": unknown group \"sever\" (expected one of [groups]: client, server)"
+            ]
+        );
+        // Without [groups] the names are Luban groups already
+        assert!(check_tables(&config.tables, false, &HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_check_tables_custom_modes() {
+        let config = config(
+            r#"
+[defaults]
+allow_custom_modes = true
+
+[tables]
+"role.Role" = { input = "../datas/role", mode = "lsit" }
+"#,
+        );
+        assert!(config.defaults.allow_custom_modes);
+        assert!(check_tables(&config.tables, true, &config.groups).is_empty());
+        assert_eq!(
+            check_tables(&config.tables, false, &config.groups),
+            vec!["\"role.Role\": unknown mode \"lsit\" (expected map, list, one, singleton; set defaults.allow_custom_modes = true to pass custom modes through)"]
+        );
+    }
+
+    #[test]
+    fn test_check_output_routes() {
+        let config = config(
+            r#"
+[[output_routes]]
+name = "ui"
+path = "ui.xml"
+patterns = ["^Ui"]
+
+[[output_routes]]
+name = "hud"
+path = "ui.xml"
+patterns = ["^Ui", "^Hud("]
+
+[[output_routes]]
+name = "ui"
+path = "other.xml"
+"#,
+        );
+        let errors = check_output_routes(&config.output_routes);
+        assert_eq!(errors.len(), 4);
+        assert_eq!(
+            errors[0],
+            "\"hud\": pattern \"^Ui\" is already matched by route \"ui\""
+        );
+        assert!(errors[1].starts_with("\"hud\": invalid pattern \"^Hud(\": "));
+        assert_eq!(
+            errors[2],
+            "\"hud\": path \"ui.xml\" is also the path of route \"ui\""
+        );
+        assert_eq!(errors[3], "\"ui\": duplicate route name");
+    }
+
+    #[test]
+    fn test_check_bean_key_format() {
+        let output = |format: &str| config(&format!("bean_key_format = {:?}\n", format)).output;
+        assert!(check_bean_key_format(&output("{module?.}{name}")).is_empty());
+        assert!(check_bean_key_format(&output("{name}")).is_empty());
+        assert_eq!(
+            check_bean_key_format(&output("{module}")),
+            vec!["bean_key_format \"{module}\" has no {name}, every bean of a module would get the same key"]
+        );
+        assert_eq!(
+            check_bean_key_format(&output("{name")),
+            vec!["Invalid output.bean_key_format \"{name\": unclosed '{'"]
+        );
+    }
+
+    #[test]
+    fn test_check_type_mappings() {
+        let config = config(
+            r#"
+[type_mappings]
+IntPair = "list,int"
+Broken = "map,int"
+Vector3 = "vector3"
+"#,
+        );
+        assert_eq!(
+            check_type_mappings(&config.type_mappings),
+            vec!["\"Broken\" = \"map,int\": expected map,K,V"]
+        );
+    }
+
+    #[test]
+    fn test_check_external_beans() {
+        let config = config(
+            r#"
+[type_mappings]
+Vector3 = "vector3"
+
+[external_beans]
+names = ["shared.LegacyBuff", "LegacyBuff", "shared.LegacyBuff", "bad name", "math.Vector3"]
+"#,
+        );
+        assert_eq!(
+            check_external_beans(&config.external_beans.names, &config.type_mappings),
+            vec![
+                "\"shared.LegacyBuff\": listed more than once",
+                "\"bad name\": not a bean name",
+                "\"math.Vector3\": also mapped by [type_mappings] Vector3 = \"vector3\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_lint() {
+        let config = config(
+            r#"
+[lint]
+preset = "default"
+fields = { pattern = "^[a-z" }
+"#,
+        );
+        let errors = check_lint(&config.lint);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("fields: invalid pattern \"^[a-z\": "));
+        assert!(check_lint(&self::config("[lint]\npreset = \"default\"\n").lint).is_empty());
+    }
+
+    #[test]
+    fn test_validate_reports_every_section() {
+        let config = config(
+            r#"bean_key_format = "{module}"
+
+[tables]
+"a.A" = { input = "a", name = "T" }
+"a.B" = { input = "b", name = "T" }
+
+[type_mappings]
+Broken = "map,int"
+"#,
+        );
+        let err = validate(&config).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<GenError>(),
+            Some(GenError::Validation(_))
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration:\n  [tables]\n    \"a.A\", \"a.B\" all generate table \"a.T\"\n  [output]\n    bean_key_format \"{module}\" has no {name}, every bean of a module would get the same key\n  [type_mappings]\n    \"Broken\" = \"map,int\": expected map,K,V"
+        );
+        assert!(validate(&self::config("")).is_ok());
+    }
+}
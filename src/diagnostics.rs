@@ -0,0 +1,179 @@
+//! Warning collection for a pipeline run. Warnings are recorded in full (for the run
+//! report and deny_warnings) and printed once per (code, primary message) group.
+
+/// A recorded warning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// Kind of problem, e.g. `suspected-typo`; groups only merge warnings of the same code
+    pub code: &'static str,
+    /// Full message, usually `"{location}: {subject} - {primary message}"`
+    pub message: String,
+}
+
+/// Warnings collected during a run
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    warnings: Vec<Warning>,
+}
+
+impl Diagnostics {
+    pub fn warn(&mut self, code: &'static str, message: impl Into<String>) {
+        self.warnings.push(Warning {
+            code,
+            message: message.into(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.warnings.len()
+    }
+
+    /// Every warning, duplicates included, in the order they were recorded
+    pub fn into_warnings(self) -> Vec<Warning> {
+        self.warnings
+    }
+
+    /// Every warning message, duplicates included, in the order they were recorded
+    pub fn messages(&self) -> Vec<String> {
+        self.warnings.iter().map(|w| w.message.clone()).collect()
+    }
+
+    /// Console lines: one per (code, primary message) group, in first-occurrence order,
+    /// with the number of further occurrences and where the first one was. Groups past
+    /// `max_warnings` are only counted in a final summary line
+    pub fn render(&self, max_warnings: Option<usize>) -> Vec<String> {
+        let mut groups: Vec<(&Warning, usize)> = Vec::new();
+        let mut index_by_key: std::collections::HashMap<(&str, &str), usize> =
+            std::collections::HashMap::new();
+        for warning in &self.warnings {
+            let key = (warning.code, split_message(&warning.message).1);
+            match index_by_key.get(&key) {
+                Some(&index) => groups[index].1 += 1,
+                None => {
+                    index_by_key.insert(key, groups.len());
+                    groups.push((warning, 1));
+                }
+            }
+        }
+
+        let shown = max_warnings.unwrap_or(groups.len()).min(groups.len());
+        let mut lines = Vec::new();
+        for (first, count) in &groups[..shown] {
+            let mut line = format!("Warning: {}", first.message);
+            if *count > 1 {
+                let more = count - 1;
+                let noun = if more == 1 {
+                    "occurrence"
+                } else {
+                    "occurrences"
+                };
+                line.push_str(&format!(" (and {} more {}", more, noun));
+                if let Some(location) = split_message(&first.message).0 {
+                    line.push_str(&format!(", first at {}", location));
+                }
+                line.push(')');
+            }
+            lines.push(line);
+        }
+        if shown < groups.len() {
+            let hidden: usize = groups[shown..].iter().map(|(_, count)| count).sum();
+            lines.push(format!(
+                "... {} more warning(s) not shown (limit of {} distinct warnings)",
+                hidden, shown
+            ));
+        }
+        lines
+    }
+}
+
+/// Split `"{location}: {subject} - {primary}"` into the location (a leading token without
+/// whitespace, such as `skill.ts:12`) and the primary message. Both prefixes are optional
+fn split_message(message: &str) -> (Option<&str>, &str) {
+    let (location, rest) = match message.split_once(": ") {
+        Some((location, rest)) if !location.contains(char::is_whitespace) => (Some(location), rest),
+        _ => (None, message),
+    };
+    let primary = rest.split_once(" - ").map_or(rest, |(_, primary)| primary);
+    (location, primary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy_warnings(count: usize) -> Diagnostics {
+        let mut diagnostics = Diagnostics::default();
+        for i in 0..count {
+            diagnostics.warn(
+                "suspected-typo",
+                format!(
+                    "legacy/gen_{}.ts:{}: Legacy{}.effect - unknown type 'SkilConfig', did you mean 'SkillConfig'?",
+                    i,
+                    i + 3,
+                    i
+                ),
+            );
+        }
+        diagnostics
+    }
+
+    #[test]
+    fn test_duplicates_render_once() {
+        let mut diagnostics = legacy_warnings(200);
+        diagnostics.warn(
+            "ambiguous-parent",
+            "Hero implements multiple bean interfaces (A, B), no parent is emitted",
+        );
+
+        assert_eq!(diagnostics.len(), 201);
+        assert_eq!(
+            diagnostics.render(None),
+            vec![
+                "Warning: legacy/gen_0.ts:3: Legacy0.effect - unknown type 'SkilConfig', did you mean 'SkillConfig'? (and 199 more occurrences, first at legacy/gen_0.ts:3)".to_string(),
+                "Warning: Hero implements multiple bean interfaces (A, B), no parent is emitted".to_string(),
+            ]
+        );
+        // The full list is kept for the report
+        assert_eq!(diagnostics.messages().len(), 201);
+        assert!(diagnostics.messages()[199].starts_with("legacy/gen_199.ts:202:"));
+    }
+
+    #[test]
+    fn test_codes_keep_groups_apart() {
+        let mut diagnostics = Diagnostics::default();
+        diagnostics.warn("a", "x.ts:1: Foo.bar - same text");
+        diagnostics.warn("b", "x.ts:2: Foo.baz - same text");
+        diagnostics.warn("a", "x.ts:3: Foo.qux - same text");
+
+        assert_eq!(
+            diagnostics.render(None),
+            vec![
+                "Warning: x.ts:1: Foo.bar - same text (and 1 more occurrence, first at x.ts:1)",
+                "Warning: x.ts:2: Foo.baz - same text",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_max_warnings_summarizes_the_rest() {
+        let mut diagnostics = Diagnostics::default();
+        for i in 0..5 {
+            diagnostics.warn("unsupported-type", format!("a.ts:{}: problem {}", i, i));
+        }
+        diagnostics.warn("unsupported-type", "a.ts:9: problem 4");
+
+        let lines = diagnostics.render(Some(2));
+        assert_eq!(
+            lines,
+            vec![
+                "Warning: a.ts:0: problem 0",
+                "Warning: a.ts:1: problem 1",
+                "... 4 more warning(s) not shown (limit of 2 distinct warnings)",
+            ]
+        );
+    }
+}
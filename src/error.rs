@@ -0,0 +1,14 @@
+use thiserror::Error;
+
+/// Typed failures a run can end with, so callers (the CLI's exit codes) can tell a
+/// rejected schema from I/O or config problems. Recover it with
+/// `anyhow::Error::downcast_ref::<GenError>()`; anything else is an internal error
+#[derive(Debug, Error)]
+pub enum GenError {
+    /// The parsed schema failed validation, or warnings were denied
+    #[error("{0}")]
+    Validation(String),
+    /// Warnings were denied and some source files failed to parse
+    #[error("{0}")]
+    Parse(String),
+}
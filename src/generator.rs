@@ -1,7 +1,12 @@
+use crate::config::{
+    AttributeOrder, OpenRangeFormat, OptionalContainers, ParentPrecedence, VirtualFieldMode,
+    VirtualFieldsConfig, XmlStyle,
+};
 use crate::parser::field_info::SizeConstraint;
 use crate::parser::{ClassInfo, EnumInfo, FieldInfo, FieldValidators, ImportMap};
 use crate::table_registry::{ResolvedTableConfig, TableRegistry};
-use crate::type_mapper::TypeMapper;
+use crate::type_mapper::{strip_nesting, TypeMapper};
+use crate::validator::range_spec;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -12,6 +17,20 @@ pub struct XmlGenerator<'a> {
     type_to_module: HashMap<String, String>,
     /// Mapping from source file path to module name (for import-based type resolution)
     file_to_module: HashMap<PathBuf, String>,
+    /// Emit child beans inside their parent <bean> element (output.nested_children)
+    nested_children: bool,
+    /// How `?` on container fields is emitted (defaults.optional_containers)
+    optional_containers: OptionalContainers,
+    /// Emit secondary indexes of list tables as `index="a,b"` (output.list_table_indexes)
+    list_table_indexes: bool,
+    /// How one-sided @Range bounds are written (output.open_range_format)
+    open_range_format: OpenRangeFormat,
+    /// Module of classes routed away from their file's module by @Module / `@module`,
+    /// keyed by (source file, class name); checked before `file_to_module`
+    class_modules: HashMap<(PathBuf, String), String>,
+    /// Attribute order and empty-bean form (output.xml_style); the indent is applied to
+    /// the finished file by `reindent_xml`
+    xml_style: XmlStyle,
 }
 
 impl<'a> XmlGenerator<'a> {
@@ -21,6 +40,12 @@ impl<'a> XmlGenerator<'a> {
             table_registry,
             type_to_module: HashMap::new(),
             file_to_module: HashMap::new(),
+            nested_children: false,
+            optional_containers: OptionalContainers::default(),
+            list_table_indexes: false,
+            open_range_format: OpenRangeFormat::default(),
+            class_modules: HashMap::new(),
+            xml_style: XmlStyle::default(),
         }
     }
 
@@ -35,6 +60,12 @@ impl<'a> XmlGenerator<'a> {
             table_registry,
             type_to_module,
             file_to_module: HashMap::new(),
+            nested_children: false,
+            optional_containers: OptionalContainers::default(),
+            list_table_indexes: false,
+            open_range_format: OpenRangeFormat::default(),
+            class_modules: HashMap::new(),
+            xml_style: XmlStyle::default(),
         }
     }
 
@@ -50,9 +81,51 @@ impl<'a> XmlGenerator<'a> {
             table_registry,
             type_to_module,
             file_to_module,
+            nested_children: false,
+            optional_containers: OptionalContainers::default(),
+            list_table_indexes: false,
+            open_range_format: OpenRangeFormat::default(),
+            class_modules: HashMap::new(),
+            xml_style: XmlStyle::default(),
         }
     }
 
+    /// Modules of classes that override their file's module (@Module / `@module`)
+    pub fn with_class_modules(mut self, class_modules: HashMap<(PathBuf, String), String>) -> Self {
+        self.class_modules = class_modules;
+        self
+    }
+
+    /// Enable or disable nesting child beans inside their parent bean element
+    pub fn with_nested_children(mut self, nested_children: bool) -> Self {
+        self.nested_children = nested_children;
+        self
+    }
+
+    /// Set how optional container fields are emitted
+    pub fn with_optional_containers(mut self, optional_containers: OptionalContainers) -> Self {
+        self.optional_containers = optional_containers;
+        self
+    }
+
+    /// Enable or disable multi-index list tables (secondary indexes in the `index` attribute)
+    pub fn with_list_table_indexes(mut self, list_table_indexes: bool) -> Self {
+        self.list_table_indexes = list_table_indexes;
+        self
+    }
+
+    /// Set how one-sided @Range bounds are written (output.open_range_format)
+    pub fn with_open_range_format(mut self, open_range_format: OpenRangeFormat) -> Self {
+        self.open_range_format = open_range_format;
+        self
+    }
+
+    /// Set the attribute order and empty-bean form (output.xml_style)
+    pub fn with_xml_style(mut self, xml_style: XmlStyle) -> Self {
+        self.xml_style = xml_style;
+        self
+    }
+
     pub fn generate(&self, classes: &[ClassInfo], module_name: &str) -> String {
         // For backward compatibility, use classes as all_classes
         self.generate_with_all_classes(classes, module_name, classes)
@@ -110,7 +183,7 @@ impl<'a> XmlGenerator<'a> {
             let sorted_enums: Vec<_> = enums.iter().collect();
 
             for enum_info in sorted_enums {
-                generate_enum(&mut lines, enum_info);
+                generate_enum(&mut lines, enum_info, self.xml_style.attribute_order);
                 lines.push(String::new());
             }
         }
@@ -138,9 +211,41 @@ impl<'a> XmlGenerator<'a> {
         // Keep original source file order (no sorting)
 
         // Generate beans
-        for class in &unique_classes {
-            self.generate_bean_with_module_map(&mut lines, class, all_classes, module_name, &class_to_module);
-            lines.push(String::new());
+        if self.nested_children {
+            // Children whose parent is generated in this module are emitted inside it;
+            // everything else stays at the top level
+            let generated: std::collections::HashSet<&str> =
+                unique_classes.iter().map(|c| c.name.as_str()).collect();
+            let mut children: HashMap<String, Vec<&ClassInfo>> = HashMap::new();
+            let mut roots: Vec<&ClassInfo> = Vec::new();
+            for &class in &unique_classes {
+                let parent = self.resolve_parent(class, all_classes);
+                if generated.contains(parent.as_str()) && parent != class.name {
+                    children.entry(parent).or_default().push(class);
+                } else {
+                    roots.push(class);
+                }
+            }
+
+            for class in roots {
+                let mut visited = std::collections::HashSet::new();
+                self.generate_bean_tree(
+                    &mut lines,
+                    class,
+                    &children,
+                    all_classes,
+                    module_name,
+                    &class_to_module,
+                    false,
+                    &mut visited,
+                );
+                lines.push(String::new());
+            }
+        } else {
+            for class in &unique_classes {
+                self.generate_bean_with_module_map(&mut lines, class, all_classes, module_name, &class_to_module);
+                lines.push(String::new());
+            }
         }
 
         // Generate tables from [tables] config in registry
@@ -165,6 +270,10 @@ impl<'a> XmlGenerator<'a> {
             lines.push(String::new());
         }
 
+        if self.xml_style.self_closing {
+            self_close_empty_beans(&mut lines);
+        }
+
         lines.push("</module>".to_string());
         lines.join("\n") + "\n"
     }
@@ -186,8 +295,19 @@ impl<'a> XmlGenerator<'a> {
             attrs.push(format!(r#"index="{}""#, config.index));
         }
 
+        // Multi-index list tables: independent keys separated by ','
+        if config.mode == "list" && self.list_table_indexes && !config.secondary_indexes.is_empty()
+        {
+            attrs.push(format!(r#"index="{}""#, config.secondary_indexes.join(",")));
+        }
+
         attrs.push(format!(r#"input="{}""#, config.input));
 
+        // Already mapped through [groups] (TableRegistry::resolve_groups)
+        if !config.groups.is_empty() {
+            attrs.push(format!(r#"group="{}""#, config.groups.join(",")));
+        }
+
         lines.push(format!(r#"    <table {} />"#, attrs.join(" ")));
     }
 
@@ -205,36 +325,102 @@ impl<'a> XmlGenerator<'a> {
         current_module: &str,
         class_to_module: &std::collections::HashMap<String, String>,
     ) {
-        let parent = if class.is_interface {
-            // Interface: no parent if no extends (not affected by the change)
-            class.extends.clone().unwrap_or_default()
-        } else {
-            // Class: resolve parent based on extends, implements, or default to TsClass
-            self.resolve_class_parent(class, all_classes)
-        };
+        self.generate_bean_lines(lines, class, all_classes, current_module, class_to_module, true);
+    }
 
-        // Resolve parent with module prefix if needed, using imports for accurate resolution
-        let resolved_parent = self.resolve_type_with_imports(&parent, current_module, class_to_module, &class.imports);
+    /// Generate a bean with its children (output.nested_children) nested inside it.
+    /// Nested beans omit `parent=`, since Luban takes the parent from the enclosing element
+    #[allow(clippy::too_many_arguments)]
+    fn generate_bean_tree<'c>(
+        &self,
+        lines: &mut Vec<String>,
+        class: &'c ClassInfo,
+        children: &HashMap<String, Vec<&'c ClassInfo>>,
+        all_classes: &[ClassInfo],
+        current_module: &str,
+        class_to_module: &std::collections::HashMap<String, String>,
+        nested: bool,
+        visited: &mut std::collections::HashSet<&'c str>,
+    ) {
+        if !visited.insert(class.name.as_str()) {
+            return;
+        }
 
-        let alias_attr = class
-            .alias
-            .as_ref()
-            .map(|a| format!(r#" alias="{}""#, escape_xml(a)))
-            .unwrap_or_default();
+        let mut bean_lines = Vec::new();
+        self.generate_bean_lines(&mut bean_lines, class, all_classes, current_module, class_to_module, !nested);
+
+        // Insert children before the closing </bean>
+        let closing = bean_lines.pop();
+        for child in children.get(&class.name).into_iter().flatten() {
+            self.generate_bean_tree(
+                &mut bean_lines,
+                child,
+                children,
+                all_classes,
+                current_module,
+                class_to_module,
+                true,
+                visited,
+            );
+        }
+        bean_lines.extend(closing);
 
-        let comment_attr = class
-            .comment
-            .as_ref()
-            .filter(|c| !c.is_empty())
-            .map(|c| format!(r#" comment="{}""#, escape_xml(c)))
-            .unwrap_or_default();
+        if nested {
+            lines.extend(bean_lines.into_iter().map(|l| format!("    {}", l)));
+        } else {
+            lines.extend(bean_lines);
+        }
+    }
 
-        let parent_attr = if resolved_parent.is_empty() {
-            String::new()
+    fn generate_bean_lines(
+        &self,
+        lines: &mut Vec<String>,
+        class: &ClassInfo,
+        all_classes: &[ClassInfo],
+        current_module: &str,
+        class_to_module: &std::collections::HashMap<String, String>,
+        emit_parent: bool,
+    ) {
+        let parent = if emit_parent {
+            self.resolve_parent(class, all_classes)
         } else {
-            format!(r#" parent="{}""#, resolved_parent)
+            String::new()
         };
 
+        // Resolve parent with module prefix if needed, using imports for accurate resolution
+        let resolved_parent = self.resolve_type_with_imports(
+            &parent,
+            current_module,
+            class_to_module,
+            &class.imports,
+        );
+
+        let attributes = render_attributes(
+            &[
+                ("name", Some(class.name.clone())),
+                ("alias", class.alias.as_deref().map(escape_xml)),
+                (
+                    "parent",
+                    Some(resolved_parent).filter(|parent| !parent.is_empty()),
+                ),
+                (
+                    "comment",
+                    class
+                        .comment
+                        .as_deref()
+                        .filter(|c| !c.is_empty())
+                        .map(escape_xml),
+                ),
+                (
+                    "tags",
+                    Some(&class.tags)
+                        .filter(|tags| !tags.is_empty())
+                        .map(|tags| escape_xml(&merge_tags(Vec::new(), tags).join(","))),
+                ),
+            ],
+            self.xml_style.attribute_order,
+        );
+
         // Add XML comment before bean if comment exists
         if let Some(comment) = &class.comment {
             if !comment.is_empty() {
@@ -242,35 +428,28 @@ impl<'a> XmlGenerator<'a> {
             }
         }
 
-        lines.push(format!(
-            r#"    <bean name="{}"{}{}{}>"#,
-            class.name, alias_attr, parent_attr, comment_attr
-        ));
+        lines.push(format!("    <bean{}>", attributes));
 
         // Collect parent field names to skip redeclared fields
         // Note: we need to look up parent by simple name, not qualified name
         let mut parent_field_names = std::collections::HashSet::new();
-        let mut current_parent = if parent.is_empty() {
-            None
-        } else {
-            Some(parent.as_str())
-        };
-        while let Some(parent_name) = current_parent {
-            if let Some(parent) = all_classes.iter().find(|c| &c.name == parent_name) {
-                for field in &parent.fields {
-                    parent_field_names.insert(field.name.as_str());
-                }
-                current_parent = parent.extends.as_ref().map(|s| s.as_str());
-            } else {
-                break;
+        for ancestor in self.collect_ancestors(class, all_classes) {
+            for field in &ancestor.fields {
+                parent_field_names.insert(field.name.as_str());
             }
         }
 
         // Only generate fields that are not redeclared from parent classes
         // Skip $type field (used for TypeScript discriminated unions, not needed in Luban)
-        for field in &class.fields {
+        for field in class.ordered_fields() {
             if !parent_field_names.contains(field.name.as_str()) && field.name != "$type" {
-                self.generate_field_with_imports(lines, field, current_module, class_to_module, &class.imports);
+                self.generate_field_with_imports(
+                    lines,
+                    field,
+                    current_module,
+                    class_to_module,
+                    &class.imports,
+                );
             }
         }
 
@@ -301,10 +480,22 @@ impl<'a> XmlGenerator<'a> {
             return String::new();
         }
 
+        // Nullable element (`ItemDrop?`, defaults.nullable_elements)
+        if let Some(inner) = type_name.strip_suffix('?') {
+            let resolved =
+                self.resolve_type_with_imports(inner, current_module, class_to_module, imports);
+            return format!("{}?", resolved);
+        }
+
         // First, check if this type was imported from a specific file
         if let Some(import_source_path) = imports.get(type_name) {
-            // Look up the module for this source file
-            if let Some(target_module) = self.file_to_module.get(import_source_path) {
+            // Look up the module for this class, or else for its source file
+            let class_module = self
+                .class_modules
+                .get(&(import_source_path.clone(), type_name.to_string()));
+            if let Some(target_module) =
+                class_module.or_else(|| self.file_to_module.get(import_source_path))
+            {
                 // If the imported type is from a different module, add the module prefix
                 if target_module != current_module && !target_module.is_empty() {
                     return format!("{}.{}", target_module, type_name);
@@ -326,23 +517,135 @@ impl<'a> XmlGenerator<'a> {
         type_name.to_string()
     }
 
-    /// Resolves the parent for a class based on:
-    /// 1. Extends keyword (highest priority)
-    /// 2. Single implements interface (only when no extends)
-    /// 3. No parent (empty string) when no extends and no/multiple implements
-    fn resolve_class_parent(&self, class: &ClassInfo, _all_classes: &[ClassInfo]) -> String {
-        // Priority 1: Use extends if present
-        if let Some(extends) = &class.extends {
-            return extends.clone();
+    /// Check that the bean hierarchy can be nested (output.nested_children): every bean
+    /// needs a single parent. Inheritance cycles already fail validation
+    /// (`validator::find_inheritance_cycles`). Returns one error per ambiguous bean.
+    pub fn nesting_errors(&self, all_classes: &[ClassInfo]) -> Vec<String> {
+        find_ambiguous_parents(all_classes)
+            .into_iter()
+            .map(|(class_name, interfaces)| {
+                format!(
+                    "{} implements multiple bean interfaces ({}); nested_children needs a single parent, use extends or @Parent",
+                    class_name,
+                    interfaces.join(", ")
+                )
+            })
+            .collect()
+    }
+
+    /// Check that no bean ends up with two fields of the same schema name once inherited
+    /// fields and [[virtual_fields]] are merged in. Redeclaring an inherited property in
+    /// TypeScript is fine (the child copy is skipped), anything else is reported with the
+    /// origin of both fields. Fields written by a mode = "override" block are exempt.
+    /// Returns one error per duplicate.
+    pub fn duplicate_field_errors(
+        &self,
+        all_classes: &[ClassInfo],
+        virtual_fields: &[VirtualFieldsConfig],
+    ) -> Vec<String> {
+        let is_override = |field: &FieldInfo| {
+            field
+                .virtual_block()
+                .and_then(|block| virtual_fields.get(block))
+                .is_some_and(|block| {
+                    block
+                        .fields
+                        .iter()
+                        .any(|f| f.name == field.name && f.mode == VirtualFieldMode::Override)
+                })
+        };
+
+        let mut errors = Vec::new();
+        for class in all_classes {
+            // Fields the bean inherits, keyed by property name and by schema name
+            let mut inherited_by_name: HashMap<&str, (&ClassInfo, &FieldInfo)> = HashMap::new();
+            let mut inherited_by_schema: HashMap<&str, (&ClassInfo, &FieldInfo)> = HashMap::new();
+            for ancestor in self.collect_ancestors(class, all_classes) {
+                for field in &ancestor.fields {
+                    inherited_by_name
+                        .entry(field.name.as_str())
+                        .or_insert((ancestor, field));
+                    inherited_by_schema
+                        .entry(field.schema_name())
+                        .or_insert((ancestor, field));
+                }
+            }
+
+            let mut own: HashMap<&str, &FieldInfo> = HashMap::new();
+            for field in class.fields.iter().filter(|f| f.name != "$type") {
+                let inherited = match inherited_by_name.get(field.name.as_str()) {
+                    // A redeclared TypeScript property is skipped in favour of the parent's,
+                    // but an injected field would silently disappear
+                    Some(_) if field.virtual_block().is_none() => continue,
+                    Some(parent) => Some(*parent),
+                    None => inherited_by_schema.get(field.schema_name()).copied(),
+                };
+
+                if let Some((ancestor, parent_field)) = inherited {
+                    if !is_override(field) && !is_override(parent_field) {
+                        errors.push(format!(
+                            "Bean '{}' has duplicate field '{}': {} and {} (inherited from {})",
+                            class.name,
+                            field.schema_name(),
+                            class.field_location(field),
+                            ancestor.field_location(parent_field),
+                            ancestor.name
+                        ));
+                    }
+                    continue;
+                }
+
+                match own.get(field.schema_name()) {
+                    Some(&first) if !is_override(field) && !is_override(first) => {
+                        errors.push(format!(
+                            "Bean '{}' has duplicate field '{}': {} and {}",
+                            class.name,
+                            field.schema_name(),
+                            class.field_location(first),
+                            class.field_location(field)
+                        ));
+                    }
+                    Some(_) => {}
+                    None => {
+                        own.insert(field.schema_name(), field);
+                    }
+                }
+            }
         }
 
-        // Priority 2: Use single implements if present
-        if class.implements.len() == 1 {
-            return class.implements[0].clone();
+        errors
+    }
+
+    /// Resolves the Luban parent of a bean (see `bean_parent`)
+    fn resolve_parent(&self, class: &ClassInfo, _all_classes: &[ClassInfo]) -> String {
+        bean_parent(class)
+    }
+
+    /// Walks the parent chain of a bean transitively, returning every ancestor that is a
+    /// parsed bean (nearest first). Stops at the first parent that isn't parsed (external
+    /// base) and on inheritance cycles, which fail validation before generation.
+    fn collect_ancestors<'c>(
+        &self,
+        class: &ClassInfo,
+        all_classes: &'c [ClassInfo],
+    ) -> Vec<&'c ClassInfo> {
+        let mut ancestors: Vec<&ClassInfo> = Vec::new();
+        let mut visited: Vec<&str> = vec![class.name.as_str()];
+        let mut parent_name = self.resolve_parent(class, all_classes);
+
+        while !parent_name.is_empty() {
+            let Some(parent) = all_classes.iter().find(|c| c.name == parent_name) else {
+                break;
+            };
+            if visited.contains(&parent.name.as_str()) {
+                break;
+            }
+            visited.push(parent.name.as_str());
+            ancestors.push(parent);
+            parent_name = self.resolve_parent(parent, all_classes);
         }
 
-        // Priority 3: No parent
-        String::new()
+        ancestors
     }
 
     fn generate_field(&self, lines: &mut Vec<String>, field: &FieldInfo) {
@@ -383,7 +686,7 @@ impl<'a> XmlGenerator<'a> {
                 };
 
                 let mut final_type = index_type;
-                if field.is_optional {
+                if field.may_be_empty() {
                     final_type.push('?');
                 }
                 final_type.push_str(&format!("#ref={}", table_ref));
@@ -400,18 +703,19 @@ impl<'a> XmlGenerator<'a> {
                     .map(|a| format!(r#" alias="{}""#, escape_xml(a)))
                     .unwrap_or_default();
 
-                // Build tags: RefOverride=field_name + custom_tags
+                // Build tags: RefOverride=field_name + custom_tags + @Tag
                 let tags_attr = {
                     let mut tags = vec![format!("RefOverride={}", ref_field)];
                     if let Some(custom) = &field.custom_tags {
                         tags.push(custom.clone());
                     }
+                    let tags = merge_tags(tags, &field.tags);
                     format!(r#" tags="{}""#, escape_xml(&tags.join(",")))
                 };
 
                 lines.push(format!(
                     r#"        <var name="{}" type="{}"{}{}{}/>"#,
-                    field.name, final_type, alias_attr, comment_attr, tags_attr
+                    field.schema_name(), final_type, alias_attr, comment_attr, tags_attr
                 ));
                 return;
             }
@@ -422,7 +726,7 @@ impl<'a> XmlGenerator<'a> {
             if let Some(constructor_type) = &field.constructor_inner_type {
                 let resolved_constructor_type = self.resolve_type_with_imports(constructor_type, current_module, class_to_module, imports);
                 let mut final_type = String::from("string");
-                if field.is_optional {
+                if field.may_be_empty() {
                     final_type.push('?');
                 }
                 final_type.push_str(&format!("#constructor={}", resolved_constructor_type));
@@ -439,21 +743,59 @@ impl<'a> XmlGenerator<'a> {
                     .map(|a| format!(r#" alias="{}""#, escape_xml(a)))
                     .unwrap_or_default();
 
-                // Build tags: custom_tags only
-                let tags_attr = field
-                    .custom_tags
-                    .as_ref()
-                    .map(|t| format!(r#" tags="{}""#, escape_xml(t)))
-                    .unwrap_or_default();
+                // Build tags: custom_tags + @Tag
+                let tags = merge_tags(field.custom_tags.iter().cloned().collect(), &field.tags);
+                let tags_attr = if tags.is_empty() {
+                    String::new()
+                } else {
+                    format!(r#" tags="{}""#, escape_xml(&tags.join(",")))
+                };
 
                 lines.push(format!(
                     r#"        <var name="{}" type="{}"{}{}{}/>"#,
-                    field.name, final_type, alias_attr, comment_attr, tags_attr
+                    field.schema_name(), final_type, alias_attr, comment_attr, tags_attr
                 ));
                 return;
             }
         }
 
+        // Handle @Converter("Type", { tag: "..." }) fields
+        // The Luban type is emitted verbatim, bypassing TypeMapper and validators
+        if let Some(converter) = &field.converter {
+            let mut final_type = converter.luban_type.clone();
+            if field.may_be_empty() {
+                final_type.push('?');
+            }
+
+            let comment_attr = field
+                .comment
+                .as_ref()
+                .map(|c| format!(r#" comment="{}""#, escape_xml(c)))
+                .unwrap_or_default();
+
+            let alias_attr = field
+                .alias
+                .as_ref()
+                .map(|a| format!(r#" alias="{}""#, escape_xml(a)))
+                .unwrap_or_default();
+
+            // Build tags: custom_tags + converter tags + @Tag
+            let mut tags: Vec<String> = field.custom_tags.iter().cloned().collect();
+            tags.extend(converter.tags.iter().cloned());
+            let tags = merge_tags(tags, &field.tags);
+            let tags_attr = if tags.is_empty() {
+                String::new()
+            } else {
+                format!(r#" tags="{}""#, escape_xml(&tags.join(",")))
+            };
+
+            lines.push(format!(
+                r#"        <var name="{}" type="{}"{}{}{}/>"#,
+                field.schema_name(), final_type, alias_attr, comment_attr, tags_attr
+            ));
+            return;
+        }
+
         // Apply @type override if present, otherwise use mapped type
         let mut mapped_type = if let Some(type_override) = &field.type_override {
             type_override.clone()
@@ -477,7 +819,7 @@ impl<'a> XmlGenerator<'a> {
             || mapped_type.starts_with("array,")
             || mapped_type.starts_with("set,");
 
-        let final_type = if is_container {
+        let mut final_type = if is_container {
             // Handle container types with size/index validators and separators
             // Note: mapped_type is already resolved with module prefixes
             self.apply_container_validators_with_module_and_separators(
@@ -494,11 +836,16 @@ impl<'a> XmlGenerator<'a> {
             self.apply_scalar_validators_with_default(
                 &mapped_type,
                 validators,
-                field.is_optional,
+                field.may_be_empty(),
                 field.default_value.as_deref(),
             )
         };
 
+        // Optional containers are emitted as required unless nullable containers are enabled
+        if is_container && field.may_be_empty() && self.optional_containers == OptionalContainers::Nullable {
+            final_type = nullable_container_type(&final_type);
+        }
+
         let comment_attr = field
             .comment
             .as_ref()
@@ -511,24 +858,25 @@ impl<'a> XmlGenerator<'a> {
             .map(|a| format!(r#" alias="{}""#, escape_xml(a)))
             .unwrap_or_default();
 
-        // Build tags: RefOverride (auto for @ref JSDoc tag only) + ObjectFactory + custom_tags
+        // Build tags: RefOverride (auto for @ref JSDoc tag only) + ObjectFactory + custom_tags + @Tag
         let tags_attr = {
             let mut tags = Vec::new();
 
             // Auto-add RefOverride=true when @ref JSDoc tag is present
             // NOT when RefKey<T> generic type is used
             if field.validators.has_ref {
-                tags.push("RefOverride=true");
+                tags.push("RefOverride=true".to_string());
             }
 
             if field.is_object_factory {
-                tags.push("ObjectFactory=true");
+                tags.push("ObjectFactory=true".to_string());
             }
 
             if let Some(custom) = &field.custom_tags {
-                tags.push(custom.as_str());
+                tags.push(custom.clone());
             }
 
+            let tags = merge_tags(tags, &field.tags);
             if tags.is_empty() {
                 String::new()
             } else {
@@ -536,9 +884,10 @@ impl<'a> XmlGenerator<'a> {
             }
         };
 
+        // Default values are source text (`mode: string = "a&b"`)
         lines.push(format!(
             r#"        <var name="{}" type="{}"{}{}{}/>"#,
-            field.name, final_type, alias_attr, comment_attr, tags_attr
+            field.schema_name(), escape_xml(&final_type), alias_attr, comment_attr, tags_attr
         ));
     }
 
@@ -549,35 +898,35 @@ impl<'a> XmlGenerator<'a> {
         current_module: &str,
         class_to_module: &std::collections::HashMap<String, String>,
     ) -> String {
+        let resolve =
+            |t: &str| self.resolve_full_type_with_module(t, current_module, class_to_module);
+
+        // Nested container: (list,T)
+        if let Some(inner) = strip_nesting(type_str) {
+            return format!("({})", resolve(inner));
+        }
+
         // Handle list,T
-        if type_str.starts_with("list,") {
-            let element = &type_str[5..];
-            let resolved_element = self.resolve_type_with_module(element, current_module, class_to_module);
-            return format!("list,{}", resolved_element);
+        if let Some(element) = type_str.strip_prefix("list,") {
+            return format!("list,{}", resolve(element));
         }
 
         // Handle map,K,V
-        if type_str.starts_with("map,") {
-            let parts: Vec<&str> = type_str[4..].splitn(2, ',').collect();
-            if parts.len() == 2 {
-                let resolved_key = self.resolve_type_with_module(parts[0], current_module, class_to_module);
-                let resolved_value = self.resolve_type_with_module(parts[1], current_module, class_to_module);
-                return format!("map,{},{}", resolved_key, resolved_value);
-            }
+        if let Some((key, value)) = type_str
+            .strip_prefix("map,")
+            .and_then(|rest| rest.split_once(','))
+        {
+            return format!("map,{},{}", resolve(key), resolve(value));
         }
 
         // Handle array,T
-        if type_str.starts_with("array,") {
-            let element = &type_str[6..];
-            let resolved_element = self.resolve_type_with_module(element, current_module, class_to_module);
-            return format!("array,{}", resolved_element);
+        if let Some(element) = type_str.strip_prefix("array,") {
+            return format!("array,{}", resolve(element));
         }
 
         // Handle set,T
-        if type_str.starts_with("set,") {
-            let element = &type_str[4..];
-            let resolved_element = self.resolve_type_with_module(element, current_module, class_to_module);
-            return format!("set,{}", resolved_element);
+        if let Some(element) = type_str.strip_prefix("set,") {
+            return format!("set,{}", resolve(element));
         }
 
         // Simple type
@@ -592,35 +941,36 @@ impl<'a> XmlGenerator<'a> {
         class_to_module: &std::collections::HashMap<String, String>,
         imports: &ImportMap,
     ) -> String {
+        let resolve = |t: &str| {
+            self.resolve_full_type_with_imports(t, current_module, class_to_module, imports)
+        };
+
+        // Nested container: (list,T)
+        if let Some(inner) = strip_nesting(type_str) {
+            return format!("({})", resolve(inner));
+        }
+
         // Handle list,T
-        if type_str.starts_with("list,") {
-            let element = &type_str[5..];
-            let resolved_element = self.resolve_type_with_imports(element, current_module, class_to_module, imports);
-            return format!("list,{}", resolved_element);
+        if let Some(element) = type_str.strip_prefix("list,") {
+            return format!("list,{}", resolve(element));
         }
 
         // Handle map,K,V
-        if type_str.starts_with("map,") {
-            let parts: Vec<&str> = type_str[4..].splitn(2, ',').collect();
-            if parts.len() == 2 {
-                let resolved_key = self.resolve_type_with_imports(parts[0], current_module, class_to_module, imports);
-                let resolved_value = self.resolve_type_with_imports(parts[1], current_module, class_to_module, imports);
-                return format!("map,{},{}", resolved_key, resolved_value);
-            }
+        if let Some((key, value)) = type_str
+            .strip_prefix("map,")
+            .and_then(|rest| rest.split_once(','))
+        {
+            return format!("map,{},{}", resolve(key), resolve(value));
         }
 
         // Handle array,T
-        if type_str.starts_with("array,") {
-            let element = &type_str[6..];
-            let resolved_element = self.resolve_type_with_imports(element, current_module, class_to_module, imports);
-            return format!("array,{}", resolved_element);
+        if let Some(element) = type_str.strip_prefix("array,") {
+            return format!("array,{}", resolve(element));
         }
 
         // Handle set,T
-        if type_str.starts_with("set,") {
-            let element = &type_str[4..];
-            let resolved_element = self.resolve_type_with_imports(element, current_module, class_to_module, imports);
-            return format!("set,{}", resolved_element);
+        if let Some(element) = type_str.strip_prefix("set,") {
+            return format!("set,{}", resolve(element));
         }
 
         // Simple type
@@ -677,11 +1027,11 @@ impl<'a> XmlGenerator<'a> {
         }
 
         // Handle range
-        if let Some((min, max)) = &validators.range {
-            // Format numbers nicely (remove unnecessary decimal points)
-            let min_str = format_number(*min);
-            let max_str = format_number(*max);
-            validator_parts.push(format!("range=[{},{}]", min_str, max_str));
+        if let Some(range) = &validators.range {
+            validator_parts.push(format!(
+                "range={}",
+                range_spec(range, base_type, self.open_range_format)
+            ));
         }
 
         // Handle set
@@ -709,7 +1059,13 @@ impl<'a> XmlGenerator<'a> {
     ) -> String {
         // Handle @ref or RefKey<T> - replace type with target table's index type
         let has_any_ref = validators.has_ref || validators.has_ref_key;
-        let effective_type = if has_any_ref {
+        let effective_type = if let Some(target) = &validators.ref_target {
+            // @Ref(Target) - the slot holds the target table's index type
+            self.table_registry
+                .get_index_type(target)
+                .unwrap_or(base_type)
+                .to_string()
+        } else if has_any_ref {
             let type_name = base_type.split('.').last().unwrap_or(base_type);
             if let Some(table_config) = self.table_registry.get_table_by_class(type_name) {
                 table_config.index_type.clone().unwrap_or_else(|| base_type.to_string())
@@ -735,8 +1091,12 @@ impl<'a> XmlGenerator<'a> {
         // Collect validator suffixes
         let mut validator_parts = Vec::new();
 
-        // Handle @ref or RefKey<T> - auto-discover target table from base_type
-        if has_any_ref {
+        // Handle @Ref(Target), or @ref / RefKey<T> - auto-discover target table from base_type
+        if let Some(target) = &validators.ref_target {
+            if let Some(table_ref) = self.table_registry.resolve_ref(target) {
+                validator_parts.push(format!("ref={}", table_ref));
+            }
+        } else if has_any_ref {
             let type_name = base_type.split('.').last().unwrap_or(base_type);
             if let Some(table_config) = self.table_registry.get_table_by_class(type_name) {
                 let table_ref = if table_config.module.is_empty() {
@@ -749,11 +1109,11 @@ impl<'a> XmlGenerator<'a> {
         }
 
         // Handle range
-        if let Some((min, max)) = &validators.range {
-            // Format numbers nicely (remove unnecessary decimal points)
-            let min_str = format_number(*min);
-            let max_str = format_number(*max);
-            validator_parts.push(format!("range=[{},{}]", min_str, max_str));
+        if let Some(range) = &validators.range {
+            validator_parts.push(format!(
+                "range={}",
+                range_spec(range, base_type, self.open_range_format)
+            ));
         }
 
         // Handle set
@@ -835,33 +1195,58 @@ impl<'a> XmlGenerator<'a> {
                 let key_type = kv_parts[0];
                 let value_type = kv_parts[1];
 
-                // Apply @refKey to key type
+                // Apply @refKey and number-literal union keys (#set=) to key type
                 let key_validators = FieldValidators {
                     has_ref: validators.has_ref_key, // @refKey applies to key
                     has_ref_key: false,
+                    ref_target: validators.ref_key_target.clone(), // @RefKey(Target) too
+                    ref_key_target: None,
                     range: None,
                     required: false,
-                    set_values: vec![],
+                    set_values: validators.key_set_values.clone(),
+                    key_set_values: vec![],
                     size: None,
                     index_field: None,
                     nominal: false,
                 };
-                let key_with_validators =
+                let mut key_with_validators =
                     self.apply_scalar_validators_with_default(key_type, &key_validators, false, None);
+                // Keep the set values' commas from splitting the map's key and value, and
+                // scope an explicit ref to the key: map,(int#ref=shop.ItemTable),int
+                if key_with_validators.contains(',') || validators.ref_key_target.is_some() {
+                    key_with_validators = format!("({})", key_with_validators);
+                }
 
                 // Apply @ref to value type
                 let value_validators = FieldValidators {
                     has_ref: validators.has_ref, // @ref applies to value
                     has_ref_key: false,
+                    ref_target: validators.ref_target.clone(),
+                    ref_key_target: None,
                     range: validators.range,
                     required: validators.required,
                     set_values: validators.set_values.clone(),
+                    key_set_values: vec![],
                     size: None,
                     index_field: None,
                     nominal: validators.nominal,
                 };
-                let value_with_validators =
-                    self.apply_scalar_validators_with_default(value_type, &value_validators, false, None);
+                // A nullable value (`ItemDrop?`) keeps its marker ahead of the validators;
+                // a nested container value (`(list,Item)`) is written as is
+                let (value_type, value_nullable) = split_nullable(value_type);
+                let mut value_with_validators = if strip_nesting(value_type).is_some() {
+                    value_type.to_string()
+                } else {
+                    self.apply_scalar_validators_with_default(
+                        value_type,
+                        &value_validators,
+                        value_nullable,
+                        None,
+                    )
+                };
+                if validators.ref_target.is_some() {
+                    value_with_validators = format!("({})", value_with_validators);
+                }
 
                 // Build the final type string for map
                 let mut result = if container_mods.is_empty() {
@@ -890,17 +1275,34 @@ impl<'a> XmlGenerator<'a> {
         let element_validators = FieldValidators {
             has_ref: validators.has_ref || validators.has_ref_key, // Both @ref and RefKey<T> apply to element
             has_ref_key: false,
+            ref_target: validators.ref_target.clone(),
+            ref_key_target: None,
             range: validators.range,
             required: validators.required,
             set_values: validators.set_values.clone(),
+            key_set_values: vec![],
             // These are container-level, not element-level
             size: None,
             index_field: None,
             nominal: validators.nominal,
         };
 
-        let element_with_validators =
-            self.apply_scalar_validators_with_default(rest, &element_validators, false, None);
+        // A nested container element (`(list,double)`) is written as is
+        let (element_type, element_nullable) = split_nullable(rest);
+        let mut element_with_validators = if strip_nesting(element_type).is_some() {
+            element_type.to_string()
+        } else {
+            self.apply_scalar_validators_with_default(
+                element_type,
+                &element_validators,
+                element_nullable,
+                None,
+            )
+        };
+        // @Ref(Target) is an element validator: list,(int#ref=shop.ItemTable)
+        if validators.ref_target.is_some() {
+            element_with_validators = format!("({})", element_with_validators);
+        }
 
         // Build the final type string
         let mut result = if container_mods.is_empty() {
@@ -958,9 +1360,12 @@ impl<'a> XmlGenerator<'a> {
         let element_validators = FieldValidators {
             has_ref: validators.has_ref,
             has_ref_key: false,
+            ref_target: validators.ref_target.clone(),
+            ref_key_target: None,
             range: validators.range,
             required: validators.required,
             set_values: validators.set_values.clone(),
+            key_set_values: vec![],
             // These are container-level, not element-level
             size: None,
             index_field: None,
@@ -1022,9 +1427,12 @@ impl<'a> XmlGenerator<'a> {
         let element_validators = FieldValidators {
             has_ref: validators.has_ref,
             has_ref_key: false,
+            ref_target: validators.ref_target.clone(),
+            ref_key_target: None,
             range: validators.range,
             required: validators.required,
             set_values: validators.set_values.clone(),
+            key_set_values: vec![],
             // These are container-level, not element-level
             size: None,
             index_field: None,
@@ -1047,6 +1455,45 @@ impl<'a> XmlGenerator<'a> {
     }
 }
 
+/// Merge @Tag (key, value) pairs into a list of "key=value" tag entries.
+/// Entries may themselves be comma-separated lists (e.g. from @tags JSDoc). When a key
+/// appears in both, the @Tag value wins and replaces the earlier entry.
+fn merge_tags(base: Vec<String>, extra: &[(String, String)]) -> Vec<String> {
+    let mut tags: Vec<String> = base
+        .iter()
+        .flat_map(|t| t.split(','))
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    for (key, value) in extra {
+        tags.retain(|t| t.split('=').next() != Some(key.as_str()));
+        tags.push(format!("{}={}", key, value));
+    }
+    tags
+}
+
+/// Split a nullable element type (`ItemDrop?`) into the type and whether it is nullable
+fn split_nullable(element_type: &str) -> (&str, bool) {
+    match element_type.strip_suffix('?') {
+        Some(inner) => (inner, true),
+        None => (element_type, false),
+    }
+}
+
+/// Mark a container type as nullable: "list,T" -> "list?,T", "(list#size=2),T" -> "(list?#size=2),T"
+fn nullable_container_type(container_type: &str) -> String {
+    let insert_at = if container_type.starts_with('(') {
+        container_type.find('#')
+    } else {
+        container_type.find(',')
+    };
+    match insert_at {
+        Some(pos) => format!("{}?{}", &container_type[..pos], &container_type[pos..]),
+        None => container_type.to_string(),
+    }
+}
+
 fn escape_xml(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
@@ -1055,14 +1502,71 @@ fn escape_xml(s: &str) -> String {
         .replace('\'', "&apos;")
 }
 
-/// Format a number, removing unnecessary decimal points
-/// e.g., 1.0 -> "1", 1.5 -> "1.5"
-fn format_number(n: f64) -> String {
-    if n.fract() == 0.0 {
-        format!("{}", n as i64)
-    } else {
-        format!("{}", n)
+/// Attribute positions in AttributeOrder::Canonical; attributes not listed go last
+const CANONICAL_ATTRIBUTE_ORDER: [&str; 11] = [
+    "name", "type", "parent", "alias", "value", "flags", "mode", "index", "input", "comment",
+    "tags",
+];
+
+/// ` key="value"` for each present attribute (values already escaped), in canonical order
+/// or, for AttributeOrder::Legacy, in the order given
+fn render_attributes(attributes: &[(&str, Option<String>)], order: AttributeOrder) -> String {
+    let mut present: Vec<(&str, &str)> = attributes
+        .iter()
+        .filter_map(|(key, value)| value.as_deref().map(|value| (*key, value)))
+        .collect();
+    if order == AttributeOrder::Canonical {
+        present.sort_by_key(|(key, _)| {
+            CANONICAL_ATTRIBUTE_ORDER
+                .iter()
+                .position(|canonical| canonical == key)
+                .unwrap_or(CANONICAL_ATTRIBUTE_ORDER.len())
+        });
+    }
+    present
+        .into_iter()
+        .map(|(key, value)| format!(r#" {}="{}""#, key, value))
+        .collect()
+}
+
+/// Turn a `<bean ...>` directly followed by its `</bean>` into `<bean .../>`
+fn self_close_empty_beans(lines: &mut Vec<String>) {
+    let mut i = 0;
+    while i + 1 < lines.len() {
+        let open = lines[i].trim_start();
+        if open.starts_with("<bean ")
+            && !open.ends_with("/>")
+            && lines[i + 1].trim() == "</bean>"
+            && lines[i + 1].len() - lines[i + 1].trim_start().len() == lines[i].len() - open.len()
+        {
+            let collapsed = format!("{}/>", lines[i].strip_suffix('>').unwrap_or(&lines[i]));
+            lines[i] = collapsed;
+            lines.remove(i + 1);
+        }
+        i += 1;
+    }
+}
+
+/// Re-indent generated XML (written with 4 spaces per level) to `indent` spaces per level.
+/// Only element and comment lines are touched; continuation lines of multi-line attribute
+/// values keep their text as is
+pub fn reindent_xml(content: &str, indent: usize) -> String {
+    if indent == 4 {
+        return content.to_string();
     }
+    content
+        .split('\n')
+        .map(|line| {
+            let body = line.trim_start_matches(' ');
+            let spaces = line.len() - body.len();
+            if body.starts_with('<') {
+                format!("{}{}", " ".repeat(spaces / 4 * indent + spaces % 4), body)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 /// Generate XML for enums only
@@ -1076,7 +1580,7 @@ pub fn generate_enum_xml(enums: &[EnumInfo], module_name: &str) -> String {
     ];
 
     for enum_info in enums {
-        generate_enum(&mut lines, enum_info);
+        generate_enum(&mut lines, enum_info, AttributeOrder::default());
         lines.push(String::new());
     }
 
@@ -1084,30 +1588,17 @@ pub fn generate_enum_xml(enums: &[EnumInfo], module_name: &str) -> String {
     lines.join("\n") + "\n"
 }
 
-fn generate_enum(lines: &mut Vec<String>, enum_info: &EnumInfo) {
-    let alias_attr = enum_info
-        .alias
-        .as_ref()
-        .map(|a| format!(r#" alias="{}""#, escape_xml(a)))
-        .unwrap_or_default();
-
-    let flags_attr = if enum_info.is_flags {
-        r#" flags="true""#.to_string()
-    } else {
-        String::new()
-    };
-
-    let tags_attr = enum_info
-        .tags
-        .as_ref()
-        .map(|t| format!(r#" tags="{}""#, escape_xml(t)))
-        .unwrap_or_default();
-
-    let comment_attr = enum_info
-        .comment
-        .as_ref()
-        .map(|c| format!(r#" comment="{}""#, escape_xml(c)))
-        .unwrap_or_default();
+fn generate_enum(lines: &mut Vec<String>, enum_info: &EnumInfo, order: AttributeOrder) {
+    let attributes = render_attributes(
+        &[
+            ("name", Some(enum_info.name.clone())),
+            ("alias", enum_info.alias.as_deref().map(escape_xml)),
+            ("flags", enum_info.is_flags.then(|| "true".to_string())),
+            ("tags", enum_info.tags.as_deref().map(escape_xml)),
+            ("comment", enum_info.comment.as_deref().map(escape_xml)),
+        ],
+        order,
+    );
 
     // Add XML comment before enum if comment exists
     if let Some(comment) = &enum_info.comment {
@@ -1116,10 +1607,7 @@ fn generate_enum(lines: &mut Vec<String>, enum_info: &EnumInfo) {
         }
     }
 
-    lines.push(format!(
-        r#"    <enum name="{}"{}{}{}{}>"#,
-        enum_info.name, alias_attr, flags_attr, tags_attr, comment_attr
-    ));
+    lines.push(format!("    <enum{}>", attributes));
 
     for variant in &enum_info.variants {
         let var_alias_attr = variant
@@ -1231,44 +1719,361 @@ pub fn generate_bean_type_enums_xml(
     lines.join("\n")
 }
 
-/// Generate a single <table> element for a class configured in [tables] section
-pub fn generate_table(class: &ClassInfo, input: &str, output: &str) -> String {
-    let config = class
-        .luban_table
-        .as_ref()
-        .expect("Class must have @LubanTable");
-
-    let mut attrs = vec![
-        format!(r#"name="{}""#, class.name),
-        format!(r#"value="{}""#, class.name),
-        format!(r#"mode="{}""#, config.mode),
-        format!(r#"index="{}""#, config.index),
-        format!(r#"input="{}""#, input),
-        format!(r#"output="{}""#, output),
+/// Generate XML for class constants (output.constants_format = "xml").
+/// Each class with constants becomes a `{Class}Constants` bean whose vars default to the
+/// constant values, plus a singleton (mode="one") table reading `constants/{Class}Constants`
+pub fn generate_constants_xml(classes: &[&ClassInfo], module_name: &str) -> String {
+    let mut lines = vec![
+        r#"<?xml version="1.0" encoding="utf-8"?>"#.to_string(),
+        format!(
+            r#"<module name="{}" comment="自动生成的类常量定义">"#,
+            escape_xml(module_name)
+        ),
+        String::new(),
     ];
 
-    if let Some(group) = &config.group {
-        attrs.push(format!(r#"group="{}""#, group));
+    for class in classes.iter().filter(|c| !c.constants.is_empty()) {
+        let bean_name = format!("{}Constants", class.name);
+        let comment_attr = class
+            .comment
+            .as_ref()
+            .map(|c| format!(r#" comment="{}""#, escape_xml(c)))
+            .unwrap_or_default();
+        lines.push(format!(r#"    <bean name="{}"{}>"#, bean_name, comment_attr));
+
+        for constant in &class.constants {
+            let var_comment_attr = constant
+                .comment
+                .as_ref()
+                .map(|c| format!(r#" comment="{}""#, escape_xml(c)))
+                .unwrap_or_default();
+            lines.push(format!(
+                r#"        <var name="{}" type="{}#default={}"{}/>"#,
+                constant.name,
+                constant.const_type,
+                escape_xml(&constant.value),
+                var_comment_attr
+            ));
+        }
+
+        lines.push("    </bean>".to_string());
+        lines.push(format!(
+            r#"    <table name="{0}Table" value="{0}" mode="one" input="constants/{0}" />"#,
+            bean_name
+        ));
+        lines.push(String::new());
     }
 
-    if let Some(tags) = &config.tags {
-        attrs.push(format!(r#"tags="{}""#, tags));
+    lines.push("</module>".to_string());
+    lines.join("\n") + "\n"
+}
+
+/// Rewrite @Index("field") references on list fields to the element bean's schema name
+/// when that field is renamed with @Name / @luban-name, since Luban indexes by column name.
+pub fn resolve_renamed_index_fields(classes: &mut [ClassInfo]) {
+    let renamed: HashMap<(String, String), String> = classes
+        .iter()
+        .flat_map(|c| {
+            c.fields.iter().filter_map(move |f| {
+                f.xml_name
+                    .as_ref()
+                    .map(|x| ((c.name.clone(), f.name.clone()), x.clone()))
+            })
+        })
+        .collect();
+    if renamed.is_empty() {
+        return;
     }
 
-    format!(r#"    <table {} />"#, attrs.join(" "))
+    for class in classes.iter_mut() {
+        for field in &mut class.fields {
+            let Some(index) = &field.validators.index_field else {
+                continue;
+            };
+            let element = ["list,", "array,", "set,"]
+                .iter()
+                .find_map(|p| field.field_type.strip_prefix(p));
+            if let Some(element) = element {
+                if let Some(xml_name) = renamed.get(&(element.to_string(), index.clone())) {
+                    field.validators.index_field = Some(xml_name.clone());
+                }
+            }
+        }
+    }
 }
 
-#[cfg(test)]
-fn generate_xml(classes: &[ClassInfo]) -> String {
-    let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
-    let table_registry = TableRegistry::new();
-    let generator = XmlGenerator::new(&type_mapper, &table_registry);
-    generator.generate(classes, "")
+/// Resolves the Luban parent of a bean. Interfaces use extends; classes use:
+/// 0. @Parent("X") decorator (highest priority, "" means no parent)
+/// 1. Extends keyword
+/// 2. Single implements interface (only when no extends)
+/// 3. No parent (empty string) when no extends and no/multiple implements
+pub(crate) fn bean_parent(class: &ClassInfo) -> String {
+    if class.is_interface {
+        return class.extends.clone().unwrap_or_default();
+    }
+
+    // Priority 0: Explicit @Parent decorator
+    if let Some(explicit) = &class.explicit_parent {
+        return explicit.clone();
+    }
+
+    // Priority 1: Use extends if present
+    if let Some(extends) = &class.extends {
+        return extends.clone();
+    }
+
+    // Priority 2: Use single implements if present
+    if class.implements.len() == 1 {
+        return class.implements[0].clone();
+    }
+
+    // Priority 3: No parent
+    String::new()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Find fields that re-declare a field of an external parent bean (one defined in
+/// hand-written XML, listed in `[parent_fields]`). The external parent is the first
+/// ancestor that isn't a parsed bean; fields a parsed ancestor already declares are
+/// skipped here (they are reported on that ancestor). Luban rejects such duplicates,
+/// but its error points at the XML. Returns one warning per shadowing field
+pub fn find_shadowed_parent_fields(
+    all_classes: &[ClassInfo],
+    parent_fields: &HashMap<String, Vec<String>>,
+) -> Vec<String> {
+    let short_name = |name: &str| name.rsplit('.').next().unwrap_or(name).to_string();
+    let known: HashMap<String, (&str, &Vec<String>)> = parent_fields
+        .iter()
+        .map(|(parent, fields)| (short_name(parent), (parent.as_str(), fields)))
+        .collect();
+
+    let mut warnings = Vec::new();
+    for class in all_classes {
+        // Walk parsed ancestors up to the first parent that isn't parsed
+        let mut inherited: std::collections::HashSet<&str> = std::collections::HashSet::new();
+        let mut visited = vec![class.name.as_str()];
+        let mut parent_name = bean_parent(class);
+        while let Some(parent) = all_classes.iter().find(|c| c.name == parent_name) {
+            if visited.contains(&parent.name.as_str()) {
+                parent_name.clear();
+                break;
+            }
+            visited.push(parent.name.as_str());
+            inherited.extend(parent.fields.iter().map(|f| f.schema_name()));
+            parent_name = bean_parent(parent);
+        }
+        if parent_name.is_empty() {
+            continue;
+        }
+        let Some((external, external_fields)) = known.get(&short_name(&parent_name)) else {
+            continue;
+        };
+
+        for field in &class.fields {
+            let name = field.schema_name();
+            if name == "$type" || inherited.contains(name) {
+                continue;
+            }
+            if external_fields.iter().any(|f| f == name) {
+                warnings.push(format!(
+                    "{}: {}.{} - re-declares field '{}' of external parent '{}' ([parent_fields]); Luban rejects duplicate fields in a bean hierarchy",
+                    class.field_location(field),
+                    class.name,
+                    field.name,
+                    name,
+                    external
+                ));
+            }
+        }
+    }
+    warnings
+}
+
+/// Find fields that re-declare an inherited field of a parsed ancestor with a different
+/// type. The child's declaration is dropped from its bean (Luban takes the field from the
+/// parent), so the narrower or wider type silently disappears. Returns one warning per field
+pub fn find_retyped_inherited_fields(all_classes: &[ClassInfo]) -> Vec<String> {
+    let effective_type = |field: &FieldInfo| {
+        field
+            .type_override
+            .clone()
+            .unwrap_or_else(|| field.field_type.clone())
+    };
+
+    let mut warnings = Vec::new();
+    for class in all_classes {
+        // Nearest declaration of each inherited field
+        let mut inherited: HashMap<&str, (&ClassInfo, &FieldInfo)> = HashMap::new();
+        let mut visited = vec![class.name.as_str()];
+        let mut parent_name = bean_parent(class);
+        while let Some(parent) = all_classes.iter().find(|c| c.name == parent_name) {
+            if visited.contains(&parent.name.as_str()) {
+                break;
+            }
+            visited.push(parent.name.as_str());
+            for field in &parent.fields {
+                inherited
+                    .entry(field.name.as_str())
+                    .or_insert((parent, field));
+            }
+            parent_name = bean_parent(parent);
+        }
+
+        for field in class.fields.iter().filter(|f| f.virtual_block().is_none()) {
+            let Some((ancestor, parent_field)) = inherited.get(field.name.as_str()) else {
+                continue;
+            };
+            let (own_type, parent_type) = (effective_type(field), effective_type(parent_field));
+            if own_type != parent_type {
+                warnings.push(format!(
+                    "{}: {}.{} - re-declares '{}' of {} as '{}', but the inherited field is '{}' ({}); the parent's field is emitted",
+                    class.field_location(field),
+                    class.name,
+                    field.name,
+                    field.name,
+                    ancestor.name,
+                    own_type,
+                    parent_type,
+                    ancestor.field_location(parent_field)
+                ));
+            }
+        }
+    }
+    warnings
+}
+
+/// Find classes whose parent is ambiguous: no extends, no @Parent, and more than one
+/// implemented interface that is itself a parsed bean. Such classes get no parent
+/// (independent of the order of the implements clause), which is rarely intended.
+/// Returns (class name, sorted matching interfaces), sorted by class name.
+pub fn find_ambiguous_parents(all_classes: &[ClassInfo]) -> Vec<(String, Vec<String>)> {
+    let bean_names: std::collections::HashSet<&str> =
+        all_classes.iter().map(|c| c.name.as_str()).collect();
+
+    let mut result: Vec<(String, Vec<String>)> = all_classes
+        .iter()
+        .filter(|c| !c.is_interface && c.extends.is_none() && c.explicit_parent.is_none())
+        .filter_map(|c| {
+            let mut matches: Vec<String> = c
+                .implements
+                .iter()
+                .filter(|i| bean_names.contains(i.as_str()))
+                .cloned()
+                .collect();
+            matches.sort();
+            matches.dedup();
+            (matches.len() > 1).then(|| (c.name.clone(), matches))
+        })
+        .collect();
+    result.sort();
+    result.dedup();
+    result
+}
+
+/// Settle the parent of classes that extend a parsed bean and also implement a parsed bean
+/// interface the base doesn't derive from, e.g. `DashAction extends BaseAction implements
+/// EntityTrigger` with BaseAction outside EntityTrigger's hierarchy. The base stays the
+/// parent unless `precedence` is `Implements` and exactly one interface conflicts, which
+/// is then recorded like @Parent. Returns one warning per conflicting class
+pub fn resolve_parent_conflicts(
+    all_classes: &mut [ClassInfo],
+    precedence: ParentPrecedence,
+) -> Vec<String> {
+    let is_bean = |name: &str| all_classes.iter().any(|c| c.name == name);
+    let mut resolved: Vec<(usize, String)> = Vec::new();
+    let mut warnings = Vec::new();
+    for (index, class) in all_classes.iter().enumerate() {
+        if class.is_interface || class.explicit_parent.is_some() {
+            continue;
+        }
+        let Some(base) = class.extends.as_deref().filter(|base| is_bean(base)) else {
+            continue;
+        };
+
+        // The base and its parsed ancestors, stopping on inheritance cycles
+        let mut hierarchy = vec![class.name.as_str()];
+        let mut parent_name = base.to_string();
+        while let Some(parent) = all_classes.iter().find(|c| c.name == parent_name) {
+            if hierarchy.contains(&parent.name.as_str()) {
+                break;
+            }
+            hierarchy.push(parent.name.as_str());
+            parent_name = bean_parent(parent);
+        }
+
+        let mut conflicting: Vec<&str> = class
+            .implements
+            .iter()
+            .map(|i| i.as_str())
+            .filter(|i| !hierarchy.contains(i) && is_bean(i))
+            .collect();
+        conflicting.sort();
+        conflicting.dedup();
+        if conflicting.is_empty() {
+            continue;
+        }
+        let parent = match (precedence, conflicting.as_slice()) {
+            (ParentPrecedence::Implements, [interface]) => {
+                resolved.push((index, interface.to_string()));
+                *interface
+            }
+            _ => base,
+        };
+        warnings.push(format!(
+            "{}: {} extends {} but implements {}, which {} doesn't derive from; parent=\"{}\" is emitted (defaults.parent_precedence), use @Parent to choose another",
+            class.source_file,
+            class.name,
+            base,
+            conflicting.join(", "),
+            base,
+            parent
+        ));
+    }
+
+    for (index, parent) in resolved {
+        all_classes[index].explicit_parent = Some(parent);
+    }
+    warnings
+}
+
+/// Generate a single <table> element for a class configured in [tables] section
+pub fn generate_table(class: &ClassInfo, input: &str, output: &str) -> String {
+    let config = class
+        .luban_table
+        .as_ref()
+        .expect("Class must have @LubanTable");
+
+    let mut attrs = vec![
+        format!(r#"name="{}""#, class.name),
+        format!(r#"value="{}""#, class.name),
+        format!(r#"mode="{}""#, config.mode),
+        format!(r#"index="{}""#, config.index),
+        format!(r#"input="{}""#, input),
+        format!(r#"output="{}""#, output),
+    ];
+
+    if let Some(group) = &config.group {
+        attrs.push(format!(r#"group="{}""#, group));
+    }
+
+    if let Some(tags) = &config.tags {
+        attrs.push(format!(r#"tags="{}""#, tags));
+    }
+
+    format!(r#"    <table {} />"#, attrs.join(" "))
+}
+
+#[cfg(test)]
+fn generate_xml(classes: &[ClassInfo]) -> String {
+    let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
+    let table_registry = TableRegistry::new();
+    let generator = XmlGenerator::new(&type_mapper, &table_registry);
+    generator.generate(classes, "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::FieldOrigin;
 
     fn make_field(name: &str, field_type: &str, optional: bool) -> FieldInfo {
         FieldInfo {
@@ -1277,6 +2082,8 @@ mod tests {
             comment: None,
             alias: None,
             is_optional: optional,
+            is_nullable: false,
+            is_readonly: false,
             validators: FieldValidators::default(),
             is_object_factory: false,
             factory_inner_type: None,
@@ -1290,6 +2097,16 @@ mod tests {
             custom_tags: None,
             ref_key_inner_type: None,
             ref_replace: None,
+            forbidden_type: None,
+            order: 0,
+            unsupported_type: None,
+            type_error: None,
+            origin: None,
+            flavors: Default::default(),
+            converter: None,
+            xml_name: None,
+            tags: Vec::new(),
+            examples: Vec::new(),
         }
     }
 
@@ -1305,6 +2122,8 @@ mod tests {
                 comment: Some("Name field".to_string()),
                 alias: None,
                 is_optional: false,
+                is_nullable: false,
+                is_readonly: false,
                 validators: FieldValidators::default(),
                 is_object_factory: false,
                 factory_inner_type: None,
@@ -1318,10 +2137,21 @@ mod tests {
                 custom_tags: None,
             ref_key_inner_type: None,
             ref_replace: None,
+            forbidden_type: None,
+            order: 0,
+            unsupported_type: None,
+            type_error: None,
+            origin: None,
+            flavors: Default::default(),
+            converter: None,
+            xml_name: None,
+            tags: Vec::new(),
+                examples: Vec::new(),
 }],
             implements: vec![],
             extends: Some("BaseClass".to_string()),
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1331,6 +2161,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -1340,14 +2182,18 @@ mod tests {
 
     #[test]
     fn test_optional_field() {
+        // `label: string | null` maps to `?` like `value?: string`
+        let mut nullable = make_field("label", "string", false);
+        nullable.is_nullable = true;
         let class = ClassInfo {
             name: "MyClass".to_string(),
             comment: None,
             alias: None,
-            fields: vec![make_field("value", "string", true)],
+            fields: vec![make_field("value", "string", true), nullable],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1357,10 +2203,23 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
-        assert!(xml.contains(r#"type="string?""#));
+        assert!(xml.contains(r#"<var name="value" type="string?"/>"#));
+        assert!(xml.contains(r#"<var name="label" type="string?"/>"#));
     }
 
     #[test]
@@ -1373,6 +2232,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1382,6 +2242,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -1400,6 +2272,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1409,6 +2282,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -1426,6 +2311,7 @@ mod tests {
             implements: vec![],
             extends: Some("ParentClass".to_string()),
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1435,6 +2321,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -1451,6 +2349,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -1460,6 +2359,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[interface]);
@@ -1479,6 +2390,7 @@ mod tests {
             implements: vec!["EntityTrigger".to_string()],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1488,6 +2400,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -1504,6 +2428,7 @@ mod tests {
             implements: vec!["Interface1".to_string(), "Interface2".to_string()],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1513,6 +2438,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -1531,6 +2468,7 @@ mod tests {
             implements: vec!["SomeInterface".to_string()],
             extends: Some("BaseClass".to_string()),
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1540,6 +2478,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -1557,6 +2507,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -1566,6 +2517,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let child_interface = ClassInfo {
@@ -1576,6 +2539,7 @@ mod tests {
             implements: vec![],
             extends: Some("EntityTrigger".to_string()),
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -1585,6 +2549,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let class = ClassInfo {
@@ -1595,6 +2571,7 @@ mod tests {
             implements: vec!["BaseTrigger".to_string()],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1604,6 +2581,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[base_interface, child_interface, class]);
@@ -1621,6 +2610,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1630,6 +2620,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -1670,6 +2672,7 @@ mod tests {
                 },
             ],
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc".to_string(),
             output_path: None,
             module_name: None,
@@ -1708,6 +2711,7 @@ mod tests {
                 },
             ],
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc".to_string(),
             output_path: None,
             module_name: None,
@@ -1748,6 +2752,7 @@ mod tests {
                 },
             ],
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc".to_string(),
             output_path: None,
             module_name: None,
@@ -1776,6 +2781,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: true,
                     factory_inner_type: Some("BaseTrigger".to_string()),
@@ -1789,6 +2796,16 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
                 FieldInfo {
                     name: "normalField".to_string(),
@@ -1796,6 +2813,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -1809,11 +2828,22 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
             ],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1823,6 +2853,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -1850,6 +2892,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -1863,6 +2907,16 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
                 FieldInfo {
                     name: "width".to_string(),
@@ -1870,6 +2924,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -1883,11 +2939,22 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
             ],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1897,6 +2964,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -1917,6 +2996,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1926,6 +3006,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let class_a = ClassInfo {
@@ -1936,6 +3028,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1945,6 +3038,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let class_m = ClassInfo {
@@ -1955,6 +3060,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -1964,6 +3070,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         // Pass classes in Z, A, M order - should preserve this order
@@ -1992,6 +3110,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -2005,6 +3125,16 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
                 FieldInfo {
                     name: "component".to_string(),
@@ -2012,6 +3142,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: true,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -2025,11 +3157,22 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
             ],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -2039,6 +3182,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -2060,6 +3215,8 @@ mod tests {
                     comment: Some("Item ID".to_string()),
                     alias: Some("物品ID".to_string()),
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -2073,6 +3230,16 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
                 FieldInfo {
                     name: "name".to_string(),
@@ -2080,6 +3247,8 @@ mod tests {
                     comment: None,
                     alias: Some("名称".to_string()),
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -2093,6 +3262,16 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
                 FieldInfo {
                     name: "value".to_string(),
@@ -2100,6 +3279,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -2113,11 +3294,22 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
             ],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -2127,6 +3319,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -2158,6 +3362,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "resource/resource-config.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -2167,6 +3372,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         // WeaponConfig is in module "weapon", extends ResourceConfig
@@ -2178,6 +3395,7 @@ mod tests {
             implements: vec![],
             extends: Some("ResourceConfig".to_string()),
             source_file: "weapon/weapon-config.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "def456".to_string(),
             is_interface: true,
             output_path: None,
@@ -2187,6 +3405,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         // Generate XML for weapon module (which references resource module)
@@ -2221,6 +3451,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "resource/resource-config.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -2230,6 +3461,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         // QualityType enum (simulated as a class for the mapping)
@@ -2241,6 +3484,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "enums/quality-type.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "ghi789".to_string(),
             is_interface: false,
             output_path: None,
@@ -2250,6 +3494,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
@@ -2278,6 +3534,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "weapon/weapon-level-config.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -2287,6 +3544,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let weapon_config = ClassInfo {
@@ -2297,6 +3566,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "weapon/weapon-config.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "def456".to_string(),
             is_interface: true,
             output_path: None,
@@ -2306,6 +3576,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
@@ -2336,6 +3618,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -2349,11 +3633,22 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
             ],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -2363,6 +3658,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -2387,6 +3694,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -2400,11 +3709,22 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
             ],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -2414,6 +3734,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -2438,6 +3770,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -2451,11 +3785,22 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
             ],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -2465,6 +3810,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -2489,6 +3846,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -2502,11 +3861,22 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
             ],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -2516,6 +3886,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -2540,6 +3922,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -2553,11 +3937,22 @@ mod tests {
                     map_separator: Some(",|".to_string()),
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
             ],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -2567,6 +3962,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -2591,6 +3998,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators {
                         size: Some(SizeConstraint::Exact(3)),
                         ..Default::default()
@@ -2607,11 +4016,22 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
             ],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -2621,6 +4041,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -2658,6 +4090,7 @@ mod tests {
                 },
             ],
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc".to_string(),
             output_path: None,
             module_name: None,
@@ -2666,7 +4099,7 @@ mod tests {
         let xml = generate_enum_xml(&[enum_info], "test");
         // Enum should have tags attribute
         assert!(
-            xml.contains(r#"<enum name="PieceAttributeType" tags="string" comment="属性类型">"#),
+            xml.contains(r#"<enum name="PieceAttributeType" comment="属性类型" tags="string">"#),
             "@tags should add tags attribute to enum. Got:\n{}",
             xml
         );
@@ -2685,6 +4118,8 @@ mod tests {
                     comment: None,
                     alias: None,
                     is_optional: false,
+                    is_nullable: false,
+                    is_readonly: false,
                     validators: FieldValidators::default(),
                     is_object_factory: false,
                     factory_inner_type: None,
@@ -2698,11 +4133,22 @@ mod tests {
                     map_separator: None,
                     ref_key_inner_type: None,
                     ref_replace: None,
+                    forbidden_type: None,
+                    order: 0,
+                    unsupported_type: None,
+                    type_error: None,
+                    origin: None,
+                    flavors: Default::default(),
+                    converter: None,
+                    xml_name: None,
+                    tags: Vec::new(),
+                    examples: Vec::new(),
                 },
             ],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -2712,6 +4158,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -2740,6 +4198,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -2749,6 +4208,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         // Build table registry from config
@@ -2783,6 +4254,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -2792,6 +4264,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         // Build table registry from config with mode="one"
@@ -2803,6 +4287,10 @@ mod tests {
                 name: None,
                 mode: Some("one".to_string()),
                 index: None,
+                secondary_indexes: vec![],
+                transform: None,
+                required: None,
+                groups: vec![],
             },
         );
         let table_registry = TableRegistry::from_config(&tables_config);
@@ -2831,6 +4319,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -2840,6 +4329,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         // Build table registry with Chinese path
@@ -2851,6 +4352,10 @@ mod tests {
                 name: None,
                 mode: None,
                 index: Some("Id".to_string()),
+                secondary_indexes: vec![],
+                transform: None,
+                required: None,
+                groups: vec![],
             },
         );
         let table_registry = TableRegistry::from_config(&tables_config);
@@ -2877,6 +4382,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -2886,6 +4392,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let xml = generate_xml(&[class]);
@@ -2909,6 +4427,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -2918,6 +4437,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         // Build table registry with custom table name
@@ -2929,6 +4460,10 @@ mod tests {
                 name: Some("TbBattle".to_string()),
                 mode: None,
                 index: Some("battleId".to_string()),
+                secondary_indexes: vec![],
+                transform: None,
+                required: None,
+                groups: vec![],
             },
         );
         let table_registry = TableRegistry::from_config(&tables_config);
@@ -2959,6 +4494,8 @@ mod tests {
                 comment: None,
                 alias: None,
                 is_optional: false,
+                is_nullable: false,
+                is_readonly: false,
                 validators: FieldValidators {
                     has_ref: true,
                     ..Default::default()
@@ -2975,10 +4512,21 @@ mod tests {
                 custom_tags: None,
             ref_key_inner_type: None,
             ref_replace: None,
+            forbidden_type: None,
+            order: 0,
+            unsupported_type: None,
+            type_error: None,
+            origin: None,
+            flavors: Default::default(),
+            converter: None,
+            xml_name: None,
+            tags: Vec::new(),
+                examples: Vec::new(),
 }],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -2988,6 +4536,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         // Build table registry with Item table
@@ -2999,6 +4559,10 @@ mod tests {
                 name: Some("TbItem".to_string()),
                 mode: None,
                 index: Some("id".to_string()),
+                secondary_indexes: vec![],
+                transform: None,
+                required: None,
+                groups: vec![],
             },
         );
         let mut table_registry = TableRegistry::from_config(&tables_config);
@@ -3012,6 +4576,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -3021,6 +4586,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
@@ -3051,6 +4628,8 @@ mod tests {
                 comment: None,
                 alias: None,
                 is_optional: false,
+                is_nullable: false,
+                is_readonly: false,
                 validators: FieldValidators {
                     has_ref: true,      // @ref for value
                     has_ref_key: true,  // RefKey<T> for key
@@ -3068,10 +4647,21 @@ mod tests {
                 custom_tags: None,
                 ref_key_inner_type: Some("Item".to_string()),
                 ref_replace: None,
+                forbidden_type: None,
+                order: 0,
+                unsupported_type: None,
+                type_error: None,
+                origin: None,
+                flavors: Default::default(),
+                converter: None,
+                xml_name: None,
+                tags: Vec::new(),
+                examples: Vec::new(),
             }],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -3081,6 +4671,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         // Build table registry with Item and Skill tables
@@ -3092,6 +4694,10 @@ mod tests {
                 name: Some("TbItem".to_string()),
                 mode: None,
                 index: Some("id".to_string()),
+                secondary_indexes: vec![],
+                transform: None,
+                required: None,
+                groups: vec![],
             },
         );
         tables_config.insert(
@@ -3101,6 +4707,10 @@ mod tests {
                 name: Some("TbSkill".to_string()),
                 mode: None,
                 index: Some("skillId".to_string()),
+                secondary_indexes: vec![],
+                transform: None,
+                required: None,
+                groups: vec![],
             },
         );
         let mut table_registry = TableRegistry::from_config(&tables_config);
@@ -3114,6 +4724,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -3123,6 +4734,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
         let skill_class = ClassInfo {
             name: "Skill".to_string(),
@@ -3132,6 +4755,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -3141,6 +4765,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
@@ -3171,6 +4807,8 @@ mod tests {
                 comment: None,
                 alias: None,
                 is_optional: false,
+                is_nullable: false,
+                is_readonly: false,
                 validators: FieldValidators {
                     has_ref: true,
                     ..Default::default()
@@ -3187,10 +4825,21 @@ mod tests {
                 custom_tags: None,
             ref_key_inner_type: None,
             ref_replace: None,
+            forbidden_type: None,
+            order: 0,
+            unsupported_type: None,
+            type_error: None,
+            origin: None,
+            flavors: Default::default(),
+            converter: None,
+            xml_name: None,
+            tags: Vec::new(),
+                examples: Vec::new(),
 }],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -3200,6 +4849,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         // Build table registry with Item table
@@ -3211,6 +4872,10 @@ mod tests {
                 name: Some("TbItem".to_string()),
                 mode: None,
                 index: Some("id".to_string()),
+                secondary_indexes: vec![],
+                transform: None,
+                required: None,
+                groups: vec![],
             },
         );
         let mut table_registry = TableRegistry::from_config(&tables_config);
@@ -3224,6 +4889,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -3233,6 +4899,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
@@ -3263,6 +4941,8 @@ mod tests {
                 comment: None,
                 alias: None,
                 is_optional: false,
+                is_nullable: false,
+                is_readonly: false,
                 validators: FieldValidators {
                     has_ref_key: true, // RefKey<Item>
                     ..Default::default()
@@ -3279,10 +4959,21 @@ mod tests {
                 custom_tags: None,
                 ref_key_inner_type: Some("Item".to_string()),
                 ref_replace: None,
+                forbidden_type: None,
+                order: 0,
+                unsupported_type: None,
+                type_error: None,
+                origin: None,
+                flavors: Default::default(),
+                converter: None,
+                xml_name: None,
+                tags: Vec::new(),
+                examples: Vec::new(),
             }],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -3292,6 +4983,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         // Build table registry with Item table
@@ -3303,6 +5006,10 @@ mod tests {
                 name: Some("TbItem".to_string()),
                 mode: None,
                 index: Some("id".to_string()),
+                secondary_indexes: vec![],
+                transform: None,
+                required: None,
+                groups: vec![],
             },
         );
         let mut table_registry = TableRegistry::from_config(&tables_config);
@@ -3316,6 +5023,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -3325,6 +5033,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
@@ -3362,6 +5082,8 @@ mod tests {
                 comment: None,
                 alias: None,
                 is_optional: false,
+                is_nullable: false,
+                is_readonly: false,
                 validators: FieldValidators {
                     has_ref_key: true, // RefKey<Item>[]
                     ..Default::default()
@@ -3378,10 +5100,21 @@ mod tests {
                 custom_tags: None,
                 ref_key_inner_type: Some("Item".to_string()),
                 ref_replace: None,
+                forbidden_type: None,
+                order: 0,
+                unsupported_type: None,
+                type_error: None,
+                origin: None,
+                flavors: Default::default(),
+                converter: None,
+                xml_name: None,
+                tags: Vec::new(),
+                examples: Vec::new(),
             }],
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: false,
             output_path: None,
@@ -3391,6 +5124,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         // Build table registry with Item table
@@ -3402,6 +5147,10 @@ mod tests {
                 name: Some("TbItem".to_string()),
                 mode: None,
                 index: Some("id".to_string()),
+                secondary_indexes: vec![],
+                transform: None,
+                required: None,
+                groups: vec![],
             },
         );
         let mut table_registry = TableRegistry::from_config(&tables_config);
@@ -3415,6 +5164,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "abc123".to_string(),
             is_interface: true,
             output_path: None,
@@ -3424,6 +5174,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
@@ -3445,4 +5207,1049 @@ mod tests {
             xml
         );
     }
+
+    #[test]
+    fn test_explicit_ref_on_container_positions() {
+        use crate::config::TableConfig;
+
+        let ref_to = |target: &str| FieldValidators {
+            ref_target: Some(target.to_string()),
+            ..Default::default()
+        };
+        let field = |name: &str, field_type: &str, validators: FieldValidators| FieldInfo {
+            validators,
+            ..make_field(name, field_type, false)
+        };
+        let mut item = make_class(
+            "ItemConfig",
+            None,
+            &[],
+            false,
+            vec![make_field("id", "int", false)],
+        );
+        item.module_name = Some("shop".to_string());
+        let mut shop = make_class(
+            "ShopConfig",
+            None,
+            &[],
+            false,
+            vec![
+                field("itemId", "double", ref_to("ItemConfig")),
+                field("itemIds", "list,double", ref_to("ItemConfig")),
+                field("prices", "map,int,double", ref_to("ItemConfig")),
+                field(
+                    "stock",
+                    "map,double,int",
+                    FieldValidators {
+                        ref_key_target: Some("ItemConfig".to_string()),
+                        ..Default::default()
+                    },
+                ),
+            ],
+        );
+        shop.module_name = Some("shop".to_string());
+
+        let mut tables_config = std::collections::HashMap::new();
+        tables_config.insert(
+            "shop.ItemConfig".to_string(),
+            TableConfig::Simple("../datas/item".to_string()),
+        );
+        let mut table_registry = TableRegistry::from_config(&tables_config);
+        let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
+        let classes = vec![item, shop];
+        assert!(table_registry
+            .set_index_types(&classes, &type_mapper)
+            .is_empty());
+
+        let generator = XmlGenerator::new(&type_mapper, &table_registry);
+        let xml = generator.generate(&classes, "shop");
+        let shop_bean = bean_block(&xml, "ShopConfig");
+        for expected in [
+            r#"<var name="itemId" type="int#ref=shop.ItemConfigTable"/>"#,
+            r#"<var name="itemIds" type="list,(int#ref=shop.ItemConfigTable)"/>"#,
+            r#"<var name="prices" type="map,int,(int#ref=shop.ItemConfigTable)"/>"#,
+            r#"<var name="stock" type="map,(int#ref=shop.ItemConfigTable),int"/>"#,
+        ] {
+            assert!(
+                shop_bean.contains(expected),
+                "{} not in:\n{}",
+                expected,
+                shop_bean
+            );
+        }
+    }
+
+    #[test]
+    fn test_list_table_secondary_indexes() {
+        use crate::config::TableConfig;
+
+        let class = make_class(
+            "SkillBinding",
+            None,
+            &[],
+            false,
+            vec![
+                make_field("skillId", "int", false),
+                make_field("ownerId", "int", false),
+            ],
+        );
+        let mut tables_config = std::collections::HashMap::new();
+        tables_config.insert(
+            "SkillBinding".to_string(),
+            TableConfig::Full {
+                input: "../datas/skill-binding".to_string(),
+                name: None,
+                mode: Some("list".to_string()),
+                index: None,
+                secondary_indexes: vec!["skillId".to_string(), "ownerId".to_string()],
+                transform: None,
+                required: None,
+                groups: vec![],
+            },
+        );
+        let mut table_registry = TableRegistry::from_config(&tables_config);
+        let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
+        assert!(table_registry
+            .set_index_types(std::slice::from_ref(&class), &type_mapper)
+            .is_empty());
+
+        let xml = XmlGenerator::new(&type_mapper, &table_registry)
+            .generate(std::slice::from_ref(&class), "");
+        assert!(xml.contains(
+            r#"<table name="SkillBindingTable" value="SkillBinding" mode="list" input="../datas/skill-binding" />"#
+        ));
+
+        let xml = XmlGenerator::new(&type_mapper, &table_registry)
+            .with_list_table_indexes(true)
+            .generate(&[class], "");
+        assert!(xml.contains(
+            r#"<table name="SkillBindingTable" value="SkillBinding" mode="list" index="skillId,ownerId" input="../datas/skill-binding" />"#
+        ));
+    }
+
+    #[test]
+    fn test_nullable_element_types() {
+        let mut config = make_class(
+            "DropConfig",
+            None,
+            &[],
+            false,
+            vec![
+                make_field("rewards", "map,string,ItemDrop?", false),
+                make_field("weights", "list,double?", false),
+            ],
+        );
+        config.module_name = Some("drop".to_string());
+        let mut item_drop = make_class("ItemDrop", None, &[], false, vec![]);
+        item_drop.module_name = Some("item".to_string());
+
+        let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
+        let table_registry = TableRegistry::new();
+        let generator = XmlGenerator::new(&type_mapper, &table_registry);
+        let all_classes = vec![config.clone(), item_drop];
+        let xml = generator.generate_with_all_classes(&[config], "drop", &all_classes);
+
+        // The marker survives module qualification of the element type
+        assert!(
+            xml.contains(r#"<var name="rewards" type="map,string,item.ItemDrop?"/>"#),
+            "{}",
+            xml
+        );
+        assert!(
+            xml.contains(r#"<var name="weights" type="list,double?"/>"#),
+            "{}",
+            xml
+        );
+    }
+
+    #[test]
+    fn test_type_mapping_to_container_with_size() {
+        let mut custom = std::collections::HashMap::new();
+        custom.insert("IntPair".to_string(), "list,Coord".to_string());
+        custom.insert("Coord".to_string(), "int".to_string());
+        let type_mapper = TypeMapper::new(&custom);
+        let table_registry = TableRegistry::new();
+        let generator = XmlGenerator::new(&type_mapper, &table_registry);
+
+        let mut range = make_field("range", "IntPair", false);
+        range.validators.size = Some(SizeConstraint::Exact(2));
+        let offset = make_field("offset", "IntPair", true);
+        let class = make_class("AreaConfig", None, &[], false, vec![range, offset]);
+
+        let xml = generator.generate(&[class], "");
+        assert!(
+            xml.contains(r#"<var name="range" type="(list#size=2),int"/>"#),
+            "{}",
+            xml
+        );
+        // Optional containers are emitted as required by default
+        assert!(
+            xml.contains(r#"<var name="offset" type="list,int"/>"#),
+            "{}",
+            xml
+        );
+    }
+
+    fn make_class(
+        name: &str,
+        extends: Option<&str>,
+        implements: &[&str],
+        is_interface: bool,
+        fields: Vec<FieldInfo>,
+    ) -> ClassInfo {
+        ClassInfo {
+            name: name.to_string(),
+            comment: None,
+            alias: None,
+            fields,
+            implements: implements.iter().map(|s| s.to_string()).collect(),
+            extends: extends.map(|s| s.to_string()),
+            source_file: "test.ts".to_string(),
+            source_path: Default::default(),
+            file_hash: "abc123".to_string(),
+            is_interface,
+            output_path: None,
+            module_name: None,
+            type_params: std::collections::HashMap::new(),
+            luban_table: None,
+            table_config: None,
+            input_path: None,
+            imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
+        }
+    }
+
+    fn bean_block<'x>(xml: &'x str, name: &str) -> &'x str {
+        let start = xml
+            .find(&format!(r#"<bean name="{}""#, name))
+            .unwrap_or_else(|| panic!("bean {} not found in:\n{}", name, xml));
+        let end = xml[start..].find("</bean>").unwrap() + start;
+        &xml[start..end]
+    }
+
+    #[test]
+    fn test_two_level_extends_chain_skips_all_ancestor_fields() {
+        let base = make_class("BaseTrigger", None, &[], false, vec![make_field("id", "int", false)]);
+        let middle = make_class(
+            "TimedTrigger",
+            Some("BaseTrigger"),
+            &[],
+            false,
+            vec![make_field("id", "int", false), make_field("delay", "double", false)],
+        );
+        let leaf = make_class(
+            "FireTrigger",
+            Some("TimedTrigger"),
+            &[],
+            false,
+            vec![
+                make_field("id", "int", false),
+                make_field("delay", "double", false),
+                make_field("burn", "double", false),
+            ],
+        );
+
+        let xml = generate_xml(&[base, middle, leaf]);
+        let fire = bean_block(&xml, "FireTrigger");
+        assert!(fire.contains(r#"parent="TimedTrigger""#));
+        assert!(fire.contains(r#"<var name="burn""#));
+        assert!(!fire.contains(r#"<var name="id""#));
+        assert!(!fire.contains(r#"<var name="delay""#));
+    }
+
+    #[test]
+    fn test_find_retyped_inherited_fields() {
+        let base = make_class("BaseTrigger", None, &[], false, vec![make_field("id", "int", false)]);
+        let middle = make_class(
+            "TimedTrigger",
+            Some("BaseTrigger"),
+            &[],
+            false,
+            vec![make_field("id", "int", false), make_field("delay", "double", false)],
+        );
+        let leaf = make_class(
+            "FireTrigger",
+            Some("TimedTrigger"),
+            &[],
+            false,
+            vec![
+                make_field("id", "long", false),
+                make_field("delay", "double", false),
+                make_field("burn", "double", false),
+            ],
+        );
+        let classes = [base, middle, leaf];
+
+        // Same-typed redeclarations are fine; the leaf's id differs from the nearest one
+        let warnings = find_retyped_inherited_fields(&classes);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains(
+            "FireTrigger.id - re-declares 'id' of TimedTrigger as 'long', but the inherited field is 'int'"
+        ));
+
+        let xml = generate_xml(&classes);
+        assert!(bean_block(&xml, "TimedTrigger").contains(r#"<var name="delay""#));
+        assert!(!bean_block(&xml, "FireTrigger").contains(r#"<var name="id""#));
+    }
+
+    #[test]
+    fn test_extends_chain_with_interface_on_grandparent() {
+        let entity = make_class("EntityTrigger", None, &[], true, vec![make_field("id", "int", false)]);
+        let base = make_class(
+            "BaseTrigger",
+            None,
+            &["EntityTrigger"],
+            false,
+            vec![make_field("id", "int", false), make_field("name", "string", false)],
+        );
+        let fire = make_class(
+            "FireTrigger",
+            Some("BaseTrigger"),
+            &[],
+            false,
+            vec![
+                make_field("id", "int", false),
+                make_field("name", "string", false),
+                make_field("burn", "double", false),
+            ],
+        );
+
+        let xml = generate_xml(&[entity, base, fire]);
+        let base_xml = bean_block(&xml, "BaseTrigger");
+        assert!(base_xml.contains(r#"parent="EntityTrigger""#));
+        assert!(!base_xml.contains(r#"<var name="id""#));
+
+        // Fields from the interface implemented by the grandparent are also skipped
+        let fire_xml = bean_block(&xml, "FireTrigger");
+        assert!(fire_xml.contains(r#"parent="BaseTrigger""#));
+        assert!(!fire_xml.contains(r#"<var name="id""#));
+        assert!(!fire_xml.contains(r#"<var name="name""#));
+        assert!(fire_xml.contains(r#"<var name="burn""#));
+    }
+
+    #[test]
+    fn test_unresolvable_base_keeps_fields() {
+        let class = make_class(
+            "FireTrigger",
+            Some("ExternalBase"),
+            &[],
+            false,
+            vec![make_field("id", "int", false)],
+        );
+
+        let xml = generate_xml(&[class]);
+        let fire = bean_block(&xml, "FireTrigger");
+        assert!(fire.contains(r#"parent="ExternalBase""#));
+        assert!(fire.contains(r#"<var name="id""#));
+    }
+
+    #[test]
+    fn test_extends_cycle_does_not_loop() {
+        let a = make_class("A", Some("B"), &[], false, vec![make_field("a", "int", false)]);
+        let b = make_class("B", Some("A"), &[], false, vec![make_field("b", "int", false)]);
+
+        let xml = generate_xml(&[a, b]);
+        assert!(bean_block(&xml, "A").contains(r#"parent="B""#));
+        assert!(bean_block(&xml, "B").contains(r#"parent="A""#));
+    }
+
+    #[test]
+    fn test_parent_decorator_wins_over_extends() {
+        let mut class = make_class(
+            "DashAction",
+            Some("BaseAction"),
+            &["EntityTrigger"],
+            false,
+            vec![make_field("distance", "double", false)],
+        );
+        class.explicit_parent = Some("CombatActionBase".to_string());
+
+        let xml = generate_xml(&[class]);
+        assert!(xml.contains(r#"<bean name="DashAction" parent="CombatActionBase">"#));
+    }
+
+    #[test]
+    fn test_parent_decorator_empty_opts_out() {
+        let mut class = make_class(
+            "Standalone",
+            None,
+            &["EntityTrigger"],
+            false,
+            vec![make_field("id", "int", false)],
+        );
+        class.explicit_parent = Some(String::new());
+
+        let xml = generate_xml(&[class]);
+        assert!(xml.contains(r#"<bean name="Standalone">"#));
+    }
+
+    #[test]
+    fn test_implements_used_without_parent_decorator() {
+        let class = make_class(
+            "Standalone",
+            None,
+            &["EntityTrigger"],
+            false,
+            vec![make_field("id", "int", false)],
+        );
+
+        let xml = generate_xml(&[class]);
+        assert!(xml.contains(r#"<bean name="Standalone" parent="EntityTrigger">"#));
+    }
+
+    #[test]
+    fn test_multi_interface_parent_is_order_independent() {
+        let entity = make_class("EntityTrigger", None, &[], true, vec![]);
+        let component = make_class("Component", None, &[], true, vec![]);
+
+        for implements in [["EntityTrigger", "Component"], ["Component", "EntityTrigger"]] {
+            let hybrid = make_class(
+                "HybridTrigger",
+                None,
+                &implements,
+                false,
+                vec![make_field("id", "int", false)],
+            );
+            let classes = vec![entity.clone(), component.clone(), hybrid];
+
+            let xml = generate_xml(&classes);
+            assert!(xml.contains(r#"<bean name="HybridTrigger">"#));
+
+            let ambiguous = find_ambiguous_parents(&classes);
+            assert_eq!(
+                ambiguous,
+                vec![(
+                    "HybridTrigger".to_string(),
+                    vec!["Component".to_string(), "EntityTrigger".to_string()]
+                )]
+            );
+        }
+    }
+
+    #[test]
+    fn test_multi_interface_with_parent_decorator_is_not_ambiguous() {
+        let entity = make_class("EntityTrigger", None, &[], true, vec![]);
+        let component = make_class("Component", None, &[], true, vec![]);
+        let mut hybrid = make_class(
+            "HybridTrigger",
+            None,
+            &["EntityTrigger", "Component"],
+            false,
+            vec![],
+        );
+        hybrid.explicit_parent = Some("EntityTrigger".to_string());
+
+        let classes = vec![entity, component, hybrid];
+        assert!(find_ambiguous_parents(&classes).is_empty());
+        assert!(generate_xml(&classes).contains(r#"<bean name="HybridTrigger" parent="EntityTrigger"/>"#));
+    }
+
+    #[test]
+    fn test_resolve_parent_conflicts() {
+        let entity = make_class("EntityTrigger", None, &[], true, vec![]);
+        let action = make_class("BaseAction", None, &[], false, vec![]);
+        let base = make_class("BaseTrigger", None, &["EntityTrigger"], false, vec![]);
+        // Implements an interface its base already derives from: no conflict
+        let fire = make_class("FireTrigger", Some("BaseTrigger"), &["EntityTrigger"], false, vec![]);
+        let dash = make_class("DashAction", Some("BaseAction"), &["EntityTrigger"], false, vec![]);
+        let classes = vec![entity, action, base, fire, dash];
+
+        let mut extends_first = classes.clone();
+        let warnings = resolve_parent_conflicts(&mut extends_first, ParentPrecedence::Extends);
+        assert_eq!(
+            warnings,
+            vec!["test.ts: DashAction extends BaseAction but implements EntityTrigger, which BaseAction doesn't derive from; parent=\"BaseAction\" is emitted (defaults.parent_precedence), use @Parent to choose another"]
+        );
+        let xml = generate_xml(&extends_first);
+        assert!(xml.contains(r#"<bean name="DashAction" parent="BaseAction"/>"#));
+        assert!(xml.contains(r#"<bean name="FireTrigger" parent="BaseTrigger"/>"#));
+
+        let mut implements_first = classes;
+        let warnings = resolve_parent_conflicts(&mut implements_first, ParentPrecedence::Implements);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("parent=\"EntityTrigger\" is emitted"));
+        let xml = generate_xml(&implements_first);
+        assert!(xml.contains(r#"<bean name="DashAction" parent="EntityTrigger"/>"#));
+        assert!(xml.contains(r#"<bean name="FireTrigger" parent="BaseTrigger"/>"#));
+    }
+
+    #[test]
+    fn test_find_shadowed_parent_fields() {
+        let parent_fields = std::collections::HashMap::from([(
+            "trigger.TsTriggerClass".to_string(),
+            vec!["priority".to_string(), "enabled".to_string()],
+        )]);
+        let damage = make_class(
+            "DamageTrigger",
+            None,
+            &["TsTriggerClass"],
+            false,
+            vec![field_at("priority", 5, None), field_at("damage", 6, None)],
+        );
+        // Reached through a parsed ancestor; `@Name("enabled")` still collides
+        let base = make_class("BaseTrigger", Some("TsTriggerClass"), &[], false, vec![]);
+        let heal = make_class(
+            "HealTrigger",
+            Some("BaseTrigger"),
+            &[],
+            false,
+            vec![field_at("isEnabled", 8, Some("enabled"))],
+        );
+        let unrelated = make_class(
+            "Other",
+            None,
+            &[],
+            false,
+            vec![field_at("priority", 2, None)],
+        );
+
+        assert_eq!(
+            find_shadowed_parent_fields(&[damage, base, heal, unrelated], &parent_fields),
+            vec![
+                "test.ts:5: DamageTrigger.priority - re-declares field 'priority' of external parent 'trigger.TsTriggerClass' ([parent_fields]); Luban rejects duplicate fields in a bean hierarchy",
+                "test.ts:8: HealTrigger.isEnabled - re-declares field 'enabled' of external parent 'trigger.TsTriggerClass' ([parent_fields]); Luban rejects duplicate fields in a bean hierarchy",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_field_tag_decorators_merge_with_custom_tags() {
+        let mut field = make_field("itemId", "int", false);
+        field.custom_tags = Some("prefix=_old,foo=bar".to_string());
+        field.tags = vec![
+            ("relocateTo".to_string(), "TbDrop".to_string()),
+            ("prefix".to_string(), "_drop".to_string()),
+        ];
+        let class = make_class("DropConfig", None, &[], false, vec![field]);
+
+        let xml = generate_xml(&[class]);
+        assert!(
+            xml.contains(r#"<var name="itemId" type="int" tags="foo=bar,relocateTo=TbDrop,prefix=_drop"/>"#),
+            "Got:\n{}",
+            xml
+        );
+    }
+
+    #[test]
+    fn test_class_tag_decorator_on_bean() {
+        let mut class = make_class("DropConfig", Some("Base"), &[], false, vec![]);
+        class.tags = vec![("export".to_string(), "server".to_string())];
+
+        let xml = generate_xml(&[class]);
+        assert!(xml.contains(r#"<bean name="DropConfig" parent="Base" tags="export=server"/>"#));
+    }
+
+    #[test]
+    fn test_merge_tags() {
+        assert_eq!(
+            merge_tags(
+                vec!["RefOverride=true".to_string(), "a=1,b=2".to_string()],
+                &[("a".to_string(), "3".to_string())]
+            ),
+            vec!["RefOverride=true", "b=2", "a=3"]
+        );
+        assert!(merge_tags(Vec::new(), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_renamed_field_uses_schema_name() {
+        let mut field = make_field("attackPower", "double", false);
+        field.xml_name = Some("attack_power".to_string());
+        let class = make_class("RoleConfig", None, &[], false, vec![field]);
+
+        let xml = generate_xml(&[class]);
+        assert!(xml.contains(r#"<var name="attack_power" type="double"/>"#));
+        assert!(!xml.contains("attackPower"));
+    }
+
+    #[test]
+    fn test_renamed_field_as_table_index() {
+        use crate::config::TableConfig;
+
+        let mut key = make_field("roleKey", "string", false);
+        key.xml_name = Some("role_key".to_string());
+        let mut class = make_class("RoleConfig", None, &[], false, vec![key]);
+        class.module_name = Some("role".to_string());
+
+        let mut tables = std::collections::HashMap::new();
+        tables.insert(
+            "role.RoleConfig".to_string(),
+            TableConfig::Full {
+                input: "../datas/role".to_string(),
+                name: None,
+                mode: None,
+                index: Some("roleKey".to_string()),
+                secondary_indexes: vec![],
+                transform: None,
+                required: None,
+                groups: vec![],
+            },
+        );
+        let mut table_registry = TableRegistry::from_config(&tables);
+        let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
+        table_registry.set_index_types(std::slice::from_ref(&class), &type_mapper);
+
+        let generator = XmlGenerator::new(&type_mapper, &table_registry);
+        let xml = generator.generate(&[class], "role");
+        assert!(
+            xml.contains(r#"<table name="RoleConfigTable" value="RoleConfig" index="role_key" input="../datas/role" />"#),
+            "Got:\n{}",
+            xml
+        );
+        assert_eq!(table_registry.get_index_type("RoleConfig"), Some("string"));
+    }
+
+    #[test]
+    fn test_index_validator_follows_renamed_field() {
+        let mut item_id = make_field("itemId", "int", false);
+        item_id.xml_name = Some("item_id".to_string());
+        let item = make_class("Item", None, &[], false, vec![item_id]);
+
+        let mut items = make_field("items", "list,Item", false);
+        items.validators.index_field = Some("itemId".to_string());
+        let bag = make_class("Bag", None, &[], false, vec![items]);
+
+        let mut classes = vec![item, bag];
+        resolve_renamed_index_fields(&mut classes);
+        assert_eq!(
+            classes[1].fields[0].validators.index_field,
+            Some("item_id".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cjk_alias_attributes() {
+        let mut field = make_field("attack", "double", false);
+        field.alias = Some("攻击力".to_string());
+        let mut class = make_class("RoleConfig", None, &[], false, vec![field]);
+        class.alias = Some("角色配置".to_string());
+
+        let xml = generate_xml(&[class]);
+        assert!(xml.contains(r#"<bean name="RoleConfig" alias="角色配置">"#));
+        assert!(xml.contains(r#"<var name="attack" type="double" alias="攻击力"/>"#));
+    }
+
+    #[test]
+    fn test_converter_field() {
+        let mut curve = make_field("curve", "string", true);
+        curve.converter = Some(crate::parser::field_info::FieldConverter {
+            luban_type: "CurveRef".to_string(),
+            tags: vec!["converter=CurveParser".to_string()],
+        });
+        curve.tags = vec![("editor".to_string(), "curve".to_string())];
+        let mut range = make_field("range", "string", false);
+        range.converter = Some(crate::parser::field_info::FieldConverter {
+            luban_type: "Range".to_string(),
+            tags: vec![],
+        });
+        let class = make_class("SkillConfig", None, &[], false, vec![curve, range]);
+
+        let xml = generate_xml(&[class]);
+        assert!(xml.contains(
+            r#"<var name="curve" type="CurveRef?" tags="converter=CurveParser,editor=curve"/>"#
+        ));
+        assert!(xml.contains(r#"<var name="range" type="Range"/>"#));
+    }
+
+    #[test]
+    fn test_nested_children() {
+        let trigger = make_class("EntityTrigger", None, &[], true, vec![]);
+        let damage = make_class(
+            "DamageTrigger",
+            None,
+            &["EntityTrigger"],
+            false,
+            vec![make_field("damage", "number", false)],
+        );
+        let area = make_class(
+            "AreaDamageTrigger",
+            Some("DamageTrigger"),
+            &[],
+            false,
+            vec![make_field("radius", "number", false)],
+        );
+        let external = make_class("RemoteTrigger", Some("ExternalBase"), &[], false, vec![]);
+        let classes = vec![trigger, damage, area, external];
+
+        let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
+        let table_registry = TableRegistry::new();
+        let generator =
+            XmlGenerator::new(&type_mapper, &table_registry).with_nested_children(true);
+        let xml = generator.generate(&classes, "");
+
+        let expected = [
+            r#"    <bean name="EntityTrigger">"#,
+            r#"        <bean name="DamageTrigger">"#,
+            r#"            <var name="damage" type="double"/>"#,
+            r#"            <bean name="AreaDamageTrigger">"#,
+            r#"                <var name="radius" type="double"/>"#,
+            r#"            </bean>"#,
+            r#"        </bean>"#,
+            r#"    </bean>"#,
+        ]
+        .join("\n");
+        assert!(xml.contains(&expected), "Got:\n{}", xml);
+
+        // Parent not generated by this tool: stays flat with parent=
+        assert!(xml.contains(r#"    <bean name="RemoteTrigger" parent="ExternalBase"/>"#));
+    }
+
+    #[test]
+    fn test_nesting_errors() {
+        let a = make_class("A", Some("B"), &[], false, vec![]);
+        let b = make_class("B", Some("A"), &[], false, vec![]);
+        let i1 = make_class("I1", None, &[], true, vec![]);
+        let i2 = make_class("I2", None, &[], true, vec![]);
+        let multi = make_class("Multi", None, &["I1", "I2"], false, vec![]);
+        let classes = vec![a, b, i1, i2, multi];
+
+        let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
+        let table_registry = TableRegistry::new();
+        let generator = XmlGenerator::new(&type_mapper, &table_registry);
+        let errors = generator.nesting_errors(&classes);
+
+        // The A <-> B cycle is left to validator::find_inheritance_cycles
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].starts_with("Multi implements multiple bean interfaces (I1, I2)"));
+    }
+
+    fn field_at(name: &str, line: usize, xml_name: Option<&str>) -> FieldInfo {
+        let mut field = make_field(name, "number", false);
+        field.origin = Some(FieldOrigin::Source {
+            file: "test.ts".to_string(),
+            line,
+        });
+        field.xml_name = xml_name.map(|x| x.to_string());
+        field
+    }
+
+    fn virtual_block(
+        class: &str,
+        name: &str,
+        mode: VirtualFieldMode,
+    ) -> VirtualFieldsConfig {
+        VirtualFieldsConfig {
+            class: class.to_string(),
+            fields: vec![crate::config::VirtualField {
+                name: name.to_string(),
+                field_type: "int".to_string(),
+                comment: None,
+                alias: None,
+                is_optional: false,
+                relocate_to: None,
+                validators: Default::default(),
+                position: None,
+                mode,
+            }],
+        }
+    }
+
+    fn duplicate_errors(
+        mut classes: Vec<ClassInfo>,
+        virtual_fields: &[VirtualFieldsConfig],
+    ) -> Vec<String> {
+        crate::virtual_fields::inject_virtual_fields(&mut classes, virtual_fields).unwrap();
+        let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
+        let table_registry = TableRegistry::new();
+        XmlGenerator::new(&type_mapper, &table_registry)
+            .duplicate_field_errors(&classes, virtual_fields)
+    }
+
+    #[test]
+    fn test_duplicate_fields_within_bean() {
+        // Two parsed fields sharing a schema name via @Name
+        let item = make_class(
+            "Item",
+            None,
+            &[],
+            false,
+            vec![field_at("price", 3, Some("cost")), field_at("cost", 4, None)],
+        );
+        assert_eq!(
+            duplicate_errors(vec![item], &[]),
+            vec!["Bean 'Item' has duplicate field 'cost': test.ts:3 and test.ts:4"]
+        );
+
+        // Injected field colliding with a renamed parsed field
+        let item = make_class("Item", None, &[], false, vec![field_at("price", 3, Some("cost"))]);
+        let blocks = [virtual_block("Item", "cost", VirtualFieldMode::Add)];
+        assert_eq!(
+            duplicate_errors(vec![item], &blocks),
+            vec!["Bean 'Item' has duplicate field 'cost': test.ts:3 and [[virtual_fields]] block 1"]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_fields_with_inherited() {
+        let parent = || make_class("Weapon", None, &[], false, vec![field_at("cost", 2, None)]);
+
+        // Redeclaring the inherited property in TypeScript is fine
+        let sword = make_class("Sword", Some("Weapon"), &[], false, vec![field_at("cost", 7, None)]);
+        assert!(duplicate_errors(vec![parent(), sword], &[]).is_empty());
+
+        // A renamed field landing on an inherited schema name is not
+        let sword = make_class(
+            "Sword",
+            Some("Weapon"),
+            &[],
+            false,
+            vec![field_at("price", 7, Some("cost"))],
+        );
+        assert_eq!(
+            duplicate_errors(vec![parent(), sword], &[]),
+            vec!["Bean 'Sword' has duplicate field 'cost': test.ts:7 and test.ts:2 (inherited from Weapon)"]
+        );
+
+        // Neither is an injected field with an inherited name
+        let sword = make_class("Sword", Some("Weapon"), &[], false, vec![]);
+        let blocks = [
+            virtual_block("Weapon", "damage", VirtualFieldMode::Add),
+            virtual_block("Sword", "cost", VirtualFieldMode::Add),
+        ];
+        assert_eq!(
+            duplicate_errors(vec![parent(), sword], &blocks),
+            vec!["Bean 'Sword' has duplicate field 'cost': [[virtual_fields]] block 2 and test.ts:2 (inherited from Weapon)"]
+        );
+
+        // ... unless it explicitly overrides the field
+        let sword = make_class("Sword", Some("Weapon"), &[], false, vec![field_at("cost", 7, None)]);
+        let blocks = [virtual_block("Sword", "cost", VirtualFieldMode::Override)];
+        assert!(duplicate_errors(vec![parent(), sword], &blocks).is_empty());
+    }
+
+    #[test]
+    fn test_optional_containers_modes() {
+        let mut tags = make_field("tags", "list,string", true);
+        tags.separator = Some("|".to_string());
+        let class = make_class(
+            "LootConfig",
+            None,
+            &[],
+            false,
+            vec![
+                tags,
+                make_field("weights", "map,string,number", true),
+                make_field("ids", "set,int", true),
+                make_field("required", "list,int", false),
+            ],
+        );
+
+        let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
+        let table_registry = TableRegistry::new();
+
+        for mode in [OptionalContainers::Drop, OptionalContainers::Warn] {
+            let generator =
+                XmlGenerator::new(&type_mapper, &table_registry).with_optional_containers(mode);
+            let xml = generator.generate(std::slice::from_ref(&class), "");
+            assert!(xml.contains(r#"<var name="tags" type="(list#sep=|),string"/>"#));
+            assert!(xml.contains(r#"<var name="weights" type="map,string,double"/>"#));
+            assert!(xml.contains(r#"<var name="ids" type="set,int"/>"#));
+        }
+
+        let generator = XmlGenerator::new(&type_mapper, &table_registry)
+            .with_optional_containers(OptionalContainers::Nullable);
+        let xml = generator.generate(std::slice::from_ref(&class), "");
+        assert!(xml.contains(r#"<var name="tags" type="(list?#sep=|),string"/>"#));
+        assert!(xml.contains(r#"<var name="weights" type="map?,string,double"/>"#));
+        assert!(xml.contains(r#"<var name="ids" type="set?,int"/>"#));
+        assert!(xml.contains(r#"<var name="required" type="list,int"/>"#));
+    }
+
+    #[test]
+    fn test_map_literal_union_and_enum_keys() {
+        let mut tiers = make_field("tiers", "map,int,RewardTier", false);
+        tiers.validators.key_set_values = vec!["1".to_string(), "2".to_string(), "3".to_string()];
+        let class = make_class(
+            "RewardConfig",
+            None,
+            &[],
+            false,
+            vec![tiers, make_field("byQuality", "map,Quality,string", false)],
+        );
+
+        let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
+        let table_registry = TableRegistry::new();
+        let type_to_module =
+            std::collections::HashMap::from([("Quality".to_string(), "enums".to_string())]);
+        let generator = XmlGenerator::with_type_mapping(&type_mapper, &table_registry, type_to_module);
+        let xml = generator.generate(std::slice::from_ref(&class), "reward");
+
+        assert!(xml.contains(r#"<var name="tiers" type="map,(int#set=1,2,3),RewardTier"/>"#));
+        assert!(xml.contains(r#"<var name="byQuality" type="map,enums.Quality,string"/>"#));
+    }
+
+    #[test]
+    fn test_generate_constants_xml() {
+        let mut role = make_class("RoleConfig", None, &[], false, vec![]);
+        role.constants = vec![
+            crate::parser::ClassConstant {
+                name: "MAX_LEVEL".to_string(),
+                const_type: "int".to_string(),
+                value: "80".to_string(),
+                comment: Some("Level cap".to_string()),
+            },
+            crate::parser::ClassConstant {
+                name: "TITLE".to_string(),
+                const_type: "string".to_string(),
+                value: "A&B".to_string(),
+                comment: None,
+            },
+        ];
+        let item = make_class("ItemConfig", None, &[], false, vec![]);
+
+        let xml = generate_constants_xml(&[&role, &item], "game");
+        assert!(xml.contains(r#"<bean name="RoleConfigConstants">"#));
+        assert!(xml.contains(
+            r#"<var name="MAX_LEVEL" type="int#default=80" comment="Level cap"/>"#
+        ));
+        assert!(xml.contains(r#"<var name="TITLE" type="string#default=A&amp;B"/>"#));
+        assert!(xml.contains(
+            r#"<table name="RoleConfigConstantsTable" value="RoleConfigConstants" mode="one" input="constants/RoleConfigConstants" />"#
+        ));
+        assert!(!xml.contains("ItemConfig"));
+    }
+
+    fn style_fixture() -> (Vec<ClassInfo>, EnumInfo) {
+        use crate::parser::EnumVariant;
+
+        let base = make_class(
+            "Base",
+            None,
+            &[],
+            false,
+            vec![make_field("id", "int", false)],
+        );
+        let mut marker = make_class("Marker", Some("Base"), &[], false, vec![]);
+        marker.alias = Some("标记".to_string());
+        marker.comment = Some("Marker bean".to_string());
+        marker.tags = vec![("export".to_string(), "server".to_string())];
+        let mut tagged = make_class("Tagged", None, &[], false, vec![]);
+        tagged.tags = vec![("export".to_string(), "server".to_string())];
+
+        let quality = EnumInfo {
+            name: "Quality".to_string(),
+            alias: Some("品质".to_string()),
+            comment: Some("Quality".to_string()),
+            is_string_enum: false,
+            is_flags: true,
+            tags: Some("ui".to_string()),
+            variants: vec![
+                EnumVariant {
+                    name: "Common".to_string(),
+                    alias: Some("普通".to_string()),
+                    value: "1".to_string(),
+                    comment: None,
+                },
+                EnumVariant {
+                    name: "Rare".to_string(),
+                    alias: None,
+                    value: "2".to_string(),
+                    comment: Some("Rare drop".to_string()),
+                },
+            ],
+            source_file: "test.ts".to_string(),
+            source_path: Default::default(),
+            file_hash: "abc".to_string(),
+            output_path: None,
+            module_name: None,
+        };
+        (vec![base, marker, tagged], quality)
+    }
+
+    #[test]
+    fn test_xml_style_canonical_golden() {
+        let (classes, quality) = style_fixture();
+        let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
+        let table_registry = TableRegistry::new();
+        let xml = XmlGenerator::new(&type_mapper, &table_registry)
+            .generate_with_all_classes_and_enums(&classes, &[quality], "game", &classes);
+
+        let expected = [
+            r#"<module name="game" comment="自动生成的 ts class Bean 定义">"#,
+            "",
+            "    <!-- Quality -->",
+            r#"    <enum name="Quality" alias="品质" flags="true" comment="Quality" tags="ui">"#,
+            r#"        <var name="Common" alias="普通" value="1"/>"#,
+            r#"        <var name="Rare" value="2" comment="Rare drop"/>"#,
+            "    </enum>",
+            "",
+            r#"    <bean name="Base">"#,
+            r#"        <var name="id" type="int"/>"#,
+            "    </bean>",
+            "",
+            "    <!-- Marker bean -->",
+            r#"    <bean name="Marker" parent="Base" alias="标记" comment="Marker bean" tags="export=server"/>"#,
+            "",
+            r#"    <bean name="Tagged" tags="export=server"/>"#,
+            "",
+            "</module>",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(xml, expected);
+
+        // Two-space indent keeps everything else byte for byte
+        let expected_2 = expected
+            .lines()
+            .map(|line| {
+                let body = line.trim_start();
+                format!("{}{}", " ".repeat((line.len() - body.len()) / 2), body)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+            + "\n";
+        assert_eq!(reindent_xml(&xml, 2), expected_2);
+        assert!(reindent_xml(&xml, 2).contains("\n  <bean name=\"Base\">\n    <var name=\"id\""));
+        assert_eq!(reindent_xml(&xml, 4), xml);
+    }
+
+    #[test]
+    fn test_xml_style_legacy_and_nested() {
+        let (classes, quality) = style_fixture();
+        let type_mapper = TypeMapper::new(&std::collections::HashMap::new());
+        let table_registry = TableRegistry::new();
+
+        let legacy = XmlGenerator::new(&type_mapper, &table_registry).with_xml_style(XmlStyle {
+            attribute_order: AttributeOrder::Legacy,
+            self_closing: false,
+            ..XmlStyle::default()
+        });
+        let xml = legacy.generate_with_all_classes_and_enums(
+            &classes,
+            std::slice::from_ref(&quality),
+            "game",
+            &classes,
+        );
+        for expected in [
+            r#"    <enum name="Quality" alias="品质" flags="true" tags="ui" comment="Quality">"#,
+            "    <bean name=\"Marker\" alias=\"标记\" parent=\"Base\" comment=\"Marker bean\" tags=\"export=server\">\n    </bean>",
+            "    <bean name=\"Tagged\" tags=\"export=server\">\n    </bean>",
+        ] {
+            assert!(xml.contains(expected), "missing {}\nGot:\n{}", expected, xml);
+        }
+
+        // An empty nested leaf collapses at its own depth; its parent stays open
+        let nested = XmlGenerator::new(&type_mapper, &table_registry).with_nested_children(true);
+        let xml = nested.generate(&classes, "game");
+        let expected = [
+            r#"    <bean name="Base">"#,
+            r#"        <var name="id" type="int"/>"#,
+            "        <!-- Marker bean -->",
+            r#"        <bean name="Marker" alias="标记" comment="Marker bean" tags="export=server"/>"#,
+            "    </bean>",
+        ]
+        .join("\n");
+        assert!(xml.contains(&expected), "Got:\n{}", xml);
+    }
 }
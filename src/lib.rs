@@ -1,17 +1,33 @@
 #![allow(dead_code)]
 
 pub mod cache;
+pub mod changelog;
 pub mod config;
+pub mod diagnostics;
+pub mod error;
 pub mod generator;
+pub mod lint;
+pub mod module_stats;
+#[cfg(feature = "napi")]
+pub mod node;
+pub mod output;
 pub mod parser;
+pub mod pipeline;
 pub mod scanner;
+pub mod schema_version;
 pub mod table_mapping;
 pub mod table_registry;
 pub mod ts_generator;
 pub mod tsconfig;
 pub mod type_mapper;
 pub mod validator;
+pub mod virtual_fields;
+pub mod xml_lint;
 
+pub use error::GenError;
+pub use pipeline::{
+    ClassPreview, FileStats, GeneratedFile, Generation, Pipeline, PipelineBuilder, RunReport,
+};
 pub use table_mapping::TableMappingResolver;
 pub use table_registry::TableRegistry;
 pub use ts_generator::TsCodeGenerator;
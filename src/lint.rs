@@ -0,0 +1,305 @@
+//! Opt-in naming conventions (`[lint]`): a regex per category (bean, field and custom table
+//! names), checked once parsing and table resolution are done. Violations of a `warn` rule
+//! are reported as warnings, so `--deny-warnings` fails on them too; violations of an
+//! `error` rule fail validation.
+
+use crate::config::{LintConfig, LintPreset, LintRule, LintSeverity};
+use crate::parser::ClassInfo;
+use crate::table_registry::ResolvedTableConfig;
+use regex::Regex;
+use serde::Serialize;
+
+/// Patterns of preset = "default", by category: PascalCase beans, camelCase fields and
+/// `Tb`-prefixed custom table names
+const DEFAULT_PRESET: [(&str, &str); 3] = [
+    ("beans", r"^[A-Z][A-Za-z0-9]*$"),
+    ("fields", r"^[a-z][A-Za-z0-9]*$"),
+    ("tables", r"^Tb[A-Z][A-Za-z0-9]*$"),
+];
+
+/// A name that breaks a [lint] rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintViolation {
+    /// Rule category: `beans`, `fields` or `tables`
+    pub rule: &'static str,
+    pub severity: LintSeverity,
+    /// `{location}: {subject} - ...`, like other warnings
+    pub message: String,
+}
+
+/// A compiled rule
+#[derive(Debug)]
+struct Rule {
+    category: &'static str,
+    pattern: Regex,
+    severity: LintSeverity,
+}
+
+/// The rules of a [lint] section
+#[derive(Debug, Default)]
+pub struct Linter {
+    rules: Vec<Rule>,
+}
+
+impl Linter {
+    /// Compile the configured rules; a category set explicitly replaces the preset's rule.
+    /// Fails with one message per invalid pattern
+    pub fn new(config: &LintConfig) -> Result<Self, Vec<String>> {
+        let configured = [
+            ("beans", &config.beans),
+            ("fields", &config.fields),
+            ("tables", &config.tables),
+        ];
+        let mut rules = Vec::new();
+        let mut errors = Vec::new();
+        for (category, rule) in configured {
+            let preset = match config.preset {
+                Some(LintPreset::Default) => DEFAULT_PRESET
+                    .iter()
+                    .find(|(c, _)| *c == category)
+                    .map(|(_, pattern)| (pattern.to_string(), config.severity)),
+                None => None,
+            };
+            let Some((pattern, severity)) = rule
+                .as_ref()
+                .map(|LintRule { pattern, severity }| (pattern.clone(), *severity))
+                .or(preset)
+            else {
+                continue;
+            };
+            match Regex::new(&pattern) {
+                Ok(pattern) => rules.push(Rule {
+                    category,
+                    pattern,
+                    severity,
+                }),
+                Err(e) => errors.push(format!(
+                    "{}: invalid pattern {:?}: {}",
+                    category, pattern, e
+                )),
+            }
+        }
+        if errors.is_empty() {
+            Ok(Self { rules })
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Check bean and field names of `classes` and the names of custom tables, in that order
+    pub fn check<'t>(
+        &self,
+        classes: &[ClassInfo],
+        tables: impl IntoIterator<Item = &'t ResolvedTableConfig>,
+    ) -> Vec<LintViolation> {
+        let mut violations = Vec::new();
+        if let Some(rule) = self.rule("beans") {
+            for class in classes.iter().filter(|c| !rule.pattern.is_match(&c.name)) {
+                violations.push(rule.violation(format!(
+                    "{}: {} - bean name doesn't match the [lint] beans pattern \"{}\"",
+                    class.source_file, class.name, rule.pattern
+                )));
+            }
+        }
+        if let Some(rule) = self.rule("fields") {
+            for class in classes {
+                for field in &class.fields {
+                    let name = field.schema_name();
+                    if !rule.pattern.is_match(name) {
+                        violations.push(rule.violation(format!(
+                            "{}: {}.{} - field name doesn't match the [lint] fields pattern \"{}\"",
+                            class.field_location(field),
+                            class.name,
+                            name,
+                            rule.pattern
+                        )));
+                    }
+                }
+            }
+        }
+        if let Some(rule) = self.rule("tables") {
+            let mut custom: Vec<&ResolvedTableConfig> = tables
+                .into_iter()
+                .filter(|t| t.name != format!("{}Table", t.class_name))
+                .collect();
+            custom.sort_by(|a, b| a.bean.cmp(&b.bean));
+            for table in custom
+                .into_iter()
+                .filter(|t| !rule.pattern.is_match(&t.name))
+            {
+                violations.push(rule.violation(format!(
+                    "[tables] \"{}\": {} - table name doesn't match the [lint] tables pattern \"{}\"",
+                    table.bean, table.name, rule.pattern
+                )));
+            }
+        }
+        violations
+    }
+
+    fn rule(&self, category: &str) -> Option<&Rule> {
+        self.rules.iter().find(|rule| rule.category == category)
+    }
+}
+
+impl Rule {
+    fn violation(&self, message: String) -> LintViolation {
+        LintViolation {
+            rule: self.category,
+            severity: self.severity,
+            message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TableConfig;
+    use crate::parser::{FieldInfo, ImportMap};
+    use crate::table_registry::TableRegistry;
+    use std::collections::HashMap;
+
+    fn lint_config(toml_text: &str) -> LintConfig {
+        toml::from_str(toml_text).unwrap()
+    }
+
+    fn class(name: &str, fields: &[&str]) -> ClassInfo {
+        ClassInfo {
+            name: name.to_string(),
+            comment: None,
+            alias: None,
+            fields: fields
+                .iter()
+                .map(|field| FieldInfo {
+                    name: field.to_string(),
+                    field_type: "int".to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            implements: vec![],
+            extends: None,
+            source_file: "src/role.ts".to_string(),
+            source_path: Default::default(),
+            file_hash: String::new(),
+            is_interface: false,
+            output_path: None,
+            module_name: None,
+            type_params: HashMap::new(),
+            luban_table: None,
+            table_config: None,
+            input_path: None,
+            imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            explicit_parent: None,
+            explicit_module: false,
+            tags: Vec::new(),
+            examples: Vec::new(),
+        }
+    }
+
+    fn messages(violations: &[LintViolation]) -> Vec<&str> {
+        violations.iter().map(|v| v.message.as_str()).collect()
+    }
+
+    #[test]
+    fn test_bean_rule() {
+        let linter = Linter::new(&lint_config(
+            "beans = { pattern = \"^[A-Z][A-Za-z0-9]*$\" }",
+        ))
+        .unwrap();
+        let classes = [
+            class("RoleConfig", &[]),
+            class("role_skill", &["Bad_Field"]),
+        ];
+        let violations = linter.check(&classes, []);
+        assert_eq!(
+            messages(&violations),
+            vec!["src/role.ts: role_skill - bean name doesn't match the [lint] beans pattern \"^[A-Z][A-Za-z0-9]*$\""]
+        );
+        assert_eq!(violations[0].rule, "beans");
+        assert_eq!(violations[0].severity, LintSeverity::Warn);
+    }
+
+    #[test]
+    fn test_field_rule() {
+        let linter = Linter::new(&lint_config(
+            "fields = { pattern = \"^[a-z][A-Za-z0-9]*$\", severity = \"error\" }",
+        ))
+        .unwrap();
+        let mut renamed = class("SkillConfig", &["cooldown"]);
+        renamed.fields[0].xml_name = Some("cool_down".to_string());
+        let classes = [class("RoleConfig", &["id", "MaxHp"]), renamed];
+        let violations = linter.check(&classes, []);
+        assert_eq!(
+            messages(&violations),
+            vec![
+                "src/role.ts: RoleConfig.MaxHp - field name doesn't match the [lint] fields pattern \"^[a-z][A-Za-z0-9]*$\"",
+                "src/role.ts: SkillConfig.cool_down - field name doesn't match the [lint] fields pattern \"^[a-z][A-Za-z0-9]*$\"",
+            ]
+        );
+        assert!(violations.iter().all(|v| v.severity == LintSeverity::Error));
+    }
+
+    #[test]
+    fn test_table_rule_only_checks_custom_names() {
+        let linter = Linter::new(&lint_config("tables = { pattern = \"^Tb\" }")).unwrap();
+        let tables: HashMap<String, TableConfig> = toml::from_str(
+            r#"
+"role.RoleConfig" = "../datas/role"
+"role.SkillConfig" = { input = "../datas/skill", name = "TbSkill" }
+"role.BuffConfig" = { input = "../datas/buff", name = "Buffs" }
+"#,
+        )
+        .unwrap();
+        let registry = TableRegistry::from_config(&tables);
+        assert_eq!(
+            messages(&linter.check(&[], registry.all_tables())),
+            vec!["[tables] \"role.BuffConfig\": Buffs - table name doesn't match the [lint] tables pattern \"^Tb\""]
+        );
+    }
+
+    #[test]
+    fn test_preset() {
+        let linter = Linter::new(&lint_config(
+            "preset = \"default\"\nseverity = \"error\"\nfields = { pattern = \"^[a-z_]+$\" }",
+        ))
+        .unwrap();
+        let classes = [class("roleConfig", &["max_hp", "maxHp"])];
+        let violations = linter.check(&classes, []);
+        // The preset's bean rule with the preset severity; the field rule replaced
+        assert_eq!(violations.len(), 2);
+        assert_eq!(
+            (violations[0].rule, violations[0].severity),
+            ("beans", LintSeverity::Error)
+        );
+        assert_eq!(
+            (violations[1].rule, violations[1].severity),
+            ("fields", LintSeverity::Warn)
+        );
+        assert!(violations[1].message.contains("roleConfig.maxHp"));
+
+        assert!(Linter::new(&LintConfig::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let errors = Linter::new(&lint_config(
+            "beans = { pattern = \"^[A-Z\" }\ntables = { pattern = \"(\" }",
+        ))
+        .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("beans: invalid pattern \"^[A-Z\": "));
+        assert!(errors[1].starts_with("tables: invalid pattern \"(\": "));
+    }
+}
@@ -0,0 +1,247 @@
+//! Per-module generation statistics (`--stats-by-module`): beans, fields, enums, tables and
+//! XML bytes of each resolved module, with deltas against the counts the cache kept from
+//! the previous run. Meant for finding which modules a growing schema comes from.
+
+use crate::parser::{ClassInfo, EnumInfo};
+use crate::table_registry::ResolvedTableConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// What one module contributes to the generated schema
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleCounts {
+    pub beans: usize,
+    /// Fields of the module's beans (inherited fields count once, on the parent)
+    pub fields: usize,
+    pub enums: usize,
+    pub tables: usize,
+    /// Size of the module's XML output(s)
+    pub xml_bytes: usize,
+}
+
+/// Change of each count since the previous run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ModuleDelta {
+    pub beans: i64,
+    pub fields: i64,
+    pub enums: i64,
+    pub tables: i64,
+    pub xml_bytes: i64,
+}
+
+impl ModuleDelta {
+    fn between(before: &ModuleCounts, after: &ModuleCounts) -> Self {
+        let diff = |before: usize, after: usize| after as i64 - before as i64;
+        Self {
+            beans: diff(before.beans, after.beans),
+            fields: diff(before.fields, after.fields),
+            enums: diff(before.enums, after.enums),
+            tables: diff(before.tables, after.tables),
+            xml_bytes: diff(before.xml_bytes, after.xml_bytes),
+        }
+    }
+}
+
+/// One row of the `--stats-by-module` table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModuleStats {
+    /// Module name (empty for the root module)
+    pub module: String,
+    #[serde(flatten)]
+    pub counts: ModuleCounts,
+    /// Since the previous run; None when the cache has no counts to compare with
+    pub delta: Option<ModuleDelta>,
+}
+
+/// Count beans, fields, enums, tables and XML bytes per module. Classes and enums without a
+/// module use `default_module`; `xml_files` are (module, content length) pairs
+pub fn count<'a>(
+    classes: impl IntoIterator<Item = &'a ClassInfo>,
+    enums: impl IntoIterator<Item = &'a EnumInfo>,
+    tables: impl IntoIterator<Item = &'a ResolvedTableConfig>,
+    xml_files: impl IntoIterator<Item = (&'a str, usize)>,
+    default_module: &str,
+) -> BTreeMap<String, ModuleCounts> {
+    let module_of =
+        |module: &Option<String>| module.clone().unwrap_or_else(|| default_module.to_string());
+    let mut counts: BTreeMap<String, ModuleCounts> = BTreeMap::new();
+    for class in classes {
+        let module = counts.entry(module_of(&class.module_name)).or_default();
+        module.beans += 1;
+        module.fields += class.fields.len();
+    }
+    for enum_info in enums {
+        counts
+            .entry(module_of(&enum_info.module_name))
+            .or_default()
+            .enums += 1;
+    }
+    for table in tables {
+        counts.entry(table.module.clone()).or_default().tables += 1;
+    }
+    for (module, bytes) in xml_files {
+        counts.entry(module.to_string()).or_default().xml_bytes += bytes;
+    }
+    counts
+}
+
+/// Rows for every module of this run or the previous one (removed modules show zero
+/// counts), sorted by bean count descending, then by name
+pub fn compare(
+    current: &BTreeMap<String, ModuleCounts>,
+    previous: Option<&BTreeMap<String, ModuleCounts>>,
+) -> Vec<ModuleStats> {
+    let mut modules: Vec<&String> = current.keys().collect();
+    if let Some(previous) = previous {
+        modules.extend(previous.keys().filter(|m| !current.contains_key(*m)));
+    }
+    let mut stats: Vec<ModuleStats> = modules
+        .into_iter()
+        .map(|module| {
+            let counts = current.get(module).copied().unwrap_or_default();
+            let delta = previous.map(|previous| {
+                ModuleDelta::between(&previous.get(module).copied().unwrap_or_default(), &counts)
+            });
+            ModuleStats {
+                module: module.clone(),
+                counts,
+                delta,
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| {
+        b.counts
+            .beans
+            .cmp(&a.counts.beans)
+            .then_with(|| a.module.cmp(&b.module))
+    });
+    stats
+}
+
+/// The rows as an aligned console table, one line per module after a header. Counts that
+/// changed since the previous run are followed by the change, e.g. `12 (+3)`
+pub fn render_table(stats: &[ModuleStats]) -> String {
+    let cell = |value: usize, delta: Option<i64>| match delta {
+        Some(delta) if delta != 0 => format!("{} ({:+})", value, delta),
+        _ => value.to_string(),
+    };
+    let mut rows: Vec<[String; 6]> = vec![[
+        "Module".to_string(),
+        "Beans".to_string(),
+        "Fields".to_string(),
+        "Enums".to_string(),
+        "Tables".to_string(),
+        "XML bytes".to_string(),
+    ]];
+    for row in stats {
+        let counts = &row.counts;
+        let delta = row.delta.as_ref();
+        rows.push([
+            if row.module.is_empty() {
+                "(root)".to_string()
+            } else {
+                row.module.clone()
+            },
+            cell(counts.beans, delta.map(|d| d.beans)),
+            cell(counts.fields, delta.map(|d| d.fields)),
+            cell(counts.enums, delta.map(|d| d.enums)),
+            cell(counts.tables, delta.map(|d| d.tables)),
+            cell(counts.xml_bytes, delta.map(|d| d.xml_bytes)),
+        ]);
+    }
+
+    let mut widths = [0; 6];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut out = String::new();
+    for row in &rows {
+        let mut line = format!("{:<width$}", row[0], width = widths[0]);
+        for (cell, width) in row.iter().zip(widths).skip(1) {
+            line.push_str(&format!("  {:>width$}", cell, width = width));
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(beans: usize, fields: usize, xml_bytes: usize) -> ModuleCounts {
+        ModuleCounts {
+            beans,
+            fields,
+            enums: 0,
+            tables: 0,
+            xml_bytes,
+        }
+    }
+
+    #[test]
+    fn test_compare_sorts_and_diffs() {
+        let current = BTreeMap::from([
+            ("item".to_string(), counts(2, 5, 300)),
+            ("role".to_string(), counts(4, 9, 500)),
+            ("shop".to_string(), counts(2, 2, 100)),
+        ]);
+        let stats = compare(&current, None);
+        let order: Vec<&str> = stats.iter().map(|s| s.module.as_str()).collect();
+        assert_eq!(order, vec!["role", "item", "shop"]);
+        assert!(stats.iter().all(|s| s.delta.is_none()));
+
+        let previous = BTreeMap::from([
+            ("item".to_string(), counts(1, 3, 200)),
+            ("skill".to_string(), counts(3, 6, 400)),
+        ]);
+        let stats = compare(&current, Some(&previous));
+        let order: Vec<&str> = stats.iter().map(|s| s.module.as_str()).collect();
+        assert_eq!(order, vec!["role", "item", "shop", "skill"]);
+        let delta = |module: &str| {
+            stats
+                .iter()
+                .find(|s| s.module == module)
+                .and_then(|s| s.delta)
+                .unwrap()
+        };
+        assert_eq!(delta("item").beans, 1);
+        assert_eq!(delta("item").xml_bytes, 100);
+        assert_eq!(delta("role").fields, 9);
+        assert_eq!(delta("skill").beans, -3);
+        assert_eq!(stats[3].counts, ModuleCounts::default());
+    }
+
+    #[test]
+    fn test_render_table_aligns_columns() {
+        let stats = vec![
+            ModuleStats {
+                module: "role".to_string(),
+                counts: counts(12, 40, 2048),
+                delta: Some(ModuleDelta {
+                    beans: 3,
+                    fields: 0,
+                    enums: 0,
+                    tables: 0,
+                    xml_bytes: -16,
+                }),
+            },
+            ModuleStats {
+                module: String::new(),
+                counts: counts(1, 2, 90),
+                delta: Some(ModuleDelta::default()),
+            },
+        ];
+        assert_eq!(
+            render_table(&stats),
+            "\
+Module    Beans  Fields  Enums  Tables   XML bytes
+role    12 (+3)      40      0       0  2048 (-16)
+(root)        1       2      0       0          90
+"
+        );
+    }
+}
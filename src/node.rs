@@ -0,0 +1,240 @@
+//! Node.js bindings (`napi` feature), exposing the `Pipeline` API as a native addon.
+//! Built and published from `node/` with napi-rs.
+
+use crate::config::Config;
+use crate::parser::{ClassInfo, EnumInfo, TsParser};
+use crate::pipeline::Pipeline;
+use anyhow::Context;
+use napi::{Error, Result};
+use napi_derive::napi;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Options shared by `run` and `check`
+#[napi(object)]
+#[derive(Default)]
+pub struct RunOptions {
+    /// Ignore the cache and treat every bean as updated
+    pub force: Option<bool>,
+    /// Print progress, per-bean lines and warnings to the console (silent by default)
+    pub verbose: Option<bool>,
+    /// Only generate beans/enums whose name matches one of these regexes
+    pub filter: Option<Vec<String>>,
+    /// Only declare tables of these groups in the TypeScript outputs
+    pub group: Option<Vec<String>>,
+    /// Throw instead of generating when any warning is reported
+    pub deny_warnings: Option<bool>,
+}
+
+#[napi(object, js_name = "RunReport")]
+pub struct JsRunReport {
+    pub beans: u32,
+    pub enums: u32,
+    pub cached: u32,
+    pub updated: u32,
+    /// Beans/enums whose source changed only in comments or formatting
+    pub formatting_only: u32,
+    pub written: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[napi(object, js_name = "CheckReport")]
+pub struct JsCheckReport {
+    pub beans: u32,
+    pub enums: u32,
+    /// Files that `run` would generate (XML and TypeScript)
+    pub files: Vec<String>,
+}
+
+#[napi(object, js_name = "Field")]
+pub struct JsField {
+    pub name: String,
+    /// Luban type before type mappings, e.g. `list,int`
+    pub field_type: String,
+    pub original_type: String,
+    /// May be absent (`name?: T`)
+    pub optional: bool,
+    /// Present but may be null/undefined (`name: T | null`)
+    pub nullable: bool,
+    /// Declared `readonly`, typed `readonly T[]` / `ReadonlyMap` etc., or a getter read
+    /// with defaults.include_getters
+    pub readonly: bool,
+    pub comment: Option<String>,
+    pub alias: Option<String>,
+    /// `@example` blocks from the JSDoc, verbatim
+    pub examples: Vec<String>,
+}
+
+#[napi(object, js_name = "Class")]
+pub struct JsClass {
+    pub name: String,
+    pub comment: Option<String>,
+    pub alias: Option<String>,
+    pub extends: Option<String>,
+    pub implements: Vec<String>,
+    pub is_interface: bool,
+    pub source_file: String,
+    pub fields: Vec<JsField>,
+    pub examples: Vec<String>,
+}
+
+#[napi(object, js_name = "EnumVariant")]
+pub struct JsEnumVariant {
+    pub name: String,
+    pub value: String,
+    pub alias: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[napi(object, js_name = "Enum")]
+pub struct JsEnum {
+    pub name: String,
+    pub comment: Option<String>,
+    pub alias: Option<String>,
+    pub is_string_enum: bool,
+    pub is_flags: bool,
+    pub source_file: String,
+    pub variants: Vec<JsEnumVariant>,
+}
+
+#[napi(object, js_name = "ParsedSources")]
+pub struct JsParsedSources {
+    pub classes: Vec<JsClass>,
+    pub enums: Vec<JsEnum>,
+}
+
+/// Run the full generation for a config file, like the CLI
+#[napi]
+pub fn run(config_path: String, options: Option<RunOptions>) -> Result<JsRunReport> {
+    let report = pipeline(&config_path, options)?.run().map_err(to_napi)?;
+    Ok(JsRunReport {
+        beans: report.beans as u32,
+        enums: report.enums as u32,
+        cached: report.cached as u32,
+        updated: report.updated as u32,
+        formatting_only: report.formatting_only as u32,
+        written: report.written.iter().map(|p| path_string(p)).collect(),
+        unchanged: report.unchanged.iter().map(|p| path_string(p)).collect(),
+        warnings: report.warnings,
+    })
+}
+
+/// Parse and validate without writing anything; throws on the same errors as `run`
+#[napi]
+pub fn check(config_path: String, options: Option<RunOptions>) -> Result<JsCheckReport> {
+    let pipeline = pipeline(&config_path, options)?;
+    let xml = pipeline.generate_xml().map_err(to_napi)?;
+    let ts = pipeline.generate_ts().map_err(to_napi)?;
+    Ok(JsCheckReport {
+        beans: xml.iter().map(|f| f.beans as u32).sum(),
+        enums: xml.iter().map(|f| f.enums as u32).sum(),
+        files: xml
+            .iter()
+            .chain(&ts)
+            .map(|f| path_string(&f.path))
+            .collect(),
+    })
+}
+
+/// Parse in-memory TypeScript sources (file name → content) without a config
+#[napi]
+pub fn parse_sources(sources: HashMap<String, String>) -> Result<JsParsedSources> {
+    let parser = TsParser::new();
+    let mut names: Vec<&String> = sources.keys().collect();
+    names.sort();
+
+    let mut parsed = JsParsedSources {
+        classes: Vec::new(),
+        enums: Vec::new(),
+    };
+    for name in names {
+        let content = &sources[name];
+        let classes = parser.parse_source(name, content).map_err(to_napi)?;
+        let enums = parser.parse_enums_source(name, content).map_err(to_napi)?;
+        parsed.classes.extend(classes.into_iter().map(js_class));
+        parsed.enums.extend(enums.into_iter().map(js_enum));
+    }
+    Ok(parsed)
+}
+
+fn pipeline(config_path: &str, options: Option<RunOptions>) -> Result<Pipeline> {
+    let config_path = PathBuf::from(config_path);
+    let config = Config::load_with_refs(&config_path)
+        .with_context(|| format!("Failed to load config from {:?}", config_path))
+        .map_err(to_napi)?;
+    let project_root = config_path.parent().unwrap_or_else(|| Path::new("."));
+    let options = options.unwrap_or_default();
+
+    let verbose = options.verbose.unwrap_or(false);
+    let mut builder = Pipeline::builder(config)
+        .project_root(project_root)
+        .force(options.force.unwrap_or(false))
+        .progress(verbose)
+        .verbose(verbose)
+        .deny_warnings(options.deny_warnings.unwrap_or(false));
+    for pattern in options.filter.unwrap_or_default() {
+        builder = builder.filter(pattern);
+    }
+    for group in options.group.unwrap_or_default() {
+        builder = builder.group(group);
+    }
+    builder.build().map_err(to_napi)
+}
+
+fn js_class(class: ClassInfo) -> JsClass {
+    JsClass {
+        name: class.name,
+        comment: class.comment,
+        alias: class.alias,
+        extends: class.extends,
+        implements: class.implements,
+        is_interface: class.is_interface,
+        source_file: class.source_file,
+        fields: class
+            .fields
+            .into_iter()
+            .map(|field| JsField {
+                name: field.name,
+                field_type: field.field_type,
+                original_type: field.original_type,
+                optional: field.is_optional,
+                nullable: field.is_nullable,
+                readonly: field.is_readonly,
+                comment: field.comment,
+                alias: field.alias,
+                examples: field.examples,
+            })
+            .collect(),
+        examples: class.examples,
+    }
+}
+
+fn js_enum(enum_info: EnumInfo) -> JsEnum {
+    JsEnum {
+        name: enum_info.name,
+        comment: enum_info.comment,
+        alias: enum_info.alias,
+        is_string_enum: enum_info.is_string_enum,
+        is_flags: enum_info.is_flags,
+        source_file: enum_info.source_file,
+        variants: enum_info
+            .variants
+            .into_iter()
+            .map(|variant| JsEnumVariant {
+                name: variant.name,
+                value: variant.value,
+                alias: variant.alias,
+                comment: variant.comment,
+            })
+            .collect(),
+    }
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+fn to_napi(e: anyhow::Error) -> Error {
+    Error::from_reason(format!("{:#}", e))
+}
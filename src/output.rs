@@ -0,0 +1,188 @@
+use crate::config::Newline;
+use std::path::Path;
+
+/// Convert every line ending in `content` to the configured newline
+pub fn normalize_newlines(content: &str, newline: Newline) -> String {
+    let lf = content.replace("\r\n", "\n");
+    match newline {
+        Newline::Lf => lf,
+        Newline::Crlf => lf.replace('\n', "\r\n"),
+    }
+}
+
+/// Write `content` to `path` atomically: the data goes to a temp file in the same
+/// directory which is then renamed over the target, so readers never see a partial file
+pub fn write_atomic(path: &Path, content: &[u8]) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let dir = parent_dir(path);
+    std::fs::create_dir_all(dir)?;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid output path: {:?}", path))?;
+    let tmp_path = dir.join(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let result = (|| -> std::io::Result<()> {
+        let mut file = std::fs::File::create(&tmp_path)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, path)
+    })();
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(anyhow::anyhow!("Failed to write {:?}: {}", path, e));
+    }
+    Ok(())
+}
+
+/// Check up front that `path` can be written as a file: it must not be an existing
+/// directory, and files must be creatable in its parent directory. Errors describe the
+/// problem without repeating `path`; callers name the output
+pub fn check_file_destination(path: &Path) -> anyhow::Result<()> {
+    if path.is_dir() {
+        anyhow::bail!("is a directory, expected a file path");
+    }
+    check_dir_destination(parent_dir(path))
+}
+
+/// Check up front that files can be created in `dir` without creating anything: the
+/// nearest existing ancestor must be a directory that accepts a probe file
+pub fn check_dir_destination(dir: &Path) -> anyhow::Result<()> {
+    let mut existing = dir;
+    while !existing.exists() {
+        existing = parent_dir(existing);
+        if existing == Path::new(".") {
+            break;
+        }
+    }
+    if !existing.is_dir() {
+        anyhow::bail!("{} is not a directory", existing.display());
+    }
+    let probe = existing.join(format!(".luban-gen.{}.probe", std::process::id()));
+    std::fs::File::create(&probe)
+        .map_err(|e| anyhow::anyhow!("{} is not writable: {}", existing.display(), e))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Directory a file is written to (`.` for a bare file name)
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// Normalize newlines and write `content` atomically unless the file already has
+/// exactly that content. Returns whether the file was written
+pub fn write_if_changed(path: &Path, content: &str, newline: Newline) -> anyhow::Result<bool> {
+    let content = normalize_newlines(content, newline);
+    if path.exists() && std::fs::read_to_string(path)? == content {
+        return Ok(false);
+    }
+    write_atomic(path, content.as_bytes())?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_normalize_newlines() {
+        let mixed = "<module>\r\n    <bean/>\n</module>\n";
+        assert_eq!(
+            normalize_newlines(mixed, Newline::Lf),
+            "<module>\n    <bean/>\n</module>\n"
+        );
+        assert_eq!(
+            normalize_newlines(mixed, Newline::Crlf),
+            "<module>\r\n    <bean/>\r\n</module>\r\n"
+        );
+
+        // Idempotent
+        let crlf = normalize_newlines(mixed, Newline::Crlf);
+        assert_eq!(normalize_newlines(&crlf, Newline::Crlf), crlf);
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("output.xml");
+
+        write_atomic(&path, b"first").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first");
+
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+
+        // No temp files are left behind
+        let entries: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, vec![std::ffi::OsString::from("output.xml")]);
+    }
+
+    #[test]
+    fn test_write_if_changed_compares_normalized_content() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("output.xml");
+
+        assert!(write_if_changed(&path, "a\nb\n", Newline::Crlf).unwrap());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\r\nb\r\n");
+
+        // Same content generated with LF is unchanged once normalized
+        assert!(!write_if_changed(&path, "a\nb\n", Newline::Crlf).unwrap());
+
+        // Switching the newline setting rewrites the file
+        assert!(write_if_changed(&path, "a\nb\n", Newline::Lf).unwrap());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\nb\n");
+    }
+
+    #[test]
+    fn test_check_file_destination_rejects_directory() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("output.xml");
+        std::fs::create_dir(&path).unwrap();
+
+        let error = check_file_destination(&path).unwrap_err().to_string();
+        assert!(error.contains("is a directory"), "{}", error);
+        // A missing file below an existing directory is fine, and nothing is created
+        let nested = dir.path().join("out/defines/output.xml");
+        check_file_destination(&nested).unwrap();
+        assert!(!dir.path().join("out").exists());
+        // A file in the way of the parent directory is not
+        std::fs::write(dir.path().join("gen"), "").unwrap();
+        assert!(check_file_destination(&dir.path().join("gen/output.xml")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_dir_destination_read_only_parent() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let cache = dir.path().join("cache");
+        std::fs::create_dir(&cache).unwrap();
+        std::fs::set_permissions(&cache, std::fs::Permissions::from_mode(0o555)).unwrap();
+        // Root ignores permission bits, so there is nothing to check
+        let bypassed = std::fs::File::create(cache.join("probe")).is_ok();
+
+        let result = check_file_destination(&cache.join("tables/beans.ts"));
+        std::fs::set_permissions(&cache, std::fs::Permissions::from_mode(0o755)).unwrap();
+        if bypassed {
+            return;
+        }
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("is not writable"), "{}", error);
+        assert!(error.contains("cache"), "{}", error);
+    }
+}
@@ -1,24 +1,66 @@
 pub mod class_info;
+pub mod comment;
 pub mod decorator;
 pub mod enum_info;
 pub mod field_info;
+pub mod source_text;
 
-pub use class_info::{ClassInfo, ImportMap, LubanTableConfig};
-pub use decorator::{parse_decorator, DecoratorArg};
+pub use class_info::{ClassConstant, ClassInfo, ImportMap, LubanTableConfig};
+pub use comment::CommentPolicy;
+pub use decorator::{parse_decorator, str_value, DecoratorArg};
 pub use enum_info::{EnumInfo, EnumVariant};
-pub use field_info::{FieldInfo, FieldValidators, SizeConstraint};
+pub use field_info::{
+    FieldInfo, FieldOrigin, FieldValidators, FlavorCondition, RangeConstraint, SizeConstraint,
+};
 
 use anyhow::Result;
+use field_info::FieldConverter;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use swc_common::{
     comments::{Comments, SingleThreadedComments},
     sync::Lrc,
-    BytePos, FileName, SourceMap,
+    BytePos, FileName, SourceMap, Spanned,
 };
 use swc_ecma_ast::*;
 use swc_ecma_parser::{Parser, StringInput, Syntax, TsSyntax};
 
+/// Kind of a declaration warning recorded while parsing (see `TsParser::take_class_warnings`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClassWarningKind {
+    /// More than 3 type parameters, or one without an `extends` constraint
+    TypeParams,
+    /// @Parent overrides the parent inferred from extends/implements
+    ParentOverride,
+    /// @Alias and a JSDoc @alias disagree
+    AliasConflict,
+    /// @Tag arguments that can't be written to Luban's tags attribute
+    InvalidTag,
+}
+
+impl ClassWarningKind {
+    /// Warning code in the run's diagnostics
+    pub fn code(self) -> &'static str {
+        match self {
+            ClassWarningKind::TypeParams => "type-params",
+            ClassWarningKind::ParentOverride => "parent-override",
+            ClassWarningKind::AliasConflict => "alias-conflict",
+            ClassWarningKind::InvalidTag => "invalid-tag",
+        }
+    }
+}
+
+/// A warning about a class or field declaration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassWarning {
+    pub kind: ClassWarningKind,
+    /// `{path}:{line}: {subject} - {message}`
+    pub message: String,
+}
+
 /// Extended type info for ObjectFactory and Constructor detection
 struct TypeInfo {
     field_type: String,
@@ -33,17 +75,108 @@ struct TypeInfo {
 
 pub struct TsParser {
     source_map: Lrc<SourceMap>,
+    comment_policy: CommentPolicy,
+    /// Runtime-only type names reported in addition to `RUNTIME_ONLY_TYPES`
+    forbidden_types: Vec<String>,
+    /// Per-file warnings, drained with `take_warnings`
+    warnings: RefCell<Vec<String>>,
+    /// Extract getters as computed fields (defaults.include_getters)
+    include_getters: bool,
+    /// Extract non-exported classes that extracted fields reference
+    /// (defaults.include_private_referenced)
+    include_private_referenced: bool,
+    /// Per-file errors, drained with `take_errors`
+    errors: RefCell<Vec<String>>,
+    /// Per-file notes for verbose output, drained with `take_notes`
+    notes: RefCell<Vec<String>>,
+    /// Declaration warnings of the file being parsed, located once it is done
+    pending_class_warnings: RefCell<Vec<(ClassWarningKind, BytePos, String)>>,
+    /// Per-file declaration warnings, drained with `take_class_warnings`
+    class_warnings: RefCell<Vec<ClassWarning>>,
+    /// Luban type of `any`/`unknown` (defaults.any_type); string when unset
+    any_type: Option<String>,
+    /// `source_file` and message locations are made relative to this root
+    project_root: Option<PathBuf>,
 }
 
 impl TsParser {
     pub fn new() -> Self {
         Self {
             source_map: Default::default(),
+            comment_policy: CommentPolicy::default(),
+            forbidden_types: Vec::new(),
+            warnings: RefCell::new(Vec::new()),
+            include_getters: false,
+            include_private_referenced: false,
+            errors: RefCell::new(Vec::new()),
+            notes: RefCell::new(Vec::new()),
+            pending_class_warnings: RefCell::new(Vec::new()),
+            class_warnings: RefCell::new(Vec::new()),
+            any_type: None,
+            project_root: None,
         }
     }
 
+    /// Normalize class, field, enum and variant comments with this policy (defaults.comment_*)
+    pub fn with_comment_policy(mut self, comment_policy: CommentPolicy) -> Self {
+        self.comment_policy = comment_policy;
+        self
+    }
+
+    /// Also report fields referencing these types (`[forbidden_types]`)
+    pub fn with_forbidden_types(mut self, forbidden_types: Vec<String>) -> Self {
+        self.forbidden_types = forbidden_types;
+        self
+    }
+
+    /// Extract public getters with an explicit return type as readonly fields tagged
+    /// `computed=true` (defaults.include_getters)
+    pub fn with_include_getters(mut self, include_getters: bool) -> Self {
+        self.include_getters = include_getters;
+        self
+    }
+
+    /// Extract classes declared without `export` when an extracted field references them,
+    /// instead of reporting an error (defaults.include_private_referenced)
+    pub fn with_include_private_referenced(mut self, include_private_referenced: bool) -> Self {
+        self.include_private_referenced = include_private_referenced;
+        self
+    }
+
+    /// Luban type `any` and `unknown` convert to (defaults.any_type); string when None.
+    /// Such fields are still marked in `FieldInfo::unsupported_type`
+    pub fn with_any_type(mut self, any_type: Option<String>) -> Self {
+        self.any_type = any_type;
+        self
+    }
+
+    /// Record `source_file` (and report locations) relative to this root instead of as
+    /// given. Paths outside it stay absolute; both use forward slashes
+    pub fn with_project_root(mut self, project_root: &Path) -> Self {
+        self.project_root = Some(project_root.to_path_buf());
+        self
+    }
+
+    /// `source_file` of declarations parsed from `path`
+    fn source_file(&self, path: &Path) -> String {
+        project_relative_path(path, self.project_root.as_deref())
+    }
+
+    /// Parse classes/interfaces from a file. A file that isn't valid UTF-8 is decoded
+    /// lossily, with a warning (see `take_warnings`)
     pub fn parse_file(&self, path: &Path) -> Result<Vec<ClassInfo>> {
-        let content = std::fs::read_to_string(path)?;
+        let content = self.read_source(path)?;
+        self.parse_classes(path, content)
+    }
+
+    /// Parse classes/interfaces from in-memory source (e.g. an unsaved editor buffer).
+    /// `name` is recorded as `source_file`; relative imports resolve against it
+    pub fn parse_source(&self, name: &str, content: &str) -> Result<Vec<ClassInfo>> {
+        self.parse_classes(Path::new(name), content.to_string())
+    }
+
+    fn parse_classes(&self, path: &Path, content: String) -> Result<Vec<ClassInfo>> {
+        let content = source_text::normalize(content);
         let file_hash = compute_hash(&content);
 
         let fm = self
@@ -64,46 +197,398 @@ impl TsParser {
 
         let module = parser
             .parse_module()
-            .map_err(|e| anyhow::anyhow!("Parse error: {:?}", e))?;
+            .map_err(|e| self.parse_error(path, e))?;
+        source_text::drop_garbled_comments(&comments);
 
         // First pass: collect import statements and resolve paths
         let imports = self.extract_imports(&module, path);
 
         let mut classes = Vec::new();
 
+        // Declarations an `export =` may refer to
+        let mut local_decls: HashMap<String, &Decl> = HashMap::new();
+        for item in &module.body {
+            if let ModuleItem::Stmt(Stmt::Decl(decl)) = item {
+                let name = match decl {
+                    Decl::Class(class_decl) => class_decl.ident.sym.to_string(),
+                    Decl::TsInterface(iface_decl) => iface_decl.id.sym.to_string(),
+                    Decl::TsModule(module_decl) => match &module_decl.id {
+                        TsModuleName::Ident(ident) => ident.sym.to_string(),
+                        TsModuleName::Str(_) => continue,
+                    },
+                    _ => continue,
+                };
+                local_decls.insert(name, decl);
+            }
+        }
+
+        let mut unsupported: Vec<(&'static str, BytePos, String)> = Vec::new();
         for item in &module.body {
             match item {
                 ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
-                    let export_pos = export.span.lo;
-                    if let Decl::Class(class_decl) = &export.decl {
-                        if let Some(mut class_info) =
-                            self.extract_class(class_decl, path, &file_hash, &comments, export_pos)
-                        {
-                            class_info.imports = imports.clone();
-                            classes.push(class_info);
-                        }
+                    self.extract_exported_decl(
+                        &export.decl,
+                        path,
+                        &file_hash,
+                        &comments,
+                        export.span.lo,
+                        &mut classes,
+                    );
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::TsExportAssignment(assign)) => {
+                    // `export = Name`: extract the class, interface or namespace it names
+                    let target = match &*assign.expr {
+                        Expr::Ident(ident) => local_decls.get(&*ident.sym).copied(),
+                        _ => None,
+                    };
+                    match target {
+                        Some(decl) => self.extract_exported_decl(
+                            decl,
+                            path,
+                            &file_hash,
+                            &comments,
+                            decl.span_lo(),
+                            &mut classes,
+                        ),
+                        None => unsupported.push((
+                            "export =",
+                            assign.span.lo,
+                            "only a class, interface or namespace declared in the same file is supported"
+                                .to_string(),
+                        )),
                     }
-                    if let Decl::TsInterface(iface_decl) = &export.decl {
-                        if let Some(mut iface_info) = self
-                            .extract_interface(iface_decl, path, &file_hash, &comments, export_pos)
-                        {
-                            iface_info.imports = imports.clone();
-                            classes.push(iface_info);
-                        }
+                }
+                ModuleItem::Stmt(Stmt::Expr(expr_stmt)) => {
+                    if let Some((construct, message)) =
+                        commonjs_export(&expr_stmt.expr, &local_decls)
+                    {
+                        unsupported.push((construct, expr_stmt.span.lo, message));
                     }
                 }
                 ModuleItem::Stmt(Stmt::Decl(Decl::Class(_class_decl))) => {
-                    // Non-exported class - skip
+                    // Non-exported class - skipped unless an extracted field references it
                 }
                 _ => {}
             }
         }
+        let private_classes: HashMap<String, &ClassDecl> = module
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_decl))) => {
+                    Some((class_decl.ident.sym.to_string(), class_decl))
+                }
+                _ => None,
+            })
+            .collect();
+        if !private_classes.is_empty() {
+            self.resolve_private_references(
+                &private_classes,
+                path,
+                &file_hash,
+                &comments,
+                &mut classes,
+            );
+        }
+        for class in &mut classes {
+            class.imports = imports.clone();
+        }
+
+        let source_file = self.source_file(path);
+        let pending = std::mem::take(&mut *self.pending_class_warnings.borrow_mut());
+        for (kind, pos, message) in pending {
+            self.class_warnings.borrow_mut().push(ClassWarning {
+                kind,
+                message: format!("{}:{}: {}", source_file, self.line_of(pos), message),
+            });
+        }
+
+        // One warning per construct, at its first occurrence
+        let mut warnings = self.warnings.borrow_mut();
+        let mut seen = Vec::new();
+        for (construct, pos, message) in &unsupported {
+            if seen.contains(construct) {
+                continue;
+            }
+            seen.push(*construct);
+            let loc = self.source_map.lookup_char_pos(*pos);
+            let count = unsupported
+                .iter()
+                .filter(|(c, _, _)| c == construct)
+                .count();
+            let mut warning = format!(
+                "{}:{}:{}: {} - {}; no classes are extracted from it",
+                self.source_file(path),
+                loc.line,
+                loc.col.0 + 1,
+                construct,
+                message
+            );
+            if count > 1 {
+                warning.push_str(&format!(" ({} occurrences in this file)", count));
+            }
+            warnings.push(warning);
+        }
 
         Ok(classes)
     }
 
+    /// Handle fields typed with a class declared without `export` in the same file: extract it
+    /// (and what it references in turn) under include_private_referenced, marked `no_runtime`
+    /// since beans.ts can't import it; otherwise record an error naming the declaration
+    fn resolve_private_references(
+        &self,
+        private_classes: &HashMap<String, &ClassDecl>,
+        path: &Path,
+        file_hash: &str,
+        comments: &SingleThreadedComments,
+        classes: &mut Vec<ClassInfo>,
+    ) {
+        let mut next = 0;
+        while next < classes.len() {
+            let mut referenced: Vec<&ClassDecl> = Vec::new();
+            let class = &classes[next];
+            for field in &class.fields {
+                let mut names: Vec<&str> = crate::validator::type_names(&field.field_type)
+                    .chain(field.factory_inner_type.as_deref())
+                    .chain(field.constructor_inner_type.as_deref())
+                    .collect();
+                names.sort();
+                names.dedup();
+                for name in names {
+                    let Some(&decl) = private_classes.get(name) else {
+                        continue;
+                    };
+                    if classes.iter().any(|c| c.name == name) {
+                        continue;
+                    }
+                    if self.include_private_referenced {
+                        referenced.push(decl);
+                        continue;
+                    }
+                    let location = match &field.origin {
+                        Some(origin) => origin.to_string(),
+                        None => self.source_file(path),
+                    };
+                    self.errors.borrow_mut().push(format!(
+                        "{}: {}.{} - type '{}' is declared but not exported in the same file (line {}); add `export`",
+                        location,
+                        class.name,
+                        field.name,
+                        name,
+                        self.line_of(decl.class.span.lo)
+                    ));
+                }
+            }
+            for decl in referenced {
+                if classes.iter().any(|c| c.name.as_str() == &*decl.ident.sym) {
+                    continue;
+                }
+                if let Some(mut class_info) =
+                    self.extract_class(decl, path, file_hash, comments, decl.class.span.lo)
+                {
+                    class_info.no_runtime = true;
+                    classes.push(class_info);
+                }
+            }
+            next += 1;
+        }
+    }
+
+    /// Extract an exported (or `export =`) class or interface, or the exported classes and
+    /// interfaces of a namespace, nested namespaces included
+    fn extract_exported_decl(
+        &self,
+        decl: &Decl,
+        path: &Path,
+        file_hash: &str,
+        comments: &SingleThreadedComments,
+        export_pos: BytePos,
+        classes: &mut Vec<ClassInfo>,
+    ) {
+        match decl {
+            Decl::Class(class_decl) => {
+                if let Some(class_info) =
+                    self.extract_class(class_decl, path, file_hash, comments, export_pos)
+                {
+                    classes.push(class_info);
+                }
+            }
+            Decl::TsInterface(iface_decl) => {
+                if let Some(iface_info) =
+                    self.extract_interface(iface_decl, path, file_hash, comments, export_pos)
+                {
+                    classes.push(iface_info);
+                }
+            }
+            // `declare module "pkg"` augments another module; only namespaces are read
+            Decl::TsModule(module_decl) if matches!(module_decl.id, TsModuleName::Ident(_)) => {
+                let mut body = module_decl.body.as_ref();
+                while let Some(TsNamespaceBody::TsNamespaceDecl(nested)) = body {
+                    body = Some(&*nested.body);
+                }
+                let Some(TsNamespaceBody::TsModuleBlock(block)) = body else {
+                    return;
+                };
+                let first = classes.len();
+                for item in &block.body {
+                    if let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) = item {
+                        self.extract_exported_decl(
+                            &export.decl,
+                            path,
+                            file_hash,
+                            comments,
+                            export.span.lo,
+                            classes,
+                        );
+                    }
+                }
+                if module_decl.declare {
+                    for class in &mut classes[first..] {
+                        class.is_ambient = true;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Per-file warnings from the files parsed so far (unsupported export patterns and
+    /// destructured constructor parameter properties), as
+    /// `"{path}:{line}:{column}: {construct} - {message}"`
+    pub fn take_warnings(&self) -> Vec<String> {
+        std::mem::take(&mut *self.warnings.borrow_mut())
+    }
+
+    /// Per-file errors from the files parsed so far (fields typed with a class that isn't
+    /// exported), as `"{path}:{line}: {Class}.{field} - {message}"`
+    pub fn take_errors(&self) -> Vec<String> {
+        std::mem::take(&mut *self.errors.borrow_mut())
+    }
+
+    /// Per-file notes from the files parsed so far (field initializers that aren't literals,
+    /// so no default is taken from them), as `"{path}:{line}: {Class}.{field} - {message}"`
+    pub fn take_notes(&self) -> Vec<String> {
+        std::mem::take(&mut *self.notes.borrow_mut())
+    }
+
+    /// Per-file declaration warnings from the files parsed so far (type parameters, @Parent
+    /// overrides, @Alias conflicts and invalid @Tag arguments)
+    pub fn take_class_warnings(&self) -> Vec<ClassWarning> {
+        std::mem::take(&mut *self.class_warnings.borrow_mut())
+    }
+
+    /// Record a declaration warning at `pos`; `parse_classes` adds the location
+    fn warn_class(&self, kind: ClassWarningKind, pos: BytePos, message: String) {
+        self.pending_class_warnings
+            .borrow_mut()
+            .push((kind, pos, message));
+    }
+
+    /// Pick between an @Alias decorator and an @alias JSDoc tag: the decorator wins,
+    /// with a warning at `pos` when both are present and disagree
+    fn prefer_decorator_alias(
+        &self,
+        owner: &str,
+        pos: BytePos,
+        decorator_alias: Option<String>,
+        jsdoc_alias: Option<String>,
+    ) -> Option<String> {
+        match (decorator_alias, jsdoc_alias) {
+            (Some(dec), Some(doc)) => {
+                if dec != doc {
+                    self.warn_class(
+                        ClassWarningKind::AliasConflict,
+                        pos,
+                        format!(
+                            "{} - @Alias(\"{}\") overrides JSDoc @alias \"{}\"",
+                            owner, dec, doc
+                        ),
+                    );
+                }
+                Some(dec)
+            }
+            (dec, doc) => dec.or(doc),
+        }
+    }
+
+    /// Parse repeatable @Tag("key", "value") decorators into (key, value) pairs.
+    /// Keys/values containing '=' or ',' would corrupt Luban's tags attribute, so they are
+    /// rejected with a warning.
+    fn parse_tag_decorators(&self, decorators: &[Decorator], owner: &str) -> Vec<(String, String)> {
+        let mut tags = Vec::new();
+        for dec in decorators {
+            let Some(parsed) = parse_decorator(dec) else {
+                continue;
+            };
+            if parsed.name != "Tag" {
+                continue;
+            }
+            let (Some(DecoratorArg::String(key)), Some(DecoratorArg::String(value))) =
+                (parsed.args.first(), parsed.args.get(1))
+            else {
+                self.warn_class(
+                    ClassWarningKind::InvalidTag,
+                    dec.span.lo,
+                    format!(
+                        "{} - @Tag expects two string arguments (key, value), skipping",
+                        owner
+                    ),
+                );
+                continue;
+            };
+            if key.is_empty() || key.contains(['=', ',']) || value.contains(['=', ',']) {
+                self.warn_class(
+                    ClassWarningKind::InvalidTag,
+                    dec.span.lo,
+                    format!(
+                        "{} - @Tag(\"{}\", \"{}\") must not be empty or contain '=' or ',', skipping",
+                        owner, key, value
+                    ),
+                );
+                continue;
+            }
+            tags.push((key.clone(), value.clone()));
+        }
+        tags
+    }
+
+    /// Parse enums from a file, decoded like `parse_file`
     pub fn parse_enums(&self, path: &Path) -> Result<Vec<EnumInfo>> {
-        let content = std::fs::read_to_string(path)?;
+        let content = self.read_source(path)?;
+        self.parse_enums_from(path, content)
+    }
+
+    /// Read a source file, warning when it had to be decoded lossily
+    fn read_source(&self, path: &Path) -> Result<String> {
+        let source = source_text::read(path)?;
+        if source.lossy {
+            self.warnings
+                .borrow_mut()
+                .push(source_text::invalid_utf8_message(&self.source_file(path)));
+        }
+        Ok(source.text)
+    }
+
+    /// `{path}:{line}:{column}: parse error: {kind}`
+    fn parse_error(&self, path: &Path, error: swc_ecma_parser::error::Error) -> anyhow::Error {
+        let loc = self.source_map.lookup_char_pos(error.span().lo);
+        anyhow::anyhow!(
+            "{}:{}:{}: parse error: {:?}",
+            self.source_file(path),
+            loc.line,
+            loc.col.0 + 1,
+            error.kind()
+        )
+    }
+
+    /// Parse enums from in-memory source, see `parse_source`
+    pub fn parse_enums_source(&self, name: &str, content: &str) -> Result<Vec<EnumInfo>> {
+        self.parse_enums_from(Path::new(name), content.to_string())
+    }
+
+    fn parse_enums_from(&self, path: &Path, content: String) -> Result<Vec<EnumInfo>> {
+        let content = source_text::normalize(content);
         let file_hash = compute_hash(&content);
 
         let fm = self
@@ -124,7 +609,8 @@ impl TsParser {
 
         let module = parser
             .parse_module()
-            .map_err(|e| anyhow::anyhow!("Parse error: {:?}", e))?;
+            .map_err(|e| self.parse_error(path, e))?;
+        source_text::drop_garbled_comments(&comments);
 
         let mut enums = Vec::new();
 
@@ -144,6 +630,101 @@ impl TsParser {
         Ok(enums)
     }
 
+    /// Relative modules a source imports or re-exports at runtime, resolved like
+    /// `extract_imports`. `import type`, imports whose named bindings are all `type`
+    /// and `export type ... from` are left out since they compile to nothing
+    pub fn parse_runtime_imports(&self, name: &str, content: &str) -> Result<Vec<PathBuf>> {
+        let path = Path::new(name);
+        let module = self.parse_module_only(path, content)?;
+
+        let current_dir = path.parent().unwrap_or(Path::new("."));
+        let mut imports = Vec::new();
+        for item in &module.body {
+            let ModuleItem::ModuleDecl(decl) = item else {
+                continue;
+            };
+            let source = match decl {
+                ModuleDecl::Import(import_decl) => {
+                    let type_only = import_decl.type_only
+                        || (!import_decl.specifiers.is_empty()
+                            && import_decl.specifiers.iter().all(|specifier| {
+                                matches!(specifier, ImportSpecifier::Named(named) if named.is_type_only)
+                            }));
+                    (!type_only).then_some(&import_decl.src)
+                }
+                ModuleDecl::ExportNamed(export) => {
+                    let type_only = export.type_only
+                        || (!export.specifiers.is_empty()
+                            && export.specifiers.iter().all(|specifier| {
+                                matches!(specifier, ExportSpecifier::Named(named) if named.is_type_only)
+                            }));
+                    export.src.as_ref().filter(|_| !type_only)
+                }
+                ModuleDecl::ExportAll(export) => (!export.type_only).then_some(&export.src),
+                _ => None,
+            };
+            let Some(src) = source else {
+                continue;
+            };
+            let source = str_value(src);
+            if !source.starts_with('.') {
+                continue;
+            }
+            if let Some(resolved) = self.resolve_import_path(current_dir, &source) {
+                if !imports.contains(&resolved) {
+                    imports.push(resolved);
+                }
+            }
+        }
+        Ok(imports)
+    }
+
+    /// Module specifiers of every `import` and `export ... from` of a source (type-only
+    /// ones included), with the 1-based line of the specifier, in source order
+    pub fn parse_module_specifiers(
+        &self,
+        name: &str,
+        content: &str,
+    ) -> Result<Vec<(String, usize)>> {
+        let module = self.parse_module_only(Path::new(name), content)?;
+        let mut specifiers = Vec::new();
+        for item in &module.body {
+            let ModuleItem::ModuleDecl(decl) = item else {
+                continue;
+            };
+            let source = match decl {
+                ModuleDecl::Import(import_decl) => Some(&import_decl.src),
+                ModuleDecl::ExportNamed(export) => export.src.as_ref(),
+                ModuleDecl::ExportAll(export) => Some(&export.src),
+                _ => None,
+            };
+            if let Some(src) = source {
+                specifiers.push((str_value(src), self.line_of(src.span.lo)));
+            }
+        }
+        Ok(specifiers)
+    }
+
+    /// Parse a source as a module without extracting anything
+    fn parse_module_only(&self, path: &Path, content: &str) -> Result<Module> {
+        let fm = self.source_map.new_source_file(
+            FileName::Real(path.to_path_buf()).into(),
+            source_text::normalize(content.to_string()),
+        );
+
+        let mut parser = Parser::new(
+            Syntax::Typescript(TsSyntax {
+                tsx: path.extension().map_or(false, |ext| ext == "tsx"),
+                decorators: true,
+                ..Default::default()
+            }),
+            StringInput::from(&*fm),
+            None,
+        );
+
+        parser.parse_module().map_err(|e| self.parse_error(path, e))
+    }
+
     fn extract_enum(
         &self,
         enum_decl: &TsEnumDecl,
@@ -185,17 +766,19 @@ impl TsParser {
         // Get cleaned comment (without @flags, @alias, and @tags lines)
         let enum_comment = raw_enum_comment
             .as_ref()
-            .map(|c| parse_jsdoc_description_excluding_tags(c, &["flags", "alias", "tags"]));
+            .map(|c| self.comment_policy.normalize(c, &["flags", "alias", "tags"]));
 
         let mut variants = Vec::new();
         let mut is_string_enum = false;
-        let mut auto_value = 1i64;
+        // Like TypeScript: the first member without an initializer is 0, later ones follow
+        // the previous numeric value
+        let mut auto_value = 0i64;
         let mut member_values: HashMap<String, i64> = HashMap::new();
 
         for member in &enum_decl.members {
             let member_name = match &member.id {
                 TsEnumMemberId::Ident(ident) => ident.sym.to_string(),
-                TsEnumMemberId::Str(s) => format!("{:?}", s.value).trim_matches('"').to_string(),
+                TsEnumMemberId::Str(s) => str_value(s),
             };
 
             // Get raw member comment
@@ -209,7 +792,7 @@ impl TsParser {
             // Get cleaned comment (without @alias line)
             let member_comment = raw_member_comment
                 .as_ref()
-                .map(|c| parse_jsdoc_description_excluding_tags(c, &["alias"]));
+                .map(|c| self.comment_policy.normalize(c, &["alias"]));
 
             // Determine value and whether it's a string enum
             // numeric_value is used for member_values tracking (for bit operations reference)
@@ -217,9 +800,7 @@ impl TsParser {
                 match &**init {
                     Expr::Lit(Lit::Str(s)) => {
                         // String enum - use original string value
-                        // s.value is Atom type, use format! to convert
-                        let str_val = format!("{:?}", s.value).trim_matches('"').to_string();
-                        (str_val, true, None)
+                        (str_value(s), true, None)
                     }
                     Expr::Lit(Lit::Num(n)) => {
                         // Number enum - use actual value
@@ -270,7 +851,8 @@ impl TsParser {
             is_flags,
             tags: enum_tags,
             variants,
-            source_file: path.to_string_lossy().to_string(),
+            source_file: self.source_file(path),
+            source_path: path.to_path_buf(),
             file_hash: file_hash.to_string(),
             output_path: None,
             module_name: None,
@@ -315,8 +897,7 @@ impl TsParser {
         for item in &module.body {
             if let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = item {
                 // Get the source path (e.g., "./skill-config" or "../resource/resource-config")
-                // src.value is Atom type, use format! to convert
-                let source = format!("{:?}", import_decl.src.value).trim_matches('"').to_string();
+                let source = str_value(&import_decl.src);
 
                 // Only process relative imports (starting with . or ..)
                 if !source.starts_with('.') {
@@ -376,13 +957,51 @@ impl TsParser {
         }
 
         // If no file found, return the base path with .ts extension (for cross-reference)
-        // This allows matching even if the file doesn't exist yet
-        let default_path = PathBuf::from(format!("{}.ts", base_path.display()));
-        default_path.canonicalize().ok().or_else(|| {
-            // If canonicalize fails, try to normalize the path manually
-            let normalized = current_dir.join(import_source);
-            Some(normalized.with_extension("ts"))
-        })
+        // This allows matching even if the file doesn't exist yet (or is an in-memory source)
+        let default_path = if import_source.ends_with(".ts") {
+            base_path
+        } else {
+            PathBuf::from(format!("{}.ts", base_path.display()))
+        };
+        default_path
+            .canonicalize()
+            .ok()
+            .or_else(|| Some(normalize_path(&default_path)))
+    }
+
+    /// Note a field initializer that gives no default: anything but a literal or an enum
+    /// member, unless a @Default / @default already sets one
+    fn note_ignored_initializer(&self, path: &Path, class: &str, field: &FieldInfo, value: &Expr) {
+        if field.default_value.is_some()
+            || initializer_default(value, &field.original_type).is_some()
+        {
+            return;
+        }
+        self.notes.borrow_mut().push(format!(
+            "{}:{}: {}.{} - initializer is not a literal or enum member, no default is emitted",
+            self.source_file(path),
+            self.line_of(value.span_lo()),
+            class,
+            field.name
+        ));
+    }
+
+    /// 1-based source line of a position
+    fn line_of(&self, pos: BytePos) -> usize {
+        self.source_map.lookup_char_pos(pos).line
+    }
+
+    /// Source origin of a field declared at `pos`: project-relative file and 1-based line
+    fn origin_of(&self, pos: BytePos) -> FieldOrigin {
+        let loc = self.source_map.lookup_char_pos(pos);
+        let file = match &*loc.file.name {
+            FileName::Real(path) => self.source_file(path),
+            name => name.to_string(),
+        };
+        FieldOrigin::Source {
+            file,
+            line: loc.line,
+        }
     }
 
     /// Get raw JSDoc comment text (without parsing)
@@ -408,12 +1027,12 @@ impl TsParser {
         comments.get_leading(pos).and_then(|cs| {
             // Try JSDoc block comment first (starts with *)
             if let Some(jsdoc) = cs.iter().filter(|c| c.text.starts_with('*')).last() {
-                return Some(parse_jsdoc_description(&jsdoc.text));
+                return Some(self.comment_policy.normalize(&jsdoc.text, &[]));
             }
             // Fall back to line comment (//)
             cs.iter()
                 .last()
-                .map(|c| c.text.trim().to_string())
+                .map(|c| self.comment_policy.normalize(&c.text, &[]))
                 .filter(|s| !s.is_empty())
         })
     }
@@ -465,9 +1084,13 @@ impl TsParser {
 
         if let Some(params) = type_params {
             if params.params.len() > 3 {
-                eprintln!(
-                    "  Warning: {} has more than 3 type parameters, only first 3 will be processed",
-                    class_name
+                self.warn_class(
+                    ClassWarningKind::TypeParams,
+                    params.span.lo,
+                    format!(
+                        "{} - more than 3 type parameters, only the first 3 are processed",
+                        class_name
+                    ),
                 );
             }
 
@@ -480,8 +1103,14 @@ impl TsParser {
                         self.convert_type_with_params(constraint, &HashMap::new());
                     result.insert(param_name, constraint_type);
                 } else {
-                    eprintln!("  Warning: {}<{}> - type parameter {} at position {} has no constraint (extends), skipping",
-                        class_name, param_name, param_name, i);
+                    self.warn_class(
+                        ClassWarningKind::TypeParams,
+                        param.span.lo,
+                        format!(
+                            "{}<{}> - type parameter {} at position {} has no constraint (extends), skipping",
+                            class_name, param_name, param_name, i
+                        ),
+                    );
                 }
             }
         }
@@ -499,6 +1128,8 @@ impl TsParser {
     ) -> Option<ClassInfo> {
         let name = class_decl.ident.sym.to_string();
         let mut fields = Vec::new();
+        let mut constants = Vec::new();
+        let mut ignored_statics = Vec::new();
         let mut implements = Vec::new();
         let mut extends = None;
 
@@ -545,10 +1176,15 @@ impl TsParser {
             .as_ref()
             .and_then(|c| parse_jsdoc_tag(c, "input"));
 
+        // Parse @module JSDoc tag (e.g., @module shared); @Module("shared") takes precedence
+        let jsdoc_module = raw_class_comment
+            .as_ref()
+            .and_then(|c| parse_jsdoc_module(c));
+
         // Extract class comment (excluding @alias, @table, @input lines)
         let class_comment = raw_class_comment
             .as_ref()
-            .map(|c| parse_jsdoc_description_excluding_tags(c, &["alias", "table", "input"]))
+            .map(|c| self.comment_policy.normalize(c, &["alias", "table", "input"]))
             .filter(|s| !s.is_empty())
             .or_else(|| self.get_leading_comment(export_pos, comments));
         let mut param_comments = self.get_param_comments(export_pos, comments);
@@ -580,9 +1216,30 @@ impl TsParser {
                     // Extract constructor parameters with modifiers
                     for param in &ctor.params {
                         if let ParamOrTsParamProp::TsParamProp(prop) = param {
+                            if let TsParamPropParam::Assign(assign_pat) = &prop.param {
+                                if !matches!(&*assign_pat.left, Pat::Ident(_)) {
+                                    let loc = self.source_map.lookup_char_pos(prop.span.lo);
+                                    self.warnings.borrow_mut().push(format!(
+                                        "{}:{}:{}: {}.constructor - destructured parameter property is not supported and is skipped, declare the fields on the class instead",
+                                        self.source_file(path),
+                                        loc.line,
+                                        loc.col.0 + 1,
+                                        name
+                                    ));
+                                    continue;
+                                }
+                            }
                             if let Some(mut field) =
                                 self.extract_param_prop_with_type_params(prop, &type_params)
                             {
+                                if let TsParamPropParam::Assign(assign_pat) = &prop.param {
+                                    self.note_ignored_initializer(
+                                        path,
+                                        &name,
+                                        &field,
+                                        &assign_pat.right,
+                                    );
+                                }
                                 // Try to get inline comment first (from parameter position)
                                 if field.comment.is_none() {
                                     field.comment = self.get_leading_comment(prop.span.lo, comments);
@@ -602,10 +1259,21 @@ impl TsParser {
                         }
                     }
                 }
+                // Static properties aren't bean fields; readonly literals become constants
+                ClassMember::ClassProp(prop) if prop.is_static => {
+                    match self.extract_class_constant(prop, comments, &type_params) {
+                        Some(Ok(constant)) => constants.push(constant),
+                        Some(Err(static_name)) => ignored_statics.push(static_name),
+                        None => {}
+                    }
+                }
                 ClassMember::ClassProp(prop) => {
                     if let Some(mut field) =
                         self.extract_class_prop_with_type_params(prop, comments, &type_params)
                     {
+                        if let Some(value) = &prop.value {
+                            self.note_ignored_initializer(path, &name, &field, value);
+                        }
                         // Check @param comment if no inline comment
                         if field.comment.is_none() {
                             if let Some(comment) = param_comments.get(&field.name) {
@@ -615,12 +1283,36 @@ impl TsParser {
                         fields.push(field);
                     }
                 }
+                // Getters only with defaults.include_getters; setters are never fields
+                ClassMember::Method(method)
+                    if self.include_getters
+                        && method.kind == MethodKind::Getter
+                        && !method.is_static =>
+                {
+                    if let Some(mut field) = self.extract_getter(method, comments, &type_params) {
+                        if field.comment.is_none() {
+                            if let Some(comment) = param_comments.get(&field.name) {
+                                field.comment = Some(comment.clone());
+                            }
+                        }
+                        fields.push(field);
+                    }
+                }
                 _ => {}
             }
         }
 
-        // Parse class decorators for @LubanTable
+        // @OnlyIn / @ExcludeFrom decorators and JSDoc tags
+        let flavors =
+            parse_flavor_condition(&class_decl.class.decorators, raw_class_comment.as_ref());
+
+        // Parse class decorators for @LubanTable, @RefKey, @Parent, @Module, @Output and @NoRuntime
         let mut luban_table = None;
+        let mut explicit_parent = None;
+        let mut decorator_module = None;
+        let mut ref_key = None;
+        let mut output_route = None;
+        let mut no_runtime = false;
         for dec in &class_decl.class.decorators {
             if let Some(parsed) = parse_decorator(dec) {
                 if parsed.name == "LubanTable" {
@@ -651,6 +1343,65 @@ impl TsParser {
                         }),
                         table_name: None,
                     });
+                } else if parsed.name == "RefKey" {
+                    ref_key = match parsed.args.first() {
+                        Some(DecoratorArg::String(s)) if !s.is_empty() => Some(s.clone()),
+                        _ => None,
+                    };
+                } else if parsed.name == "Parent" {
+                    explicit_parent = match parsed.args.first() {
+                        Some(DecoratorArg::String(s)) => Some(s.clone()),
+                        Some(DecoratorArg::Identifier(s)) => Some(s.clone()),
+                        _ => None,
+                    };
+                } else if parsed.name == "Module" {
+                    decorator_module = match parsed.args.first() {
+                        Some(DecoratorArg::String(s)) => Some(s.clone()),
+                        _ => None,
+                    };
+                } else if parsed.name == "Output" {
+                    output_route = match parsed.args.first() {
+                        Some(DecoratorArg::String(s)) if !s.is_empty() => Some(s.clone()),
+                        _ => None,
+                    };
+                } else if parsed.name == "NoRuntime" {
+                    no_runtime = true;
+                }
+            }
+        }
+
+        // @LubanTable({ index: "..." }) also declares the table index
+        let ref_key = ref_key.or_else(|| {
+            luban_table
+                .as_ref()
+                .map(|t| t.index.clone())
+                .filter(|index| !index.is_empty())
+        });
+
+        let module_name = decorator_module.or(jsdoc_module);
+        let class_tags = self.parse_tag_decorators(&class_decl.class.decorators, &name);
+        let class_alias = self.prefer_decorator_alias(
+            &name,
+            class_decl.ident.span.lo,
+            parse_alias_decorator(&class_decl.class.decorators),
+            class_alias,
+        );
+
+        // Warn when @Parent overrides a parent inferred from extends/implements
+        if let Some(explicit) = &explicit_parent {
+            let inferred = extends
+                .clone()
+                .or_else(|| (implements.len() == 1).then(|| implements[0].clone()));
+            if let Some(inferred) = inferred {
+                if &inferred != explicit {
+                    self.warn_class(
+                        ClassWarningKind::ParentOverride,
+                        class_decl.ident.span.lo,
+                        format!(
+                            "{} - @Parent(\"{}\") overrides parent '{}' from extends/implements",
+                            name, explicit, inferred
+                        ),
+                    );
                 }
             }
         }
@@ -662,16 +1413,32 @@ impl TsParser {
             fields,
             implements,
             extends,
-            source_file: path.to_string_lossy().to_string(),
+            source_file: self.source_file(path),
+            source_path: path.to_path_buf(),
             file_hash: file_hash.to_string(),
             is_interface: false,
             output_path: None,
-            module_name: None,
+            explicit_module: module_name.is_some(),
+            module_name,
             type_params,
             luban_table,
             table_config,
             input_path,
             imports: ImportMap::new(), // Will be filled in by parse_file
+            output_route,
+            is_ambient: class_decl.declare || is_declaration_file(path),
+            constants,
+            ignored_statics,
+            ref_key,
+            tags: class_tags,
+            explicit_parent,
+            is_virtual: false,
+            no_runtime,
+            flavors,
+            examples: raw_class_comment
+                .as_deref()
+                .map(parse_jsdoc_examples)
+                .unwrap_or_default(),
         })
     }
 
@@ -722,10 +1489,18 @@ impl TsParser {
             .as_ref()
             .and_then(|c| parse_jsdoc_tag(c, "input"));
 
+        // Parse @module JSDoc tag (e.g., @module shared)
+        let module_name = raw_iface_comment
+            .as_ref()
+            .and_then(|c| parse_jsdoc_module(c));
+
+        // @onlyIn / @excludeFrom JSDoc tags (interfaces can't have decorators)
+        let flavors = parse_flavor_condition(&[], raw_iface_comment.as_ref());
+
         // Extract interface comment (excluding @alias, @table, @input lines)
         let iface_comment = raw_iface_comment
             .as_ref()
-            .map(|c| parse_jsdoc_description_excluding_tags(c, &["alias", "table", "input"]))
+            .map(|c| self.comment_policy.normalize(c, &["alias", "table", "input"]))
             .filter(|s| !s.is_empty())
             .or_else(|| self.get_leading_comment(export_pos, comments));
         let param_comments = self.get_param_comments(export_pos, comments);
@@ -740,18 +1515,24 @@ impl TsParser {
         });
 
         for member in &iface_decl.body.body {
-            if let TsTypeElement::TsPropertySignature(prop) = member {
-                if let Some(mut field) =
+            let field = match member {
+                TsTypeElement::TsPropertySignature(prop) => {
                     self.extract_interface_prop_with_type_params(prop, comments, &type_params)
-                {
-                    // Check @param comment if no inline comment
-                    if field.comment.is_none() {
-                        if let Some(comment) = param_comments.get(&field.name) {
-                            field.comment = Some(comment.clone());
-                        }
+                }
+                // Getters only with defaults.include_getters; setters are never fields
+                TsTypeElement::TsGetterSignature(getter) if self.include_getters => {
+                    self.extract_getter_signature(getter, comments, &type_params)
+                }
+                _ => None,
+            };
+            if let Some(mut field) = field {
+                // Check @param comment if no inline comment
+                if field.comment.is_none() {
+                    if let Some(comment) = param_comments.get(&field.name) {
+                        field.comment = Some(comment.clone());
                     }
-                    fields.push(field);
                 }
+                fields.push(field);
             }
         }
 
@@ -762,16 +1543,32 @@ impl TsParser {
             fields,
             implements: vec![],
             extends,
-            source_file: path.to_string_lossy().to_string(),
+            source_file: self.source_file(path),
+            source_path: path.to_path_buf(),
             file_hash: file_hash.to_string(),
             is_interface: true,
             output_path: None,
-            module_name: None,
+            explicit_module: module_name.is_some(),
+            module_name,
             type_params,
             luban_table: None,
             table_config,
             input_path,
             imports: ImportMap::new(), // Will be filled in by parse_file
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            is_virtual: false,
+            no_runtime: false,
+            flavors,
+            examples: raw_iface_comment
+                .as_deref()
+                .map(parse_jsdoc_examples)
+                .unwrap_or_default(),
         })
     }
 
@@ -792,13 +1589,10 @@ impl TsParser {
                 ident.id.optional,
             ),
             TsParamPropParam::Assign(assign_pat) => {
-                // 处理带默认值的参数，如 `public flyingDebrisCount = 10`
+                // 处理带默认值的参数，如 `public flyingDebrisCount = 10`：可省略，有默认值
                 match &*assign_pat.left {
-                    Pat::Ident(ident) => (
-                        ident.id.sym.to_string(),
-                        ident.type_ann.as_ref(),
-                        ident.id.optional,
-                    ),
+                    Pat::Ident(ident) => (ident.id.sym.to_string(), ident.type_ann.as_ref(), true),
+                    // Destructured parameters have no single property name (warned by the caller)
                     _ => return None,
                 }
             }
@@ -830,31 +1624,103 @@ impl TsParser {
             });
 
         // Parse field decorators from TsParamProp (including @RefReplace)
-        let (validators, ref_replace) = parse_field_decorators_with_ref_replace(&prop.decorators);
+        let (mut validators, ref_replace) =
+            parse_field_decorators_with_ref_replace(&prop.decorators);
+        let type_error = type_ann
+            .and_then(|ann| apply_literal_union_validators(&ann.type_ann, &mut validators));
+        let unsupported_type = type_ann
+            .and_then(|ann| unsupported_type_construct(&ann.type_ann))
+            .map(str::to_string);
+        let forbidden_type = type_ann.and_then(|ann| self.forbidden_type_ref(&ann.type_ann));
+        let tags = self.parse_tag_decorators(&prop.decorators, &name);
+        // @Default(...) takes precedence over a literal parameter default
+        let default_value =
+            parse_default_decorator(&prop.decorators).or_else(|| match &prop.param {
+                TsParamPropParam::Assign(assign_pat) => {
+                    initializer_default(&assign_pat.right, &type_info.original_type)
+                }
+                TsParamPropParam::Ident(_) => None,
+            });
+        let xml_name = parse_name_decorator(&prop.decorators);
+        let field_alias = parse_alias_decorator(&prop.decorators);
+        let converter = parse_converter_decorator(&prop.decorators);
 
         Some(FieldInfo {
             name,
             field_type: type_info.field_type,
             comment: None,
-            alias: None,
+            alias: field_alias,
             is_optional,
+            is_nullable: type_ann.is_some_and(|ann| is_nullable_union(&ann.type_ann)),
+            is_readonly: prop.readonly
+                || type_ann.is_some_and(|ann| is_readonly_type(&ann.type_ann)),
             validators,
             is_object_factory: type_info.is_object_factory,
             factory_inner_type: type_info.factory_inner_type,
             is_constructor: type_info.is_constructor,
             constructor_inner_type: type_info.constructor_inner_type,
             original_type: type_info.original_type,
-            default_value: None,
+            default_value,
             type_override: None,
             separator: None,
             map_separator: None,
             custom_tags: None,
             ref_key_inner_type: type_info.ref_key_inner_type,
             ref_replace,
+            forbidden_type,
+            order: prop.span.lo.0,
+            unsupported_type,
+            type_error,
+            origin: Some(self.origin_of(prop.span.lo)),
+            flavors: parse_flavor_condition(&prop.decorators, None),
+            converter,
+            xml_name,
+            tags,
+            examples: Vec::new(),
         })
     }
 
     #[allow(dead_code)]
+    /// `static readonly NAME = <literal>` as a class constant.
+    /// Err(name) for other public static properties, None for private/protected ones
+    fn extract_class_constant(
+        &self,
+        prop: &ClassProp,
+        comments: &SingleThreadedComments,
+        type_params: &HashMap<String, String>,
+    ) -> Option<Result<ClassConstant, String>> {
+        if prop.accessibility == Some(Accessibility::Private)
+            || prop.accessibility == Some(Accessibility::Protected)
+        {
+            return None;
+        }
+        let PropName::Ident(ident) = &prop.key else {
+            return None;
+        };
+        let name = ident.sym.to_string();
+
+        let literal = prop
+            .value
+            .as_deref()
+            .filter(|_| prop.readonly)
+            .and_then(literal_value);
+        let Some((literal_type, value)) = literal else {
+            return Some(Err(name));
+        };
+        let const_type = prop
+            .type_ann
+            .as_ref()
+            .map(|ann| self.convert_type_extended(&ann.type_ann, type_params).field_type)
+            .unwrap_or(literal_type);
+
+        Some(Ok(ClassConstant {
+            name,
+            const_type,
+            value,
+            comment: self.get_leading_comment(prop.span.lo, comments),
+        }))
+    }
+
     fn extract_class_prop(
         &self,
         prop: &ClassProp,
@@ -909,10 +1775,25 @@ impl TsParser {
         let raw_comment = self.get_raw_leading_comment(prop.span.lo, comments);
 
         // Parse @alias tag from field comment
-        let field_alias = raw_comment.as_ref().and_then(|c| parse_jsdoc_tag(c, "alias"));
+        let field_alias = self.prefer_decorator_alias(
+            &name,
+            prop.span.lo,
+            parse_alias_decorator(&prop.decorators),
+            raw_comment.as_ref().and_then(|c| parse_jsdoc_tag(c, "alias")),
+        );
 
         // Parse new JSDoc tags for field modifiers
-        let default_value = raw_comment.as_ref().and_then(|c| parse_jsdoc_tag(c, "default"));
+        // @Default(...) decorator takes precedence over the @default JSDoc tag, which takes
+        // precedence over a literal initializer (`count: number = 3`, `mode = Mode.Auto`)
+        let default_value = parse_default_decorator(&prop.decorators)
+            .or_else(|| raw_comment.as_ref().and_then(|c| parse_jsdoc_tag(c, "default")))
+            .or_else(|| {
+                let value = prop.value.as_deref()?;
+                initializer_default(value, &type_info.original_type)
+            });
+        // @Name(...) decorator takes precedence over the @luban-name JSDoc tag
+        let xml_name = parse_name_decorator(&prop.decorators)
+            .or_else(|| raw_comment.as_ref().and_then(|c| parse_jsdoc_tag(c, "luban-name")));
         let type_override = raw_comment.as_ref().and_then(|c| parse_jsdoc_tag(c, "type"));
         let separator = raw_comment.as_ref().and_then(|c| parse_jsdoc_tag(c, "sep"));
         let map_separator = raw_comment.as_ref().and_then(|c| parse_jsdoc_tag(c, "mapsep"));
@@ -924,13 +1805,28 @@ impl TsParser {
         // Get cleaned comment (without @alias and other JSDoc modifier lines)
         let comment = raw_comment
             .as_ref()
-            .map(|c| parse_jsdoc_description_excluding_tags(c, &["alias", "default", "type", "sep", "mapsep", "tags", "ref"]))
+            .map(|c| self.comment_policy.normalize(c, &["alias", "default", "type", "sep", "mapsep", "tags", "ref", "luban-name"]))
             .filter(|c| !c.is_empty());
 
         // Parse field decorators from ClassProp (including @RefReplace)
         let (mut validators, ref_replace) = parse_field_decorators_with_ref_replace(&prop.decorators);
+        let tags = self.parse_tag_decorators(&prop.decorators, &name);
+        let converter = parse_converter_decorator(&prop.decorators);
         validators.has_ref = has_ref;
         validators.has_ref_key = type_info.ref_key_inner_type.is_some();
+        let type_error = prop
+            .type_ann
+            .as_ref()
+            .and_then(|ann| apply_literal_union_validators(&ann.type_ann, &mut validators));
+        let unsupported_type = prop
+            .type_ann
+            .as_ref()
+            .and_then(|ann| unsupported_type_construct(&ann.type_ann))
+            .map(str::to_string);
+        let forbidden_type = prop
+            .type_ann
+            .as_ref()
+            .and_then(|ann| self.forbidden_type_ref(&ann.type_ann));
 
         Some(FieldInfo {
             name,
@@ -938,6 +1834,15 @@ impl TsParser {
             comment,
             alias: field_alias,
             is_optional: prop.is_optional,
+            is_nullable: prop
+                .type_ann
+                .as_ref()
+                .is_some_and(|ann| is_nullable_union(&ann.type_ann)),
+            is_readonly: prop.readonly
+                || prop
+                    .type_ann
+                    .as_ref()
+                    .is_some_and(|ann| is_readonly_type(&ann.type_ann)),
             validators,
             is_object_factory: type_info.is_object_factory,
             factory_inner_type: type_info.factory_inner_type,
@@ -951,21 +1856,81 @@ impl TsParser {
             custom_tags,
             ref_key_inner_type: type_info.ref_key_inner_type,
             ref_replace,
+            forbidden_type,
+            order: prop.span.lo.0,
+            unsupported_type,
+            type_error,
+            origin: Some(self.origin_of(prop.span.lo)),
+            flavors: parse_flavor_condition(&prop.decorators, raw_comment.as_ref()),
+            converter,
+            xml_name,
+            tags,
+            examples: raw_comment
+                .as_deref()
+                .map(parse_jsdoc_examples)
+                .unwrap_or_default(),
         })
     }
 
-    #[allow(dead_code)]
-    fn extract_interface_prop(
+    /// A getter with an explicit return type, read like a readonly property and tagged
+    /// `computed=true`
+    fn extract_getter(
         &self,
-        prop: &TsPropertySignature,
+        method: &ClassMethod,
         comments: &SingleThreadedComments,
+        type_params: &HashMap<String, String>,
     ) -> Option<FieldInfo> {
-        self.extract_interface_prop_with_type_params(prop, comments, &HashMap::new())
+        let prop = ClassProp {
+            span: method.span,
+            key: method.key.clone(),
+            type_ann: Some(method.function.return_type.clone()?),
+            decorators: method.function.decorators.clone(),
+            accessibility: method.accessibility,
+            readonly: true,
+            ..Default::default()
+        };
+        let mut field = self.extract_class_prop_with_type_params(&prop, comments, type_params)?;
+        field
+            .tags
+            .push(("computed".to_string(), "true".to_string()));
+        Some(field)
     }
 
-    fn extract_interface_prop_with_type_params(
+    /// Interface counterpart of `extract_getter`
+    fn extract_getter_signature(
         &self,
-        prop: &TsPropertySignature,
+        getter: &TsGetterSignature,
+        comments: &SingleThreadedComments,
+        type_params: &HashMap<String, String>,
+    ) -> Option<FieldInfo> {
+        let prop = TsPropertySignature {
+            span: getter.span,
+            key: getter.key.clone(),
+            computed: getter.computed,
+            type_ann: Some(getter.type_ann.clone()?),
+            readonly: true,
+            optional: false,
+        };
+        let mut field =
+            self.extract_interface_prop_with_type_params(&prop, comments, type_params)?;
+        field
+            .tags
+            .push(("computed".to_string(), "true".to_string()));
+        Some(field)
+    }
+
+    #[allow(dead_code)]
+    fn extract_interface_prop(
+        &self,
+        prop: &TsPropertySignature,
+        comments: &SingleThreadedComments,
+    ) -> Option<FieldInfo> {
+        self.extract_interface_prop_with_type_params(prop, comments, &HashMap::new())
+    }
+
+    fn extract_interface_prop_with_type_params(
+        &self,
+        prop: &TsPropertySignature,
         comments: &SingleThreadedComments,
         type_params: &HashMap<String, String>,
     ) -> Option<FieldInfo> {
@@ -996,6 +1961,7 @@ impl TsParser {
 
         // Parse new JSDoc tags for field modifiers
         let default_value = raw_comment.as_ref().and_then(|c| parse_jsdoc_tag(c, "default"));
+        let xml_name = raw_comment.as_ref().and_then(|c| parse_jsdoc_tag(c, "luban-name"));
         let type_override = raw_comment.as_ref().and_then(|c| parse_jsdoc_tag(c, "type"));
         let separator = raw_comment.as_ref().and_then(|c| parse_jsdoc_tag(c, "sep"));
         let map_separator = raw_comment.as_ref().and_then(|c| parse_jsdoc_tag(c, "mapsep"));
@@ -1007,15 +1973,28 @@ impl TsParser {
         // Get cleaned comment (without @alias and other JSDoc modifier lines)
         let comment = raw_comment
             .as_ref()
-            .map(|c| parse_jsdoc_description_excluding_tags(c, &["alias", "default", "type", "sep", "mapsep", "tags", "ref"]))
+            .map(|c| self.comment_policy.normalize(c, &["alias", "default", "type", "sep", "mapsep", "tags", "ref", "luban-name"]))
             .filter(|c| !c.is_empty());
 
         // Build validators with JSDoc tags
-        let validators = FieldValidators {
+        let mut validators = FieldValidators {
             has_ref,
             has_ref_key: type_info.ref_key_inner_type.is_some(),
             ..Default::default()
         };
+        let type_error = prop
+            .type_ann
+            .as_ref()
+            .and_then(|ann| apply_literal_union_validators(&ann.type_ann, &mut validators));
+        let unsupported_type = prop
+            .type_ann
+            .as_ref()
+            .and_then(|ann| unsupported_type_construct(&ann.type_ann))
+            .map(str::to_string);
+        let forbidden_type = prop
+            .type_ann
+            .as_ref()
+            .and_then(|ann| self.forbidden_type_ref(&ann.type_ann));
 
         Some(FieldInfo {
             name,
@@ -1023,6 +2002,15 @@ impl TsParser {
             comment,
             alias: field_alias,
             is_optional: prop.optional,
+            is_nullable: prop
+                .type_ann
+                .as_ref()
+                .is_some_and(|ann| is_nullable_union(&ann.type_ann)),
+            is_readonly: prop.readonly
+                || prop
+                    .type_ann
+                    .as_ref()
+                    .is_some_and(|ann| is_readonly_type(&ann.type_ann)),
             validators,
             is_object_factory: type_info.is_object_factory,
             factory_inner_type: type_info.factory_inner_type,
@@ -1036,6 +2024,19 @@ impl TsParser {
             custom_tags,
             ref_key_inner_type: type_info.ref_key_inner_type,
             ref_replace: None, // Interfaces don't support decorators
+            forbidden_type,
+            order: prop.span.lo.0,
+            unsupported_type,
+            type_error,
+            origin: Some(self.origin_of(prop.span.lo)),
+            flavors: parse_flavor_condition(&[], raw_comment.as_ref()),
+            converter: None,
+            xml_name,
+            tags: Vec::new(),
+            examples: raw_comment
+                .as_deref()
+                .map(parse_jsdoc_examples)
+                .unwrap_or_default(),
         })
     }
 
@@ -1050,6 +2051,13 @@ impl TsParser {
         ts_type: &TsType,
         type_params: &HashMap<String, String>,
     ) -> TypeInfo {
+        // `readonly T[]` is `T[]` for every pattern below
+        if let TsType::TsTypeOperator(op) = ts_type {
+            if op.op == TsTypeOperatorOp::ReadOnly {
+                return self.convert_type_extended(&op.type_ann, type_params);
+            }
+        }
+
         // Check for $type<T> pattern first - extract inner type directly
         if let TsType::TsTypeRef(type_ref) = ts_type {
             if let TsEntityName::Ident(ident) = &type_ref.type_name {
@@ -1225,7 +2233,7 @@ impl TsParser {
         // Check for Map<RefKey<T>, V> pattern - detect RefKey in map key position
         if let TsType::TsTypeRef(type_ref) = ts_type {
             if let TsEntityName::Ident(ident) = &type_ref.type_name {
-                if ident.sym.to_string() == "Map" || ident.sym.to_string() == "Record" {
+                if matches!(&*ident.sym, "Map" | "ReadonlyMap" | "Record") {
                     if let Some(params) = &type_ref.type_params {
                         if params.params.len() >= 2 {
                             // Check if key type is RefKey<T>
@@ -1256,6 +2264,19 @@ impl TsParser {
             }
         }
 
+        // Keep number-literal unions as written, e.g. for typing table keys in tables.d.ts
+        if let Some(Ok(values)) = numeric_literal_union(ts_type) {
+            return TypeInfo {
+                field_type: original_type,
+                original_type: values.join(" | "),
+                is_object_factory: false,
+                factory_inner_type: None,
+                is_constructor: false,
+                constructor_inner_type: None,
+                ref_key_inner_type: None,
+            };
+        }
+
         TypeInfo {
             field_type: original_type.clone(),
             original_type,
@@ -1393,12 +2414,23 @@ impl TsParser {
                 TsKeywordTypeKind::TsNumberKeyword => "double".to_string(),
                 TsKeywordTypeKind::TsStringKeyword => "string".to_string(),
                 TsKeywordTypeKind::TsBooleanKeyword => "bool".to_string(),
+                TsKeywordTypeKind::TsAnyKeyword | TsKeywordTypeKind::TsUnknownKeyword => self
+                    .any_type
+                    .clone()
+                    .unwrap_or_else(|| "string".to_string()),
                 _ => "string".to_string(),
             },
             TsType::TsArrayType(arr) => {
-                let element_type = self.convert_type_with_params(&arr.elem_type, type_params);
+                let element_type = self.convert_element_type(&arr.elem_type, type_params);
                 format!("list,{}", element_type)
             }
+            TsType::TsParenthesizedType(paren) => {
+                self.convert_type_with_params(&paren.type_ann, type_params)
+            }
+            // `readonly T[]` converts like `T[]`
+            TsType::TsTypeOperator(op) if op.op == TsTypeOperatorOp::ReadOnly => {
+                self.convert_type_with_params(&op.type_ann, type_params)
+            }
             TsType::TsTypeRef(type_ref) => {
                 let type_name = match &type_ref.type_name {
                     TsEntityName::Ident(ident) => ident.sym.to_string(),
@@ -1423,8 +2455,7 @@ impl TsParser {
                     "Array" | "ReadonlyArray" => {
                         if let Some(params) = &type_ref.type_params {
                             if let Some(first) = params.params.first() {
-                                let element_type =
-                                    self.convert_type_with_params(first, type_params);
+                                let element_type = self.convert_element_type(first, type_params);
                                 return format!("list,{}", element_type);
                             }
                         }
@@ -1435,18 +2466,18 @@ impl TsParser {
                             if let Some(first) = params.params.first() {
                                 let element_type =
                                     self.convert_type_with_params(first, type_params);
-                                return format!("set,{}", element_type);
+                                return format!("set,{}", nest_container(element_type));
                             }
                         }
                         "set,string".to_string()
                     }
-                    "Map" | "Record" => {
+                    "Map" | "ReadonlyMap" | "Record" => {
                         if let Some(params) = &type_ref.type_params {
                             if params.params.len() >= 2 {
                                 let key_type =
                                     self.convert_type_with_params(&params.params[0], type_params);
                                 let value_type =
-                                    self.convert_type_with_params(&params.params[1], type_params);
+                                    self.convert_element_type(&params.params[1], type_params);
                                 return format!("map,{},{}", key_type, value_type);
                             }
                         }
@@ -1456,6 +2487,10 @@ impl TsParser {
                 }
             }
             TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(union)) => {
+                // `1 | 2 | 3` is an int restricted to those values (see apply_literal_union_validators)
+                if let Some(Ok(_)) = numeric_literal_union(ts_type) {
+                    return "int".to_string();
+                }
                 // Take first non-undefined/null type
                 for member in &union.types {
                     match &**member {
@@ -1476,6 +2511,109 @@ impl TsParser {
             _ => "string".to_string(),
         }
     }
+
+    /// First runtime-only type (Roblox Instance classes, `Promise`, `[forbidden_types]` names)
+    /// referenced anywhere in a field type: containers, generic arguments and unions included
+    fn forbidden_type_ref(&self, ts_type: &TsType) -> Option<String> {
+        match ts_type {
+            TsType::TsArrayType(arr) => self.forbidden_type_ref(&arr.elem_type),
+            TsType::TsParenthesizedType(paren) => self.forbidden_type_ref(&paren.type_ann),
+            TsType::TsTypeOperator(op) if op.op == TsTypeOperatorOp::ReadOnly => {
+                self.forbidden_type_ref(&op.type_ann)
+            }
+            TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(union)) => {
+                union
+                    .types
+                    .iter()
+                    .find_map(|member| self.forbidden_type_ref(member))
+            }
+            TsType::TsTypeRef(type_ref) => {
+                let TsEntityName::Ident(ident) = &type_ref.type_name else {
+                    return None;
+                };
+                let name = ident.sym.as_ref();
+                if RUNTIME_ONLY_TYPES.contains(&name)
+                    || self.forbidden_types.iter().any(|t| t == name)
+                {
+                    return Some(name.to_string());
+                }
+                type_ref
+                    .type_params
+                    .as_ref()?
+                    .params
+                    .iter()
+                    .find_map(|param| self.forbidden_type_ref(param))
+            }
+            _ => None,
+        }
+    }
+
+    /// Convert a list element or map value type, marking it nullable (`ItemDrop?`) when the
+    /// TS type is a union with null/undefined. Nested containers are never marked.
+    /// The pipeline strips the marker unless defaults.nullable_elements is set
+    fn convert_element_type(
+        &self,
+        ts_type: &TsType,
+        type_params: &HashMap<String, String>,
+    ) -> String {
+        let element_type = self.convert_type_with_params(ts_type, type_params);
+        if is_nullable_union(ts_type) && !element_type.contains(',') {
+            format!("{}?", element_type)
+        } else {
+            nest_container(element_type)
+        }
+    }
+}
+
+/// An element, key or value type that is a container itself, parenthesized so the
+/// enclosing container keeps its shape: `list,(list,double)`, `map,string,(list,Item)`
+fn nest_container(element_type: String) -> String {
+    if crate::type_mapper::container_of(&element_type).is_some() {
+        format!("({})", element_type)
+    } else {
+        element_type
+    }
+}
+
+/// A union such as `T | null` or `T | undefined` (parentheses allowed) with at least
+/// one member that isn't null/undefined
+fn is_nullable_union(ts_type: &TsType) -> bool {
+    match ts_type {
+        TsType::TsParenthesizedType(paren) => is_nullable_union(&paren.type_ann),
+        TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(union)) => {
+            let is_nullish = |member: &TsType| {
+                matches!(
+                    member,
+                    TsType::TsKeywordType(kw) if matches!(
+                        kw.kind,
+                        TsKeywordTypeKind::TsUndefinedKeyword | TsKeywordTypeKind::TsNullKeyword
+                    )
+                )
+            };
+            union.types.iter().any(|m| is_nullish(m)) && !union.types.iter().all(|m| is_nullish(m))
+        }
+        _ => false,
+    }
+}
+
+/// A read-only container type: `readonly T[]`, `ReadonlyArray<T>`, `ReadonlyMap<K, V>` or
+/// `ReadonlySet<T>` (parentheses and `| undefined` allowed)
+fn is_readonly_type(ts_type: &TsType) -> bool {
+    match ts_type {
+        TsType::TsTypeOperator(op) => op.op == TsTypeOperatorOp::ReadOnly,
+        TsType::TsParenthesizedType(paren) => is_readonly_type(&paren.type_ann),
+        TsType::TsTypeRef(type_ref) => matches!(
+            &type_ref.type_name,
+            TsEntityName::Ident(ident) if matches!(
+                &*ident.sym,
+                "ReadonlyArray" | "ReadonlyMap" | "ReadonlySet"
+            )
+        ),
+        TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(union)) => {
+            union.types.iter().any(|member| is_readonly_type(member))
+        }
+        _ => false,
+    }
 }
 
 impl Default for TsParser {
@@ -1484,29 +2622,387 @@ impl Default for TsParser {
     }
 }
 
-fn compute_hash(content: &str) -> String {
-    use md5::{Digest, Md5};
-    let mut hasher = Md5::new();
-    hasher.update(content.as_bytes());
-    format!("{:x}", hasher.finalize())
+/// Values of a union of number literals (`1 | 2 | 3`, null/undefined ignored).
+/// None when the type has no number literal members, an error when they are mixed
+/// with other types or aren't integers
+fn numeric_literal_union(ts_type: &TsType) -> Option<Result<Vec<String>, String>> {
+    let TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(union)) = ts_type
+    else {
+        return None;
+    };
+
+    let mut values = Vec::new();
+    let mut members = Vec::new();
+    let mut mixed = false;
+    for member in &union.types {
+        match &**member {
+            TsType::TsKeywordType(kw)
+                if matches!(
+                    kw.kind,
+                    TsKeywordTypeKind::TsUndefinedKeyword | TsKeywordTypeKind::TsNullKeyword
+                ) => {}
+            TsType::TsLitType(TsLitType {
+                lit: TsLit::Number(num),
+                ..
+            }) => {
+                let value = if num.value.fract() == 0.0 {
+                    format!("{}", num.value as i64)
+                } else {
+                    mixed = true;
+                    num.value.to_string()
+                };
+                members.push(value.clone());
+                values.push(value);
+            }
+            TsType::TsLitType(TsLitType {
+                lit: TsLit::Str(s), ..
+            }) => {
+                mixed = true;
+                members.push(format!("\"{}\"", str_value(s)));
+            }
+            TsType::TsKeywordType(kw) => {
+                mixed = true;
+                members.push(
+                    match kw.kind {
+                        TsKeywordTypeKind::TsNumberKeyword => "number",
+                        TsKeywordTypeKind::TsStringKeyword => "string",
+                        TsKeywordTypeKind::TsBooleanKeyword => "boolean",
+                        _ => "unknown",
+                    }
+                    .to_string(),
+                );
+            }
+            TsType::TsTypeRef(TsTypeRef {
+                type_name: TsEntityName::Ident(ident),
+                ..
+            }) => {
+                mixed = true;
+                members.push(ident.sym.to_string());
+            }
+            _ => {
+                mixed = true;
+                members.push("...".to_string());
+            }
+        }
+    }
+
+    if values.is_empty() {
+        return None;
+    }
+    if mixed {
+        return Some(Err(format!(
+            "union `{}` must contain only integer literals (or a single type) to be used as a map key",
+            members.join(" | ")
+        )));
+    }
+    Some(Ok(values))
 }
 
-/// Parse JSDoc comment to extract the description (first line before any @tags)
-fn parse_jsdoc_description(text: &str) -> String {
-    let mut description = String::new();
-    for line in text.lines() {
-        let line = line.trim().trim_start_matches('*').trim();
-        if line.starts_with('@') {
-            break;
+/// Luban type and value of a literal initializer (`80`, `-1.5`, `"abc"`, `true`, `80 as const`)
+fn literal_value(expr: &Expr) -> Option<(String, String)> {
+    let number = |value: f64| {
+        if value.fract() == 0.0 {
+            ("int".to_string(), format!("{}", value as i64))
+        } else {
+            ("double".to_string(), value.to_string())
+        }
+    };
+    match expr {
+        Expr::Lit(Lit::Num(num)) => Some(number(num.value)),
+        Expr::Unary(UnaryExpr {
+            op: UnaryOp::Minus,
+            arg,
+            ..
+        }) => match &**arg {
+            Expr::Lit(Lit::Num(num)) => Some(number(-num.value)),
+            _ => None,
+        },
+        Expr::Lit(Lit::Str(s)) => Some(("string".to_string(), str_value(s))),
+        Expr::Lit(Lit::Bool(b)) => Some(("bool".to_string(), b.value.to_string())),
+        Expr::TsConstAssertion(TsConstAssertion { expr, .. })
+        | Expr::Paren(ParenExpr { expr, .. }) => literal_value(expr),
+        _ => None,
+    }
+}
+
+/// Default value of a field initializer: a number, string or boolean literal, or a member
+/// of the field's enum type (`mode: Mode = Mode.Auto`, written as the member name like
+/// Luban's enum data)
+fn initializer_default(expr: &Expr, declared_type: &str) -> Option<String> {
+    if let Some((_, value)) = literal_value(expr) {
+        return Some(value);
+    }
+    match expr {
+        Expr::Member(MemberExpr {
+            obj,
+            prop: MemberProp::Ident(member),
+            ..
+        }) => match &**obj {
+            Expr::Ident(enum_name) if &*enum_name.sym == declared_type => {
+                Some(member.sym.to_string())
+            }
+            _ => None,
+        },
+        Expr::TsConstAssertion(TsConstAssertion { expr, .. })
+        | Expr::Paren(ParenExpr { expr, .. }) => initializer_default(expr, declared_type),
+        _ => None,
+    }
+}
+
+/// Types that only exist at runtime and can never be stored in a config: Roblox Instance
+/// classes, script signals and async wrappers. Classes defined in the parsed sources
+/// with the same name are not reported (see `validator::find_forbidden_types`)
+pub const RUNTIME_ONLY_TYPES: &[&str] = &[
+    // Async wrappers
+    "Promise",
+    "PromiseLike",
+    // Script signals
+    "RBXScriptSignal",
+    "RBXScriptConnection",
+    // Instance classes
+    "Instance",
+    "PVInstance",
+    "BasePart",
+    "Part",
+    "MeshPart",
+    "UnionOperation",
+    "Model",
+    "Folder",
+    "Workspace",
+    "Player",
+    "Humanoid",
+    "Camera",
+    "Tool",
+    "Attachment",
+    "Sound",
+    "Animation",
+    "AnimationTrack",
+    "Animator",
+    "Decal",
+    "Texture",
+    "ParticleEmitter",
+    "GuiObject",
+    "ScreenGui",
+    "BillboardGui",
+    "Frame",
+    "TextLabel",
+    "ImageLabel",
+    "RemoteEvent",
+    "RemoteFunction",
+    "BindableEvent",
+    "Script",
+    "LocalScript",
+    "ModuleScript",
+];
+
+/// Name of a type construct that `convert_type` can only turn into string:
+/// `keyof T`, `typeof x`, a conditional type, or `any`/`unknown` (string unless
+/// defaults.any_type sets another type), anywhere the conversion looks
+fn unsupported_type_construct(ts_type: &TsType) -> Option<&'static str> {
+    match ts_type {
+        TsType::TsTypeOperator(op) if op.op == TsTypeOperatorOp::KeyOf => Some("keyof"),
+        TsType::TsTypeQuery(_) => Some("typeof"),
+        TsType::TsConditionalType(_) => Some("conditional type"),
+        TsType::TsKeywordType(kw) => match kw.kind {
+            TsKeywordTypeKind::TsAnyKeyword => Some("any"),
+            TsKeywordTypeKind::TsUnknownKeyword => Some("unknown"),
+            _ => None,
+        },
+        TsType::TsArrayType(arr) => unsupported_type_construct(&arr.elem_type),
+        TsType::TsTypeOperator(op) if op.op == TsTypeOperatorOp::ReadOnly => {
+            unsupported_type_construct(&op.type_ann)
+        }
+        TsType::TsTypeRef(type_ref) => type_ref
+            .type_params
+            .as_ref()?
+            .params
+            .iter()
+            .find_map(|param| unsupported_type_construct(param)),
+        // Unions convert to their first non-null member
+        TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(union)) => union
+            .types
+            .iter()
+            .find(|member| {
+                !matches!(
+                    &***member,
+                    TsType::TsKeywordType(kw) if matches!(
+                        kw.kind,
+                        TsKeywordTypeKind::TsUndefinedKeyword | TsKeywordTypeKind::TsNullKeyword
+                    )
+                )
+            })
+            .and_then(|member| unsupported_type_construct(member)),
+        _ => None,
+    }
+}
+
+/// Key type of a `Map<K, V>` / `ReadonlyMap<K, V>` / `Record<K, V>`
+fn map_key_type(ts_type: &TsType) -> Option<&TsType> {
+    let TsType::TsTypeRef(type_ref) = ts_type else {
+        return None;
+    };
+    let TsEntityName::Ident(ident) = &type_ref.type_name else {
+        return None;
+    };
+    if !matches!(&*ident.sym, "Map" | "ReadonlyMap" | "Record") {
+        return None;
+    }
+    let params = type_ref.type_params.as_ref()?;
+    (params.params.len() >= 2).then(|| &*params.params[0])
+}
+
+/// Turn number-literal unions into `#set=` validators: on the field itself (`tier: 1 | 2`,
+/// unless @Set is given) or on the key of a map (`Record<1 | 2, T>`).
+/// Returns an error message when a map key union mixes number literals with other types
+fn apply_literal_union_validators(
+    ts_type: &TsType,
+    validators: &mut FieldValidators,
+) -> Option<String> {
+    if let Some(Ok(values)) = numeric_literal_union(ts_type) {
+        if validators.set_values.is_empty() {
+            validators.set_values = values;
+        }
+        return None;
+    }
+
+    match map_key_type(ts_type).and_then(numeric_literal_union)? {
+        Ok(values) => {
+            validators.key_set_values = values;
+            None
+        }
+        Err(e) => Some(e),
+    }
+}
+
+/// Whether the file is a TypeScript declaration file (.d.ts/.d.mts/.d.cts)
+/// CommonJS-style export statements the parser can't follow: `module.exports = ...`,
+/// `exports.X = ...`, and classes assigned onto an object (`Bundle.Skill = SkillConfig`).
+/// Returns the construct and what was found
+fn commonjs_export(
+    expr: &Expr,
+    local_decls: &HashMap<String, &Decl>,
+) -> Option<(&'static str, String)> {
+    let Expr::Assign(assign) = expr else {
+        return None;
+    };
+    let AssignTarget::Simple(SimpleAssignTarget::Member(member)) = &assign.left else {
+        return None;
+    };
+    // Innermost object of `a.b.c`, and the property right after it
+    let mut obj = &*member.obj;
+    let mut first_prop = &member.prop;
+    while let Expr::Member(inner) = obj {
+        first_prop = &inner.prop;
+        obj = &*inner.obj;
+    }
+    let root = match obj {
+        Expr::Ident(ident) => ident.sym.to_string(),
+        _ => return None,
+    };
+    let first_prop = match first_prop {
+        MemberProp::Ident(ident) => Some(ident.sym.to_string()),
+        _ => None,
+    };
+    if root == "exports" || (root == "module" && first_prop.as_deref() == Some("exports")) {
+        let target = if root == "exports" {
+            "exports"
+        } else {
+            "module.exports"
+        };
+        return Some((
+            "module.exports",
+            format!(
+                "assignment to `{}` is not supported, use `export class` or `export =`",
+                target
+            ),
+        ));
+    }
+
+    let class_name = match &*assign.right {
+        Expr::Class(class_expr) => Some(
+            class_expr
+                .ident
+                .as_ref()
+                .map_or("<anonymous>".to_string(), |ident| ident.sym.to_string()),
+        ),
+        Expr::Ident(ident) if matches!(local_decls.get(&*ident.sym), Some(Decl::Class(_))) => {
+            Some(ident.sym.to_string())
         }
-        if !line.is_empty() {
-            if !description.is_empty() {
-                description.push(' ');
+        _ => None,
+    }?;
+    Some((
+        "namespace object assignment",
+        format!(
+            "class '{}' is assigned onto '{}', declare it with `export class` inside an exported namespace instead",
+            class_name, root
+        ),
+    ))
+}
+
+fn is_declaration_file(path: &Path) -> bool {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    [".d.ts", ".d.mts", ".d.cts"]
+        .iter()
+        .any(|ext| file_name.ends_with(ext))
+}
+
+/// `path` with forward slashes, whatever separator it was given with
+pub fn slash_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// `path` relative to `root` with forward slashes, or the whole path (also with forward
+/// slashes) when it is not under `root`. Both are resolved lexically and compared in
+/// forward-slash form, so Windows-style input gives the same result on every platform
+pub fn project_relative_path(path: &Path, root: Option<&Path>) -> String {
+    let lexical = |path: &Path| slash_path(&normalize_path(Path::new(&slash_path(path))));
+    let path = lexical(path);
+    let Some(root) = root.map(lexical) else {
+        return path;
+    };
+    if root.is_empty() {
+        return path;
+    }
+    match path
+        .strip_prefix(root.trim_end_matches('/'))
+        .and_then(|rest| rest.strip_prefix('/'))
+    {
+        Some(relative) => relative.to_string(),
+        None => path,
+    }
+}
+
+/// Lexically resolve `.` and `..` components without touching the filesystem
+pub fn normalize_path(path: &Path) -> PathBuf {
+    use std::path::Component;
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) {
+                    normalized.pop();
+                } else if !normalized.has_root() {
+                    normalized.push("..");
+                }
             }
-            description.push_str(line);
+            other => normalized.push(other),
         }
     }
-    description
+    normalized
+}
+
+/// Hash of the normalized text (`source_text::normalize`), so a file hashes the same with
+/// CRLF or LF line endings and with or without a BOM
+pub fn compute_hash(content: &str) -> String {
+    use md5::{Digest, Md5};
+    let mut hasher = Md5::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())
 }
 
 /// Parse JSDoc @param tags into a map of param_name -> description
@@ -1542,6 +3038,43 @@ fn parse_jsdoc_params(text: &str, params: &mut HashMap<String, String>) {
     }
 }
 
+/// Collect `@example` blocks: the rest of the tag line plus every line up to the next tag
+/// (tags inside a ``` fence don't count), with the JSDoc `* ` prefix removed. Indentation,
+/// line breaks and fences are kept verbatim; empty examples are skipped
+fn parse_jsdoc_examples(text: &str) -> Vec<String> {
+    let mut examples = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+    let mut in_fence = false;
+    for line in text.lines() {
+        let line = line.trim_start();
+        let line = line.strip_prefix('*').unwrap_or(line);
+        let line = line.strip_prefix(' ').unwrap_or(line).trim_end();
+        if !in_fence && line.trim_start().starts_with('@') {
+            examples.extend(current.take().map(|lines| lines.join("\n")));
+            let example = line
+                .trim_start()
+                .strip_prefix("@example")
+                .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace));
+            if let Some(rest) = example {
+                current = Some(vec![rest.trim()]);
+            }
+            continue;
+        }
+        if let Some(lines) = &mut current {
+            if line.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+            }
+            lines.push(line);
+        }
+    }
+    examples.extend(current.map(|lines| lines.join("\n")));
+    examples
+        .into_iter()
+        .map(|example| example.trim_matches('\n').to_string())
+        .filter(|example| !example.is_empty())
+        .collect()
+}
+
 /// Check if a JSDoc comment contains @ignore tag (standalone, no value needed)
 fn has_jsdoc_ignore_tag(text: &str) -> bool {
     for line in text.lines() {
@@ -1594,38 +3127,149 @@ fn parse_jsdoc_tag(text: &str, tag_name: &str) -> Option<String> {
     None
 }
 
-/// Parse JSDoc description excluding specific tags
-/// Returns description text without lines containing the specified tags
-/// Handles both @tag="value" and @tag:value formats
-fn parse_jsdoc_description_excluding_tags(text: &str, exclude_tags: &[&str]) -> String {
-    let mut description = String::new();
+/// Parse a `@module shared` JSDoc tag (also `@module="shared"` / `@module:shared`).
+/// `@module ""` selects the root module; a bare `@module` is ignored
+fn parse_jsdoc_module(text: &str) -> Option<String> {
+    if let Some(module) = parse_jsdoc_tag(text, "module") {
+        return Some(module);
+    }
     for line in text.lines() {
         let line = line.trim().trim_start_matches('*').trim();
-        // Skip lines with excluded tags (both @tag= and @tag: formats)
-        let is_excluded = exclude_tags.iter().any(|tag| {
-            let tag_prefix_eq = format!("@{}=", tag);
-            let tag_prefix_colon = format!("@{}:", tag);
-            line.starts_with(&tag_prefix_eq) || line.starts_with(&tag_prefix_colon)
-        });
-        if is_excluded {
+        let Some(rest) = line.strip_prefix("@module") else {
+            continue;
+        };
+        if !rest.starts_with(char::is_whitespace) {
             continue;
         }
-        // Stop at other @tags (like @param)
-        if line.starts_with('@') {
-            break;
+        let value = rest.trim();
+        if value == "\"\"" || value == "''" {
+            return Some(String::new());
         }
-        if !line.is_empty() {
-            if !description.is_empty() {
-                description.push(' ');
-            }
-            description.push_str(line);
+        if let Some(module) = value.split_whitespace().next() {
+            return Some(module.to_string());
         }
     }
-    description
+    None
+}
+
+/// Parse @OnlyIn("full") / @ExcludeFrom("lite", "demo") decorators and the `@onlyIn full` /
+/// `@excludeFrom lite, demo` JSDoc tags. Decorators and tags add up
+fn parse_flavor_condition(
+    decorators: &[Decorator],
+    raw_comment: Option<&String>,
+) -> FlavorCondition {
+    let mut flavors = FlavorCondition::default();
+    for parsed in decorators.iter().filter_map(parse_decorator) {
+        let target = match parsed.name.as_str() {
+            "OnlyIn" => &mut flavors.only_in,
+            "ExcludeFrom" => &mut flavors.exclude_from,
+            _ => continue,
+        };
+        target.extend(parsed.args.iter().filter_map(|arg| match arg {
+            DecoratorArg::String(s) if !s.is_empty() => Some(s.clone()),
+            _ => None,
+        }));
+    }
+    if let Some(comment) = raw_comment {
+        flavors.only_in.extend(parse_jsdoc_list(comment, "onlyIn"));
+        flavors
+            .exclude_from
+            .extend(parse_jsdoc_list(comment, "excludeFrom"));
+    }
+    flavors
+}
+
+/// Values of every `@tag a, b` / `@tag="a,b"` / `@tag:a` line, split on commas and whitespace
+fn parse_jsdoc_list(text: &str, tag_name: &str) -> Vec<String> {
+    let prefix = format!("@{}", tag_name);
+    let mut values = Vec::new();
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches('*').trim();
+        let Some(rest) = line.strip_prefix(&prefix) else {
+            continue;
+        };
+        let rest = match rest.strip_prefix('=').or_else(|| rest.strip_prefix(':')) {
+            Some(value) => value,
+            None if rest.starts_with(char::is_whitespace) => rest,
+            None => continue,
+        };
+        values.extend(
+            rest.split(|c: char| c == ',' || c.is_whitespace())
+                .map(|value| value.trim_matches(['"', '\'']))
+                .filter(|value| !value.is_empty())
+                .map(str::to_string),
+        );
+    }
+    values
+}
+
+/// Parse a @Default(5) / @Default("melee") / @Default(true) decorator into the
+/// default value string used for Luban's `#default=`
+fn parse_default_decorator(decorators: &[Decorator]) -> Option<String> {
+    decorators
+        .iter()
+        .filter_map(parse_decorator)
+        .find(|d| d.name == "Default")
+        .and_then(|d| match d.args.first() {
+            Some(DecoratorArg::Number(n)) => Some(n.to_string()),
+            Some(DecoratorArg::String(s)) => Some(s.clone()),
+            Some(DecoratorArg::Bool(b)) => Some(b.to_string()),
+            Some(DecoratorArg::Identifier(i)) => Some(i.clone()),
+            _ => None,
+        })
+}
+
+/// Parse an @Alias("角色配置") decorator on a class or field
+fn parse_alias_decorator(decorators: &[Decorator]) -> Option<String> {
+    decorators
+        .iter()
+        .filter_map(parse_decorator)
+        .find(|d| d.name == "Alias")
+        .and_then(|d| match d.args.first() {
+            Some(DecoratorArg::String(s)) if !s.is_empty() => Some(s.clone()),
+            _ => None,
+        })
+}
+
+/// Parse a @Converter("CurveRef", { tag: "converter=CurveParser" }) decorator.
+/// `tag` may also be an array of tags
+fn parse_converter_decorator(decorators: &[Decorator]) -> Option<FieldConverter> {
+    let parsed = decorators
+        .iter()
+        .filter_map(parse_decorator)
+        .find(|d| d.name == "Converter")?;
+    let luban_type = match parsed.args.first() {
+        Some(DecoratorArg::String(s)) if !s.is_empty() => s.clone(),
+        _ => return None,
+    };
+    let tags = match parsed.named_args.get("tag") {
+        Some(DecoratorArg::String(tag)) => vec![tag.clone()],
+        Some(DecoratorArg::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                DecoratorArg::String(tag) => Some(tag.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    Some(FieldConverter { luban_type, tags })
+}
+
+/// Parse a @Name("column_name") decorator renaming the field in the schema
+fn parse_name_decorator(decorators: &[Decorator]) -> Option<String> {
+    decorators
+        .iter()
+        .filter_map(parse_decorator)
+        .find(|d| d.name == "Name")
+        .and_then(|d| match d.args.first() {
+            Some(DecoratorArg::String(s)) if !s.is_empty() => Some(s.clone()),
+            _ => None,
+        })
 }
 
 /// Parse field decorators and return FieldValidators
-/// Note: @Ref decorator is removed, use JSDoc @ref instead
+/// Note: @Ref(Target) names the target explicitly; JSDoc @ref discovers it from the field type
 fn parse_field_decorators(decorators: &[Decorator]) -> FieldValidators {
     let (validators, _) = parse_field_decorators_with_ref_replace(decorators);
     validators
@@ -1644,8 +3288,20 @@ fn parse_field_decorators_with_ref_replace(decorators: &[Decorator]) -> (FieldVa
                         if let (Some(DecoratorArg::Number(min)), Some(DecoratorArg::Number(max))) =
                             (parsed.args.get(0), parsed.args.get(1))
                         {
-                            validators.range = Some((*min, *max));
+                            validators.range = Some(RangeConstraint::Between(*min, *max));
                         }
+                    } else {
+                        // @Range({ min: 0 }), @Range({ max: 59 }) or both
+                        let bound = |key: &str| match parsed.named_args.get(key) {
+                            Some(DecoratorArg::Number(n)) => Some(*n),
+                            _ => None,
+                        };
+                        validators.range = match (bound("min"), bound("max")) {
+                            (Some(min), Some(max)) => Some(RangeConstraint::Between(min, max)),
+                            (Some(min), None) => Some(RangeConstraint::Min(min)),
+                            (None, Some(max)) => Some(RangeConstraint::Max(max)),
+                            (None, None) => None,
+                        };
                     }
                 }
                 "Required" => {
@@ -1684,6 +3340,19 @@ fn parse_field_decorators_with_ref_replace(decorators: &[Decorator]) -> (FieldVa
                 "Nominal" => {
                     validators.nominal = true;
                 }
+                "Ref" | "RefKey" => {
+                    // @Ref(ItemConfig) / @RefKey(ItemConfig); the class decorator
+                    // @RefKey("itemId") is parsed separately
+                    let target = match parsed.args.first() {
+                        Some(DecoratorArg::Identifier(name)) => Some(name.clone()),
+                        _ => None,
+                    };
+                    if parsed.name == "Ref" {
+                        validators.ref_target = target;
+                    } else {
+                        validators.ref_key_target = target;
+                    }
+                }
                 "RefReplace" => {
                     // @RefReplace<T, "field">() - extract type params
                     if parsed.type_params.len() >= 2 {
@@ -1715,11 +3384,8 @@ export class MyClass {
     public active?: boolean;
 }
 "#;
-        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
-        file.write_all(ts_code.as_bytes()).unwrap();
-
         let parser = TsParser::new();
-        let classes = parser.parse_file(file.path()).unwrap();
+        let classes = parser.parse_source("my-class.ts", ts_code).unwrap();
 
         assert_eq!(classes.len(), 1);
         assert_eq!(classes[0].name, "MyClass");
@@ -1728,6 +3394,14 @@ export class MyClass {
         assert_eq!(classes[0].fields[0].field_type, "string");
         assert_eq!(classes[0].fields[1].field_type, "double");
         assert!(classes[0].fields[2].is_optional);
+        assert_eq!(
+            classes[0].fields[0].origin,
+            Some(FieldOrigin::Source {
+                file: "my-class.ts".to_string(),
+                line: 3
+            })
+        );
+        assert_eq!(classes[0].fields[2].line(), Some(5));
     }
 
     #[test]
@@ -1739,11 +3413,8 @@ export class MyTrigger implements EntityTrigger {
     public damage: number;
 }
 "#;
-        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
-        file.write_all(ts_code.as_bytes()).unwrap();
-
         let parser = TsParser::new();
-        let classes = parser.parse_file(file.path()).unwrap();
+        let classes = parser.parse_source("my-class.ts", ts_code).unwrap();
 
         assert_eq!(classes.len(), 1);
         assert_eq!(classes[0].implements, vec!["EntityTrigger"]);
@@ -1757,72 +3428,476 @@ export class MyClass {
     public numbers: Array<number>;
 }
 "#;
-        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
-        file.write_all(ts_code.as_bytes()).unwrap();
-
         let parser = TsParser::new();
-        let classes = parser.parse_file(file.path()).unwrap();
+        let classes = parser.parse_source("my-class.ts", ts_code).unwrap();
 
         assert_eq!(classes[0].fields[0].field_type, "list,string");
         assert_eq!(classes[0].fields[1].field_type, "list,double");
     }
 
     #[test]
-    fn test_parse_map_types() {
+    fn test_parse_source_matches_parse_file() {
         let ts_code = r#"
-export class MyClass {
-    public data: Map<string, number>;
-    public record: Record<string, boolean>;
+export class Item {
+    public id: number;
+}
+
+export enum Quality {
+    Common = 1,
 }
 "#;
         let mut file = NamedTempFile::with_suffix(".ts").unwrap();
         file.write_all(ts_code.as_bytes()).unwrap();
 
         let parser = TsParser::new();
-        let classes = parser.parse_file(file.path()).unwrap();
+        let from_file = parser.parse_file(file.path()).unwrap();
+        let from_memory = parser.parse_source("virtual/item.ts", ts_code).unwrap();
 
-        assert_eq!(classes[0].fields[0].field_type, "map,string,double");
-        assert_eq!(classes[0].fields[1].field_type, "map,string,bool");
+        assert_eq!(from_memory.len(), 1);
+        assert_eq!(from_memory[0].source_file, "virtual/item.ts");
+        assert_eq!(from_memory[0].file_hash, from_file[0].file_hash);
+
+        let enums = parser.parse_enums_source("virtual/item.ts", ts_code).unwrap();
+        assert_eq!(enums[0].name, "Quality");
+        assert_eq!(enums[0].source_file, "virtual/item.ts");
     }
 
     #[test]
-    fn test_parse_set_types() {
+    fn test_parse_source_resolves_relative_imports() {
         let ts_code = r#"
-export class MyClass {
-    public items: Set<string>;
-    public numbers: Set<number>;
-    public readonlyItems: ReadonlySet<Item>;
+import { Reward } from "../common/reward";
+import { Quality } from "./quality";
+
+export class Item {
+    public reward: Reward;
+    public quality: Quality;
 }
 "#;
-        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
-        file.write_all(ts_code.as_bytes()).unwrap();
+        let parser = TsParser::new();
+        let classes = parser
+            .parse_source("virtual-root/items/item.ts", ts_code)
+            .unwrap();
+
+        assert_eq!(
+            classes[0].imports.get("Reward"),
+            Some(&PathBuf::from("virtual-root/common/reward.ts"))
+        );
+        assert_eq!(
+            classes[0].imports.get("Quality"),
+            Some(&PathBuf::from("virtual-root/items/quality.ts"))
+        );
+    }
+
+    #[test]
+    fn test_parse_module_specifiers() {
+        let ts_code = r#"export { RoleConfig } from "./role";
+import type { Quality } from "@shared/quality";
+export * from "./items";
 
+export type { Loot } from "./loot";
+export const VERSION = 1;
+"#;
         let parser = TsParser::new();
-        let classes = parser.parse_file(file.path()).unwrap();
+        let specifiers = parser
+            .parse_module_specifiers("registration.ts", ts_code)
+            .unwrap();
 
-        assert_eq!(classes[0].fields[0].field_type, "set,string");
-        assert_eq!(classes[0].fields[1].field_type, "set,double");
-        assert_eq!(classes[0].fields[2].field_type, "set,Item");
+        assert_eq!(
+            specifiers,
+            vec![
+                ("./role".to_string(), 1),
+                ("@shared/quality".to_string(), 2),
+                ("./items".to_string(), 3),
+                ("./loot".to_string(), 5),
+            ]
+        );
     }
 
     #[test]
-    fn test_parse_interface_extends() {
+    fn test_parse_runtime_imports() {
         let ts_code = r#"
-export interface BaseTrigger {
-    id: number;
-}
-
-export interface EntityTrigger extends BaseTrigger {
-    num: number;
+import { Reward } from "../common/reward";
+import type { Quality } from "./quality";
+import { type Rarity, type Tier } from "./rarity";
+import { type Stat, statKeys } from "./stat";
+import "./side-effect";
+import { Signal } from "@rbxts/signal";
+export { Drop } from "./drop";
+export type { Loot } from "./loot";
+export * from "./shared";
+
+export class Item {
+    public reward: Reward;
 }
 "#;
-        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
-        file.write_all(ts_code.as_bytes()).unwrap();
-
         let parser = TsParser::new();
-        let classes = parser.parse_file(file.path()).unwrap();
-
-        assert_eq!(classes.len(), 2);
+        let imports = parser
+            .parse_runtime_imports("virtual-root/items/item.ts", ts_code)
+            .unwrap();
+
+        assert_eq!(
+            imports,
+            [
+                "virtual-root/common/reward.ts",
+                "virtual-root/items/stat.ts",
+                "virtual-root/items/side-effect.ts",
+                "virtual-root/items/drop.ts",
+                "virtual-root/items/shared.ts",
+            ]
+            .map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path(Path::new("a/./b/../c.ts")), PathBuf::from("a/c.ts"));
+        assert_eq!(normalize_path(Path::new("../a/b")), PathBuf::from("../a/b"));
+        assert_eq!(normalize_path(Path::new("/a/../../b")), PathBuf::from("/b"));
+    }
+
+    #[test]
+    fn test_parse_map_types() {
+        let ts_code = r#"
+export class MyClass {
+    public data: Map<string, number>;
+    public record: Record<string, boolean>;
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+
+        assert_eq!(classes[0].fields[0].field_type, "map,string,double");
+        assert_eq!(classes[0].fields[1].field_type, "map,string,bool");
+    }
+
+    #[test]
+    fn test_parse_nullable_element_types() {
+        let ts_code = r#"
+export class MyClass {
+    public rewards: Map<string, ItemDrop | undefined>;
+    public values: Array<number | null>;
+    public ids: (number | undefined)[];
+    public nested: Array<number[] | null>;
+    public plain: ItemDrop | undefined;
+}
+"#;
+        let parser = TsParser::new();
+        let classes = parser.parse_source("test.ts", ts_code).unwrap();
+        let fields = &classes[0].fields;
+
+        assert_eq!(fields[0].field_type, "map,string,ItemDrop?");
+        assert_eq!(fields[1].field_type, "list,double?");
+        assert_eq!(fields[2].field_type, "list,double?");
+        // Nested containers and top-level unions are never marked
+        assert_eq!(fields[3].field_type, "list,(list,double)");
+        assert_eq!(fields[4].field_type, "ItemDrop");
+    }
+
+    #[test]
+    fn test_parse_nested_container_types() {
+        let ts_code = r#"
+export class MyClass {
+    public grid: Array<Array<number>>;
+    public cube: number[][][];
+    public drops: Map<string, Item[]>;
+    public table: Map<string, Map<number, Item[]>>;
+    public groups: Set<string[]>;
+}
+"#;
+        let parser = TsParser::new();
+        let classes = parser.parse_source("test.ts", ts_code).unwrap();
+        let types: Vec<&str> = classes[0]
+            .fields
+            .iter()
+            .map(|f| f.field_type.as_str())
+            .collect();
+
+        assert_eq!(
+            types,
+            vec![
+                "list,(list,double)",
+                "list,(list,(list,double))",
+                "map,string,(list,Item)",
+                "map,string,(map,double,(list,Item))",
+                "set,(list,string)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_set_types() {
+        let ts_code = r#"
+export class MyClass {
+    public items: Set<string>;
+    public numbers: Set<number>;
+    public readonlyItems: ReadonlySet<Item>;
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+
+        assert_eq!(classes[0].fields[0].field_type, "set,string");
+        assert_eq!(classes[0].fields[1].field_type, "set,double");
+        assert_eq!(classes[0].fields[2].field_type, "set,Item");
+    }
+
+    #[test]
+    fn test_parse_readonly_types() {
+        let ts_code = r#"
+export class MyClass {
+    public names: readonly string[];
+    public costs: ReadonlyMap<string, number>;
+    public grid: readonly (readonly number[])[];
+    public tags: ReadonlySet<string>;
+    public ids: ReadonlyArray<number>;
+    public drops?: readonly RefKey<ItemConfig>[];
+    public plain: string[];
+    public readonly fixed: number[];
+    constructor(public readonly levels: readonly number[], public limits: ReadonlyMap<number, string>) {}
+}
+"#;
+        let parser = TsParser::new();
+        let classes = parser.parse_source("test.ts", ts_code).unwrap();
+        let field = |name: &str| classes[0].fields.iter().find(|f| f.name == name).unwrap();
+
+        assert_eq!(field("names").field_type, "list,string");
+        assert_eq!(field("costs").field_type, "map,string,double");
+        assert_eq!(field("grid").field_type, "list,(list,double)");
+        assert_eq!(field("tags").field_type, "set,string");
+        assert_eq!(field("ids").field_type, "list,double");
+        assert_eq!(field("drops").field_type, "list,ItemConfig");
+        assert_eq!(
+            field("drops").ref_key_inner_type.as_deref(),
+            Some("ItemConfig")
+        );
+        assert_eq!(field("levels").field_type, "list,double");
+        assert_eq!(field("limits").field_type, "map,double,string");
+        assert!(classes[0]
+            .fields
+            .iter()
+            .all(|f| f.unsupported_type.is_none()));
+
+        // Read-only types are recorded like the `readonly` modifier
+        for name in [
+            "names", "costs", "grid", "tags", "ids", "drops", "fixed", "levels", "limits",
+        ] {
+            assert!(field(name).is_readonly, "{}", name);
+        }
+        assert!(!field("plain").is_readonly);
+    }
+
+    #[test]
+    fn test_parse_optional_and_nullable() {
+        let ts_code = r#"
+export class MyClass {
+    public required: number;
+    public absent?: number;
+    public empty: string | null;
+    public either?: ItemConfig | undefined;
+    public maybe: (number | undefined);
+    constructor(public start: number | null = null, public end?: number) {}
+}
+
+export interface MyInterface {
+    required: number;
+    absent?: number;
+    empty: number | undefined;
+    either?: number | null;
+}
+"#;
+        let parser = TsParser::new();
+        let classes = parser.parse_source("test.ts", ts_code).unwrap();
+        let flags = |class: &ClassInfo| -> Vec<(String, bool, bool)> {
+            class
+                .fields
+                .iter()
+                .map(|f| (f.name.clone(), f.is_optional, f.is_nullable))
+                .collect()
+        };
+        let expected = |list: &[(&str, bool, bool)]| -> Vec<(String, bool, bool)> {
+            list.iter()
+                .map(|(name, optional, nullable)| (name.to_string(), *optional, *nullable))
+                .collect()
+        };
+
+        assert_eq!(
+            flags(&classes[0]),
+            expected(&[
+                ("required", false, false),
+                ("absent", true, false),
+                ("empty", false, true),
+                ("either", true, true),
+                ("maybe", false, true),
+                ("start", true, true),
+                ("end", true, false),
+            ])
+        );
+        assert_eq!(
+            flags(&classes[1]),
+            expected(&[
+                ("required", false, false),
+                ("absent", true, false),
+                ("empty", false, true),
+                ("either", true, true),
+            ])
+        );
+        assert_eq!(classes[0].fields[3].field_type, "ItemConfig");
+        assert!(!classes[0].fields[0].may_be_empty());
+        assert!(classes[0].fields[1..].iter().all(|f| f.may_be_empty()));
+    }
+
+    #[test]
+    fn test_parse_jsdoc_comments() {
+        let ts_code = r#"
+/**
+ * 角色配置
+ * 每个角色一行
+ *
+ * 按等级排序，导表时校验唯一性。
+ * @alias="角色"
+ */
+// 旧的行注释
+export class RoleConfig {
+    /**
+     * 最大生命值
+     * @default="100"
+     */
+    public maxHp: number;
+    // 只有行注释
+    public name: string;
+    constructor(
+        /** 等级 <从 1 开始> */
+        public level: number,
+    ) {}
+}
+
+/** 道具配置 */
+export interface ItemConfig {
+    /**
+     * 堆叠上限
+     * @type="int"
+     */
+    stack: number;
+}
+"#;
+        let parser = TsParser::new();
+        let classes = parser.parse_source("test.ts", ts_code).unwrap();
+        let role = &classes[0];
+
+        // Lines of the first paragraph are joined, tags dropped, and the JSDoc wins over
+        // the line comment
+        assert_eq!(role.comment.as_deref(), Some("角色配置 每个角色一行"));
+        let comment = |name: &str| {
+            role.fields
+                .iter()
+                .find(|f| f.name == name)
+                .and_then(|f| f.comment.as_deref())
+        };
+        assert_eq!(comment("maxHp"), Some("最大生命值"));
+        assert_eq!(comment("name"), Some("只有行注释"));
+        assert_eq!(comment("level"), Some("等级 <从 1 开始>"));
+
+        let item = &classes[1];
+        assert_eq!(item.comment.as_deref(), Some("道具配置"));
+        assert_eq!(item.fields[0].comment.as_deref(), Some("堆叠上限"));
+    }
+
+    #[test]
+    fn test_garbled_comments_dropped() {
+        // What a lossily decoded GBK file looks like
+        let ts_code = "// \u{fffd}\u{fffd}\n/** \u{fffd}\u{fffd} */\nexport class LegacyConfig {\n    /** \u{fffd} */\n    id: number;\n    /** 名称 */\n    name: string;\n}\n";
+        let classes = TsParser::new().parse_source("legacy.ts", ts_code).unwrap();
+        let class = &classes[0];
+        assert_eq!(class.comment, None);
+        assert_eq!(class.fields[0].comment, None);
+        assert_eq!(class.fields[1].comment.as_deref(), Some("名称"));
+    }
+
+    #[test]
+    fn test_parse_jsdoc_examples() {
+        let ts_code = r#"
+/**
+ * 技能配置
+ * @example
+ * ```ts
+ * const skill = tables.SkillTable.get(1001);
+ * if (skill) {
+ *     cast(skill);
+ * }
+ * ```
+ * @alias="技能"
+ */
+export class SkillConfig {
+    /**
+     * 冷却时间（秒）
+     * @example 3.5 表示 3.5 秒冷却
+     * @example 0
+     * 没有冷却，可连续释放
+     * @default="1"
+     */
+    public cooldown: number;
+    /** 名称 */
+    public name: string;
+}
+
+export interface BuffConfig {
+    /** @example 5 */
+    duration: number;
+}
+"#;
+        let policy = CommentPolicy {
+            keep_tags: vec!["example".to_string()],
+            ..Default::default()
+        };
+        let parser = TsParser::new().with_comment_policy(policy);
+        let classes = parser.parse_source("test.ts", ts_code).unwrap();
+        let skill = &classes[0];
+
+        assert_eq!(
+            skill.examples,
+            vec!["```ts\nconst skill = tables.SkillTable.get(1001);\nif (skill) {\n    cast(skill);\n}\n```"]
+        );
+        assert_eq!(skill.comment.as_deref(), Some("技能配置"));
+        assert_eq!(skill.alias.as_deref(), Some("技能"));
+
+        let cooldown = &skill.fields[0];
+        assert_eq!(
+            cooldown.examples,
+            vec!["3.5 表示 3.5 秒冷却", "0\n没有冷却，可连续释放"]
+        );
+        // Left out of the comment even though the tag is in keep_tags
+        assert_eq!(cooldown.comment.as_deref(), Some("冷却时间（秒）"));
+        assert_eq!(cooldown.default_value.as_deref(), Some("1"));
+        assert!(skill.fields[1].examples.is_empty());
+
+        assert_eq!(classes[1].fields[0].examples, vec!["5"]);
+        assert_eq!(classes[1].fields[0].comment, None);
+    }
+
+    #[test]
+    fn test_parse_interface_extends() {
+        let ts_code = r#"
+export interface BaseTrigger {
+    id: number;
+}
+
+export interface EntityTrigger extends BaseTrigger {
+    num: number;
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+
+        assert_eq!(classes.len(), 2);
 
         // BaseTrigger has no extends
         assert_eq!(classes[0].name, "BaseTrigger");
@@ -1917,6 +3992,42 @@ export enum SkillStyle {
         assert_eq!(e.variants[2].value, "3");
     }
 
+    #[test]
+    fn test_parse_auto_increment_enum() {
+        let ts_code = r#"
+export enum Quality {
+    Common,
+    Rare,
+    Epic = 10,
+    Legendary,
+}
+
+export class ItemConfig {
+    public quality: Quality;
+}
+"#;
+        let parser = TsParser::new();
+        let enums = parser.parse_enums_source("item.ts", ts_code).unwrap();
+        let classes = parser.parse_source("item.ts", ts_code).unwrap();
+
+        let values: Vec<(&str, &str)> = enums[0]
+            .variants
+            .iter()
+            .map(|v| (v.name.as_str(), v.value.as_str()))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("Common", "0"),
+                ("Rare", "1"),
+                ("Epic", "10"),
+                ("Legendary", "11")
+            ]
+        );
+        // Fields typed with the enum keep its name
+        assert_eq!(classes[0].fields[0].field_type, "Quality");
+    }
+
     #[test]
     fn test_parse_enum_flags_tag() {
         let ts_code = r#"
@@ -2534,15 +4645,42 @@ export class ItemSkillConfig {
     }
 
     #[test]
-    fn test_parse_ref_replace_decorator() {
+    fn test_parse_ref_decorators() {
         let ts_code = r#"
-export class DropConfig {
-    /** Item reference with field replacement */
-    @RefReplace<Item, "itemName">()
-    public itemRef: string;
+export class ShopConfig {
+    @Ref(ItemConfig)
+    public itemIds: number[];
 
-    /** Normal field */
-    public count: number;
+    @RefKey(ItemConfig)
+    @Ref(CurrencyConfig)
+    public prices: Map<number, number>;
+}
+"#;
+        let classes = TsParser::new().parse_source("shop.ts", ts_code).unwrap();
+        let validators = &classes[0].fields[0].validators;
+        assert_eq!(validators.ref_target.as_deref(), Some("ItemConfig"));
+        assert_eq!(validators.ref_key_target, None);
+        assert!(!validators.has_ref);
+        let validators = &classes[0].fields[1].validators;
+        assert_eq!(validators.ref_target.as_deref(), Some("CurrencyConfig"));
+        assert_eq!(validators.ref_key_target.as_deref(), Some("ItemConfig"));
+    }
+
+    #[test]
+    fn test_parse_parent_decorator() {
+        let ts_code = r#"
+@Parent("CombatActionBase")
+export class DashAction extends BaseAction {
+    public distance: number;
+}
+
+@Parent("")
+export class Standalone implements EntityTrigger {
+    public id: number;
+}
+
+export class Plain extends BaseAction {
+    public id: number;
 }
 "#;
         let mut file = NamedTempFile::with_suffix(".ts").unwrap();
@@ -2551,34 +4689,47 @@ export class DropConfig {
         let parser = TsParser::new();
         let classes = parser.parse_file(file.path()).unwrap();
 
-        assert_eq!(classes.len(), 1);
-        let class = &classes[0];
-        assert_eq!(class.fields.len(), 2);
-
-        // itemRef with @RefReplace<Item, "itemName">()
-        assert_eq!(class.fields[0].name, "itemRef");
-        assert_eq!(class.fields[0].ref_replace, Some(("Item".to_string(), "itemName".to_string())));
-
-        // count without @RefReplace
-        assert_eq!(class.fields[1].name, "count");
-        assert_eq!(class.fields[1].ref_replace, None);
+        assert_eq!(classes.len(), 3);
+        assert_eq!(classes[0].explicit_parent, Some("CombatActionBase".to_string()));
+        assert_eq!(classes[0].extends, Some("BaseAction".to_string()));
+        assert_eq!(classes[1].explicit_parent, Some(String::new()));
+        assert_eq!(classes[2].explicit_parent, None);
     }
 
     #[test]
-    fn test_parse_ref_key_scalar_and_array() {
+    fn test_parse_module_decorator_and_jsdoc_tag() {
         let ts_code = r#"
-export class RefKeyConfig {
-    /** Scalar RefKey<T> */
-    public item: RefKey<Item>;
+@Module("shared")
+export class StatBlock {
+    public hp: number;
+}
 
-    /** Array RefKey<T>[] */
-    public items: RefKey<Item>[];
+/** @module shared */
+export class DropTable {
+    public id: number;
+}
 
-    /** Generic Array<RefKey<T>> */
-    public itemList: Array<RefKey<Item>>;
+/**
+ * Global tag
+ * @module ""
+ */
+export class GlobalTag {
+    public id: number;
+}
 
-    /** Normal field */
-    public count: number;
+/** @module other */
+@Module("shared")
+export class Both {
+    public id: number;
+}
+
+/** @module battle */
+export interface BattleStat {
+    atk: number;
+}
+
+export class Plain {
+    public id: number;
 }
 "#;
         let mut file = NamedTempFile::with_suffix(".ts").unwrap();
@@ -2586,32 +4737,1013 @@ export class RefKeyConfig {
 
         let parser = TsParser::new();
         let classes = parser.parse_file(file.path()).unwrap();
+        let module_of = |name: &str| {
+            let class = classes.iter().find(|c| c.name == name).unwrap();
+            (class.module_name.clone(), class.explicit_module)
+        };
 
-        assert_eq!(classes.len(), 1);
-        let class = &classes[0];
-        assert_eq!(class.fields.len(), 4);
+        assert_eq!(module_of("StatBlock"), (Some("shared".to_string()), true));
+        assert_eq!(module_of("GlobalTag"), (Some(String::new()), true));
+        assert_eq!(module_of("Both"), (Some("shared".to_string()), true));
+        assert_eq!(module_of("BattleStat"), (Some("battle".to_string()), true));
+        assert_eq!(module_of("DropTable"), (Some("shared".to_string()), true));
+        assert_eq!(module_of("Plain"), (None, false));
+    }
 
-        // item: RefKey<Item> -> scalar with ref_key
-        assert_eq!(class.fields[0].name, "item");
-        assert_eq!(class.fields[0].field_type, "Item");
-        assert!(class.fields[0].validators.has_ref_key);
-        assert_eq!(class.fields[0].ref_key_inner_type, Some("Item".to_string()));
+    #[test]
+    fn test_parse_output_decorator() {
+        let ts_code = r#"
+@Output("editor")
+export class DebugSpawnConfig {
+    public id: number;
+}
 
-        // items: RefKey<Item>[] -> list with ref_key
-        assert_eq!(class.fields[1].name, "items");
-        assert_eq!(class.fields[1].field_type, "list,Item");
-        assert!(class.fields[1].validators.has_ref_key);
-        assert_eq!(class.fields[1].ref_key_inner_type, Some("Item".to_string()));
+export class ItemConfig {
+    public id: number;
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
 
-        // itemList: Array<RefKey<Item>> -> list with ref_key
-        assert_eq!(class.fields[2].name, "itemList");
-        assert_eq!(class.fields[2].field_type, "list,Item");
-        assert!(class.fields[2].validators.has_ref_key);
-        assert_eq!(class.fields[2].ref_key_inner_type, Some("Item".to_string()));
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
 
-        // count: number -> normal field
-        assert_eq!(class.fields[3].name, "count");
+        assert_eq!(classes[0].output_route, Some("editor".to_string()));
+        assert_eq!(classes[1].output_route, None);
+    }
+
+    #[test]
+    fn test_parse_no_runtime_decorator() {
+        let ts_code = r#"
+@NoRuntime()
+export class DropEntry {
+    public id: number;
+}
+
+export class ItemConfig {
+    public id: number;
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+
+        assert!(classes[0].no_runtime);
+        assert!(!classes[1].no_runtime);
+    }
+
+    #[test]
+    fn test_parse_range_decorator_bounds() {
+        let ts_code = r#"
+export class TimerConfig {
+    @Range(1, 100)
+    public level: number;
+
+    @Range({ min: 0 })
+    public count: number;
+
+    @Range({ max: 59 })
+    public seconds: number;
+
+    @Range({ min: -1, max: 1 })
+    public bias: number;
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+        let ranges: Vec<_> = classes[0]
+            .fields
+            .iter()
+            .map(|f| f.validators.range)
+            .collect();
+
+        assert_eq!(
+            ranges,
+            vec![
+                Some(RangeConstraint::Between(1.0, 100.0)),
+                Some(RangeConstraint::Min(0.0)),
+                Some(RangeConstraint::Max(59.0)),
+                Some(RangeConstraint::Between(-1.0, 1.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_flavor_conditions() {
+        let ts_code = r#"
+@OnlyIn("full", "dev")
+export class DebugConfig {
+    public id: number;
+}
+
+export class ItemConfig {
+    public id: number;
+
+    @ExcludeFrom("lite")
+    public bigIcon: string;
+
+    /**
+     * Preview clip
+     * @onlyIn full
+     * @excludeFrom demo, trial
+     */
+    public preview: string;
+}
+
+/**
+ * @excludeFrom="lite"
+ */
+export interface ExtraStats {
+    crit: number;
+}
+"#;
+        let parser = TsParser::new();
+        let classes = parser.parse_source("flavors.ts", ts_code).unwrap();
+        let flavors = |only_in: &[&str], exclude_from: &[&str]| FlavorCondition {
+            only_in: only_in.iter().map(|f| f.to_string()).collect(),
+            exclude_from: exclude_from.iter().map(|f| f.to_string()).collect(),
+        };
+
+        assert_eq!(classes[0].flavors, flavors(&["full", "dev"], &[]));
+        assert_eq!(classes[1].flavors, FlavorCondition::default());
+        assert_eq!(classes[1].fields[1].flavors, flavors(&[], &["lite"]));
+        assert_eq!(
+            classes[1].fields[2].flavors,
+            flavors(&["full"], &["demo", "trial"])
+        );
+        assert_eq!(classes[1].fields[2].comment.as_deref(), Some("Preview clip"));
+        assert_eq!(classes[2].flavors, flavors(&[], &["lite"]));
+
+        assert!(classes[1].fields[2].flavors.includes("full"));
+        assert!(!classes[1].fields[2].flavors.includes("lite"));
+        assert!(!classes[1].fields[2].flavors.includes("demo"));
+        assert!(classes[1].fields[1].flavors.includes("full"));
+    }
+
+    #[test]
+    fn test_unexported_referenced_class() {
+        let ts_code = r#"
+class DropEntry {
+    public itemId: number;
+    public bonus: Bonus;
+}
+
+class Bonus {
+    public amount: number;
+}
+
+class Unused {
+    public x: number;
+}
+
+export class DropConfig {
+    public id: number;
+    public entries: DropEntry[];
+}
+"#;
+        let parser = TsParser::new();
+        let classes = parser.parse_source("drop.ts", ts_code).unwrap();
+        assert_eq!(classes.len(), 1);
+        assert_eq!(
+            parser.take_errors(),
+            vec!["drop.ts:17: DropConfig.entries - type 'DropEntry' is declared but not exported in the same file (line 2); add `export`"]
+        );
+
+        let parser = TsParser::new().with_include_private_referenced(true);
+        let classes = parser.parse_source("drop.ts", ts_code).unwrap();
+        let names: Vec<_> = classes.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["DropConfig", "DropEntry", "Bonus"]);
+        assert!(!classes[0].no_runtime);
+        assert!(classes[1].no_runtime && classes[2].no_runtime);
+        assert!(parser.take_errors().is_empty());
+    }
+
+    #[test]
+    fn test_typed_constructor_params_with_defaults() {
+        let ts_code = r#"
+export class MoveAction {
+    constructor(
+        public readonly speed: number = 16,
+        public label: string = "idle",
+        public name?: string,
+        public { x, y }: Point = { x: 0, y: 0 },
+    ) {}
+}
+"#;
+        let parser = TsParser::new();
+        let classes = parser.parse_source("move.ts", ts_code).unwrap();
+        let fields = &classes[0].fields;
+        assert_eq!(fields.len(), 3);
+
+        assert_eq!(fields[0].name, "speed");
+        assert_eq!(fields[0].field_type, "double");
+        assert!(fields[0].is_optional && fields[0].is_readonly);
+        assert_eq!(fields[0].default_value.as_deref(), Some("16"));
+
+        assert_eq!(fields[1].name, "label");
+        assert_eq!(fields[1].field_type, "string");
+        assert!(fields[1].is_optional && !fields[1].is_readonly);
+        assert_eq!(fields[1].default_value.as_deref(), Some("idle"));
+
+        assert_eq!(fields[2].name, "name");
+        assert!(fields[2].is_optional);
+        assert_eq!(fields[2].default_value, None);
+
+        // The destructured parameter is skipped with a warning
+        assert_eq!(
+            parser.take_warnings(),
+            vec!["move.ts:7:9: MoveAction.constructor - destructured parameter property is not supported and is skipped, declare the fields on the class instead".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_default_decorator() {
+        let ts_code = r#"
+export class WeaponConfig {
+    @Default(5)
+    public count: number = 3;
+
+    @Default("melee")
+    public kind: string;
+
+    /**
+     * @default="1"
+     */
+    @Default(true)
+    public enabled: boolean;
+
+    /**
+     * @default="2"
+     */
+    public level: number;
+
+    constructor(@Default(1.5) public speed: number) {}
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+        let class = &classes[0];
+        let default_of = |name: &str| {
+            class
+                .fields
+                .iter()
+                .find(|f| f.name == name)
+                .unwrap()
+                .default_value
+                .clone()
+        };
+
+        assert_eq!(default_of("count"), Some("5".to_string()));
+        assert_eq!(default_of("kind"), Some("melee".to_string()));
+        assert_eq!(default_of("enabled"), Some("true".to_string()));
+        assert_eq!(default_of("level"), Some("2".to_string()));
+        assert_eq!(default_of("speed"), Some("1.5".to_string()));
+    }
+
+    #[test]
+    fn test_parse_initializer_defaults() {
+        let ts_code = r#"
+export class SpawnConfig {
+    public count: number = 3;
+    public mode: string = "auto";
+    public enabled: boolean = false;
+    public offset: number = -1.5;
+    public quality: Quality = Quality.Rare;
+    public tags: string[] = [];
+    public seed: number = Math.random();
+    /**
+     * @default="7"
+     */
+    public level: number = compute();
+    constructor(public kind: Kind = Kind.Melee, public origin: Point = makePoint()) {}
+}
+"#;
+        let parser = TsParser::new();
+        let classes = parser.parse_source("spawn.ts", ts_code).unwrap();
+        let default_of = |name: &str| {
+            classes[0]
+                .fields
+                .iter()
+                .find(|f| f.name == name)
+                .unwrap()
+                .default_value
+                .clone()
+        };
+
+        assert_eq!(default_of("count").as_deref(), Some("3"));
+        assert_eq!(default_of("mode").as_deref(), Some("auto"));
+        assert_eq!(default_of("enabled").as_deref(), Some("false"));
+        assert_eq!(default_of("offset").as_deref(), Some("-1.5"));
+        assert_eq!(default_of("quality").as_deref(), Some("Rare"));
+        assert_eq!(default_of("kind").as_deref(), Some("Melee"));
+        assert_eq!(default_of("tags"), None);
+        assert_eq!(default_of("seed"), None);
+        assert_eq!(default_of("level").as_deref(), Some("7"));
+        assert_eq!(default_of("origin"), None);
+
+        // Ignored initializers are noted, unless a @default covers them
+        assert_eq!(
+            parser.take_notes(),
+            vec![
+                "spawn.ts:8: SpawnConfig.tags - initializer is not a literal or enum member, no default is emitted",
+                "spawn.ts:9: SpawnConfig.seed - initializer is not a literal or enum member, no default is emitted",
+                "spawn.ts:14: SpawnConfig.origin - initializer is not a literal or enum member, no default is emitted",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_name_decorator_and_jsdoc_tag() {
+        let ts_code = r#"
+export class RoleConfig {
+    @Name("attack_power")
+    public attackPower: number;
+
+    /**
+     * 移动速度
+     * @luban-name:move_speed
+     */
+    public moveSpeed: number;
+
+    public plain: number;
+}
+
+export interface IRole {
+    /** @luban-name="role_id" */
+    roleId: number;
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+
+        let role = &classes[0];
+        assert_eq!(role.fields[0].name, "attackPower");
+        assert_eq!(role.fields[0].xml_name, Some("attack_power".to_string()));
+        assert_eq!(role.fields[1].xml_name, Some("move_speed".to_string()));
+        assert_eq!(role.fields[1].comment, Some("移动速度".to_string()));
+        assert_eq!(role.fields[2].xml_name, None);
+        assert_eq!(role.fields[2].schema_name(), "plain");
+
+        let iface = &classes[1];
+        assert_eq!(iface.fields[0].schema_name(), "role_id");
+    }
+
+    #[test]
+    fn test_parse_ref_key_decorator() {
+        let ts_code = r#"
+@RefKey("itemId")
+export class Item {
+    public itemId: string;
+}
+
+@LubanTable({ mode: "map", index: "code" })
+export class Skill {
+    public code: string;
+}
+
+export class Plain {
+    public id: number;
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+        assert_eq!(classes[0].ref_key, Some("itemId".to_string()));
+        assert_eq!(classes[1].ref_key, Some("code".to_string()));
+        assert_eq!(classes[2].ref_key, None);
+    }
+
+    #[test]
+    fn test_parse_converter_decorator() {
+        let ts_code = r#"
+export class SkillConfig {
+    @Converter("CurveRef", { tag: "converter=CurveParser" })
+    public curve?: string;
+
+    @Converter("Range", { tag: ["converter=RangeParser", "inline=true"] })
+    public range: string;
+
+    public plain: string;
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+        let fields = &classes[0].fields;
+
+        let curve = fields[0].converter.as_ref().unwrap();
+        assert_eq!(curve.luban_type, "CurveRef");
+        assert_eq!(curve.tags, vec!["converter=CurveParser"]);
+        assert!(fields[0].is_optional);
+
+        let range = fields[1].converter.as_ref().unwrap();
+        assert_eq!(range.tags, vec!["converter=RangeParser", "inline=true"]);
+
+        assert!(fields[2].converter.is_none());
+    }
+
+    #[test]
+    fn test_parse_alias_decorator() {
+        let ts_code = r#"
+/**
+ * @alias:旧名字
+ */
+@Alias("角色配置")
+export class RoleConfig {
+    @Alias("攻击力")
+    public attack: number;
+
+    /**
+     * @alias:防御
+     */
+    public defense: number;
+
+    constructor(@Alias("速度") public speed: number) {}
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+        let class = &classes[0];
+        assert_eq!(class.alias, Some("角色配置".to_string()));
+
+        let alias_of = |name: &str| class.fields.iter().find(|f| f.name == name).unwrap().alias.clone();
+        assert_eq!(alias_of("attack"), Some("攻击力".to_string()));
+        assert_eq!(alias_of("defense"), Some("防御".to_string()));
+        assert_eq!(alias_of("speed"), Some("速度".to_string()));
+    }
+
+    #[test]
+    fn test_parse_tag_decorators() {
+        let ts_code = r#"
+@Tag("export", "server")
+export class DropConfig {
+    @Tag("relocateTo", "TbDrop")
+    @Tag("prefix", "_drop")
+    @Tag("bad", "a,b")
+    public itemId: number;
+
+    constructor(@Tag("editor", "hidden") public weight: number) {}
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+
+        assert_eq!(classes.len(), 1);
+        let class = &classes[0];
+        assert_eq!(
+            class.tags,
+            vec![("export".to_string(), "server".to_string())]
+        );
+
+        let item_id = class.fields.iter().find(|f| f.name == "itemId").unwrap();
+        assert_eq!(
+            item_id.tags,
+            vec![
+                ("relocateTo".to_string(), "TbDrop".to_string()),
+                ("prefix".to_string(), "_drop".to_string()),
+            ]
+        );
+        let weight = class.fields.iter().find(|f| f.name == "weight").unwrap();
+        assert_eq!(
+            weight.tags,
+            vec![("editor".to_string(), "hidden".to_string())]
+        );
+
+        let warnings = parser.take_class_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, ClassWarningKind::InvalidTag);
+        assert!(warnings[0]
+            .message
+            .contains(":6: itemId - @Tag(\"bad\", \"a,b\")"));
+        assert!(parser.take_class_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_parse_ref_replace_decorator() {
+        let ts_code = r#"
+export class DropConfig {
+    /** Item reference with field replacement */
+    @RefReplace<Item, "itemName">()
+    public itemRef: string;
+
+    /** Normal field */
+    public count: number;
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+
+        assert_eq!(classes.len(), 1);
+        let class = &classes[0];
+        assert_eq!(class.fields.len(), 2);
+
+        // itemRef with @RefReplace<Item, "itemName">()
+        assert_eq!(class.fields[0].name, "itemRef");
+        assert_eq!(class.fields[0].ref_replace, Some(("Item".to_string(), "itemName".to_string())));
+
+        // count without @RefReplace
+        assert_eq!(class.fields[1].name, "count");
+        assert_eq!(class.fields[1].ref_replace, None);
+    }
+
+    #[test]
+    fn test_parse_ref_key_scalar_and_array() {
+        let ts_code = r#"
+export class RefKeyConfig {
+    /** Scalar RefKey<T> */
+    public item: RefKey<Item>;
+
+    /** Array RefKey<T>[] */
+    public items: RefKey<Item>[];
+
+    /** Generic Array<RefKey<T>> */
+    public itemList: Array<RefKey<Item>>;
+
+    /** Normal field */
+    public count: number;
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+
+        assert_eq!(classes.len(), 1);
+        let class = &classes[0];
+        assert_eq!(class.fields.len(), 4);
+
+        // item: RefKey<Item> -> scalar with ref_key
+        assert_eq!(class.fields[0].name, "item");
+        assert_eq!(class.fields[0].field_type, "Item");
+        assert!(class.fields[0].validators.has_ref_key);
+        assert_eq!(class.fields[0].ref_key_inner_type, Some("Item".to_string()));
+
+        // items: RefKey<Item>[] -> list with ref_key
+        assert_eq!(class.fields[1].name, "items");
+        assert_eq!(class.fields[1].field_type, "list,Item");
+        assert!(class.fields[1].validators.has_ref_key);
+        assert_eq!(class.fields[1].ref_key_inner_type, Some("Item".to_string()));
+
+        // itemList: Array<RefKey<Item>> -> list with ref_key
+        assert_eq!(class.fields[2].name, "itemList");
+        assert_eq!(class.fields[2].field_type, "list,Item");
+        assert!(class.fields[2].validators.has_ref_key);
+        assert_eq!(class.fields[2].ref_key_inner_type, Some("Item".to_string()));
+
+        // count: number -> normal field
+        assert_eq!(class.fields[3].name, "count");
         assert!(!class.fields[3].validators.has_ref_key);
         assert_eq!(class.fields[3].ref_key_inner_type, None);
     }
+
+    #[test]
+    fn test_parse_number_literal_union_keys() {
+        let ts_code = r#"
+export class RewardConfig {
+    public tiers: Record<1 | 2 | 3, RewardTier>;
+    public byQuality: Record<Quality, string>;
+    public broken: Record<1 | "a", RewardTier>;
+    public tier: 1 | 2;
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+        let fields = &classes[0].fields;
+
+        assert_eq!(fields[0].field_type, "map,int,RewardTier");
+        assert_eq!(fields[0].validators.key_set_values, vec!["1", "2", "3"]);
+        assert!(fields[0].type_error.is_none());
+
+        assert_eq!(fields[1].field_type, "map,Quality,string");
+        assert!(fields[1].validators.key_set_values.is_empty());
+
+        let error = fields[2].type_error.as_deref().unwrap();
+        assert!(error.contains("1 | \"a\""));
+
+        assert_eq!(fields[3].field_type, "int");
+        assert_eq!(fields[3].original_type, "1 | 2");
+        assert_eq!(fields[3].validators.set_values, vec!["1", "2"]);
+    }
+
+    #[test]
+    fn test_parse_static_readonly_constants() {
+        let ts_code = r#"
+export class RoleConfig {
+    /** Level cap */
+    static readonly MAX_LEVEL = 80;
+    public static readonly DEFAULT_NAME = "hero";
+    static readonly CRIT_RATE: float = 1.5;
+    static readonly MIN_DELTA = -3;
+    static readonly PVP = true as const;
+    static readonly LEVELS = [1, 2];
+    static counter = 0;
+    private static readonly SECRET = 1;
+    public level: number;
+}
+"#;
+        let mut file = NamedTempFile::with_suffix(".ts").unwrap();
+        file.write_all(ts_code.as_bytes()).unwrap();
+
+        let parser = TsParser::new();
+        let classes = parser.parse_file(file.path()).unwrap();
+        let class = &classes[0];
+
+        assert_eq!(class.fields.len(), 1);
+        assert_eq!(class.fields[0].name, "level");
+
+        let constants: Vec<_> = class
+            .constants
+            .iter()
+            .map(|c| (c.name.as_str(), c.const_type.as_str(), c.value.as_str()))
+            .collect();
+        assert_eq!(
+            constants,
+            vec![
+                ("MAX_LEVEL", "int", "80"),
+                ("DEFAULT_NAME", "string", "hero"),
+                ("CRIT_RATE", "float", "1.5"),
+                ("MIN_DELTA", "int", "-3"),
+                ("PVP", "bool", "true"),
+            ]
+        );
+        assert_eq!(class.constants[0].comment.as_deref(), Some("Level cap"));
+        assert_eq!(class.ignored_statics, vec!["LEVELS", "counter"]);
+    }
+
+    #[test]
+    fn test_parse_declare_class_is_ambient() {
+        let ts_code = r#"
+export declare class LegacyConfig {
+    id: number;
+}
+
+export class ItemConfig {
+    id: number;
+}
+"#;
+        let parser = TsParser::new();
+        let classes = parser.parse_source("legacy.ts", ts_code).unwrap();
+        assert_eq!(classes.len(), 2);
+        assert!(classes[0].is_ambient);
+        assert_eq!(classes[0].fields[0].name, "id");
+        assert!(!classes[1].is_ambient);
+
+        let classes = parser
+            .parse_source("types.d.ts", "export class DeclaredConfig { id: number; }")
+            .unwrap();
+        assert!(classes[0].is_ambient);
+    }
+
+    #[test]
+    fn test_parse_unsupported_type_constructs() {
+        let ts_code = r#"
+export class RoleConfig {
+    public statKey: keyof StatTable;
+    public defaults: typeof DEFAULTS;
+    public value: T extends string ? string : number;
+    public keys: Array<keyof StatTable>;
+    public name: string;
+}
+"#;
+        let parser = TsParser::new();
+        let classes = parser.parse_source("role.ts", ts_code).unwrap();
+        let constructs: Vec<_> = classes[0]
+            .fields
+            .iter()
+            .map(|f| (f.field_type.as_str(), f.unsupported_type.as_deref()))
+            .collect();
+        assert_eq!(
+            constructs,
+            vec![
+                ("string", Some("keyof")),
+                ("string", Some("typeof")),
+                ("string", Some("conditional type")),
+                ("list,string", Some("keyof")),
+                ("string", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_any_and_unknown_types() {
+        let ts_code = r#"
+export class EventConfig {
+    public payload: any;
+    public extra: unknown;
+    public args: any[];
+    public meta: Map<string, unknown>;
+    public id: number;
+}
+"#;
+        let types = |parser: TsParser| -> Vec<(String, Option<String>)> {
+            parser.parse_source("event.ts", ts_code).unwrap()[0]
+                .fields
+                .iter()
+                .map(|f| (f.field_type.clone(), f.unsupported_type.clone()))
+                .collect()
+        };
+        let expected = |any_type: &str| -> Vec<(String, Option<String>)> {
+            vec![
+                (any_type.to_string(), Some("any".to_string())),
+                (any_type.to_string(), Some("unknown".to_string())),
+                (format!("list,{}", any_type), Some("any".to_string())),
+                (
+                    format!("map,string,{}", any_type),
+                    Some("unknown".to_string()),
+                ),
+                ("double".to_string(), None),
+            ]
+        };
+
+        assert_eq!(types(TsParser::new()), expected("string"));
+        // defaults.any_type
+        assert_eq!(
+            types(TsParser::new().with_any_type(Some("json".to_string()))),
+            expected("json")
+        );
+    }
+    #[test]
+    fn test_parse_forbidden_type_references() {
+        let ts_code = r#"
+export interface WeaponConfig {
+    model: Instance;
+    loaded: Promise<ItemConfig>;
+    parts: Map<string, BasePart | undefined>;
+    cleanup: Janitor;
+    item: ItemConfig;
+}
+"#;
+        let parser = TsParser::new().with_forbidden_types(vec!["Janitor".to_string()]);
+        let classes = parser.parse_source("weapon.ts", ts_code).unwrap();
+        let forbidden: Vec<_> = classes[0]
+            .fields
+            .iter()
+            .map(|f| f.forbidden_type.as_deref())
+            .collect();
+        assert_eq!(
+            forbidden,
+            vec![
+                Some("Instance"),
+                Some("Promise"),
+                Some("BasePart"),
+                Some("Janitor"),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_export_assignment_and_namespaces() {
+        let ts_code = r#"
+class ConfigBundle {
+    public id: number;
+}
+
+export namespace Skills {
+    export class SkillConfig {
+        public power: number;
+    }
+    class Hidden {}
+}
+
+export = ConfigBundle;
+"#;
+        let parser = TsParser::new();
+        let classes = parser.parse_source("legacy/bundle.ts", ts_code).unwrap();
+
+        let names: Vec<&str> = classes.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["SkillConfig", "ConfigBundle"]);
+        assert_eq!(classes[1].fields[0].name, "id");
+        assert!(parser.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_warn_commonjs_exports() {
+        let ts_code = r#"
+class SkillConfig {
+    public power: number;
+}
+const Bundle = {};
+
+module.exports = { SkillConfig };
+Bundle.Skill = SkillConfig;
+module.exports.Other = class Other {};
+"#;
+        let parser = TsParser::new();
+        let classes = parser.parse_source("legacy/dynamic.ts", ts_code).unwrap();
+
+        assert!(classes.is_empty());
+        assert_eq!(
+            parser.take_warnings(),
+            vec![
+                "legacy/dynamic.ts:7:1: module.exports - assignment to `module.exports` is not supported, use `export class` or `export =`; no classes are extracted from it (2 occurrences in this file)".to_string(),
+                "legacy/dynamic.ts:8:1: namespace object assignment - class 'SkillConfig' is assigned onto 'Bundle', declare it with `export class` inside an exported namespace instead; no classes are extracted from it".to_string(),
+            ]
+        );
+        // Drained
+        assert!(parser.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_parse_getters_and_readonly() {
+        let ts_code = r#"
+export class HeroConfig {
+    public readonly id: number;
+    public name: string;
+    constructor(public readonly level: number) {}
+
+    /** Shown in the UI */
+    get displayName(): string {
+        return this.name;
+    }
+    set displayName(value: string) {}
+    get untyped() {
+        return 1;
+    }
+    private get secret(): number {
+        return 0;
+    }
+    static get count(): number {
+        return 0;
+    }
+}
+
+export interface ItemView {
+    readonly id: number;
+    get label(): string;
+    set label(value: string);
+}
+"#;
+        let names = |classes: &[ClassInfo], index: usize| -> Vec<String> {
+            classes[index]
+                .fields
+                .iter()
+                .map(|f| f.name.clone())
+                .collect()
+        };
+
+        let classes = TsParser::new().parse_source("hero.ts", ts_code).unwrap();
+        assert_eq!(names(&classes, 0), vec!["id", "name", "level"]);
+        assert_eq!(names(&classes, 1), vec!["id"]);
+        let readonly: Vec<bool> = classes[0].fields.iter().map(|f| f.is_readonly).collect();
+        assert_eq!(readonly, vec![true, false, true]);
+        assert!(classes[1].fields[0].is_readonly);
+
+        let parser = TsParser::new().with_include_getters(true);
+        let classes = parser.parse_source("hero.ts", ts_code).unwrap();
+        assert_eq!(
+            names(&classes, 0),
+            vec!["id", "name", "level", "displayName"]
+        );
+        let getter = &classes[0].fields[3];
+        assert_eq!(getter.field_type, "string");
+        assert_eq!(getter.comment.as_deref(), Some("Shown in the UI"));
+        assert!(getter.is_readonly);
+        assert_eq!(
+            getter.tags,
+            vec![("computed".to_string(), "true".to_string())]
+        );
+        assert_eq!(names(&classes, 1), vec!["id", "label"]);
+        assert_eq!(classes[1].fields[1].tags[0].0, "computed");
+    }
+
+    #[test]
+    fn test_source_paths_independent_of_separator() {
+        let ts_code = "class Hidden {\n    x: number;\n}\n\nexport class Item {\n    id: number;\n    hidden: Hidden;\n}\n";
+        let resolver = crate::ts_generator::ImportResolver::default();
+        // (project root, source file, generated file)
+        let inputs = [
+            (
+                "C:\\proj",
+                "C:\\proj\\src\\item.ts",
+                "C:\\proj\\out\\beans.ts",
+            ),
+            ("C:/proj", "C:/proj/src/item.ts", "C:/proj/out/beans.ts"),
+            (
+                "C:\\proj\\",
+                "C:/proj/./src\\item.ts",
+                "C:\\proj\\out/beans.ts",
+            ),
+        ];
+        let results: Vec<_> = inputs
+            .iter()
+            .map(|(root, source, output)| {
+                let parser = TsParser::new().with_project_root(Path::new(root));
+                let classes = parser.parse_source(source, ts_code).unwrap();
+                (
+                    // Cache key
+                    classes[0].source_file.clone(),
+                    resolver.resolve(Path::new(output), classes[0].file_path()),
+                    // Locations in messages
+                    parser.take_errors(),
+                )
+            })
+            .collect();
+        assert_eq!(results[0].0, "src/item.ts");
+        assert_eq!(results[0].1, "../src/item");
+        assert_eq!(
+            results[0].2,
+            vec!["src/item.ts:7: Item.hidden - type 'Hidden' is declared but not exported in the same file (line 1); add `export`"]
+        );
+        assert!(results.iter().all(|result| result == &results[0]));
+
+        assert_eq!(
+            project_relative_path(
+                Path::new("D:\\shared\\item.ts"),
+                Some(Path::new("C:\\proj"))
+            ),
+            "D:/shared/item.ts"
+        );
+        assert_eq!(
+            project_relative_path(
+                Path::new("C:\\project\\item.ts"),
+                Some(Path::new("C:\\proj"))
+            ),
+            "C:/project/item.ts"
+        );
+    }
+
+    #[test]
+    fn test_bom_and_crlf_sources() {
+        let lf = "/** 物品 */\nexport class ItemConfig {\n    id: number;\n}\n\nexport enum Quality {\n    Common = 1,\n}\n";
+        let bom_crlf = format!("\u{feff}{}", lf.replace('\n', "\r\n"));
+
+        let parser = TsParser::new();
+        let plain = parser.parse_source("item.ts", lf).unwrap();
+        let converted = parser.parse_source("item.ts", &bom_crlf).unwrap();
+        assert_eq!(converted.len(), 1);
+        assert_eq!(converted[0].comment.as_deref(), Some("物品"));
+        assert_eq!(converted[0].fields[0].line(), Some(3));
+        // Same hash, so a checkout with other line endings doesn't invalidate the cache
+        assert_eq!(converted[0].file_hash, plain[0].file_hash);
+
+        let enums = parser.parse_enums_source("item.ts", &bom_crlf).unwrap();
+        assert_eq!(enums[0].name, "Quality");
+        assert_eq!(
+            enums[0].file_hash,
+            parser.parse_enums_source("item.ts", lf).unwrap()[0].file_hash
+        );
+    }
+
+    #[test]
+    fn test_parse_file_errors_name_the_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let parser = TsParser::new().with_project_root(temp.path());
+
+        let broken = temp.path().join("broken.ts");
+        std::fs::write(
+            &broken,
+            "export class Broken {\n    id: number\n    name: = 1;\n}\n",
+        )
+        .unwrap();
+        let err = parser.parse_file(&broken).unwrap_err().to_string();
+        assert!(err.starts_with("broken.ts:3:"), "{}", err);
+        assert!(err.contains("parse error"), "{}", err);
+
+        let missing = temp.path().join("missing.ts");
+        let err = parser.parse_file(&missing).unwrap_err().to_string();
+        assert_eq!(err, format!("Failed to read {}", missing.display()));
+
+        // Not UTF-8: decoded lossily, with a warning naming the file
+        let legacy = temp.path().join("legacy.ts");
+        std::fs::write(
+            &legacy,
+            b"// \xce\xef\xc6\xb7\nexport class Legacy {\n    id: number;\n}\n",
+        )
+        .unwrap();
+        let classes = parser.parse_file(&legacy).unwrap();
+        assert_eq!(classes[0].name, "Legacy");
+        assert_eq!(
+            parser.take_warnings(),
+            vec![source_text::invalid_utf8_message("legacy.ts")]
+        );
+    }
 }
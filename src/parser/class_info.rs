@@ -1,12 +1,13 @@
-use super::field_info::FieldInfo;
+use super::field_info::{FieldInfo, FlavorCondition};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Import information: maps imported type name to the resolved source file path
 pub type ImportMap = HashMap<String, PathBuf>;
 
 /// Configuration for Luban table generation from @luban-table decorator
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LubanTableConfig {
     pub mode: String,
     pub index: String,
@@ -17,13 +18,24 @@ pub struct LubanTableConfig {
 }
 
 /// Configuration for Luban table from @table JSDoc tag
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsDocTableConfig {
     pub mode: String,
     pub index: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+/// A class constant from `static readonly NAME = <literal>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassConstant {
+    pub name: String,
+    /// Luban type: from the type annotation, otherwise int/double/string/bool from the literal
+    pub const_type: String,
+    /// Literal value (strings unquoted)
+    pub value: String,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClassInfo {
     pub name: String,
     pub comment: Option<String>,
@@ -33,16 +45,30 @@ pub struct ClassInfo {
     #[allow(dead_code)]
     pub implements: Vec<String>,
     pub extends: Option<String>,
+    /// Source file relative to the project root, with forward slashes (absolute when
+    /// outside the root). Used for cache keys, messages and model.json
     pub source_file: String,
+    /// Source file as given to the parser, native form; used for file IO and import paths
+    #[serde(skip)]
+    pub source_path: PathBuf,
+    #[serde(skip)]
     pub file_hash: String,
     pub is_interface: bool,
     /// Custom output path for this class (overrides default output)
     pub output_path: Option<PathBuf>,
+    /// Named output from the @Output("editor") class decorator, or an [[output_routes]] pattern.
+    /// Routed classes are written to that route's XML instead of `output_path`
+    pub output_route: Option<String>,
     /// Custom module name for this class (overrides default module_name)
     pub module_name: Option<String>,
+    /// `module_name` comes from the class itself (@Module("shared") or a `@module shared`
+    /// JSDoc tag) and takes precedence over the per-source and global defaults.
+    /// Some("") forces the root module
+    pub explicit_module: bool,
     /// Generic type parameters mapping: T -> ConstraintType
     /// e.g., {"T": "SkillMetadata", "K": "string"}
     #[allow(dead_code)]
+    #[serde(skip)]
     pub type_params: HashMap<String, String>,
     /// Luban table configuration from @luban-table decorator
     pub luban_table: Option<LubanTableConfig>,
@@ -52,5 +78,61 @@ pub struct ClassInfo {
     pub input_path: Option<String>,
     /// Import mappings: type_name -> resolved source file path
     /// Used for cross-module type resolution when same-named types exist in different modules
+    #[serde(skip)]
     pub imports: ImportMap,
+    /// Table index field from @RefKey("itemId") (or @LubanTable({ index })).
+    /// Used when the [tables] entry doesn't set `index`
+    pub ref_key: Option<String>,
+    /// Whether this bean is defined in config ([[virtual_beans]]) rather than TypeScript
+    pub is_virtual: bool,
+    /// Left out of beans.ts (@NoRuntime() or a [beans] exclude pattern): the class is only
+    /// read back from Luban data, never constructed from TypeScript. Its XML bean and
+    /// tables.d.ts typing are still generated
+    pub no_runtime: bool,
+    /// Build flavors the class is generated for; classes excluded from the active flavor are
+    /// dropped before validation
+    pub flavors: FlavorCondition,
+    /// Declared in an ambient context (`declare class`, or any class in a .d.ts file):
+    /// there is no runtime constructor, so it's left out of beans.ts
+    pub is_ambient: bool,
+    /// Explicit parent from @Parent("Base") class decorator.
+    /// Some("") means "no parent" even if extends/implements would provide one
+    pub explicit_parent: Option<String>,
+    /// Arbitrary bean tags from @Tag("key", "value") class decorators
+    pub tags: Vec<(String, String)>,
+    /// `static readonly NAME = <literal>` properties; not part of `fields`
+    pub constants: Vec<ClassConstant>,
+    /// Public static properties that aren't readonly literals (skipped, reported in verbose mode)
+    pub ignored_statics: Vec<String>,
+    /// `@example` blocks from the JSDoc, verbatim; never part of `comment`
+    #[serde(default)]
+    pub examples: Vec<String>,
+}
+
+impl ClassInfo {
+    /// Path to read the source or compute imports from: `source_path`, or `source_file`
+    /// for classes built without one
+    pub fn file_path(&self) -> &Path {
+        if self.source_path.as_os_str().is_empty() {
+            Path::new(&self.source_file)
+        } else {
+            &self.source_path
+        }
+    }
+
+    /// Location of a field for messages: its origin (`file:line` or the config entry that
+    /// injected it), else the class source file
+    pub fn field_location(&self, field: &FieldInfo) -> String {
+        match &field.origin {
+            Some(origin) => origin.to_string(),
+            None => self.source_file.clone(),
+        }
+    }
+
+    /// Fields in declaration order (see `FieldInfo::order`)
+    pub fn ordered_fields(&self) -> Vec<&FieldInfo> {
+        let mut fields: Vec<&FieldInfo> = self.fields.iter().collect();
+        fields.sort_by_key(|f| f.order);
+        fields
+    }
 }
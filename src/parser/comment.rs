@@ -0,0 +1,233 @@
+use crate::config::{CommentStyle, DefaultsConfig};
+
+/// How JSDoc descriptions are turned into class, field, enum and variant comments
+#[derive(Debug, Clone, Default)]
+pub struct CommentPolicy {
+    pub style: CommentStyle,
+    /// Maximum length in characters; longer comments end with `…`
+    pub max_length: Option<usize>,
+    /// Tags (without `@`) whose lines are kept instead of dropped
+    pub keep_tags: Vec<String>,
+}
+
+impl From<&DefaultsConfig> for CommentPolicy {
+    fn from(defaults: &DefaultsConfig) -> Self {
+        Self {
+            style: defaults.comment_style,
+            max_length: defaults.comment_max_length,
+            keep_tags: defaults.comment_keep_tags.clone(),
+        }
+    }
+}
+
+impl CommentPolicy {
+    /// Normalize a raw JSDoc (or line) comment. `exclude_tags` lines (`@tag=` / `@tag:`) are
+    /// skipped on their own; any other tag line drops itself and its continuation lines
+    /// unless the tag is in `keep_tags` (`@example` is always dropped, it's kept in
+    /// `examples` instead). Fenced code blocks are dropped and whitespace is collapsed
+    /// before the style and length limit are applied. Kept tag lines of later paragraphs
+    /// are kept by the first-paragraph style too
+    pub fn normalize(&self, text: &str, exclude_tags: &[&str]) -> String {
+        // Lines per paragraph, flagged when they belong to a kept tag
+        let mut paragraphs: Vec<Vec<(&str, bool)>> = vec![Vec::new()];
+        let mut in_tag = false;
+        let mut keeping = true;
+        let mut in_fence = false;
+
+        for line in text.lines() {
+            let line = line.trim().trim_start_matches('*').trim();
+            if line.starts_with("```") {
+                in_fence = !in_fence;
+                continue;
+            }
+            if in_fence {
+                continue;
+            }
+            if let Some(tag) = line.strip_prefix('@') {
+                let is_excluded = exclude_tags.iter().any(|t| {
+                    tag.strip_prefix(t)
+                        .is_some_and(|rest| rest.starts_with('=') || rest.starts_with(':'))
+                });
+                if is_excluded {
+                    continue;
+                }
+                let name = tag
+                    .split(|c: char| c.is_whitespace() || c == '=' || c == ':')
+                    .next()
+                    .unwrap_or("");
+                keeping = name != "example" && self.keep_tags.iter().any(|t| t == name);
+                in_tag = true;
+                if !keeping {
+                    continue;
+                }
+            } else if !keeping {
+                continue;
+            }
+
+            if line.is_empty() {
+                if paragraphs.last().is_some_and(|p| !p.is_empty()) {
+                    paragraphs.push(Vec::new());
+                }
+            } else {
+                paragraphs.last_mut().unwrap().push((line, in_tag));
+            }
+        }
+
+        let first = paragraphs[0].iter().map(|(line, _)| *line);
+        let lines: Vec<&str> = match self.style {
+            CommentStyle::Full => paragraphs
+                .concat()
+                .into_iter()
+                .map(|(line, _)| line)
+                .collect(),
+            CommentStyle::FirstParagraph => first
+                .chain(
+                    paragraphs[1..]
+                        .iter()
+                        .flatten()
+                        .filter(|(_, is_tag)| *is_tag)
+                        .map(|(line, _)| *line),
+                )
+                .collect(),
+            CommentStyle::FirstLine => first.take(1).collect(),
+            CommentStyle::FirstSentence => first.collect(),
+        };
+        let mut comment = lines
+            .iter()
+            .flat_map(|l| l.split_whitespace())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if self.style == CommentStyle::FirstSentence {
+            comment.truncate(first_sentence_end(&comment));
+        }
+
+        match self.max_length {
+            Some(max) if comment.chars().count() > max => {
+                let kept: String = comment.chars().take(max.saturating_sub(1)).collect();
+                format!("{}…", kept.trim_end())
+            }
+            _ => comment,
+        }
+    }
+}
+
+/// Byte offset just past the first sentence: a CJK full stop, or `.`/`!`/`?` followed by
+/// whitespace or the end of the text (so `v1.2` doesn't end a sentence)
+fn first_sentence_end(text: &str) -> usize {
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let end = i + c.len_utf8();
+        match c {
+            '。' | '！' | '？' => return end,
+            '.' | '!' | '?' if !matches!(chars.peek(), Some((_, n)) if !n.is_whitespace()) => {
+                return end
+            }
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY: &str = r#"*
+     * Skill   configuration for
+     * player characters. Loaded at startup.
+     *
+     * Second paragraph with **markdown**.
+     *
+     * ```ts
+     * const skill = new SkillConfig();
+     * ```
+     * @alias:技能
+     * @param id the skill id
+     *   continued param text
+     * @deprecated use SkillConfigV2
+     "#;
+
+    fn policy(style: CommentStyle) -> CommentPolicy {
+        CommentPolicy {
+            style,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_full_style() {
+        assert_eq!(
+            policy(CommentStyle::Full).normalize(MESSY, &["alias"]),
+            "Skill configuration for player characters. Loaded at startup. Second paragraph with **markdown**."
+        );
+    }
+
+    #[test]
+    fn test_first_paragraph_style() {
+        let first_paragraph = policy(CommentStyle::FirstParagraph);
+        assert_eq!(
+            first_paragraph.normalize(MESSY, &["alias"]),
+            "Skill configuration for player characters. Loaded at startup."
+        );
+        assert_eq!(
+            first_paragraph.normalize("* Damage dealt.\n*\n* Applied after armor.", &[]),
+            "Damage dealt."
+        );
+    }
+
+    #[test]
+    fn test_first_line_style() {
+        assert_eq!(
+            policy(CommentStyle::FirstLine).normalize(MESSY, &["alias"]),
+            "Skill configuration for"
+        );
+    }
+
+    #[test]
+    fn test_first_sentence_style() {
+        let first_sentence = policy(CommentStyle::FirstSentence);
+        assert_eq!(
+            first_sentence.normalize(MESSY, &["alias"]),
+            "Skill configuration for player characters."
+        );
+        assert_eq!(
+            first_sentence.normalize("* Uses v1.2 rules。第二句。", &[]),
+            "Uses v1.2 rules。"
+        );
+    }
+
+    #[test]
+    fn test_keep_tags() {
+        let policy = CommentPolicy {
+            style: CommentStyle::Full,
+            keep_tags: vec!["deprecated".to_string()],
+            ..Default::default()
+        };
+        let comment = policy.normalize(MESSY, &["alias"]);
+        assert!(comment.ends_with("**markdown**. @deprecated use SkillConfigV2"));
+        assert!(!comment.contains("@param"));
+        assert!(!comment.contains("continued"));
+
+        // The first-paragraph style (default) keeps them after the first paragraph
+        let policy = CommentPolicy {
+            keep_tags: vec!["deprecated".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.normalize(MESSY, &["alias"]),
+            "Skill configuration for player characters. Loaded at startup. @deprecated use SkillConfigV2"
+        );
+    }
+
+    #[test]
+    fn test_max_length() {
+        let policy = CommentPolicy {
+            max_length: Some(20),
+            ..Default::default()
+        };
+        let comment = policy.normalize(MESSY, &["alias"]);
+        assert_eq!(comment, "Skill configuration…");
+        assert_eq!(comment.chars().count(), 20);
+        assert_eq!(policy.normalize("* Short", &[]), "Short");
+    }
+}
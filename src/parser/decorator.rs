@@ -4,6 +4,7 @@ use swc_ecma_ast::*;
 pub enum DecoratorArg {
     Number(f64),
     String(String),
+    Bool(bool),
     Identifier(String),
     Array(Vec<DecoratorArg>),
 }
@@ -52,26 +53,13 @@ pub fn parse_decorator(decorator: &Decorator) -> Option<ParsedDecorator> {
 
             for arg in &call.args {
                 match &*arg.expr {
-                    Expr::Lit(Lit::Num(n)) => {
-                        args.push(DecoratorArg::Number(n.value));
-                    }
-                    Expr::Lit(Lit::Str(s)) => {
-                        // Use format!("{:?}", ...) to convert Wtf8Atom to string
-                        let str_val = format!("{:?}", s.value).trim_matches('"').to_string();
-                        args.push(DecoratorArg::String(str_val));
-                    }
-                    Expr::Ident(ident) => {
-                        args.push(DecoratorArg::Identifier(ident.sym.to_string()));
-                    }
                     Expr::Object(obj) => {
                         for prop in &obj.props {
                             if let PropOrSpread::Prop(prop) = prop {
                                 if let Prop::KeyValue(kv) = &**prop {
                                     let key = match &kv.key {
                                         PropName::Ident(i) => i.sym.to_string(),
-                                        PropName::Str(s) => {
-                                            format!("{:?}", s.value).trim_matches('"').to_string()
-                                        }
+                                        PropName::Str(s) => str_value(s),
                                         _ => continue,
                                     };
                                     let value = parse_expr_to_arg(&kv.value);
@@ -82,7 +70,11 @@ pub fn parse_decorator(decorator: &Decorator) -> Option<ParsedDecorator> {
                             }
                         }
                     }
-                    _ => {}
+                    expr => {
+                        if let Some(v) = parse_expr_to_arg(expr) {
+                            args.push(v);
+                        }
+                    }
                 }
             }
 
@@ -103,14 +95,36 @@ pub fn parse_decorator(decorator: &Decorator) -> Option<ParsedDecorator> {
     }
 }
 
+/// Get the cooked value of a string literal (escapes already resolved).
+/// Lone surrogates, which only WTF-8 can hold, are replaced lossily.
+pub fn str_value(s: &Str) -> String {
+    s.value.to_atom_lossy().as_str().to_string()
+}
+
 fn parse_expr_to_arg(expr: &Expr) -> Option<DecoratorArg> {
     match expr {
         Expr::Lit(Lit::Num(n)) => Some(DecoratorArg::Number(n.value)),
-        Expr::Lit(Lit::Str(s)) => {
-            let str_val = format!("{:?}", s.value).trim_matches('"').to_string();
-            Some(DecoratorArg::String(str_val))
-        }
+        Expr::Lit(Lit::Str(s)) => Some(DecoratorArg::String(str_value(s))),
+        Expr::Lit(Lit::Bool(b)) => Some(DecoratorArg::Bool(b.value)),
         Expr::Ident(ident) => Some(DecoratorArg::Identifier(ident.sym.to_string())),
+        // Negative numbers: -10 is a unary minus applied to a numeric literal
+        Expr::Unary(unary) if unary.op == UnaryOp::Minus => match &*unary.arg {
+            Expr::Lit(Lit::Num(n)) => Some(DecoratorArg::Number(-n.value)),
+            _ => None,
+        },
+        // Template literals without substitutions: `text`
+        Expr::Tpl(tpl) if tpl.exprs.is_empty() => tpl
+            .quasis
+            .first()
+            .and_then(|q| q.cooked.as_ref())
+            .map(|cooked| DecoratorArg::String(cooked.to_atom_lossy().as_str().to_string())),
+        Expr::Array(arr) => Some(DecoratorArg::Array(
+            arr.elems
+                .iter()
+                .flatten()
+                .filter_map(|elem| parse_expr_to_arg(&elem.expr))
+                .collect(),
+        )),
         _ => None,
     }
 }
@@ -133,7 +147,7 @@ fn extract_type_params(type_args: &TsTypeParamInstantiation) -> Vec<String> {
             // Literal type: "fieldName"
             TsType::TsLitType(lit_type) => {
                 if let TsLit::Str(s) = &lit_type.lit {
-                    Some(format!("{:?}", s.value).trim_matches('"').to_string())
+                    Some(str_value(s))
                 } else {
                     None
                 }
@@ -142,3 +156,156 @@ fn extract_type_params(type_args: &TsTypeParamInstantiation) -> Vec<String> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_common::{sync::Lrc, FileName, SourceMap};
+    use swc_ecma_parser::{Parser, StringInput, Syntax, TsSyntax};
+
+    /// Parse the decorators attached to the first class in `code`
+    fn class_decorators(code: &str) -> Vec<ParsedDecorator> {
+        let cm: Lrc<SourceMap> = Default::default();
+        let fm = cm.new_source_file(FileName::Anon.into(), code.to_string());
+        let mut parser = Parser::new(
+            Syntax::Typescript(TsSyntax {
+                decorators: true,
+                ..Default::default()
+            }),
+            StringInput::from(&*fm),
+            None,
+        );
+        let module = parser.parse_module().unwrap();
+        for item in &module.body {
+            if let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Class(class_decl),
+                ..
+            })) = item
+            {
+                return class_decl
+                    .class
+                    .decorators
+                    .iter()
+                    .filter_map(parse_decorator)
+                    .collect();
+            }
+        }
+        panic!("no exported class in test source");
+    }
+
+    fn string_arg(arg: &DecoratorArg) -> &str {
+        match arg {
+            DecoratorArg::String(s) => s,
+            other => panic!("expected string arg, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_string_args_with_quotes_and_backslashes() {
+        let decorators = class_decorators(
+            r#"
+@Tag("path", "C:\\data")
+@Alias("说明\"引号\"")
+@Tag('single', 'it\'s')
+export class Foo {}
+"#,
+        );
+        assert_eq!(string_arg(&decorators[0].args[1]), r"C:\data");
+        assert_eq!(string_arg(&decorators[1].args[0]), "说明\"引号\"");
+        assert_eq!(string_arg(&decorators[2].args[1]), "it's");
+    }
+
+    #[test]
+    fn test_string_args_with_escapes_and_cjk() {
+        let decorators = class_decorators(
+            r#"
+@Tag("note", "第一行\n第二行\t完")
+@Tag("unicode", "\u89d2\u8272")
+export class Foo {}
+"#,
+        );
+        assert_eq!(string_arg(&decorators[0].args[1]), "第一行\n第二行\t完");
+        assert_eq!(string_arg(&decorators[1].args[1]), "角色");
+    }
+
+    #[test]
+    fn test_named_and_type_param_strings() {
+        let decorators = class_decorators(
+            r#"
+@Meta({ "显示名": "攻击\\力", label: "a\"b" })
+@RefReplace<Item, "名称">()
+export class Foo {}
+"#,
+        );
+        assert_eq!(string_arg(&decorators[0].named_args["显示名"]), r"攻击\力");
+        assert_eq!(string_arg(&decorators[0].named_args["label"]), "a\"b");
+        assert_eq!(decorators[1].type_params, vec!["Item", "名称"]);
+    }
+
+    #[test]
+    fn test_negative_bool_and_template_args() {
+        let decorators = class_decorators(
+            r#"
+@Range(-10, 10)
+@Required(false)
+@Alias(`角色`)
+@Meta({ min: -0.5, enabled: true, label: `a\tb` })
+export class Foo {}
+"#,
+        );
+        assert!(
+            matches!(decorators[0].args[..], [DecoratorArg::Number(min), DecoratorArg::Number(max)] if min == -10.0 && max == 10.0)
+        );
+        assert!(matches!(
+            decorators[1].args[..],
+            [DecoratorArg::Bool(false)]
+        ));
+        assert_eq!(string_arg(&decorators[2].args[0]), "角色");
+
+        let named = &decorators[3].named_args;
+        assert!(matches!(named["min"], DecoratorArg::Number(n) if n == -0.5));
+        assert!(matches!(named["enabled"], DecoratorArg::Bool(true)));
+        assert_eq!(string_arg(&named["label"]), "a\tb");
+    }
+
+    #[test]
+    fn test_template_literal_with_substitution_is_ignored() {
+        let decorators = class_decorators(
+            r#"
+@Alias(`role_${id}`)
+export class Foo {}
+"#,
+        );
+        assert!(decorators[0].args.is_empty());
+    }
+
+    #[test]
+    fn test_nested_array_args() {
+        let decorators = class_decorators(
+            r#"
+@Set([1, -2, [true, `x`]], "tail")
+@Meta({ bounds: [-1.5, 2] })
+export class Foo {}
+"#,
+        );
+        let DecoratorArg::Array(items) = &decorators[0].args[0] else {
+            panic!("expected array arg");
+        };
+        assert!(
+            matches!(items[..2], [DecoratorArg::Number(a), DecoratorArg::Number(b)] if a == 1.0 && b == -2.0)
+        );
+        let DecoratorArg::Array(inner) = &items[2] else {
+            panic!("expected nested array");
+        };
+        assert!(matches!(inner[0], DecoratorArg::Bool(true)));
+        assert_eq!(string_arg(&inner[1]), "x");
+        assert_eq!(string_arg(&decorators[0].args[1]), "tail");
+
+        let DecoratorArg::Array(bounds) = &decorators[1].named_args["bounds"] else {
+            panic!("expected array named arg");
+        };
+        assert!(
+            matches!(bounds[..], [DecoratorArg::Number(a), DecoratorArg::Number(b)] if a == -1.5 && b == 2.0)
+        );
+    }
+}
@@ -1,7 +1,8 @@
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 /// Represents an enum variant (member)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnumVariant {
     /// Variant name (e.g., "Role")
     pub name: String,
@@ -14,7 +15,7 @@ pub struct EnumVariant {
 }
 
 /// Represents a TypeScript enum
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnumInfo {
     /// Enum name
     pub name: String,
@@ -30,12 +31,27 @@ pub struct EnumInfo {
     pub tags: Option<String>,
     /// Enum variants
     pub variants: Vec<EnumVariant>,
-    /// Source file path
+    /// Source file relative to the project root, with forward slashes
     pub source_file: String,
+    /// Source file as given to the parser, native form
+    #[serde(skip)]
+    pub source_path: PathBuf,
     /// File hash for caching
+    #[serde(skip)]
     pub file_hash: String,
     /// Custom output path for this enum
     pub output_path: Option<PathBuf>,
     /// Custom module name for this enum
     pub module_name: Option<String>,
 }
+
+impl EnumInfo {
+    /// See `ClassInfo::file_path`
+    pub fn file_path(&self) -> &Path {
+        if self.source_path.as_os_str().is_empty() {
+            Path::new(&self.source_file)
+        } else {
+            &self.source_path
+        }
+    }
+}
@@ -1,10 +1,38 @@
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SizeConstraint {
     Exact(usize),
     Range(usize, usize),
 }
 
-#[derive(Debug, Clone, Default)]
+/// Bounds from @Range(min, max), @Range({ min }) or @Range({ max }); both bounds are inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RangeConstraint {
+    Between(f64, f64),
+    Min(f64),
+    Max(f64),
+}
+
+impl RangeConstraint {
+    pub fn min(&self) -> Option<f64> {
+        match self {
+            Self::Between(min, _) | Self::Min(min) => Some(*min),
+            Self::Max(_) => None,
+        }
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        match self {
+            Self::Between(_, max) | Self::Max(max) => Some(*max),
+            Self::Min(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FieldValidators {
     /// @ref JSDoc tag - applies to scalar, list element, or map value
     /// Auto-discovers target table from field type
@@ -12,22 +40,65 @@ pub struct FieldValidators {
     /// RefKey<T> generic type - applies to map key only
     /// Set when Map<RefKey<T>, V> pattern is detected
     pub has_ref_key: bool,
-    pub range: Option<(f64, f64)>,
+    /// @Ref(ItemConfig) - the field holds keys of ItemConfig's table; applies to a scalar,
+    /// list/array/set element or map value, which takes the table's index type
+    pub ref_target: Option<String>,
+    /// @RefKey(ItemConfig) on a map field - the map key holds keys of ItemConfig's table
+    pub ref_key_target: Option<String>,
+    pub range: Option<RangeConstraint>,
     pub required: bool,
     pub size: Option<SizeConstraint>,
     pub set_values: Vec<String>,
+    /// Allowed map keys from a number-literal union key, e.g. Record<1 | 2 | 3, T>
+    pub key_set_values: Vec<String>,
     pub index_field: Option<String>,
     pub nominal: bool,
 }
 
-#[derive(Debug, Clone)]
+/// Build flavors a class or field is generated for, from @OnlyIn("full") / @ExcludeFrom("lite")
+/// or the `@onlyIn full` / `@excludeFrom lite` JSDoc tags
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlavorCondition {
+    /// Only generated for these flavors; empty means every flavor
+    pub only_in: Vec<String>,
+    /// Never generated for these flavors
+    pub exclude_from: Vec<String>,
+}
+
+impl FlavorCondition {
+    /// Whether the class or field is kept when generating `flavor`
+    pub fn includes(&self, flavor: &str) -> bool {
+        (self.only_in.is_empty() || self.only_in.iter().any(|f| f == flavor))
+            && !self.exclude_from.iter().any(|f| f == flavor)
+    }
+}
+
+/// Custom schema representation from @Converter("CurveRef", { tag: "converter=CurveParser" })
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldConverter {
+    /// Luban type emitted verbatim (bypasses TypeMapper)
+    pub luban_type: String,
+    /// Extra tags appended to the field, e.g. "converter=CurveParser"
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldInfo {
     pub name: String,
     pub field_type: String,
     pub comment: Option<String>,
     /// Optional alias from @alias tag in JSDoc
     pub alias: Option<String>,
+    /// The property may be absent: `name?: T`, or a constructor parameter with a default
     pub is_optional: bool,
+    /// The property is present but may hold null/undefined: `name: T | null`,
+    /// `name: T | undefined`
+    #[serde(default)]
+    pub is_nullable: bool,
+    /// Declared `readonly`, typed with a read-only container (`readonly T[]`,
+    /// `ReadonlyArray`/`ReadonlyMap`/`ReadonlySet`), or a getter extracted with
+    /// defaults.include_getters
+    pub is_readonly: bool,
     pub validators: FieldValidators,
     /// Whether this field is ObjectFactory<T> type
     pub is_object_factory: bool,
@@ -54,6 +125,86 @@ pub struct FieldInfo {
     /// RefReplace decorator: (TypeName, FieldName) from @RefReplace<T, "field">()
     /// Generates type from T's index_type and tags="RefOverride=field"
     pub ref_replace: Option<(String, String)>,
+    /// Arbitrary tags from @Tag("key", "value") decorators, in declaration order
+    pub tags: Vec<(String, String)>,
+    /// Schema column name from @Name("x") decorator or @luban-name JSDoc tag.
+    /// Only the XML uses it; TypeScript outputs keep the property name
+    pub xml_name: Option<String>,
+    /// Custom Luban type and tags from the @Converter decorator
+    pub converter: Option<FieldConverter>,
+    /// Where the field was declared, or the config entry that injected it.
+    /// None only for fields built in code
+    pub origin: Option<FieldOrigin>,
+    /// Unsupported TypeScript type shape found while parsing (e.g. a map key union mixing
+    /// number literals with other types), reported by validation
+    pub type_error: Option<String>,
+    /// Type construct that has no Luban equivalent (`keyof`, `typeof`, conditional types,
+    /// `any`/`unknown`) and was converted to string; warned about unless @type overrides the type
+    pub unsupported_type: Option<String>,
+    /// Runtime-only type the field references (a Roblox Instance class such as `BasePart`,
+    /// `Promise`, or a `[forbidden_types]` name); reported by validation
+    pub forbidden_type: Option<String>,
+    /// Position in the declaring class: the source offset of the declaration at parse time,
+    /// renumbered to the final position once virtual fields are injected.
+    /// XML generation emits vars sorted by it, since Luban column order follows var order
+    #[serde(skip)]
+    pub order: u32,
+    /// Build flavors the field is generated for
+    pub flavors: FlavorCondition,
+    /// `@example` blocks from the JSDoc, verbatim; never part of `comment`
+    #[serde(default)]
+    pub examples: Vec<String>,
+}
+
+impl FieldInfo {
+    /// Whether the field may end up without a value (absent or null). Luban's `?` models
+    /// both, so this is what the XML and schema hashes use
+    pub fn may_be_empty(&self) -> bool {
+        self.is_optional || self.is_nullable
+    }
+
+    /// Name of the field in the Luban schema (renamed via @Name, else the property name)
+    pub fn schema_name(&self) -> &str {
+        self.xml_name.as_deref().unwrap_or(&self.name)
+    }
+
+    /// 1-based line of the TypeScript declaration (parsed fields only)
+    pub fn line(&self) -> Option<usize> {
+        match self.origin {
+            Some(FieldOrigin::Source { line, .. }) => Some(line),
+            _ => None,
+        }
+    }
+
+    /// Index of the [[virtual_fields]] block that added or overrode this field
+    pub fn virtual_block(&self) -> Option<usize> {
+        match self.origin {
+            Some(FieldOrigin::VirtualConfig { index }) => Some(index),
+            _ => None,
+        }
+    }
+}
+
+/// Where a field comes from: a TypeScript declaration or a config entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FieldOrigin {
+    /// Declared in TypeScript; `file` is project-relative like `ClassInfo::source_file`
+    Source { file: String, line: usize },
+    /// Added or overridden by the [[virtual_fields]] block at `index` (0-based)
+    VirtualConfig { index: usize },
+    /// Declared by the [[virtual_beans]] entry at `index` (0-based)
+    VirtualBean { index: usize },
+}
+
+impl std::fmt::Display for FieldOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Source { file, line } => write!(f, "{}:{}", file, line),
+            Self::VirtualConfig { index } => write!(f, "[[virtual_fields]] block {}", index + 1),
+            Self::VirtualBean { index } => write!(f, "[[virtual_beans]] entry {}", index + 1),
+        }
+    }
 }
 
 impl Default for FieldInfo {
@@ -64,6 +215,8 @@ impl Default for FieldInfo {
             comment: None,
             alias: None,
             is_optional: false,
+            is_nullable: false,
+            is_readonly: false,
             validators: FieldValidators::default(),
             is_object_factory: false,
             factory_inner_type: None,
@@ -77,6 +230,16 @@ impl Default for FieldInfo {
             custom_tags: None,
             ref_key_inner_type: None,
             ref_replace: None,
+            tags: Vec::new(),
+            xml_name: None,
+            converter: None,
+            origin: None,
+            flavors: FlavorCondition::default(),
+            examples: Vec::new(),
+            type_error: None,
+            order: 0,
+            unsupported_type: None,
+            forbidden_type: None,
         }
     }
 }
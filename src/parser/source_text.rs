@@ -0,0 +1,105 @@
+//! Source text as handed to swc. A UTF-8 BOM (which swc rejects as the first token) is
+//! dropped and CRLF line endings become LF, so a file parses, reports lines and hashes the
+//! same whichever editor or checkout wrote it. Files that aren't valid UTF-8 (e.g. a
+//! GBK-encoded legacy file) decode lossily, leaving the caller to warn or fail.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use swc_common::comments::SingleThreadedComments;
+
+/// A decoded source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceText {
+    /// Normalized text (see `normalize`)
+    pub text: String,
+    /// The bytes were not valid UTF-8; invalid sequences became U+FFFD
+    pub lossy: bool,
+}
+
+impl SourceText {
+    /// Decode raw file bytes, lossily when they aren't valid UTF-8
+    pub fn decode(bytes: Vec<u8>) -> Self {
+        let (text, lossy) = match String::from_utf8(bytes) {
+            Ok(text) => (text, false),
+            Err(e) => (String::from_utf8_lossy(e.as_bytes()).into_owned(), true),
+        };
+        Self {
+            text: normalize(text),
+            lossy,
+        }
+    }
+}
+
+/// Read and decode a source file; a read error names the file
+pub fn read(path: &Path) -> Result<SourceText> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(SourceText::decode(bytes))
+}
+
+/// Drop a leading UTF-8 BOM and turn CRLF line endings into LF
+pub fn normalize(mut text: String) -> String {
+    if text.starts_with('\u{feff}') {
+        text.drain(..'\u{feff}'.len_utf8());
+    }
+    if text.contains("\r\n") {
+        text = text.replace("\r\n", "\n");
+    }
+    text
+}
+
+/// Drop comments containing U+FFFD: in a lossily decoded file they are mojibake, which must
+/// not become bean, field or enum comments in the generated schema
+pub fn drop_garbled_comments(comments: &SingleThreadedComments) {
+    let (mut leading, mut trailing) = comments.borrow_all_mut();
+    for list in leading.values_mut().chain(trailing.values_mut()) {
+        list.retain(|comment| !comment.text.contains('\u{fffd}'));
+    }
+}
+
+/// Warning (or, with defaults.invalid_utf8 = "error", error) for a lossily decoded file
+pub fn invalid_utf8_message(file: &str) -> String {
+    format!(
+        "{}: not valid UTF-8, invalid bytes were replaced with U+FFFD; re-save the file as UTF-8",
+        file
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let plain = SourceText::decode(b"export class A {}\n".to_vec());
+        assert_eq!(plain.text, "export class A {}\n");
+        assert!(!plain.lossy);
+
+        let bom = SourceText::decode(b"\xef\xbb\xbfexport class A {}\r\n".to_vec());
+        assert_eq!(bom.text, "export class A {}\n");
+        assert!(!bom.lossy);
+
+        // "// 物品" in GBK
+        let gbk = SourceText::decode(b"// \xce\xef\xc6\xb7\r\nexport class A {}\r\n".to_vec());
+        assert!(gbk.lossy);
+        assert!(gbk.text.starts_with("// \u{fffd}"));
+        assert!(gbk.text.ends_with("\nexport class A {}\n"));
+        assert!(!gbk.text.contains('\r'));
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("a\r\nb\r\n".to_string()), "a\nb\n");
+        assert_eq!(normalize("\u{feff}a\nb".to_string()), "a\nb");
+        // A lone CR is not a line ending here
+        assert_eq!(normalize("a\rb".to_string()), "a\rb");
+        // Only a leading BOM is dropped
+        assert_eq!(normalize("a\u{feff}".to_string()), "a\u{feff}");
+    }
+
+    #[test]
+    fn test_read_error_names_file() {
+        let err = read(Path::new("/nonexistent/dir/role.ts")).unwrap_err();
+        assert_eq!(err.to_string(), "Failed to read /nonexistent/dir/role.ts");
+    }
+}
@@ -0,0 +1,3149 @@
+//! Programmatic generation API.
+//!
+//! `Pipeline` runs the same scan → parse → validate → generate steps as the
+//! `luban-gen` CLI, but can also return intermediate results (parsed classes,
+//! rendered XML and TypeScript) without writing any files.
+
+use crate::cache::{Cache, CacheStatus, CachedClass, CachedFile};
+use crate::changelog::{self, BeanDelta, SchemaSnapshot};
+use crate::config::{
+    Config, ConstantsFormat, InvalidUtf8, LintSeverity, Newline, OptionalContainers, OutputRoute,
+    SourceConfig, TableConfig, TableReconcile,
+};
+use crate::diagnostics::{Diagnostics, Warning};
+use crate::error::GenError;
+use crate::generator::{
+    find_ambiguous_parents, find_retyped_inherited_fields, find_shadowed_parent_fields,
+    generate_bean_type_enums_xml, generate_constants_xml, reindent_xml, resolve_parent_conflicts,
+    resolve_renamed_index_fields, XmlGenerator,
+};
+use crate::lint::{LintViolation, Linter};
+use crate::module_stats::{self, ModuleStats};
+use crate::output::{self, write_if_changed};
+use crate::parser::{
+    compute_hash, normalize_path, project_relative_path, source_text, ClassInfo, CommentPolicy,
+    EnumInfo, FieldInfo, TsParser,
+};
+use crate::scanner;
+use crate::table_registry::{luban_group, unknown_mode_error, ResolvedTableConfig, TableRegistry};
+use crate::ts_generator::import_cycles;
+use crate::ts_generator::register_estimate::{self, RegisterPressure, LUAU_REGISTER_LIMIT};
+use crate::ts_generator::{generate_constants_ts, BeanKeyFormat, TsCodeGenerator};
+use crate::tsconfig::{PathResolver, TsConfig};
+use crate::type_mapper::TypeMapper;
+use crate::validator::{UnknownType, UnknownTypeKind};
+use crate::{schema_version, validator, virtual_fields, xml_lint};
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// A generated file that has not been written yet
+#[derive(Debug, Clone)]
+pub struct GeneratedFile {
+    /// Output path, resolved against the project root
+    pub path: PathBuf,
+    /// What the file is, for messages, e.g. `beans chunk 2`
+    pub artifact: String,
+    pub content: String,
+    /// Number of beans in this file
+    pub beans: usize,
+    /// Number of enums in this file
+    pub enums: usize,
+    /// Module of a module XML file
+    pub module: Option<String>,
+}
+
+/// Parse time and cache status of one source file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileStats {
+    pub path: PathBuf,
+    /// Time spent parsing the file, in milliseconds
+    pub parse_ms: f64,
+    pub classes: usize,
+    pub enums: usize,
+    /// Every bean/enum from this file matched the cache
+    pub cached: bool,
+    /// Parsing took longer than `limits.parse_budget_ms`
+    pub over_budget: bool,
+    /// Not valid UTF-8; parsed after replacing invalid bytes (defaults.invalid_utf8)
+    pub lossy_utf8: bool,
+}
+
+/// Summary of a `Pipeline::run` (serialized by the CLI's `--report`)
+#[derive(Debug, Default, Serialize)]
+pub struct RunReport {
+    /// Number of generated beans (classes, interfaces and virtual beans)
+    pub beans: usize,
+    /// Number of generated enums
+    pub enums: usize,
+    /// Beans/enums whose source hash matched the cache
+    pub cached: usize,
+    /// Beans/enums that are new or structurally changed since the last run
+    pub updated: usize,
+    /// Beans/enums whose source file changed but whose structure didn't (comment or
+    /// formatting edits), reported as "unchanged (formatting only)"
+    pub formatting_only: usize,
+    /// The schema snapshot (bean fields and tables) differs from the previous run's, or
+    /// there was none
+    pub schema_changed: bool,
+    /// Files whose content changed and were written
+    pub written: Vec<PathBuf>,
+    /// Files that already had the generated content
+    pub unchanged: Vec<PathBuf>,
+    /// Warnings reported while parsing and validating
+    pub warnings: Vec<String>,
+    /// Field types no parsed source defines, classified as external / suspected typo / unknown
+    pub unknown_types: Vec<UnknownType>,
+    /// Source files that failed to parse; their beans are missing from the outputs
+    pub parse_errors: Vec<String>,
+    /// Per-file parse time and cache status, in scan order
+    pub file_stats: Vec<FileStats>,
+    /// Structural hash of each generated bean by Beans key (see `schema_version`)
+    pub schema_versions: BTreeMap<String, String>,
+    /// Classes (`module.Name`) and fields (`module.Name.field`) the build flavor left out
+    pub omitted: Vec<String>,
+    /// Field changes of each updated bean since the previous run's schema snapshot
+    pub field_changes: Vec<BeanDelta>,
+    /// Per-module counts with their change since the previous run, largest modules first;
+    /// only filled with `stats_by_module`
+    pub module_stats: Vec<ModuleStats>,
+    /// Names breaking a [lint] rule of severity "warn" (also listed in `warnings`)
+    pub lint: Vec<LintViolation>,
+}
+
+impl RunReport {
+    /// A bean or enum is new or changed structurally, or the schema snapshot changed.
+    /// False when every change since the last run was a comment or formatting edit
+    pub fn has_structural_changes(&self) -> bool {
+        self.updated > 0 || self.schema_changed
+    }
+}
+
+/// Everything a run would produce, without writing outputs or the cache (`Pipeline::generate`)
+#[derive(Debug, Clone, Default)]
+pub struct Generation {
+    /// Parsed classes, interfaces and virtual beans; not restricted by filters
+    pub classes: Vec<ClassInfo>,
+    pub enums: Vec<EnumInfo>,
+    /// XML outputs, as `Pipeline::generate_xml`
+    pub xml: Vec<GeneratedFile>,
+    /// TypeScript outputs, as `Pipeline::generate_ts`
+    pub ts: Vec<GeneratedFile>,
+    /// Beans/enums whose source hash matched the cache on disk
+    pub cached: usize,
+    /// Beans/enums that are new or structurally changed since the last run
+    pub updated: usize,
+    /// Beans/enums whose only changes are comments or formatting
+    pub formatting_only: usize,
+    /// Warnings reported while parsing and validating, with their codes (e.g.
+    /// `unknown-type`), duplicates included
+    pub warnings: Vec<Warning>,
+    /// Field types no parsed source defines
+    pub unknown_types: Vec<UnknownType>,
+    /// Source files that failed to parse
+    pub parse_errors: Vec<String>,
+    /// Names breaking a [lint] rule of severity "warn" (also listed in `warnings`)
+    pub lint: Vec<LintViolation>,
+}
+
+/// What a full run would generate for one class (`Pipeline::preview`)
+#[derive(Debug, Clone)]
+pub struct ClassPreview {
+    /// `module.Name` (just `Name` in the root module)
+    pub name: String,
+    /// Sources that were parsed: the files declaring the class and everything they import
+    pub parsed_files: Vec<PathBuf>,
+    /// The `<bean>` element (nested children included) and the `<table>` it is the value of
+    pub xml: String,
+    /// The beans.ts entry; None for classes left out of beans.ts (interfaces, @NoRuntime, ...)
+    pub beans_entry: Option<String>,
+    /// tables.d.ts lines mentioning the class; empty without a table or TypeScript output
+    pub tables_lines: Vec<String>,
+}
+
+impl std::fmt::Display for ClassPreview {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "== {} (XML) ==", self.name)?;
+        writeln!(f, "{}", self.xml)?;
+        writeln!(f, "== beans.ts ==")?;
+        writeln!(
+            f,
+            "{}",
+            self.beans_entry.as_deref().unwrap_or("(not in beans.ts)")
+        )?;
+        writeln!(f, "== tables.d.ts ==")?;
+        if self.tables_lines.is_empty() {
+            writeln!(f, "(no table)")?;
+        }
+        for line in &self.tables_lines {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Builder for `Pipeline`
+pub struct PipelineBuilder {
+    config: Config,
+    project_root: PathBuf,
+    force: bool,
+    verbose: bool,
+    progress: bool,
+    deny_warnings: bool,
+    stats: bool,
+    stats_by_module: bool,
+    max_warnings: Option<usize>,
+    filters: Vec<String>,
+    virtual_sources: Option<BTreeMap<PathBuf, String>>,
+    changed_files: Option<Vec<PathBuf>>,
+    changelog: Option<PathBuf>,
+    emit_model: Option<PathBuf>,
+    report: Option<PathBuf>,
+    flavor: Option<String>,
+    module: Option<String>,
+    groups: Vec<String>,
+}
+
+impl PipelineBuilder {
+    /// Directory that relative paths in the config are resolved against
+    /// (the directory containing the config file for the CLI)
+    pub fn project_root(mut self, project_root: impl Into<PathBuf>) -> Self {
+        self.project_root = project_root.into();
+        self
+    }
+
+    /// Ignore the cache and treat every bean as updated
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Print per-bean progress (with `progress`)
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Print the banner, progress lines and warnings to stdout/stderr as the CLI does.
+    /// Off by default: library runs are silent and report through `RunReport` and
+    /// `Generation`
+    pub fn progress(mut self, progress: bool) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Fail instead of generating when any warning is reported
+    pub fn deny_warnings(mut self, deny_warnings: bool) -> Self {
+        self.deny_warnings = deny_warnings;
+        self
+    }
+
+    /// Print cache hit rate, total parse time and the slowest files after a run
+    /// (always printed in verbose mode; with `progress`)
+    pub fn stats(mut self, stats: bool) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Print beans, fields, enums, tables and XML bytes per module after a run, with the
+    /// change since the previous run, and add them to `RunReport::module_stats`
+    pub fn stats_by_module(mut self, stats_by_module: bool) -> Self {
+        self.stats_by_module = stats_by_module;
+        self
+    }
+
+    /// Print at most this many distinct warnings; the rest are only counted.
+    /// The run report always keeps every warning
+    pub fn max_warnings(mut self, max_warnings: usize) -> Self {
+        self.max_warnings = Some(max_warnings);
+        self
+    }
+
+    /// Only generate beans and enums whose name matches this regex (may be repeated).
+    /// Types referenced by them are still resolved against every parsed class
+    pub fn filter(mut self, pattern: impl Into<String>) -> Self {
+        self.filters.push(pattern.into());
+        self
+    }
+
+    /// Parse these in-memory sources (virtual path → content) instead of scanning `[[sources]]`.
+    /// Relative virtual paths are resolved against the project root
+    pub fn virtual_sources<P, S>(mut self, sources: impl IntoIterator<Item = (P, S)>) -> Self
+    where
+        P: Into<PathBuf>,
+        S: Into<String>,
+    {
+        self.virtual_sources = Some(
+            sources
+                .into_iter()
+                .map(|(path, content)| (path.into(), content.into()))
+                .collect(),
+        );
+        self
+    }
+
+    /// Only re-parse these files (relative to the project root) instead of scanning
+    /// `[[sources]]`; every other source comes from the per-file cache
+    /// (output.per_file_cache). Listed files outside the sources are warned about and
+    /// ignored, deleted ones are dropped from the cache
+    pub fn changed_files<P: Into<PathBuf>>(mut self, paths: impl IntoIterator<Item = P>) -> Self {
+        self.changed_files = Some(paths.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Append the schema changes since the previous run (as recorded in the cache)
+    /// to this Markdown file
+    pub fn changelog(mut self, path: impl Into<PathBuf>) -> Self {
+        self.changelog = Some(path.into());
+        self
+    }
+
+    /// Write the parsed and resolved model as JSON to this file on every run
+    /// (see `Pipeline::model_json`)
+    pub fn emit_model(mut self, path: impl Into<PathBuf>) -> Self {
+        self.emit_model = Some(path.into());
+        self
+    }
+
+    /// Write the `RunReport` of `Pipeline::run` as JSON to this file (`--report`)
+    pub fn report(mut self, path: impl Into<PathBuf>) -> Self {
+        self.report = Some(path.into());
+        self
+    }
+
+    /// Build flavor: classes and fields marked @OnlyIn / @ExcludeFrom for other flavors are
+    /// left out of every output. Overrides `project.flavor`
+    pub fn flavor(mut self, flavor: impl Into<String>) -> Self {
+        self.flavor = Some(flavor.into());
+        self
+    }
+
+    /// Module of classes and enums without one of their own (no [[sources]] module_name or
+    /// @Module). Overrides `defaults.module` and `output.module_name`
+    pub fn module(mut self, module: impl Into<String>) -> Self {
+        self.module = Some(module.into());
+        self
+    }
+
+    /// Only declare tables in this group (may be repeated) in the TypeScript outputs: a
+    /// [groups] name or a Luban group. The XML keeps every table, Luban filters by group
+    /// itself at data export
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.groups.push(group.into());
+        self
+    }
+
+    /// Load tsconfig and validate the filters
+    pub fn build(self) -> Result<Pipeline> {
+        let tsconfig_path = self.project_root.join(&self.config.project.tsconfig);
+        let tsconfig = TsConfig::load(&tsconfig_path)
+            .with_context(|| format!("Failed to load tsconfig from {:?}", tsconfig_path))?;
+
+        let filters = self
+            .filters
+            .iter()
+            .map(|p| Regex::new(p).with_context(|| format!("Invalid filter pattern: {}", p)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let (default_module, origin) = match (&self.module, &self.config.defaults.module) {
+            (Some(module), _) => (module.clone(), "--module"),
+            (None, Some(module)) => (module.clone(), "defaults.module"),
+            (None, None) => (self.config.output.module_name.clone(), "output.module_name"),
+        };
+        if self.changed_files.is_some() {
+            if !self.config.output.per_file_cache {
+                anyhow::bail!(GenError::Validation(
+                    "--stdin-filelist needs the per-file cache, set output.per_file_cache = true"
+                        .to_string()
+                ));
+            }
+            if self.virtual_sources.is_some() {
+                anyhow::bail!("Changed files can't be combined with virtual sources");
+            }
+        }
+        let group_names = &self.config.groups;
+        if !group_names.is_empty() {
+            let unknown = self.groups.iter().find(|group| {
+                !group_names.contains_key(*group) && !group_names.values().any(|g| g == *group)
+            });
+            if let Some(unknown) = unknown {
+                let mut known: Vec<&str> = group_names.keys().map(String::as_str).collect();
+                known.sort();
+                anyhow::bail!(GenError::Validation(format!(
+                    "Unknown group '{}' (--group), expected one of: {}",
+                    unknown,
+                    known.join(", ")
+                )));
+            }
+        }
+        let groups = (!self.groups.is_empty()).then(|| {
+            self.groups
+                .iter()
+                .map(|group| luban_group(group, group_names).to_string())
+                .collect()
+        });
+        if let Err(suggestion) = validator::check_module_name(&default_module) {
+            anyhow::bail!(GenError::Validation(format!(
+                "Default module '{}' ({}) is not a valid Luban module name, use '{}'",
+                default_module, origin, suggestion
+            )));
+        }
+        let flavor = self.flavor.or_else(|| self.config.project.flavor.clone());
+
+        Ok(Pipeline {
+            config: self.config,
+            project_root: self.project_root,
+            tsconfig,
+            force: self.force,
+            verbose: self.verbose,
+            progress: self.progress,
+            deny_warnings: self.deny_warnings,
+            stats: self.stats,
+            stats_by_module: self.stats_by_module,
+            max_warnings: self.max_warnings,
+            filters,
+            virtual_sources: self.virtual_sources,
+            changed_files: self.changed_files,
+            changelog: self.changelog,
+            emit_model: self.emit_model,
+            report: self.report,
+            flavor,
+            default_module,
+            groups,
+        })
+    }
+}
+
+/// A TypeScript source to parse, read from disk unless `content` is given
+struct SourceInput {
+    path: PathBuf,
+    content: Option<String>,
+    output_path: Option<PathBuf>,
+    module_name: Option<String>,
+}
+
+/// Parsed, validated schema ready for generation
+struct Model {
+    classes: Vec<ClassInfo>,
+    enums: Vec<EnumInfo>,
+    type_mapper: TypeMapper,
+    table_registry: TableRegistry,
+    /// Every warning reported while building the model, duplicates included
+    warnings: Vec<Warning>,
+    unknown_types: Vec<UnknownType>,
+    /// Files that failed to parse (also reported as warnings)
+    parse_errors: Vec<String>,
+    /// Parse timings; `cached` is filled in by `run`
+    file_stats: Vec<FileStats>,
+    /// What the build flavor left out (see `RunReport::omitted`)
+    omitted: Vec<String>,
+    /// [lint] warnings (see `RunReport::lint`)
+    lint: Vec<LintViolation>,
+    /// Parse results of every source file, for the cache; empty without
+    /// output.per_file_cache
+    files: Vec<CachedFile>,
+    /// The cache on disk, when loaded (see `build_model_for`); per-file entries reused by
+    /// a `changed` build are moved out
+    cache: Option<Cache>,
+}
+
+/// One generation run over a loaded config
+pub struct Pipeline {
+    config: Config,
+    project_root: PathBuf,
+    tsconfig: TsConfig,
+    force: bool,
+    verbose: bool,
+    progress: bool,
+    deny_warnings: bool,
+    stats: bool,
+    stats_by_module: bool,
+    max_warnings: Option<usize>,
+    filters: Vec<Regex>,
+    virtual_sources: Option<BTreeMap<PathBuf, String>>,
+    /// `--stdin-filelist`: the only files to parse (see `PipelineBuilder::changed_files`)
+    changed_files: Option<Vec<PathBuf>>,
+    changelog: Option<PathBuf>,
+    emit_model: Option<PathBuf>,
+    report: Option<PathBuf>,
+    flavor: Option<String>,
+    /// Module of classes and enums without their own: --module, else defaults.module,
+    /// else output.module_name
+    default_module: String,
+    /// `--group`: Luban groups whose tables the TypeScript outputs declare (all when unset)
+    groups: Option<Vec<String>>,
+}
+
+/// Version of the `--emit-model` layout, bumped when a stable field changes
+const MODEL_FORMAT_VERSION: u32 = 2;
+
+/// Parsed and resolved schema written by `--emit-model`
+#[derive(Serialize)]
+struct ModelJson<'m> {
+    format_version: u32,
+    classes: Vec<ClassInfo>,
+    enums: Vec<EnumInfo>,
+    tables: Vec<&'m ResolvedTableConfig>,
+}
+
+impl Pipeline {
+    pub fn builder(config: Config) -> PipelineBuilder {
+        PipelineBuilder {
+            config,
+            project_root: PathBuf::from("."),
+            force: false,
+            verbose: false,
+            progress: false,
+            deny_warnings: false,
+            stats: false,
+            stats_by_module: false,
+            max_warnings: None,
+            filters: Vec::new(),
+            virtual_sources: None,
+            changed_files: None,
+            changelog: None,
+            emit_model: None,
+            report: None,
+            flavor: None,
+            module: None,
+            groups: Vec::new(),
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Print a line to stdout when `PipelineBuilder::progress` is set
+    fn say(&self, line: impl std::fmt::Display) {
+        if self.progress {
+            println!("{}", line);
+        }
+    }
+
+    /// Module used for classes and enums without one of their own
+    pub fn default_module(&self) -> &str {
+        &self.default_module
+    }
+
+    /// Parse all sources and apply config-driven changes (virtual beans/fields) and validation.
+    /// Includes interfaces and virtual beans; not restricted by filters
+    pub fn parsed_classes(&self) -> Result<Vec<ClassInfo>> {
+        Ok(self.build_model()?.classes)
+    }
+
+    /// The parsed and resolved model (classes, enums and tables) as pretty JSON for snapshot
+    /// tests: sorted by module and name, fields in declaration order, effective module names
+    /// and project-relative source paths. File hashes, source offsets and import maps are left
+    /// out. Not restricted by filters
+    pub fn model_json(&self) -> Result<String> {
+        let model = self.build_model()?;
+        self.render_model_json(&model)
+    }
+
+    /// Render every XML output (bean/enum modules and bean type enums) without writing them
+    pub fn generate_xml(&self) -> Result<Vec<GeneratedFile>> {
+        let model = self.build_model()?;
+        self.render_xml(&model)
+    }
+
+    /// Render the TypeScript table code (and constants.ts for output.constants_format = "ts")
+    /// without writing it. Empty when neither output is configured
+    pub fn generate_ts(&self) -> Result<Vec<GeneratedFile>> {
+        let model = self.build_model()?;
+        self.generate_ts_from(&model)
+    }
+
+    /// Build the model once and return the parsed schema, every XML and TypeScript output and
+    /// the warnings, without writing outputs or the cache. `cached`/`updated` are counted
+    /// against the cache on disk (everything is updated with `force`)
+    pub fn generate(&self) -> Result<Generation> {
+        let mut model = self.build_model_for(None, self.changed_files.as_deref(), !self.force)?;
+        // Not loaded with `force`: everything counts as updated
+        let cache = model.cache.take().unwrap_or_default();
+        let statuses = self
+            .selected_classes(&model)
+            .map(|class| {
+                let structure = schema_version::structure_hash(class, &model.type_mapper);
+                cache.status(&class.name, &class.file_hash, &structure)
+            })
+            .chain(self.selected_enums(&model).map(|enum_info| {
+                let structure = schema_version::enum_hash(enum_info);
+                cache.status(&enum_info.name, &enum_info.file_hash, &structure)
+            }))
+            .collect::<Vec<_>>();
+        let count = |status: CacheStatus| statuses.iter().filter(|s| **s == status).count();
+
+        let xml = self.render_xml(&model)?;
+        let ts = self.generate_ts_from(&model)?;
+        Ok(Generation {
+            cached: count(CacheStatus::Cached),
+            updated: count(CacheStatus::Updated),
+            formatting_only: count(CacheStatus::FormattingOnly),
+            xml,
+            ts,
+            classes: model.classes,
+            enums: model.enums,
+            warnings: model.warnings,
+            unknown_types: model.unknown_types,
+            parse_errors: model.parse_errors,
+            lint: model.lint,
+        })
+    }
+
+    /// Render what a full run would generate for one class (`Name` or `module.Name`),
+    /// without writing outputs or the cache. Only the files declaring the class (looked up
+    /// in the cache, else by a text scan of the sources) and the files they import,
+    /// transitively, are parsed. Unknown names fail with the closest declared names
+    pub fn preview(&self, name: &str) -> Result<ClassPreview> {
+        let (module, class_name) = match name.rsplit_once('.') {
+            Some((module, class_name)) => (Some(module), class_name),
+            None => (None, name),
+        };
+        let sources = self.source_inputs(&mut Diagnostics::default())?;
+        let (cache, _) = Cache::load(&self.project_root.join(&self.config.output.cache_file));
+        let declares = Regex::new(&format!(
+            r"\b(?:class|interface)\s+{}\b",
+            regex::escape(class_name)
+        ))?;
+
+        // The cache knows the file of a short name; module-qualified names may be ambiguous
+        let cached = cache
+            .get_entry(class_name)
+            .filter(|_| module.is_none())
+            .map(|entry| source_key(&self.project_root.join(&entry.source)));
+        let mut declaring: Vec<&SourceInput> = sources
+            .iter()
+            .filter(|source| cached.as_ref() == Some(&source_key(&source.path)))
+            .collect();
+        if declaring.is_empty() {
+            declaring = sources
+                .iter()
+                .filter(|source| read_source(source).is_some_and(|c| declares.is_match(&c)))
+                .collect();
+        }
+        if declaring.is_empty() {
+            let declared = Regex::new(r"\b(?:class|interface)\s+([A-Za-z_$][\w$]*)")?;
+            let mut names: Vec<String> = cache.entries.keys().cloned().collect();
+            for source in &sources {
+                if let Some(content) = read_source(source) {
+                    names.extend(declared.captures_iter(&content).map(|c| c[1].to_string()));
+                }
+            }
+            names.sort();
+            names.dedup();
+            let matches =
+                virtual_fields::nearest_names(class_name, names.iter().map(String::as_str), 5);
+            if matches.is_empty() {
+                anyhow::bail!("Unknown class '{}' (no similar class names found)", name);
+            }
+            anyhow::bail!(
+                "Unknown class '{}'; close matches: {}",
+                name,
+                matches.join(", ")
+            );
+        }
+
+        // Follow imports so referenced types resolve to the same modules as in a full run
+        let by_key: HashMap<PathBuf, &SourceInput> = sources
+            .iter()
+            .map(|source| (source_key(&source.path), source))
+            .collect();
+        let mut files = HashSet::new();
+        let mut pending: Vec<PathBuf> = declaring.iter().map(|s| source_key(&s.path)).collect();
+        let parser = TsParser::new();
+        while let Some(key) = pending.pop() {
+            let Some(source) = by_key.get(&key) else {
+                continue;
+            };
+            if !files.insert(key) {
+                continue;
+            }
+            let classes = match &source.content {
+                Some(content) => parser.parse_source(&source.path.to_string_lossy(), content),
+                None => parser.parse_file(&source.path),
+            };
+            for class in classes.unwrap_or_default() {
+                pending.extend(class.imports.values().map(|path| source_key(path)));
+            }
+        }
+
+        let model = self.build_model_for(Some(&files), None, false)?;
+        let default_module = &self.default_module;
+        let module_of = |class: &ClassInfo| {
+            class
+                .module_name
+                .clone()
+                .unwrap_or_else(|| default_module.clone())
+        };
+        let class = model
+            .classes
+            .iter()
+            .find(|c| c.name == class_name && (module.is_none() || module == Some(&module_of(c))))
+            .with_context(|| format!("Class '{}' is not declared in the parsed sources", name))?;
+        let class_module = module_of(class);
+
+        let xml = self
+            .render_xml(&model)?
+            .iter()
+            .find_map(|file| xml_fragment(&file.content, &class_module, &class.name))
+            .unwrap_or_default();
+        let ts_files = self.render_ts(&model)?;
+        let file_name = |file: &GeneratedFile| {
+            file.path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+        let entry_suffix = format!(": {},", class.name);
+        let beans_entry = ts_files
+            .iter()
+            .filter(|f| file_name(f).starts_with("beans") && !file_name(f).ends_with(".d.ts"))
+            .flat_map(|f| f.content.lines())
+            .find(|line| line.trim_end().ends_with(&entry_suffix))
+            .map(|line| line.trim().to_string());
+        let mentions = Regex::new(&format!(r"\b{}\b", regex::escape(&class.name)))?;
+        let tables_lines = ts_files
+            .iter()
+            .filter(|f| file_name(f) == "tables.d.ts")
+            .flat_map(|f| f.content.lines())
+            .filter(|line| !line.trim_start().starts_with("import ") && mentions.is_match(line))
+            .map(|line| line.trim().to_string())
+            .collect();
+
+        let mut parsed_files: Vec<PathBuf> = files.into_iter().collect();
+        parsed_files.sort();
+        Ok(ClassPreview {
+            name: if class_module.is_empty() {
+                class.name.clone()
+            } else {
+                format!("{}.{}", class_module, class.name)
+            },
+            parsed_files,
+            xml,
+            beans_entry,
+            tables_lines,
+        })
+    }
+
+    /// Run the full generation: write changed outputs and update the cache
+    pub fn run(&self) -> Result<RunReport> {
+        let report = self.run_with(self.changed_files.as_deref())?;
+        if let Some(report_path) = &self.report {
+            let json =
+                serde_json::to_string_pretty(&report).context("Failed to serialize report")?;
+            let json = output::normalize_newlines(&json, self.config.output.newline);
+            output::write_atomic(report_path, json.as_bytes())
+                .with_context(|| format!("Failed to write report to {:?}", report_path))?;
+        }
+        Ok(report)
+    }
+
+    /// `run` re-parsing only `changed` (deleted files drop out) with every other source
+    /// from the per-file cache, like `PipelineBuilder::changed_files`; a full run without
+    /// output.per_file_cache or with virtual sources. Used by the CLI watch mode
+    pub fn run_changed(&self, changed: &[PathBuf]) -> Result<RunReport> {
+        if self.config.output.per_file_cache && self.virtual_sources.is_none() {
+            self.run_with(Some(changed))
+        } else {
+            self.run_with(None)
+        }
+    }
+
+    /// Whether `path` belongs to a `[[sources]]` entry, with its scan options applied (the
+    /// file need not exist)
+    pub fn is_source(&self, path: &Path) -> bool {
+        self.source_entry(&normalize_path(&self.resolve(path)))
+            .is_some()
+    }
+
+    fn run_with(&self, changed: Option<&[PathBuf]>) -> Result<RunReport> {
+        let start = Instant::now();
+        let config = &self.config;
+        let mut report = RunReport::default();
+
+        self.say(format_args!(
+            "Luban Schema Generator v{}",
+            env!("CARGO_PKG_VERSION")
+        ));
+        self.say("=".repeat(50));
+
+        // Fail before parsing rather than after the whole pipeline ran
+        self.check_output_destinations()?;
+
+        let cache_path = self.project_root.join(&config.output.cache_file);
+        if self.force {
+            self.say("[Force mode] Ignoring cache, regenerating all beans...");
+        }
+
+        let mut model = self.build_model_for(None, changed, true)?;
+
+        // Snapshot for field deltas and the changelog, module counts for --stats-by-module;
+        // --force still keeps the old ones
+        let schema = SchemaSnapshot::capture(
+            &model.classes,
+            &model.type_mapper,
+            &model.table_registry,
+            &self.default_module,
+        );
+        let mut cache = model.cache.take().unwrap_or_default();
+        let previous_schema = cache.schema.take();
+        let previous_modules = std::mem::take(&mut cache.modules);
+        if self.force {
+            cache = Cache::new();
+        }
+
+        // Check cache: file hash first, then the structural hash, so comment and
+        // formatting edits are not counted as updates
+        self.say("\n[3/4] Checking cache...");
+        report.schema_changed = previous_schema.as_ref() != Some(&schema);
+        let mut updated_files: HashSet<&str> = HashSet::new();
+        for class in self.selected_classes(&model) {
+            let structure = schema_version::structure_hash(class, &model.type_mapper);
+            let status = cache.status(&class.name, &class.file_hash, &structure);
+            cache.set_entry(
+                &class.name,
+                &class.source_file,
+                &class.file_hash,
+                &structure,
+            );
+            if status != CacheStatus::Cached {
+                updated_files.insert(&class.source_file);
+            }
+            match status {
+                CacheStatus::Cached => {
+                    report.cached += 1;
+                    if self.verbose {
+                        self.say(format_args!("  [cached] {}", class.name));
+                    }
+                }
+                CacheStatus::FormattingOnly => {
+                    report.formatting_only += 1;
+                    if self.verbose {
+                        self.say(format_args!(
+                            "  [unchanged (formatting only)] {}",
+                            class.name
+                        ));
+                    }
+                }
+                CacheStatus::Updated => {
+                    report.updated += 1;
+                    if self.verbose {
+                        self.say(format_args!("  [update] {}", class.name));
+                    }
+                    let delta = previous_schema.as_ref().and_then(|previous| {
+                        let key = changelog::bean_key(class, &self.default_module);
+                        BeanDelta::between(previous, &schema, &key)
+                    });
+                    if let Some(delta) = delta {
+                        if self.verbose {
+                            for line in delta.lines() {
+                                self.say(format_args!("      {}", line));
+                            }
+                        }
+                        report.field_changes.push(delta);
+                    }
+                }
+            }
+        }
+        for enum_info in self.selected_enums(&model) {
+            let structure = schema_version::enum_hash(enum_info);
+            let status = cache.status(&enum_info.name, &enum_info.file_hash, &structure);
+            cache.set_entry(
+                &enum_info.name,
+                &enum_info.source_file,
+                &enum_info.file_hash,
+                &structure,
+            );
+            if status != CacheStatus::Cached {
+                updated_files.insert(&enum_info.source_file);
+            }
+            match status {
+                CacheStatus::Cached => {
+                    report.cached += 1;
+                    if self.verbose {
+                        self.say(format_args!("  [cached enum] {}", enum_info.name));
+                    }
+                }
+                CacheStatus::FormattingOnly => {
+                    report.formatting_only += 1;
+                    if self.verbose {
+                        self.say(format_args!(
+                            "  [unchanged (formatting only) enum] {}",
+                            enum_info.name
+                        ));
+                    }
+                }
+                CacheStatus::Updated => {
+                    report.updated += 1;
+                    if self.verbose {
+                        self.say(format_args!("  [update enum] {}", enum_info.name));
+                    }
+                }
+            }
+        }
+        self.say(format_args!(
+            "  Cached: {}, Updated: {}, Unchanged (formatting only): {}",
+            report.cached, report.updated, report.formatting_only
+        ));
+        report.file_stats = model.file_stats.clone();
+        for file in &mut report.file_stats {
+            file.cached = !updated_files.contains(self.source_file(&file.path).as_str());
+        }
+
+        // Generate XML
+        self.say("\n[4/4] Generating XML...");
+        let xml_files = self.render_xml(&model)?;
+        // Rendered up front so TypeScript errors (e.g. Beans key collisions) abort before any write
+        let ts_files = self.render_ts(&model)?;
+        let constants_file = match config.output.constants_format {
+            ConstantsFormat::Ts => self.render_constants(&model),
+            ConstantsFormat::Xml => None,
+        };
+        let register_warnings =
+            self.check_register_pressure(ts_files.iter().chain(&constants_file))?;
+        let cycle_warnings = self.check_import_cycles(&ts_files)?;
+        for file in &xml_files {
+            if write_generated(file, config.output.newline)? {
+                if file.beans + file.enums > 0 {
+                    self.say(format_args!(
+                        "  Written {} beans, {} enums to {:?}",
+                        file.beans, file.enums, file.path
+                    ));
+                } else {
+                    self.say(format_args!("  Written bean type enums to {:?}", file.path));
+                }
+                report.written.push(file.path.clone());
+            } else {
+                self.say(format_args!("  No changes for {:?}", file.path));
+                report.unchanged.push(file.path.clone());
+            }
+        }
+        if report.written.is_empty() {
+            self.say("  No changes, skipping all writes");
+        }
+
+        // Generate TypeScript table code if configured
+        if let Some(table_output_path) = &config.output.table_output_path {
+            self.say("\n[5/5] Generating TypeScript table code...");
+            for file in ts_files {
+                if write_generated(&file, config.output.newline)? {
+                    report.written.push(file.path);
+                } else {
+                    report.unchanged.push(file.path);
+                }
+            }
+            self.say(format_args!(
+                "  Written TypeScript tables to {:?}",
+                self.project_root.join(table_output_path)
+            ));
+        }
+
+        if let Some(file) = constants_file {
+            if write_generated(&file, config.output.newline)? {
+                self.say(format_args!("  Written constants to {:?}", file.path));
+                report.written.push(file.path);
+            } else {
+                report.unchanged.push(file.path);
+            }
+        }
+
+        if let Some(file) = self.render_schema_versions(&model)? {
+            if write_generated(&file, config.output.newline)? {
+                self.say(format_args!("  Written schema versions to {:?}", file.path));
+                report.written.push(file.path);
+            } else {
+                report.unchanged.push(file.path);
+            }
+        }
+
+        // Diff the schema against the previous run
+        if let (Some(changelog_path), Some(previous)) = (&self.changelog, &previous_schema) {
+            let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+            match changelog::render_changes(previous, &schema, &date) {
+                Some(section) => {
+                    changelog::append_section(changelog_path, &section).with_context(|| {
+                        format!("Failed to write changelog to {:?}", changelog_path)
+                    })?;
+                    self.say(format_args!(
+                        "  Appended schema changes to {:?}",
+                        changelog_path
+                    ));
+                }
+                None => self.say("  No schema changes for the changelog"),
+            }
+        }
+        cache.schema = Some(schema);
+        cache.files = model.files.clone();
+        let modules = module_stats::count(
+            self.selected_classes(&model),
+            self.selected_enums(&model),
+            model.table_registry.all_tables(),
+            xml_files
+                .iter()
+                .filter_map(|f| Some((f.module.as_deref()?, f.content.len()))),
+            &self.default_module,
+        );
+        if self.stats_by_module {
+            let previous = (!previous_modules.is_empty()).then_some(&previous_modules);
+            report.module_stats = module_stats::compare(&modules, previous);
+        }
+        cache.modules = modules;
+
+        if let Some(model_path) = &self.emit_model {
+            let json = self.render_model_json(&model)?;
+            let written = write_if_changed(model_path, &json, config.output.newline)
+                .with_context(|| format!("Failed to write model to {:?}", model_path))?;
+            if self.verbose {
+                if written {
+                    self.say(format_args!("  Written model to {:?}", model_path));
+                } else {
+                    self.say(format_args!("  No changes for {:?}", model_path));
+                }
+            }
+        }
+
+        // Save cache
+        cache.save(&cache_path).with_context(|| {
+            format!(
+                "Failed to write cache to {}",
+                absolute_path(&cache_path).display()
+            )
+        })?;
+
+        report.beans = self.selected_classes(&model).count();
+        report.enums = self.selected_enums(&model).count();
+        report.warnings = model.warnings.iter().map(|w| w.message.clone()).collect();
+        report.warnings.extend(register_warnings);
+        report.warnings.extend(cycle_warnings);
+        report.unknown_types = model.unknown_types.clone();
+        report.parse_errors = model.parse_errors.clone();
+        report.schema_versions = self.schema_versions(&model)?;
+        report.omitted = model.omitted.clone();
+        report.lint = model.lint.clone();
+
+        let module_files = xml_files.iter().filter(|f| f.beans + f.enums > 0).count();
+        self.say(format_args!("\n{}", "=".repeat(50)));
+        self.say(format_args!(
+            "Done! Generated {} beans, {} enums to {} file(s) in {:?}",
+            report.beans,
+            report.enums,
+            module_files,
+            start.elapsed()
+        ));
+        if !report.warnings.is_empty() {
+            self.say(format_args!("{} warning(s)", report.warnings.len()));
+        }
+        if self.progress && (self.stats || self.verbose) {
+            print_stats(&report);
+        }
+        if self.stats_by_module {
+            self.say("\nStats by module:");
+            for line in module_stats::render_table(&report.module_stats).lines() {
+                self.say(format_args!("  {}", line));
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Check that every configured output can be written: file outputs must not be existing
+    /// directories and their directories must be creatable and writable. Nothing is created
+    pub fn check_output_destinations(&self) -> Result<()> {
+        let config = &self.config;
+        let resolve = |path: &PathBuf| absolute_path(&self.project_root.join(path));
+        let mut files: Vec<(String, PathBuf)> = vec![
+            ("module XML".to_string(), resolve(&config.output.path)),
+            ("cache".to_string(), resolve(&config.output.cache_file)),
+        ];
+        for source in &config.sources {
+            let output_path = match source {
+                SourceConfig::Directory { output_path, .. }
+                | SourceConfig::File { output_path, .. }
+                | SourceConfig::Files { output_path, .. }
+                | SourceConfig::Glob { output_path, .. }
+                | SourceConfig::Registration { output_path, .. } => output_path,
+            };
+            if let Some(path) = output_path {
+                files.push(("module XML".to_string(), resolve(path)));
+            }
+        }
+        for route in &config.output_routes {
+            files.push((
+                format!("output route '{}'", route.name),
+                resolve(&route.path),
+            ));
+        }
+        let optional = [
+            ("bean type enums XML", &config.output.bean_types_path),
+            ("constants", &config.output.constants_path),
+            ("schema versions", &config.output.schema_versions_path),
+        ];
+        for (artifact, path) in optional {
+            if let Some(path) = path {
+                files.push((artifact.to_string(), resolve(path)));
+            }
+        }
+        // --changelog is relative to the working directory, like the run writes it
+        if let Some(changelog) = &self.changelog {
+            files.push(("changelog".to_string(), absolute_path(changelog)));
+        }
+        if let Some(model_path) = &self.emit_model {
+            files.push(("model".to_string(), absolute_path(model_path)));
+        }
+        if let Some(report_path) = &self.report {
+            files.push(("report".to_string(), absolute_path(report_path)));
+        }
+
+        let mut errors = Vec::new();
+        for (artifact, path) in files {
+            if let Err(e) = output::check_file_destination(&path) {
+                errors.push(format!("{} ({}): {}", artifact, path.display(), e));
+            }
+        }
+        if let Some(table_output_path) = &config.output.table_output_path {
+            let dir = resolve(table_output_path);
+            if let Err(e) = output::check_dir_destination(&dir) {
+                errors.push(format!("TypeScript tables ({}): {}", dir.display(), e));
+            }
+        }
+        if !errors.is_empty() {
+            anyhow::bail!("Cannot write generated files:\n  {}", errors.join("\n  "));
+        }
+        Ok(())
+    }
+
+    /// Directories to watch for source changes (used by the CLI watch mode)
+    pub fn watch_paths(&self) -> Vec<PathBuf> {
+        let mut watch_paths: Vec<PathBuf> = Vec::new();
+        let mut watch_parent = |file: &Path| {
+            if let Some(parent) = file.parent() {
+                watch_paths.push(parent.to_path_buf());
+            }
+        };
+
+        let mut dirs = Vec::new();
+        for source in &self.config.sources {
+            match source {
+                SourceConfig::Directory { path, .. } => dirs.push(self.resolve(path)),
+                // Watch parent directory for single files
+                SourceConfig::File { path, .. } => watch_parent(&self.resolve(path)),
+                SourceConfig::Files { paths, .. } => {
+                    for path in paths {
+                        watch_parent(&self.resolve(path));
+                    }
+                }
+                SourceConfig::Glob { pattern, .. } => {
+                    // Expand glob to get initial files
+                    if let Ok(files) = scanner::expand_glob(&self.resolve_pattern(pattern)) {
+                        for file in files {
+                            watch_parent(&file);
+                        }
+                    }
+                }
+                // Watch the directories of the registered files
+                SourceConfig::Registration { path, .. } => {
+                    let files = self
+                        .registration_files(&self.resolve(path), &mut Diagnostics::default())
+                        .unwrap_or_default();
+                    for file in files {
+                        watch_parent(&file);
+                    }
+                }
+            }
+        }
+        watch_paths.extend(dirs);
+
+        // Deduplicate watch paths
+        watch_paths.sort();
+        watch_paths.dedup();
+        watch_paths.retain(|p| p.exists());
+        watch_paths
+    }
+
+    /// How a source file appears in cache keys and messages, like `ClassInfo::source_file`
+    fn source_file(&self, path: &Path) -> String {
+        project_relative_path(path, Some(&self.project_root))
+    }
+
+    fn resolve(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.project_root.join(path)
+        }
+    }
+
+    fn resolve_pattern(&self, pattern: &str) -> String {
+        if Path::new(pattern).is_absolute() {
+            pattern.to_string()
+        } else {
+            self.project_root
+                .join(pattern)
+                .to_string_lossy()
+                .to_string()
+        }
+    }
+
+    fn matches_filters(&self, name: &str) -> bool {
+        self.filters.is_empty() || self.filters.iter().any(|f| f.is_match(name))
+    }
+
+    fn selected_classes<'m>(&'m self, model: &'m Model) -> impl Iterator<Item = &'m ClassInfo> {
+        model
+            .classes
+            .iter()
+            .filter(|c| self.matches_filters(&c.name))
+    }
+
+    fn selected_enums<'m>(&'m self, model: &'m Model) -> impl Iterator<Item = &'m EnumInfo> {
+        model.enums.iter().filter(|e| self.matches_filters(&e.name))
+    }
+
+    fn generate_ts_from(&self, model: &Model) -> Result<Vec<GeneratedFile>> {
+        let mut files = self.render_ts(model)?;
+        if self.config.output.constants_format == ConstantsFormat::Ts {
+            files.extend(self.render_constants(model));
+        }
+        files.extend(self.render_schema_versions(model)?);
+        Ok(files)
+    }
+
+    /// Collect source files with their output paths and module names.
+    /// Warns about source directories that yield no files after filtering
+    fn collect_sources(
+        &self,
+        warnings: &mut Diagnostics,
+    ) -> Result<Vec<(PathBuf, Option<PathBuf>, Option<String>)>> {
+        let mut source_dirs: Vec<(
+            PathBuf,
+            scanner::ScanConfig,
+            Option<PathBuf>,
+            Option<String>,
+        )> = Vec::new();
+        let mut single_files: Vec<(PathBuf, Option<PathBuf>, Option<String>)> = Vec::new();
+        // Files reached from several registration files are parsed once, for the first
+        let mut registered: HashSet<PathBuf> = HashSet::new();
+
+        for source in &self.config.sources {
+            match source {
+                SourceConfig::Directory {
+                    path,
+                    scan_options,
+                    output_path,
+                    module_name,
+                } => {
+                    let scan_config = scanner::ScanConfig::from(scan_options);
+                    let resolved = self.resolve(path);
+                    if !resolved.exists() {
+                        anyhow::bail!("Source directory not found: {:?}", resolved);
+                    }
+                    if !resolved.is_dir() {
+                        anyhow::bail!("Source path is not a directory: {:?}", resolved);
+                    }
+                    source_dirs.push((
+                        resolved,
+                        scan_config,
+                        output_path.clone(),
+                        module_name.clone(),
+                    ));
+                }
+                SourceConfig::File {
+                    path,
+                    output_path,
+                    module_name,
+                } => {
+                    let resolved = self.resolve(path);
+                    if !resolved.exists() {
+                        anyhow::bail!("Source file not found: {:?}", resolved);
+                    }
+                    if !resolved.is_file() {
+                        anyhow::bail!("Source path is not a file: {:?}", resolved);
+                    }
+                    single_files.push((resolved, output_path.clone(), module_name.clone()));
+                }
+                SourceConfig::Files {
+                    paths,
+                    output_path,
+                    module_name,
+                } => {
+                    for path in paths {
+                        let resolved = self.resolve(path);
+                        if !resolved.exists() {
+                            anyhow::bail!("Source file not found: {:?}", resolved);
+                        }
+                        if !resolved.is_file() {
+                            anyhow::bail!("Source path is not a file: {:?}", resolved);
+                        }
+                        single_files.push((resolved, output_path.clone(), module_name.clone()));
+                    }
+                }
+                SourceConfig::Registration {
+                    path,
+                    output_path,
+                    module_name,
+                } => {
+                    let resolved = self.resolve(path);
+                    if !resolved.exists() {
+                        anyhow::bail!("Registration file not found: {:?}", resolved);
+                    }
+                    if !resolved.is_file() {
+                        anyhow::bail!("Registration path is not a file: {:?}", resolved);
+                    }
+                    for file in self.registration_files(&resolved, warnings)? {
+                        if registered.insert(file.clone()) {
+                            single_files.push((file, output_path.clone(), module_name.clone()));
+                        }
+                    }
+                }
+                SourceConfig::Glob {
+                    pattern,
+                    output_path,
+                    module_name,
+                } => {
+                    let files = scanner::expand_glob(&self.resolve_pattern(pattern))?;
+                    for file in files {
+                        single_files.push((file, output_path.clone(), module_name.clone()));
+                    }
+                }
+            }
+        }
+
+        let mut ts_files: Vec<(PathBuf, Option<PathBuf>, Option<String>)> = Vec::new();
+        for (dir, scan_config, output_path, module_name) in &source_dirs {
+            let files = scanner::scan_directory_with_options(dir, scan_config)?;
+            if files.is_empty() {
+                warnings.warn(
+                    "empty-source",
+                    format!(
+                        "Source directory {:?} contains no TypeScript files after filtering",
+                        dir
+                    ),
+                );
+            }
+            for file in files {
+                ts_files.push((file, output_path.clone(), module_name.clone()));
+            }
+        }
+        ts_files.extend(single_files);
+        Ok(ts_files)
+    }
+
+    /// Files of a registration source: the registration file and every file it reaches
+    /// through `import` and `export ... from`, followed transitively, in discovery order.
+    /// Type-only imports are followed too since they name bean types. Package imports are
+    /// skipped; relative or tsconfig `paths` specifiers that resolve to no file are warned
+    /// about with the importing file and line
+    fn registration_files(
+        &self,
+        registration: &Path,
+        warnings: &mut Diagnostics,
+    ) -> Result<Vec<PathBuf>> {
+        let parser = TsParser::new();
+        let resolver = PathResolver::new(&self.tsconfig, &self.project_root);
+        let registration = normalize_path(registration);
+        let mut files = vec![registration.clone()];
+        let mut seen: HashSet<PathBuf> = HashSet::from([registration.clone()]);
+        let mut next = 0;
+        while next < files.len() {
+            let file = files[next].clone();
+            next += 1;
+            let content = source_text::read(&file)?.text;
+            let specifiers = parser.parse_module_specifiers(&file.to_string_lossy(), &content)?;
+            for (specifier, line) in specifiers {
+                let is_relative = specifier.starts_with('.');
+                let base = if is_relative {
+                    file.parent().unwrap_or(Path::new(".")).join(&specifier)
+                } else {
+                    resolver.resolve(&specifier)
+                };
+                match resolve_module_file(&base) {
+                    Some(resolved) => {
+                        if seen.insert(resolved.clone()) {
+                            files.push(resolved);
+                        }
+                    }
+                    None if is_relative || resolver.is_alias(&specifier) => warnings.warn(
+                        "unresolved-import",
+                        format!(
+                            "{}:{}: import '{}' doesn't resolve to a source file, not followed (registration {})",
+                            self.source_file(&file),
+                            line,
+                            specifier,
+                            self.source_file(&registration)
+                        ),
+                    ),
+                    // A package
+                    None => {}
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    /// Sources to parse: the virtual sources if set, else the scanned [[sources]]
+    fn source_inputs(&self, warnings: &mut Diagnostics) -> Result<Vec<SourceInput>> {
+        Ok(match &self.virtual_sources {
+            Some(sources) => sources
+                .iter()
+                .map(|(path, content)| SourceInput {
+                    path: normalize_path(&self.resolve(path)),
+                    content: Some(content.clone()),
+                    output_path: None,
+                    module_name: None,
+                })
+                .collect(),
+            None => self
+                .collect_sources(warnings)?
+                .into_iter()
+                .map(|(path, output_path, module_name)| SourceInput {
+                    path,
+                    content: None,
+                    output_path,
+                    module_name,
+                })
+                .collect(),
+        })
+    }
+
+    /// `--stdin-filelist` sources: the listed files that belong to a `[[sources]]` entry,
+    /// the per-file cache entries of every other file, and the position of each file (cached
+    /// files keep the order of the run that cached them, new files go last). Until the
+    /// cache has per-file entries, every source is scanned and parsed. The per-file entries
+    /// are moved out of `cache`
+    fn changed_inputs(
+        &self,
+        changed: &[PathBuf],
+        cache: &mut Cache,
+        warnings: &mut Diagnostics,
+    ) -> Result<(Vec<SourceInput>, Vec<CachedFile>, HashMap<String, usize>)> {
+        if cache.files.is_empty() {
+            self.say("  No per-file cache yet, scanning all sources");
+            return Ok((self.source_inputs(warnings)?, Vec::new(), HashMap::new()));
+        }
+
+        let mut order: HashMap<String, usize> = cache
+            .files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| (file.path.clone(), i))
+            .collect();
+        let mut listed: HashSet<String> = HashSet::new();
+        let mut inputs = Vec::new();
+        for path in changed {
+            let path = normalize_path(&self.resolve(path));
+            let source_file = self.source_file(&path);
+            let Some((output_path, module_name)) = self.source_entry(&path) else {
+                warnings.warn(
+                    "outside-sources",
+                    format!(
+                        "{}: not part of any [[sources]] entry, ignored",
+                        source_file
+                    ),
+                );
+                continue;
+            };
+            if !listed.insert(source_file.clone()) {
+                continue;
+            }
+            if !path.is_file() {
+                // Deleted: its beans leave the outputs with its cache entry
+                if self.verbose {
+                    self.say(format_args!("  Removed {}", source_file));
+                }
+                continue;
+            }
+            let next = order.len();
+            order.entry(source_file).or_insert(next);
+            inputs.push(SourceInput {
+                path,
+                content: None,
+                output_path,
+                module_name,
+            });
+        }
+        let reused: Vec<CachedFile> = std::mem::take(&mut cache.files)
+            .into_iter()
+            .filter(|file| !listed.contains(&file.path))
+            .collect();
+        self.say(format_args!(
+            "  {} changed file(s), {} from the per-file cache",
+            inputs.len(),
+            reused.len()
+        ));
+        Ok((inputs, reused, order))
+    }
+
+    /// Output path and module name a scan gives `path` (normalized): those of the first
+    /// `[[sources]]` entry it belongs to, None when it belongs to none. The file need not exist
+    fn source_entry(&self, path: &Path) -> Option<(Option<PathBuf>, Option<String>)> {
+        let is_path = |file: &PathBuf| normalize_path(&self.resolve(file)) == path;
+        self.config.sources.iter().find_map(|source| {
+            let (matches, output_path, module_name) = match source {
+                SourceConfig::Directory {
+                    path: dir,
+                    scan_options,
+                    output_path,
+                    module_name,
+                } => (
+                    scanner::ScanConfig::from(scan_options)
+                        .accepts(&normalize_path(&self.resolve(dir)), path),
+                    output_path,
+                    module_name,
+                ),
+                SourceConfig::File {
+                    path: file,
+                    output_path,
+                    module_name,
+                } => (is_path(file), output_path, module_name),
+                SourceConfig::Files {
+                    paths,
+                    output_path,
+                    module_name,
+                } => (paths.iter().any(is_path), output_path, module_name),
+                SourceConfig::Glob {
+                    pattern,
+                    output_path,
+                    module_name,
+                } => {
+                    let pattern = normalize_path(Path::new(&self.resolve_pattern(pattern)));
+                    let matches = glob::Pattern::new(&pattern.to_string_lossy())
+                        .is_ok_and(|pattern| pattern.matches_path(path));
+                    (matches, output_path, module_name)
+                }
+                SourceConfig::Registration {
+                    path: registration,
+                    output_path,
+                    module_name,
+                } => {
+                    let matches = self
+                        .registration_files(
+                            &self.resolve(registration),
+                            &mut Diagnostics::default(),
+                        )
+                        .is_ok_and(|files| files.iter().any(|file| file == path));
+                    (matches, output_path, module_name)
+                }
+            };
+            matches.then(|| (output_path.clone(), module_name.clone()))
+        })
+    }
+
+    /// Scan, parse, apply virtual beans/fields and validate
+    fn build_model(&self) -> Result<Model> {
+        self.build_model_for(None, self.changed_files.as_deref(), false)
+    }
+
+    /// `build_model` over the sources whose `source_key` is in `only` (all when None), or
+    /// over the `changed` sources and the per-file cache. [tables] entries whose bean isn't
+    /// among them are not reported as missing. With `load_cache` the cache on disk is
+    /// returned in `Model::cache`
+    fn build_model_for(
+        &self,
+        only: Option<&HashSet<PathBuf>>,
+        changed: Option<&[PathBuf]>,
+        load_cache: bool,
+    ) -> Result<Model> {
+        let config = &self.config;
+        let type_mapper = TypeMapper::new(&config.type_mappings)
+            .with_nested_containers(config.output.nested_containers);
+
+        // Scan for TypeScript files and track their output paths and module names
+        self.say("\n[1/4] Scanning sources...");
+        let mut warnings = Diagnostics::default();
+        let needs_cache = load_cache || (changed.is_some() && only.is_none() && !self.force);
+        let mut cache = needs_cache.then(|| self.load_cache(&mut warnings));
+        let (mut ts_files, reused, file_order) = match (changed, only, cache.as_mut()) {
+            (Some(changed), None, Some(cache)) if !self.force => {
+                self.changed_inputs(changed, cache, &mut warnings)?
+            }
+            _ => (
+                self.source_inputs(&mut warnings)?,
+                Vec::new(),
+                HashMap::new(),
+            ),
+        };
+        if let Some(only) = only {
+            ts_files.retain(|source| only.contains(&source_key(&source.path)));
+        }
+        // Oversized files are usually generated output that slipped into a source directory
+        let limits = &config.limits;
+        if limits.max_file_size > 0 {
+            ts_files.retain(|source| {
+                let size = match &source.content {
+                    Some(content) => content.len() as u64,
+                    None => std::fs::metadata(&source.path).map_or(0, |m| m.len()),
+                };
+                if size <= limits.max_file_size {
+                    return true;
+                }
+                warnings.warn(
+                    "file-too-large",
+                    format!(
+                        "{}: file is {} KB, over limits.max_file_size ({} KB); skipped",
+                        self.source_file(&source.path),
+                        size / 1024,
+                        limits.max_file_size / 1024
+                    ),
+                );
+                false
+            });
+        }
+        self.say(format_args!("  Found {} TypeScript files", ts_files.len()));
+
+        // Parse files in parallel, setting output_path and module_name for each class
+        self.say("\n[2/4] Parsing TypeScript files...");
+
+        let comment_policy = CommentPolicy::from(&config.defaults);
+        let per_file_cache = config.output.per_file_cache;
+        let parse_results: Vec<_> = ts_files
+            .par_iter()
+            .map(|source| {
+                let SourceInput {
+                    path,
+                    content,
+                    output_path,
+                    module_name,
+                } = source;
+                let started = Instant::now();
+                // Create parser per-thread since SourceMap isn't Sync
+                let ts_parser = TsParser::new()
+                    .with_project_root(&self.project_root)
+                    .with_comment_policy(comment_policy.clone())
+                    .with_forbidden_types(config.forbidden_types.names.clone())
+                    .with_include_getters(config.defaults.include_getters)
+                    .with_include_private_referenced(config.defaults.include_private_referenced)
+                    .with_any_type(config.defaults.any_type.clone());
+                let mut failures = Vec::new();
+                // Read once for both passes; decoding strips a BOM and normalizes CRLF
+                let (content, lossy_utf8) = match content {
+                    Some(content) => (source_text::normalize(content.clone()), false),
+                    None => match source_text::read(path) {
+                        Ok(source) => (source.text, source.lossy),
+                        Err(e) => {
+                            failures.push(format!("{:#}", e));
+                            (String::new(), false)
+                        }
+                    },
+                };
+                let name = path.to_string_lossy();
+                let parsed_classes = ts_parser.parse_source(&name, &content);
+                let classes = match parsed_classes {
+                    Ok(mut classes) => {
+                        // Set output_path and module_name for all classes from this file;
+                        // @Module / `@module` on a class wins over the source's module
+                        for class in &mut classes {
+                            class.output_path = output_path.clone();
+                            if !class.explicit_module {
+                                class.module_name = module_name.clone();
+                            }
+                        }
+                        classes
+                    }
+                    Err(e) => {
+                        failures.push(format!("Failed to parse classes from {:?}: {}", path, e));
+                        vec![]
+                    }
+                };
+                let export_warnings = ts_parser.take_warnings();
+                let class_warnings = ts_parser.take_class_warnings();
+                let unexported = ts_parser.take_errors();
+                if self.verbose {
+                    for note in ts_parser.take_notes() {
+                        self.say(format_args!("  Note: {}", note));
+                    }
+                }
+                let parsed_enums = ts_parser.parse_enums_source(&name, &content);
+                let enums = match parsed_enums {
+                    Ok(mut enums) => {
+                        for e in &mut enums {
+                            e.output_path = output_path.clone();
+                            e.module_name = module_name.clone();
+                        }
+                        enums
+                    }
+                    Err(e) => {
+                        failures.push(format!("Failed to parse enums from {:?}: {}", path, e));
+                        vec![]
+                    }
+                };
+                let parse_ms = started.elapsed().as_secs_f64() * 1000.0;
+                let stats = FileStats {
+                    path: path.clone(),
+                    parse_ms,
+                    classes: classes.len(),
+                    enums: enums.len(),
+                    cached: false,
+                    over_budget: limits.parse_budget_ms > 0
+                        && parse_ms > limits.parse_budget_ms as f64,
+                    lossy_utf8,
+                };
+                let cached = per_file_cache.then(|| CachedFile {
+                    path: self.source_file(path),
+                    hash: compute_hash(&content),
+                    classes: classes.iter().map(CachedClass::new).collect(),
+                    enums: enums.clone(),
+                    parse_errors: failures.clone(),
+                    warnings: export_warnings.clone(),
+                    class_warnings: class_warnings.clone(),
+                    lossy_utf8,
+                });
+                (
+                    classes,
+                    enums,
+                    failures,
+                    stats,
+                    export_warnings,
+                    unexported,
+                    cached,
+                    class_warnings,
+                )
+            })
+            .collect();
+
+        // Files that aren't valid UTF-8 were parsed with the invalid bytes replaced
+        let lossy_files: Vec<String> = parse_results
+            .iter()
+            .filter(|r| r.3.lossy_utf8)
+            .map(|r| self.source_file(&r.3.path))
+            .chain(
+                reused
+                    .iter()
+                    .filter(|f| f.lossy_utf8)
+                    .map(|f| f.path.clone()),
+            )
+            .collect();
+        if config.defaults.invalid_utf8 == InvalidUtf8::Error && !lossy_files.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Source files are not valid UTF-8 (re-save them as UTF-8, or set defaults.invalid_utf8 = \"warn\" to decode them lossily):\n  {}",
+                lossy_files.join("\n  ")
+            )));
+        }
+        for file in &lossy_files {
+            warnings.warn("invalid-utf8", source_text::invalid_utf8_message(file));
+        }
+
+        // Fields typed with a class their file declares without `export`
+        let unexported: Vec<String> = parse_results
+            .iter()
+            .flat_map(|r| r.5.iter().cloned())
+            .collect();
+        if !unexported.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Field types declared without export (or set defaults.include_private_referenced = true):\n  {}",
+                unexported.join("\n  ")
+            )));
+        }
+
+        let mut parse_errors = Vec::new();
+        let reported = parse_results.iter().map(|r| (&r.2, &r.4, &r.7)).chain(
+            reused
+                .iter()
+                .map(|f| (&f.parse_errors, &f.warnings, &f.class_warnings)),
+        );
+        for (failures, export_warnings, class_warnings) in reported {
+            for failure in failures {
+                warnings.warn("parse-error", failure.clone());
+                parse_errors.push(failure.clone());
+            }
+            for warning in export_warnings {
+                warnings.warn("unsupported-syntax", warning.clone());
+            }
+            for warning in class_warnings {
+                warnings.warn(warning.kind.code(), warning.message.clone());
+            }
+        }
+        let mut all_classes: Vec<_> = parse_results
+            .iter()
+            .flat_map(|(c, _, _, _, _, _, _, _)| c.clone())
+            .collect();
+        let file_stats: Vec<FileStats> = parse_results.iter().map(|r| r.3.clone()).collect();
+        for file in file_stats.iter().filter(|f| f.over_budget) {
+            warnings.warn(
+                "slow-parse",
+                format!(
+                    "{}: parsing took {:.0} ms, over limits.parse_budget_ms ({} ms)",
+                    self.source_file(&file.path),
+                    file.parse_ms,
+                    limits.parse_budget_ms
+                ),
+            );
+        }
+        let mut all_enums: Vec<_> = parse_results
+            .iter()
+            .flat_map(|(_, e, _, _, _, _, _, _)| e.clone())
+            .collect();
+        let mut files: Vec<CachedFile> = parse_results
+            .into_iter()
+            .filter_map(|(_, _, _, _, _, _, cached, _)| cached)
+            .collect();
+
+        // --stdin-filelist: everything not listed comes from the per-file cache, in the
+        // order of the run that cached it
+        for file in reused {
+            let source_path = normalize_path(&self.resolve(Path::new(&file.path)));
+            let (classes, enums) = file.clone().restore(&source_path);
+            all_classes.extend(classes);
+            all_enums.extend(enums);
+            files.push(file);
+        }
+        if !file_order.is_empty() {
+            let position = |source_file: &str| file_order.get(source_file).copied();
+            all_classes.sort_by_key(|c| position(&c.source_file));
+            all_enums.sort_by_key(|e| position(&e.source_file));
+            files.sort_by_key(|f| position(&f.path));
+        }
+
+        self.say(format_args!(
+            "  Extracted {} classes/interfaces, {} enums",
+            all_classes.len(),
+            all_enums.len()
+        ));
+        if config.defaults.skip_ambient {
+            let before = all_classes.len();
+            all_classes.retain(|c| !c.is_ambient);
+            if self.verbose && all_classes.len() < before {
+                self.say(format_args!(
+                    "  Skipped {} ambient classes (defaults.skip_ambient)",
+                    before - all_classes.len()
+                ));
+            }
+        }
+        // Source and @Module module names end up in the XML as-is
+        let module_errors = validator::find_invalid_modules(&all_classes, &all_enums);
+        if !module_errors.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Invalid module names:\n  {}",
+                module_errors.join("\n  ")
+            )));
+        }
+        if self.verbose {
+            for class in &all_classes {
+                for name in &class.ignored_statics {
+                    self.say(format_args!(
+                        "  Note: {}.{} - static property is not a readonly literal, skipped",
+                        class.name, name
+                    ));
+                }
+            }
+        }
+
+        // Drop what the build flavor excludes, with the [tables] entries of dropped classes
+        let mut tables = config.tables.clone();
+        let mut omitted = Vec::new();
+        if let Some(flavor) = &self.flavor {
+            omitted = apply_flavor(&mut all_classes, &mut tables, flavor, &self.default_module)?;
+            self.say(format_args!(
+                "  Flavor '{}': omitted {} classes/fields",
+                flavor,
+                omitted.len()
+            ));
+        }
+
+        // Add [[virtual_beans]] from config
+        if !config.virtual_beans.is_empty() {
+            let virtual_beans =
+                virtual_fields::build_virtual_beans(&config.virtual_beans, &all_classes)?;
+            if self.verbose {
+                self.say(format_args!(
+                    "  Added {} virtual beans from config",
+                    virtual_beans.len()
+                ));
+            }
+            all_classes.extend(virtual_beans);
+        }
+
+        // Inject [[virtual_fields]] from config
+        if !config.virtual_fields.is_empty() {
+            let unmatched =
+                virtual_fields::inject_virtual_fields(&mut all_classes, &config.virtual_fields)?;
+            for u in unmatched {
+                warnings.warn("virtual-field", u.to_string());
+            }
+        }
+
+        // `T | null` list elements / map values lose their `?` unless nullable elements are enabled
+        if !config.defaults.nullable_elements {
+            for warning in strip_nullable_elements(&mut all_classes) {
+                warnings.warn("nullable-element", warning);
+            }
+        }
+
+        // Route beans to named outputs ([[output_routes]] / @Output)
+        assign_output_routes(&mut all_classes, &config.output_routes)?;
+
+        // Leave data-only classes out of beans.ts (@NoRuntime / [beans] exclude)
+        exclude_from_beans(&mut all_classes, &config.beans.exclude)?;
+        if self.verbose {
+            let excluded = all_classes.iter().filter(|c| c.no_runtime).count();
+            if excluded > 0 {
+                self.say(format_args!(
+                    "  Excluded {} classes from beans.ts (@NoRuntime / [beans] exclude)",
+                    excluded
+                ));
+            }
+        }
+        for warning in validator::find_cross_route_references(&all_classes) {
+            warnings.warn("cross-route", warning);
+        }
+
+        // Point @Index validators at renamed (@Name) element fields
+        resolve_renamed_index_fields(&mut all_classes);
+
+        // Check that default values match their field types
+        let default_errors = validator::validate_default_values(&all_classes, &type_mapper);
+        if !default_errors.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Invalid default values:\n  {}",
+                default_errors.join("\n  ")
+            )));
+        }
+
+        // @Range bounds on integer fields must be whole numbers
+        let range_errors = validator::validate_range_bounds(&all_classes, &type_mapper);
+        if !range_errors.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Invalid @Range bounds:\n  {}",
+                range_errors.join("\n  ")
+            )));
+        }
+
+        // Optional containers lose their `?` unless nullable containers are enabled
+        if config.defaults.optional_containers == OptionalContainers::Warn {
+            for warning in validator::find_optional_containers(&all_classes, &type_mapper) {
+                warnings.warn("optional-container", warning);
+            }
+        }
+
+        // Recursive beans end through a list or an optional field, never through required ones
+        for warning in validator::find_unbounded_recursion(&all_classes, &type_mapper) {
+            warnings.warn("unbounded-recursion", warning);
+        }
+
+        // keyof/typeof/conditional types fall back to string unless @type overrides them
+        for warning in validator::find_unsupported_types(&all_classes) {
+            warnings.warn("unsupported-type", warning);
+        }
+
+        // any/unknown are schema holes unless defaults.any_type explicitly allows them
+        if config.defaults.any_type.is_none() {
+            let untyped = validator::find_untyped_fields(&all_classes);
+            if config.defaults.strict_types && !untyped.is_empty() {
+                anyhow::bail!(GenError::Validation(format!(
+                    "Fields typed any/unknown (defaults.strict_types):\n  {}",
+                    untyped.join("\n  ")
+                )));
+            }
+            for warning in untyped {
+                warnings.warn("untyped-field", warning);
+            }
+        }
+
+        // Roblox Instances, signals and Promises can't be stored in configs
+        let forbidden = validator::find_forbidden_types(&all_classes, &all_enums, &type_mapper);
+        if config.defaults.strict_types && !forbidden.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Runtime-only field types (defaults.strict_types):\n  {}",
+                forbidden.join("\n  ")
+            )));
+        }
+        for warning in forbidden {
+            warnings.warn("forbidden-type", warning);
+        }
+
+        // Field types nothing defines: [external_beans] are fine, near misses are likely typos
+        let unknown_types = validator::classify_unknown_types(
+            &all_classes,
+            &all_enums,
+            &type_mapper,
+            &config.external_beans.names,
+        );
+        // Known names are collected after parsing, so beans from other files resolve
+        let mut unknown_errors = Vec::new();
+        for unknown in &unknown_types {
+            match unknown.kind {
+                UnknownTypeKind::External => {}
+                _ if config.defaults.strict_types => unknown_errors.push(unknown.to_string()),
+                UnknownTypeKind::SuspectedTypo => {
+                    warnings.warn("suspected-typo", unknown.to_string())
+                }
+                UnknownTypeKind::Unknown => warnings.warn("unknown-type", unknown.to_string()),
+            }
+        }
+        if !unknown_errors.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Unknown field types (defaults.strict_types):\n  {}",
+                unknown_errors.join("\n  ")
+            )));
+        }
+
+        // @Converter fully defines the Luban type, so it can't be combined with @type
+        let converter_errors = validator::validate_converters(&all_classes);
+        if !converter_errors.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Invalid @Converter fields:\n  {}",
+                converter_errors.join("\n  ")
+            )));
+        }
+
+        // Map keys must be scalars, enums or number-literal unions
+        let map_key_errors = validator::validate_map_keys(&all_classes, &type_mapper);
+        if !map_key_errors.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Invalid map keys:\n  {}",
+                map_key_errors.join("\n  ")
+            )));
+        }
+
+        // Classes extending one bean and implementing an unrelated bean interface
+        let parent_conflicts =
+            resolve_parent_conflicts(&mut all_classes, config.defaults.parent_precedence);
+        for warning in parent_conflicts {
+            warnings.warn("conflicting-parent", warning);
+        }
+
+        // Luban rejects beans that (transitively) inherit from themselves
+        let cycles = validator::find_inheritance_cycles(&all_classes);
+        if !cycles.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Inheritance cycles:\n  {}",
+                cycles.join("\n  ")
+            )));
+        }
+
+        // Warn about classes implementing several bean interfaces without extends/@Parent
+        for (class_name, interfaces) in find_ambiguous_parents(&all_classes) {
+            warnings.warn(
+                "ambiguous-parent",
+                format!(
+                    "{} implements multiple bean interfaces ({}), no parent is emitted; use extends or @Parent to choose one",
+                    class_name,
+                    interfaces.join(", ")
+                ),
+            );
+        }
+
+        // Fields that Luban would reject as duplicates of a hand-written parent bean's
+        for warning in find_shadowed_parent_fields(&all_classes, &config.parent_fields) {
+            warnings.warn("shadowed-parent-field", warning);
+        }
+
+        // Redeclared inherited fields are dropped from the child bean, whatever their type
+        for warning in find_retyped_inherited_fields(&all_classes) {
+            warnings.warn("retyped-field", warning);
+        }
+
+        // Build table registry from [tables] config
+        let mut table_registry = TableRegistry::from_config(&tables);
+        table_registry.resolve_groups(&config.groups);
+
+        // Modes outside the known set are typos unless defaults.allow_custom_modes passes them
+        // through to a Luban fork (Config::load rejects them too; this covers configs built in code)
+        let mut custom_modes: Vec<&ResolvedTableConfig> = table_registry
+            .all_tables()
+            .filter(|table| table.has_custom_mode())
+            .collect();
+        custom_modes.sort_by(|a, b| a.bean.cmp(&b.bean));
+        if !config.defaults.allow_custom_modes && !custom_modes.is_empty() {
+            let errors: Vec<String> = custom_modes
+                .iter()
+                .map(|table| unknown_mode_error(&table.bean, &table.mode))
+                .collect();
+            anyhow::bail!(GenError::Validation(format!(
+                "Invalid table modes:\n  {}",
+                errors.join("\n  ")
+            )));
+        }
+        for table in custom_modes {
+            warnings.warn(
+                "custom-table-mode",
+                format!(
+                    "[tables] \"{}\": custom mode \"{}\" is passed through to the XML as is; tables.d.ts types {} as unknown",
+                    table.bean, table.mode, table.name
+                ),
+            );
+        }
+
+        // Resolve table indexes ([tables] index > @RefKey > "id") and their types
+        let index_errors = table_registry.set_index_types(&all_classes, &type_mapper);
+        if !index_errors.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Invalid table indexes:\n  {}",
+                index_errors.join("\n  ")
+            )));
+        }
+        for warning in table_registry.ignored_ref_keys(&all_classes) {
+            warnings.warn("ignored-ref-key", warning);
+        }
+
+        // @Ref(Target) / @RefKey(Target) need a target table whose index fits the slot
+        let ref_errors = validator::validate_refs(&all_classes, &type_mapper, &table_registry);
+        if !ref_errors.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Invalid @Ref fields:\n  {}",
+                ref_errors.join("\n  ")
+            )));
+        }
+
+        if self.verbose {
+            self.say(format_args!(
+                "  Registered {} tables from [tables] config",
+                tables.len()
+            ));
+        }
+
+        // Validate that all configured tables have corresponding beans
+        if !tables.is_empty() && only.is_none() {
+            let existing_beans: HashSet<String> = all_classes
+                .iter()
+                .map(|class| changelog::bean_key(class, &self.default_module))
+                .collect();
+
+            let missing_beans = table_registry.validate_beans_exist(&existing_beans);
+            if !missing_beans.is_empty() {
+                anyhow::bail!(GenError::Validation(format!(
+                    "The following tables are configured but their beans do not exist:\n  - {}\n\nPlease check your [tables] configuration and ensure the corresponding TypeScript classes/interfaces exist.",
+                    missing_beans.join("\n  - ")
+                )));
+            }
+        }
+
+        // Tables declared in code (@LubanTable / @table) and [tables] entries must agree
+        if config.defaults.table_reconcile != TableReconcile::Off {
+            let drift = validator::reconcile_tables(&all_classes, &tables);
+            if config.defaults.table_reconcile == TableReconcile::Error && !drift.is_empty() {
+                anyhow::bail!(GenError::Validation(format!(
+                    "Tables declared in code and [tables] disagree (defaults.table_reconcile):\n  {}",
+                    drift.join("\n  ")
+                )));
+            }
+            for warning in drift {
+                warnings.warn("table-reconcile", warning);
+            }
+        }
+
+        // Naming conventions ([lint]) once bean, field and table names are final
+        let linter = Linter::new(&config.lint).map_err(|errors| {
+            GenError::Validation(format!(
+                "Invalid configuration:\n  [lint]\n    {}",
+                errors.join("\n    ")
+            ))
+        })?;
+        let (lint_errors, lint): (Vec<LintViolation>, Vec<LintViolation>) = linter
+            .check(&all_classes, table_registry.all_tables())
+            .into_iter()
+            .partition(|violation| violation.severity == LintSeverity::Error);
+        if !lint_errors.is_empty() {
+            let messages: Vec<&str> = lint_errors.iter().map(|v| v.message.as_str()).collect();
+            anyhow::bail!(GenError::Validation(format!(
+                "Naming convention violations ([lint]):\n  {}",
+                messages.join("\n  ")
+            )));
+        }
+        for violation in &lint {
+            warnings.warn("lint", violation.message.clone());
+        }
+
+        // Identical warnings are printed once; the model keeps the full list for the report
+        if self.progress {
+            for line in warnings.render(self.max_warnings) {
+                eprintln!("  {}", line);
+            }
+        }
+        if self.deny_warnings && !warnings.is_empty() {
+            let message = format!(
+                "{} warning(s) with deny_warnings enabled:\n  {}",
+                warnings.len(),
+                warnings.messages().join("\n  ")
+            );
+            if parse_errors.is_empty() {
+                anyhow::bail!(GenError::Validation(message));
+            }
+            anyhow::bail!(GenError::Parse(message));
+        }
+
+        Ok(Model {
+            classes: all_classes,
+            enums: all_enums,
+            type_mapper,
+            table_registry,
+            warnings: warnings.into_warnings(),
+            unknown_types,
+            parse_errors,
+            file_stats,
+            omitted,
+            lint,
+            files,
+            cache,
+        })
+    }
+
+    /// Load the cache on disk, warning when it was unreadable and a fallback was used
+    fn load_cache(&self, warnings: &mut Diagnostics) -> Cache {
+        let (cache, fallback) =
+            Cache::load(&self.project_root.join(&self.config.output.cache_file));
+        if let Some(fallback) = fallback {
+            warnings.warn("cache-fallback", fallback);
+        }
+        cache
+    }
+
+    /// Make the file at `root_output` import the per-module files of output.split_by_module
+    /// (`<import name="beans_role.xml"/>`), adding an empty root module file when no class
+    /// or enum is in the root module
+    fn import_module_files(
+        &self,
+        files: &mut Vec<GeneratedFile>,
+        keys: &[(PathBuf, String)],
+        root_output: &Path,
+        module_output: impl Fn(&str) -> Option<PathBuf>,
+    ) {
+        let mut imports: Vec<String> = keys
+            .iter()
+            .filter(|(path, module)| module_output(module).as_ref() == Some(path))
+            .filter_map(|(path, _)| path.file_name())
+            .map(|name| format!("    <import name=\"{}\"/>", name.to_string_lossy()))
+            .collect();
+        imports.sort();
+        if imports.is_empty() {
+            return;
+        }
+
+        let root_path = self.project_root.join(root_output);
+        let index = match files.iter().position(|f| f.path == root_path) {
+            Some(index) => index,
+            None => {
+                files.push(GeneratedFile {
+                    path: root_path,
+                    artifact: "module XML for ''".to_string(),
+                    content: "<module name=\"\">\n</module>\n".to_string(),
+                    beans: 0,
+                    enums: 0,
+                    module: Some(String::new()),
+                });
+                files.len() - 1
+            }
+        };
+        let root = &mut files[index];
+        let (open, rest) = root
+            .content
+            .split_once('\n')
+            .unwrap_or((root.content.as_str(), ""));
+        root.content = format!(
+            "{}\n{}\n\n{}",
+            open,
+            imports.join("\n"),
+            rest.trim_start_matches('\n')
+        );
+    }
+
+    fn render_xml(&self, model: &Model) -> Result<Vec<GeneratedFile>> {
+        let config = &self.config;
+        // Beans by (module, name), so the XML doesn't depend on the input order (directory
+        // walk, registration list, --stdin-filelist, virtual sources)
+        let mut classes: Vec<&ClassInfo> = self.selected_classes(model).collect();
+        classes.sort_by(|a, b| {
+            let module = |c: &ClassInfo| c.module_name.clone().unwrap_or_default();
+            (module(a), &a.name).cmp(&(module(b), &b.name))
+        });
+        let enums: Vec<&EnumInfo> = self.selected_enums(model).collect();
+
+        // Build type-to-module mapping including enums
+        let mut type_to_module: HashMap<String, String> = HashMap::new();
+        for enum_info in &model.enums {
+            if let Some(module) = &enum_info.module_name {
+                type_to_module.insert(enum_info.name.clone(), module.clone());
+            }
+        }
+
+        // Build file-to-module mapping for cross-module type resolution
+        // This maps source file paths to their module names
+        let mut file_to_module: HashMap<PathBuf, String> = HashMap::new();
+        // Classes moved to another module by @Module / `@module`, by (file, class name)
+        let mut class_modules: HashMap<(PathBuf, String), String> = HashMap::new();
+        let sources = model
+            .classes
+            .iter()
+            .map(|c| (c.file_path(), &c.module_name, Some(c)))
+            .chain(
+                model
+                    .enums
+                    .iter()
+                    .map(|e| (e.file_path(), &e.module_name, None)),
+            );
+        for (source_path, module_name, class) in sources {
+            if let Some(module) = module_name {
+                // Canonicalize the source file path for consistent matching
+                let source_path = source_path.to_path_buf();
+                // If canonicalize fails, use the original path
+                let source_path = source_path.canonicalize().unwrap_or(source_path);
+                match class {
+                    Some(class) if class.explicit_module => {
+                        class_modules.insert((source_path, class.name.clone()), module.clone());
+                    }
+                    _ => {
+                        file_to_module.insert(source_path, module.clone());
+                    }
+                }
+            }
+        }
+
+        let xml_generator = XmlGenerator::with_type_and_file_mapping(
+            &model.type_mapper,
+            &model.table_registry,
+            type_to_module,
+            file_to_module,
+        )
+        .with_nested_children(config.output.nested_children)
+        .with_optional_containers(config.defaults.optional_containers)
+        .with_list_table_indexes(config.output.list_table_indexes)
+        .with_open_range_format(config.output.open_range_format)
+        .with_xml_style(config.output.xml_style)
+        .with_class_modules(class_modules);
+
+        // Nesting child beans needs an unambiguous, acyclic hierarchy
+        if config.output.nested_children {
+            let nesting_errors = xml_generator.nesting_errors(&model.classes);
+            if !nesting_errors.is_empty() {
+                anyhow::bail!(GenError::Validation(format!(
+                    "Cannot nest child beans (output.nested_children):\n  {}",
+                    nesting_errors.join("\n  ")
+                )));
+            }
+        }
+
+        // Inherited and injected fields must not collide with a bean's own fields
+        let duplicate_errors =
+            xml_generator.duplicate_field_errors(&model.classes, &config.virtual_fields);
+        if !duplicate_errors.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Duplicate fields:\n  {}",
+                duplicate_errors.join("\n  ")
+            )));
+        }
+
+        // Group classes and enums by (output_path, module_name), keeping first-seen order
+        let default_output = config.output.path.clone();
+        let default_module = self.default_module.clone();
+        // output.split_by_module: modules other than the root one get their own file
+        let module_output = |module: &str| {
+            (config.output.split_by_module && !module.is_empty())
+                .then(|| default_output.with_file_name(format!("beans_{}.xml", module)))
+        };
+        let key_of = |output_path: &Option<PathBuf>, module_name: &Option<String>| {
+            let module = module_name
+                .clone()
+                .unwrap_or_else(|| default_module.clone());
+            let path = output_path
+                .clone()
+                .or_else(|| module_output(&module))
+                .unwrap_or_else(|| default_output.clone());
+            (path, module)
+        };
+        let mut keys: Vec<(PathBuf, String)> = Vec::new();
+        let mut grouped: HashMap<(PathBuf, String), (Vec<ClassInfo>, Vec<EnumInfo>)> =
+            HashMap::new();
+        let route_paths: HashMap<&str, &PathBuf> = config
+            .output_routes
+            .iter()
+            .map(|r| (r.name.as_str(), &r.path))
+            .collect();
+        // A class moved to another module by @Module / `@module` is written where that
+        // module's sources go (the default output when no source uses the module)
+        let mut module_outputs: HashMap<String, Option<PathBuf>> = HashMap::new();
+        let module_sources = model
+            .classes
+            .iter()
+            .filter(|c| !c.explicit_module)
+            .map(|c| (&c.module_name, &c.output_path))
+            .chain(model.enums.iter().map(|e| (&e.module_name, &e.output_path)));
+        for (module_name, output_path) in module_sources {
+            module_outputs
+                .entry(key_of(&None, module_name).1)
+                .or_insert_with(|| output_path.clone());
+        }
+        for class in &classes {
+            let output_path = match &class.output_route {
+                Some(route) => route_paths.get(route.as_str()).map(|p| (*p).clone()),
+                None if class.explicit_module => module_outputs
+                    .get(&key_of(&None, &class.module_name).1)
+                    .cloned()
+                    .flatten(),
+                None => class.output_path.clone(),
+            };
+            let key = key_of(&output_path, &class.module_name);
+            if !grouped.contains_key(&key) {
+                keys.push(key.clone());
+            }
+            grouped.entry(key).or_default().0.push((*class).clone());
+        }
+        for enum_info in &enums {
+            let key = key_of(&enum_info.output_path, &enum_info.module_name);
+            if !grouped.contains_key(&key) {
+                keys.push(key.clone());
+            }
+            grouped.entry(key).or_default().1.push((*enum_info).clone());
+        }
+
+        // Luban reads one <module> per file, so two modules can't share an output path
+        let mut module_by_path: HashMap<&PathBuf, &str> = HashMap::new();
+        let mut shared_paths = Vec::new();
+        for (path, module_name) in &keys {
+            match module_by_path.get(path) {
+                Some(first) => shared_paths.push(format!(
+                    "{}: modules '{}' and '{}'",
+                    path.display(),
+                    first,
+                    module_name
+                )),
+                None => {
+                    module_by_path.insert(path, module_name);
+                }
+            }
+        }
+        if !shared_paths.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Several modules would be written to the same XML file; give each module its own output_path (a class moved by @Module can use @Output):\n  {}",
+                shared_paths.join("\n  ")
+            )));
+        }
+
+        let mut files = Vec::new();
+        for key in &keys {
+            let Some((group_classes, group_enums)) = grouped.get(key) else {
+                continue;
+            };
+            let (out_path, module_name) = key;
+            let content = xml_generator.generate_with_all_classes_and_enums(
+                group_classes,
+                group_enums,
+                module_name,
+                &model.classes,
+            );
+            files.push(GeneratedFile {
+                path: self.project_root.join(out_path),
+                artifact: format!("module XML for '{}'", module_name),
+                content,
+                beans: group_classes.len(),
+                enums: group_enums.len(),
+                module: Some(module_name.clone()),
+            });
+        }
+        if config.output.split_by_module {
+            self.import_module_files(&mut files, &keys, &default_output, module_output);
+        }
+
+        // Generate bean type enums XML if configured (grouped by parent)
+        if let Some(bean_types_path) = &config.output.bean_types_path {
+            // Collect beans with their extends (parent), aliases, and comments
+            let beans_with_parents: Vec<(&str, String, Option<&str>, Option<&str>)> = classes
+                .iter()
+                .map(|c| {
+                    (
+                        c.name.as_str(),
+                        c.explicit_parent
+                            .clone()
+                            .or_else(|| c.extends.clone())
+                            .unwrap_or_default(),
+                        c.alias.as_deref(),
+                        c.comment.as_deref(),
+                    )
+                })
+                .collect();
+            let beans_refs: Vec<(&str, &str, Option<&str>, Option<&str>)> = beans_with_parents
+                .iter()
+                .map(|(name, parent, alias, comment)| (*name, parent.as_str(), *alias, *comment))
+                .collect();
+
+            files.push(GeneratedFile {
+                path: self.project_root.join(bean_types_path),
+                artifact: "bean type enums XML".to_string(),
+                content: generate_bean_type_enums_xml(&beans_refs, &default_module),
+                beans: 0,
+                enums: 0,
+                module: None,
+            });
+        }
+
+        if config.output.constants_format == ConstantsFormat::Xml {
+            files.extend(self.render_constants(model));
+        }
+
+        let indent = config.output.xml_style.indent;
+        for file in &mut files {
+            file.content = reindent_xml(&file.content, indent);
+        }
+
+        // Catch schema mistakes here rather than in Luban, pointing at the TypeScript source
+        let lint_options = xml_lint::LintOptions {
+            nullable_containers: config.defaults.optional_containers
+                == OptionalContainers::Nullable,
+            custom_table_modes: config.defaults.allow_custom_modes,
+        };
+        let violations = xml_lint::lint(
+            files.iter().map(|f| (f.path.as_path(), f.content.as_str())),
+            &model.classes,
+            &default_module,
+            lint_options,
+        );
+        if !violations.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Generated XML violates Luban schema rules:\n  {}",
+                violations.join("\n  ")
+            )));
+        }
+
+        Ok(files)
+    }
+
+    /// Render class constants to output.constants_path in the configured format.
+    /// None when unset or no class has constants
+    fn render_constants(&self, model: &Model) -> Option<GeneratedFile> {
+        let constants_path = self.config.output.constants_path.as_ref()?;
+        let classes: Vec<&ClassInfo> = self
+            .selected_classes(model)
+            .filter(|c| !c.constants.is_empty())
+            .collect();
+        if classes.is_empty() {
+            return None;
+        }
+
+        let content = match self.config.output.constants_format {
+            ConstantsFormat::Xml => generate_constants_xml(&classes, &self.default_module),
+            ConstantsFormat::Ts => generate_constants_ts(&classes),
+        };
+        Some(GeneratedFile {
+            path: self.project_root.join(constants_path),
+            artifact: "constants".to_string(),
+            content,
+            beans: match self.config.output.constants_format {
+                ConstantsFormat::Xml => classes.len(),
+                ConstantsFormat::Ts => 0,
+            },
+            enums: 0,
+            module: None,
+        })
+    }
+
+    /// Structural hash of each selected bean, keyed like beans.ts (output.bean_key_format)
+    fn schema_versions(&self, model: &Model) -> Result<BTreeMap<String, String>> {
+        let classes: Vec<&ClassInfo> = self.selected_classes(model).collect();
+        Ok(schema_version::schema_versions(
+            &classes,
+            &model.type_mapper,
+            &BeanKeyFormat::parse(&self.config.output.bean_key_format)?,
+            &self.default_module,
+        ))
+    }
+
+    /// See `model_json`
+    fn render_model_json(&self, model: &Model) -> Result<String> {
+        let default_module = &self.default_module;
+        let mut classes = model.classes.clone();
+        for class in &mut classes {
+            class.module_name = Some(class.module_name.clone().unwrap_or(default_module.clone()));
+            class.fields.sort_by_key(|f| f.order);
+        }
+        classes.sort_by(|a, b| (&a.module_name, &a.name).cmp(&(&b.module_name, &b.name)));
+        let mut enums = model.enums.clone();
+        for enum_info in &mut enums {
+            enum_info.module_name = Some(
+                enum_info
+                    .module_name
+                    .clone()
+                    .unwrap_or(default_module.clone()),
+            );
+        }
+        enums.sort_by(|a, b| (&a.module_name, &a.name).cmp(&(&b.module_name, &b.name)));
+        let mut tables: Vec<&ResolvedTableConfig> = model.table_registry.all_tables().collect();
+        tables.sort_by(|a, b| a.bean.cmp(&b.bean));
+
+        let json = ModelJson {
+            format_version: MODEL_FORMAT_VERSION,
+            classes,
+            enums,
+            tables,
+        };
+        serde_json::to_string_pretty(&json).context("Failed to serialize the model")
+    }
+
+    /// Render output.schema_versions_path. None when unset
+    fn render_schema_versions(&self, model: &Model) -> Result<Option<GeneratedFile>> {
+        let Some(path) = &self.config.output.schema_versions_path else {
+            return Ok(None);
+        };
+        Ok(Some(GeneratedFile {
+            path: self.project_root.join(path),
+            artifact: "schema versions".to_string(),
+            content: schema_version::generate_schema_versions_ts(&self.schema_versions(model)?),
+            beans: 0,
+            enums: 0,
+            module: None,
+        }))
+    }
+
+    /// Estimate the Luau register pressure of generated .ts modules (declaration files compile
+    /// to nothing). Files whose definite registers exceed the limit fail the run; files whose
+    /// pessimistic estimate comes within output.register_margin of it are returned as warnings
+    fn check_register_pressure<'f>(
+        &self,
+        files: impl IntoIterator<Item = &'f GeneratedFile>,
+    ) -> Result<Vec<String>> {
+        let margin = self.config.output.register_margin;
+        let max_imports = self.config.output.max_imports_per_file;
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        for file in files {
+            let name = file.path.to_string_lossy();
+            if !name.ends_with(".ts") || name.ends_with(".d.ts") {
+                continue;
+            }
+            // Scale the chunk size so the pessimistic estimate lands below the margin
+            let suggested = |total: usize| {
+                (max_imports * LUAU_REGISTER_LIMIT.saturating_sub(margin) / total.max(1)).max(1)
+            };
+            match register_estimate::check(&file.content, margin) {
+                RegisterPressure::Ok => {}
+                RegisterPressure::NearLimit(estimate) => warnings.push(format!(
+                    "{}: {}, within {} of Luau's {}-register limit; consider lowering output.max_imports_per_file from {} to {}",
+                    name,
+                    estimate,
+                    margin,
+                    LUAU_REGISTER_LIMIT,
+                    max_imports,
+                    suggested(estimate.total())
+                )),
+                RegisterPressure::OverLimit(estimate) => errors.push(format!(
+                    "{}: {} exceeds Luau's {}-register limit; lower output.max_imports_per_file from {} to {}",
+                    name,
+                    estimate,
+                    LUAU_REGISTER_LIMIT,
+                    max_imports,
+                    suggested(estimate.total())
+                )),
+            }
+        }
+        if !errors.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "Generated TypeScript would exceed Luau's register limit:\n  {}",
+                errors.join("\n  ")
+            )));
+        }
+        if self.progress {
+            for warning in &warnings {
+                eprintln!("  Warning: {}", warning);
+            }
+        }
+        if self.deny_warnings && !warnings.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "{} warning(s) with deny_warnings enabled:\n  {}",
+                warnings.len(),
+                warnings.join("\n  ")
+            )));
+        }
+        Ok(warnings)
+    }
+
+    /// Find runtime require cycles from the generated .ts modules through the sources they
+    /// import back into output.table_output_path (see `import_cycles`), returned as warnings
+    fn check_import_cycles(&self, files: &[GeneratedFile]) -> Result<Vec<String>> {
+        let Some(table_output_path) = &self.config.output.table_output_path else {
+            return Ok(Vec::new());
+        };
+        let output_dir = source_key(&absolute_path(&self.project_root.join(table_output_path)));
+        let parser = TsParser::new();
+        let runtime_imports = |path: &Path, content: &str| {
+            parser
+                .parse_runtime_imports(&path.to_string_lossy(), content)
+                .unwrap_or_default()
+        };
+        let virtual_sources: HashMap<PathBuf, &String> = self
+            .virtual_sources
+            .iter()
+            .flatten()
+            .map(|(path, content)| (source_key(&normalize_path(&self.resolve(path))), content))
+            .collect();
+
+        let generated: Vec<(PathBuf, Vec<PathBuf>)> = files
+            .iter()
+            .filter(|f| {
+                let name = f.path.to_string_lossy();
+                name.ends_with(".ts") && !name.ends_with(".d.ts")
+            })
+            .map(|f| {
+                let path = absolute_path(&f.path);
+                let imports = runtime_imports(&path, &f.content);
+                (source_key(&path), imports)
+            })
+            .collect();
+        let cycles = import_cycles::find_cycles(&generated, &output_dir, |module| {
+            let content = match virtual_sources.get(module) {
+                Some(content) => Some(content.to_string()),
+                None => source_text::read(module).ok().map(|source| source.text),
+            };
+            content
+                .map(|content| runtime_imports(module, &content))
+                .unwrap_or_default()
+        });
+
+        let root = source_key(&absolute_path(&self.project_root));
+        let warnings: Vec<String> = cycles
+            .iter()
+            .map(|cycle| {
+                format!(
+                    "circular require at runtime: {}; use `import type` where only types are needed, or move the import out of the config modules",
+                    cycle.render(&root)
+                )
+            })
+            .collect();
+        if self.progress {
+            for warning in &warnings {
+                eprintln!("  Warning: {}", warning);
+            }
+        }
+        if self.deny_warnings && !warnings.is_empty() {
+            anyhow::bail!(GenError::Validation(format!(
+                "{} warning(s) with deny_warnings enabled:\n  {}",
+                warnings.len(),
+                warnings.join("\n  ")
+            )));
+        }
+        Ok(warnings)
+    }
+
+    fn render_ts(&self, model: &Model) -> Result<Vec<GeneratedFile>> {
+        let Some(table_output_path) = &self.config.output.table_output_path else {
+            return Ok(Vec::new());
+        };
+        let project_root = self.project_root.as_path();
+
+        // For TypeScript generation, determine the true project root
+        // (where assets/ folder is located)
+        // Check if we're in a subdirectory by looking for parent indicators
+        let ts_project_root = if project_root
+            .parent()
+            .map(|p| p.join("Cargo.toml").exists() || p.join("assets").exists())
+            .unwrap_or(false)
+        {
+            // Parent has Cargo.toml or assets/, use parent as project root
+            project_root.parent().unwrap_or(project_root).to_path_buf()
+        } else {
+            // Use current project_root
+            project_root.to_path_buf()
+        };
+
+        let mut ts_generator = TsCodeGenerator::new(
+            project_root.join(table_output_path),
+            ts_project_root,
+            self.selected_classes(model).cloned().collect(),
+            &self.tsconfig,
+            self.default_module.clone(),
+            &model.table_registry,
+        )
+        .with_newline(self.config.output.newline)
+        .with_config_dir(project_root.to_path_buf())
+        .with_max_imports_per_file(self.config.output.max_imports_per_file)
+        .with_beans_export(self.config.output.beans_export)
+        .with_bean_key_format(BeanKeyFormat::parse(&self.config.output.bean_key_format)?)
+        .with_ts_type_overrides(&self.config.ts_type_overrides, &model.type_mapper);
+        if let Some(groups) = &self.groups {
+            ts_generator = ts_generator.with_groups(groups.clone());
+        }
+
+        Ok(ts_generator
+            .render()?
+            .into_iter()
+            .map(|(path, content)| GeneratedFile {
+                artifact: ts_artifact(&path),
+                path,
+                content,
+                beans: 0,
+                enums: 0,
+                module: None,
+            })
+            .collect())
+    }
+}
+
+/// Artifact name of a file rendered by TsCodeGenerator
+fn ts_artifact(path: &Path) -> String {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    match file_name.as_ref() {
+        "tables.d.ts" => "table declarations".to_string(),
+        "beans.ts" => "beans index".to_string(),
+        "singletons.ts" => "table singletons".to_string(),
+        name => match name
+            .strip_prefix("beans_")
+            .and_then(|rest| rest.strip_suffix(".ts"))
+        {
+            Some(chunk) => format!("beans chunk {}", chunk),
+            None => name.to_string(),
+        },
+    }
+}
+
+/// Write a generated file unless unchanged, naming the artifact and its absolute path on failure
+fn write_generated(file: &GeneratedFile, newline: Newline) -> Result<bool> {
+    write_if_changed(&file.path, &file.content, newline).with_context(|| {
+        format!(
+            "Failed to write {} to {}",
+            file.artifact,
+            absolute_path(&file.path).display()
+        )
+    })
+}
+
+fn absolute_path(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The source file an import of `base` loads, tried like TypeScript: the path itself, then
+/// with `.ts` / `.tsx` / `.d.ts` appended, then its directory's index file. Not followed
+/// into node_modules
+fn resolve_module_file(base: &Path) -> Option<PathBuf> {
+    let base = normalize_path(base);
+    if base.components().any(|c| c.as_os_str() == "node_modules") {
+        return None;
+    }
+    let is_source = |path: &Path| {
+        path.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| ext == "ts" || ext == "tsx")
+    };
+    if is_source(&base) {
+        return Some(base);
+    }
+    let base = base.to_string_lossy();
+    [
+        ".ts",
+        ".tsx",
+        ".d.ts",
+        "/index.ts",
+        "/index.tsx",
+        "/index.d.ts",
+    ]
+    .iter()
+    .map(|suffix| PathBuf::from(format!("{}{}", base, suffix)))
+    .find(|path| path.is_file())
+}
+
+/// Key identifying a source file across the scanned path and resolved import paths
+fn source_key(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| normalize_path(path))
+}
+
+/// Content of a source, from memory or disk (None when unreadable), decoded like the parser
+fn read_source(source: &SourceInput) -> Option<String> {
+    match &source.content {
+        Some(content) => Some(content.clone()),
+        None => source_text::read(&source.path)
+            .ok()
+            .map(|source| source.text),
+    }
+}
+
+/// The `<bean>` element of `name` (with nested children) and the `<table>` elements it is
+/// the value of, from a rendered XML file. None unless the file is `module`'s
+fn xml_fragment(content: &str, module: &str, name: &str) -> Option<String> {
+    if !content.contains(&format!(r#"<module name="{}""#, module)) {
+        return None;
+    }
+    let lines: Vec<&str> = content.lines().collect();
+    let bean_open = format!(r#"<bean name="{}""#, name);
+    let start = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with(&bean_open))?;
+    let indent = &lines[start][..lines[start].len() - lines[start].trim_start().len()];
+    let end = if lines[start].trim_end().ends_with("/>") {
+        start
+    } else {
+        let close = format!("{}</bean>", indent);
+        start
+            + lines[start..]
+                .iter()
+                .position(|line| line.trim_end() == close)?
+    };
+    let table_value = format!(r#" value="{}""#, name);
+    let mut fragment = lines[start..=end].to_vec();
+    fragment.extend(
+        lines
+            .iter()
+            .filter(|line| line.trim_start().starts_with("<table ") && line.contains(&table_value)),
+    );
+    Some(fragment.join("\n"))
+}
+
+/// Print cache hit rate, total parse time and the 10 slowest files
+fn print_stats(report: &RunReport) {
+    let checked = report.cached + report.formatting_only + report.updated;
+    let hit_rate = if checked == 0 {
+        0.0
+    } else {
+        report.cached as f64 * 100.0 / checked as f64
+    };
+    let parse_ms: f64 = report.file_stats.iter().map(|f| f.parse_ms).sum();
+
+    println!("\nStats:");
+    println!(
+        "  Cache hit rate: {:.1}% ({}/{} beans/enums)",
+        hit_rate, report.cached, checked
+    );
+    println!(
+        "  Parse time: {:.1}ms across {} file(s)",
+        parse_ms,
+        report.file_stats.len()
+    );
+
+    let mut slowest: Vec<&FileStats> = report.file_stats.iter().collect();
+    slowest.sort_by(|a, b| b.parse_ms.total_cmp(&a.parse_ms));
+    if !slowest.is_empty() {
+        println!("  Slowest files:");
+    }
+    for file in slowest.iter().take(10) {
+        println!(
+            "    {:>8.1}ms  {} ({} classes{}{})",
+            file.parse_ms,
+            file.path.display(),
+            file.classes,
+            if file.cached { ", cached" } else { "" },
+            if file.over_budget {
+                ", over budget"
+            } else {
+                ""
+            }
+        );
+    }
+}
+
+/// Drop the nullable marker the parser puts on list elements and map values
+/// (`list,double?` -> `list,double`). Returns one warning per affected field
+fn strip_nullable_elements(classes: &mut [ClassInfo]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for class in classes.iter_mut() {
+        for i in 0..class.fields.len() {
+            let field = &class.fields[i];
+            let Some(stripped) = field.field_type.strip_suffix('?') else {
+                continue;
+            };
+            if !validator::is_container_type(stripped) {
+                continue;
+            }
+            let stripped = stripped.to_string();
+            warnings.push(format!(
+                "{}: {}.{} - nullable elements are emitted as required (type {}); set defaults.nullable_elements = true",
+                class.field_location(field), class.name, field.name, field.field_type
+            ));
+            let field = &mut class.fields[i];
+            field.field_type = stripped;
+            if let Some(original) = field.original_type.strip_suffix('?') {
+                field.original_type = original.to_string();
+            }
+        }
+    }
+    warnings
+}
+
+/// Remove the classes and fields `flavor` excludes (@OnlyIn / @ExcludeFrom) and the [tables]
+/// entries of removed classes. Returns what was omitted (`module.Name` / `module.Name.field`).
+/// Fails when something kept still needs a removed element: a table index or secondary
+/// index, an @Index key, a parent or a field type (including @Ref targets)
+fn apply_flavor(
+    classes: &mut Vec<ClassInfo>,
+    tables: &mut HashMap<String, TableConfig>,
+    flavor: &str,
+    default_module: &str,
+) -> Result<Vec<String>> {
+    let qualified = |class: &ClassInfo| changelog::bean_key(class, default_module);
+    let is_named = |field: &FieldInfo, name: &str| {
+        field.name == name || field.xml_name.as_deref() == Some(name)
+    };
+
+    let mut omitted = Vec::new();
+    let mut omitted_classes = HashSet::new();
+    for class in classes.iter().filter(|c| !c.flavors.includes(flavor)) {
+        omitted.push(qualified(class));
+        omitted_classes.insert(class.name.clone());
+    }
+    classes.retain(|c| c.flavors.includes(flavor));
+    tables.retain(|key, _| !omitted.contains(key));
+
+    // Removed fields by class name
+    let mut omitted_fields: HashMap<String, Vec<FieldInfo>> = HashMap::new();
+    for class in classes.iter_mut() {
+        let (kept, removed): (Vec<_>, Vec<_>) = std::mem::take(&mut class.fields)
+            .into_iter()
+            .partition(|f| f.flavors.includes(flavor));
+        class.fields = kept;
+        for field in &removed {
+            omitted.push(format!("{}.{}", qualified(class), field.name));
+        }
+        if !removed.is_empty() {
+            omitted_fields.insert(class.name.clone(), removed);
+        }
+    }
+
+    let mut errors = Vec::new();
+    for class in classes.iter() {
+        let table = tables.get(&qualified(class));
+        if let (Some(table), Some(removed)) = (table, omitted_fields.get(&class.name)) {
+            let index = match table {
+                TableConfig::Full {
+                    index: Some(index), ..
+                } => index.as_str(),
+                _ => class.ref_key.as_deref().unwrap_or("id"),
+            };
+            let indexes =
+                std::iter::once(index).chain(table.secondary_indexes().iter().map(String::as_str));
+            for index in indexes {
+                for field in removed.iter().filter(|f| is_named(f, index)) {
+                    errors.push(format!(
+                        "{}: {}.{} - is a table index but is excluded from flavor '{}'",
+                        class.field_location(field),
+                        class.name,
+                        field.name,
+                        flavor
+                    ));
+                }
+            }
+        }
+
+        let parents = class
+            .extends
+            .iter()
+            .chain(&class.explicit_parent)
+            .chain(&class.implements);
+        for parent in parents {
+            if validator::type_names(parent).any(|name| omitted_classes.contains(name)) {
+                errors.push(format!(
+                    "{}: {} - parent '{}' is excluded from flavor '{}'",
+                    class.source_file, class.name, parent, flavor
+                ));
+            }
+        }
+
+        for field in &class.fields {
+            for name in validator::type_names(&field.field_type) {
+                if omitted_classes.contains(name) {
+                    errors.push(format!(
+                        "{}: {}.{} - type '{}' is excluded from flavor '{}'",
+                        class.field_location(field),
+                        class.name,
+                        field.name,
+                        name,
+                        flavor
+                    ));
+                }
+                let Some(index_field) = &field.validators.index_field else {
+                    continue;
+                };
+                let removed = omitted_fields.get(name);
+                if removed.is_some_and(|removed| removed.iter().any(|f| is_named(f, index_field))) {
+                    errors.push(format!(
+                        "{}: {}.{} - @Index field '{}' of {} is excluded from flavor '{}'",
+                        class.field_location(field),
+                        class.name,
+                        field.name,
+                        index_field,
+                        name,
+                        flavor
+                    ));
+                }
+            }
+            let validators = &field.validators;
+            for target in validators
+                .ref_target
+                .iter()
+                .chain(&validators.ref_key_target)
+            {
+                if omitted_classes.contains(target) {
+                    errors.push(format!(
+                        "{}: {}.{} - @Ref target '{}' is excluded from flavor '{}'",
+                        class.field_location(field),
+                        class.name,
+                        field.name,
+                        target,
+                        flavor
+                    ));
+                }
+            }
+        }
+    }
+    if !errors.is_empty() {
+        errors.dedup();
+        anyhow::bail!(GenError::Validation(format!(
+            "Flavor '{}' excludes elements that are still used:\n  {}",
+            flavor,
+            errors.join("\n  ")
+        )));
+    }
+    Ok(omitted)
+}
+
+/// Mark classes whose name matches a [beans] exclude pattern as `no_runtime`.
+/// Fails on invalid patterns
+fn exclude_from_beans(classes: &mut [ClassInfo], patterns: &[String]) -> Result<()> {
+    let compiled = patterns
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern)
+                .with_context(|| format!("Invalid pattern {:?} in [beans] exclude", pattern))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    for class in classes.iter_mut() {
+        if compiled.iter().any(|regex| regex.is_match(&class.name)) {
+            class.no_runtime = true;
+        }
+    }
+    Ok(())
+}
+
+/// Set `output_route` from the first [[output_routes]] entry whose pattern matches the class
+/// name, unless @Output already chose one. Fails on invalid patterns, duplicate route names
+/// and @Output names without a matching route
+fn assign_output_routes(classes: &mut [ClassInfo], routes: &[OutputRoute]) -> Result<()> {
+    let mut names = HashSet::new();
+    let mut compiled = Vec::new();
+    for route in routes {
+        if !names.insert(route.name.as_str()) {
+            anyhow::bail!("Duplicate output route '{}'", route.name);
+        }
+        for pattern in &route.patterns {
+            let regex = Regex::new(pattern).with_context(|| {
+                format!(
+                    "Invalid pattern {:?} in output route '{}'",
+                    pattern, route.name
+                )
+            })?;
+            compiled.push((regex, route.name.as_str()));
+        }
+    }
+
+    let mut errors = Vec::new();
+    for class in classes.iter_mut() {
+        match &class.output_route {
+            Some(route) if !names.contains(route.as_str()) => errors.push(format!(
+                "{}: {} - @Output(\"{}\") has no matching [[output_routes]] entry",
+                class.source_file, class.name, route
+            )),
+            Some(_) => {}
+            None => {
+                class.output_route = compiled
+                    .iter()
+                    .find(|(regex, _)| regex.is_match(&class.name))
+                    .map(|(_, name)| name.to_string());
+            }
+        }
+    }
+    if !errors.is_empty() {
+        anyhow::bail!(GenError::Validation(format!(
+            "Unknown output routes:\n  {}",
+            errors.join("\n  ")
+        )));
+    }
+    Ok(())
+}
@@ -4,10 +4,22 @@ use glob::glob;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
-#[derive(Default)]
+/// Build output and VCS directories skipped unless `skip_common_dirs` is off
+pub const COMMON_SKIPPED_DIRS: &[&str] = &["dist", "out", ".git"];
+
 pub struct ScanConfig {
     pub include_dts: bool,
     pub include_node_modules: bool,
+    /// Directory levels below the scanned directory to descend into (0 = only its own files)
+    pub max_depth: Option<usize>,
+    pub skip_hidden: bool,
+    pub skip_common_dirs: bool,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self::from(&ScanOptions::default())
+    }
 }
 
 impl From<&ScanOptions> for ScanConfig {
@@ -15,34 +27,27 @@ impl From<&ScanOptions> for ScanConfig {
         Self {
             include_dts: opts.include_dts,
             include_node_modules: opts.include_node_modules,
+            max_depth: opts.max_depth,
+            skip_hidden: opts.skip_hidden,
+            skip_common_dirs: opts.skip_common_dirs,
         }
     }
 }
 
-#[allow(dead_code)]
-pub fn scan_directory(dir: &Path) -> Result<Vec<PathBuf>> {
-    scan_directory_with_options(dir, &ScanConfig::default())
-}
-
-pub fn scan_directory_with_options(dir: &Path, config: &ScanConfig) -> Result<Vec<PathBuf>> {
-    let mut files = Vec::new();
-
-    for entry in WalkDir::new(dir)
-        .follow_links(true)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
-
-        if !path.is_file() {
-            continue;
-        }
+impl ScanConfig {
+    /// Whether a directory below the scan root should be pruned
+    fn skips_dir(&self, name: &str) -> bool {
+        (self.skip_hidden && name.starts_with('.'))
+            || (self.skip_common_dirs && COMMON_SKIPPED_DIRS.contains(&name))
+    }
 
+    /// Whether a file the directory walk reached is a source
+    fn accepts_file(&self, path: &Path) -> bool {
         let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
 
         // Include .ts and .tsx files
         if !file_name.ends_with(".ts") && !file_name.ends_with(".tsx") {
-            continue;
+            return false;
         }
 
         // Exclude test files
@@ -51,22 +56,70 @@ pub fn scan_directory_with_options(dir: &Path, config: &ScanConfig) -> Result<Ve
             || file_name.ends_with(".spec.tsx")
             || file_name.ends_with(".test.tsx")
         {
-            continue;
+            return false;
         }
 
         // Exclude declaration files unless configured
-        if !config.include_dts && file_name.ends_with(".d.ts") {
-            continue;
+        if !self.include_dts && file_name.ends_with(".d.ts") {
+            return false;
         }
 
         // Exclude node_modules unless configured
-        if !config.include_node_modules
-            && path.components().any(|c| c.as_os_str() == "node_modules")
+        self.include_node_modules || !path.components().any(|c| c.as_os_str() == "node_modules")
+    }
+
+    /// Whether scanning `dir` would return `path`, without walking the directory (the file
+    /// need not exist). Both paths should be normalized the same way
+    pub fn accepts(&self, dir: &Path, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(dir) else {
+            return false;
+        };
+        let parts: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let Some((_, dirs)) = parts.split_last() else {
+            return false;
+        };
+        if self
+            .max_depth
+            .is_some_and(|max_depth| dirs.len() > max_depth)
         {
-            continue;
+            return false;
         }
+        !dirs.iter().any(|d| d == ".." || self.skips_dir(d)) && self.accepts_file(path)
+    }
+}
+
+#[allow(dead_code)]
+pub fn scan_directory(dir: &Path) -> Result<Vec<PathBuf>> {
+    scan_directory_with_options(dir, &ScanConfig::default())
+}
+
+pub fn scan_directory_with_options(dir: &Path, config: &ScanConfig) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    // Sorted so the scan order (and with it the output order) doesn't depend on the filesystem
+    let mut walker = WalkDir::new(dir).follow_links(true).sort_by_file_name();
+    if let Some(max_depth) = config.max_depth {
+        // Files directly inside `dir` are at walkdir depth 1
+        walker = walker.max_depth(max_depth + 1);
+    }
+
+    for entry in walker
+        .into_iter()
+        .filter_entry(|e| {
+            e.depth() == 0
+                || !e.file_type().is_dir()
+                || !config.skips_dir(&e.file_name().to_string_lossy())
+        })
+        .filter_map(|e| e.ok())
+    {
+        let path = entry.path();
 
-        files.push(path.to_path_buf());
+        if path.is_file() && config.accepts_file(path) {
+            files.push(path.to_path_buf());
+        }
     }
 
     Ok(files)
@@ -122,6 +175,20 @@ mod tests {
         assert_eq!(files.len(), 2);
     }
 
+    #[test]
+    fn test_include_dts() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.ts"), "export class A {}").unwrap();
+        fs::write(dir.path().join("c.d.ts"), "export declare class C {}").unwrap();
+
+        let config = ScanConfig {
+            include_dts: true,
+            ..Default::default()
+        };
+        let files = scan_directory_with_options(dir.path(), &config).unwrap();
+        assert_eq!(files.len(), 2);
+    }
+
     #[test]
     fn test_exclude_test_files() {
         let dir = TempDir::new().unwrap();
@@ -159,6 +226,104 @@ mod tests {
         assert_eq!(files.len(), 1);
     }
 
+    #[test]
+    fn test_max_depth() {
+        let dir = TempDir::new().unwrap();
+        let nested = dir.path().join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+
+        fs::write(dir.path().join("root.ts"), "export class Root {}").unwrap();
+        fs::write(dir.path().join("a").join("one.ts"), "export class One {}").unwrap();
+        fs::write(nested.join("two.ts"), "export class Two {}").unwrap();
+
+        let scan = |max_depth| {
+            let config = ScanConfig {
+                max_depth,
+                ..Default::default()
+            };
+            scan_directory_with_options(dir.path(), &config)
+                .unwrap()
+                .len()
+        };
+        assert_eq!(scan(Some(0)), 1);
+        assert_eq!(scan(Some(1)), 2);
+        assert_eq!(scan(None), 3);
+    }
+
+    #[test]
+    fn test_accepts() {
+        let dir = Path::new("/p/src");
+        let config = ScanConfig {
+            max_depth: Some(1),
+            skip_hidden: true,
+            ..Default::default()
+        };
+        assert!(config.accepts(dir, Path::new("/p/src/item.ts")));
+        assert!(config.accepts(dir, Path::new("/p/src/role/role.tsx")));
+        assert!(!config.accepts(dir, Path::new("/p/src/a/b/deep.ts")));
+        assert!(!config.accepts(dir, Path::new("/p/src/item.test.ts")));
+        assert!(!config.accepts(dir, Path::new("/p/src/types.d.ts")));
+        assert!(!config.accepts(dir, Path::new("/p/src/dist/item.ts")));
+        assert!(!config.accepts(dir, Path::new("/p/src/.cache/item.ts")));
+        assert!(!config.accepts(dir, Path::new("/p/src/readme.md")));
+        assert!(!config.accepts(dir, Path::new("/p/other/item.ts")));
+        assert!(!config.accepts(dir, Path::new("/p/src")));
+        assert!(ScanConfig::default().accepts(dir, Path::new("/p/src/.cache/item.ts")));
+    }
+
+    #[test]
+    fn test_skip_hidden_dirs() {
+        let dir = TempDir::new().unwrap();
+        let hidden = dir.path().join(".turbo");
+        fs::create_dir(&hidden).unwrap();
+
+        fs::write(dir.path().join("a.ts"), "export class A {}").unwrap();
+        fs::write(dir.path().join(".b.ts"), "export class B {}").unwrap();
+        fs::write(hidden.join("c.ts"), "export class C {}").unwrap();
+
+        // Hidden directories are scanned by default; hidden files are never filtered
+        assert_eq!(scan_directory(dir.path()).unwrap().len(), 3);
+
+        let config = ScanConfig {
+            skip_hidden: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            scan_directory_with_options(dir.path(), &config)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // A hidden source directory itself is still scanned
+        let files = scan_directory_with_options(&hidden, &config).unwrap();
+        assert_eq!(files.len(), 1);
+    }
+
+    #[test]
+    fn test_skip_common_dirs() {
+        let dir = TempDir::new().unwrap();
+        for name in ["dist", "out", ".git"] {
+            let sub = dir.path().join(name);
+            fs::create_dir(&sub).unwrap();
+            fs::write(sub.join("a.ts"), "export class A {}").unwrap();
+        }
+        fs::write(dir.path().join("main.ts"), "export class Main {}").unwrap();
+
+        assert_eq!(scan_directory(dir.path()).unwrap().len(), 1);
+
+        let config = ScanConfig {
+            skip_common_dirs: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            scan_directory_with_options(dir.path(), &config)
+                .unwrap()
+                .len(),
+            4
+        );
+    }
+
     #[test]
     fn test_expand_glob_pattern() {
         let dir = TempDir::new().unwrap();
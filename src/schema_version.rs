@@ -0,0 +1,258 @@
+//! Structural version hash of each bean, for runtime schema checks (save data migration).
+//!
+//! The hash covers only what changes the shape of the data, so comments, JSDoc, formatting
+//! and other edits that change the source file hash leave it alone. It is the MD5 hex
+//! digest of this text, one item per line:
+//!
+//! ```text
+//! parent:{parent bean, empty when none}
+//! {schema field name}:{Luban type}
+//! ...
+//! ```
+//!
+//! Fields are listed in Luban column order (declaration order, the order of the XML
+//! `<var>`s). The Luban type is the one written to the XML (an `@type` override, else the
+//! mapped TypeScript type) with `?` for optional fields. Fields inherited from the parent
+//! are not part of the hash; a parent's changes show up in the parent's own hash.
+//!
+//! `structure_hash` and `enum_hash` cover a little more (aliases, tags and validators,
+//! which the generated XML carries) and let the cache tell schema changes apart from
+//! comment and formatting edits to the same file.
+
+use crate::generator::bean_parent;
+use crate::parser::{ClassInfo, EnumInfo, FieldInfo};
+use crate::ts_generator::BeanKeyFormat;
+use crate::type_mapper::TypeMapper;
+use std::collections::BTreeMap;
+
+/// Luban type of a field as the schema sees it: `?` marks optional and nullable fields
+pub fn field_luban_type(field: &FieldInfo, type_mapper: &TypeMapper) -> String {
+    let mut luban_type = match &field.type_override {
+        Some(type_override) => type_override.clone(),
+        None => type_mapper.map_full_type(&field.field_type),
+    };
+    if field.may_be_empty() && !luban_type.ends_with('?') {
+        luban_type.push('?');
+    }
+    luban_type
+}
+
+/// Structural hash of one bean (see the module docs)
+pub fn bean_hash(class: &ClassInfo, type_mapper: &TypeMapper) -> String {
+    md5_hex(&bean_text(class, type_mapper))
+}
+
+/// Hash of a bean's schema as the cache compares it: `bean_hash`'s text plus the bean's
+/// alias and tags and each field's alias, tags, default and validators. Comments and
+/// source formatting are left out
+pub fn structure_hash(class: &ClassInfo, type_mapper: &TypeMapper) -> String {
+    let mut text = bean_text(class, type_mapper);
+    text.push_str(&format!("alias:{:?}\ntags:{:?}\n", class.alias, class.tags));
+    for field in class.ordered_fields() {
+        text.push_str(&format!(
+            "{}#{:?}#{:?}#{:?}#{:?}#{}\n",
+            field.schema_name(),
+            field.alias,
+            field.tags,
+            field.custom_tags,
+            field.default_value,
+            serde_json::to_string(&field.validators).unwrap_or_default()
+        ));
+    }
+    md5_hex(&text)
+}
+
+/// Hash of an enum's schema: alias, flags, tags and each variant's name, alias and value
+pub fn enum_hash(enum_info: &EnumInfo) -> String {
+    let mut text = format!(
+        "alias:{:?}\nflags:{}\ntags:{:?}\n",
+        enum_info.alias, enum_info.is_flags, enum_info.tags
+    );
+    for variant in &enum_info.variants {
+        text.push_str(&format!(
+            "{}:{:?}:{}\n",
+            variant.name, variant.alias, variant.value
+        ));
+    }
+    md5_hex(&text)
+}
+
+/// `parent:` line and one `name:type` line per field
+fn bean_text(class: &ClassInfo, type_mapper: &TypeMapper) -> String {
+    let mut text = format!("parent:{}\n", bean_parent(class));
+    for field in class.ordered_fields() {
+        text.push_str(&format!(
+            "{}:{}\n",
+            field.schema_name(),
+            field_luban_type(field, type_mapper)
+        ));
+    }
+    text
+}
+
+fn md5_hex(text: &str) -> String {
+    use md5::{Digest, Md5};
+
+    let mut hasher = Md5::new();
+    hasher.update(text.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Hash of every bean keyed by its Beans key. Classes without a module use `default_module`
+pub fn schema_versions(
+    classes: &[&ClassInfo],
+    type_mapper: &TypeMapper,
+    key_format: &BeanKeyFormat,
+    default_module: &str,
+) -> BTreeMap<String, String> {
+    classes
+        .iter()
+        .map(|class| {
+            let module = class.module_name.as_deref().unwrap_or(default_module);
+            (
+                key_format.format(module, &class.name),
+                bean_hash(class, type_mapper),
+            )
+        })
+        .collect()
+}
+
+/// Generate schemaVersions.ts (output.schema_versions_path)
+pub fn generate_schema_versions_ts(versions: &BTreeMap<String, String>) -> String {
+    let mut lines = vec![
+        "/** Structural hash of each bean (fields, Luban types, order, parent), by Beans key */"
+            .to_string(),
+        "export const SchemaVersions = {".to_string(),
+    ];
+    for (key, hash) in versions {
+        lines.push(format!("    \"{}\": \"{}\",", key, hash));
+    }
+    lines.push("} as const;".to_string());
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TsParser;
+    use std::collections::HashMap;
+
+    fn hash_of(source: &str) -> String {
+        let classes = TsParser::new().parse_source("role.ts", source).unwrap();
+        bean_hash(&classes[0], &TypeMapper::new(&HashMap::new()))
+    }
+
+    const ROLE: &str = r#"
+/** Role config */
+export class RoleConfig extends BaseConfig {
+    /** Role id */
+    id: number;
+    name: string;
+    hp?: number;
+}
+"#;
+
+    #[test]
+    fn test_comment_edits_keep_the_hash() {
+        let edited = r#"
+/**
+ * Role configuration, loaded at startup.
+ */
+export class RoleConfig extends BaseConfig {
+    // The unique id
+    id: number;
+
+    /** Display name */
+    name: string;
+    hp?: number; // optional
+}
+"#;
+        assert_eq!(hash_of(ROLE), hash_of(edited));
+        assert_eq!(hash_of(ROLE).len(), 32);
+    }
+
+    #[test]
+    fn test_structural_changes_change_the_hash() {
+        let base = hash_of(ROLE);
+        let changes = [
+            // Type change
+            ROLE.replace("name: string", "name: number"),
+            // Optional → required
+            ROLE.replace("hp?: number", "hp: number"),
+            // Field order
+            ROLE.replace(
+                "    id: number;\n    name: string;",
+                "    name: string;\n    id: number;",
+            ),
+            // Rename
+            ROLE.replace("hp?:", "health?:"),
+            // Parent
+            ROLE.replace(" extends BaseConfig", ""),
+        ];
+        for changed in &changes {
+            assert_ne!(hash_of(changed), base, "{}", changed);
+        }
+    }
+
+    #[test]
+    fn test_structure_hash() {
+        let type_mapper = TypeMapper::new(&HashMap::new());
+        let structure = |source: &str| {
+            let classes = TsParser::new().parse_source("role.ts", source).unwrap();
+            structure_hash(&classes[0], &type_mapper)
+        };
+        let base = structure(ROLE);
+        assert_eq!(
+            structure(&ROLE.replace("/** Role id */", "/** Unique role id */")),
+            base
+        );
+        assert_eq!(structure(&ROLE.replace("    hp?", "\n\n    hp?")), base);
+
+        // Validators and aliases reach the XML but leave the data shape alone
+        let ranged = ROLE.replace("    hp?", "    @Range(1, 100)\n    hp?");
+        assert_ne!(structure(&ranged), base);
+        assert_eq!(hash_of(&ranged), hash_of(ROLE));
+        assert_ne!(
+            structure(&ROLE.replace("/** Role config */", "/** @alias=\"角色\" */")),
+            base
+        );
+        assert_ne!(
+            structure(&ROLE.replace("name: string", "name: number")),
+            base
+        );
+
+        let enums = |source: &str| {
+            let enums = TsParser::new().parse_enums_source("q.ts", source).unwrap();
+            enum_hash(&enums[0])
+        };
+        let quality = "/** Quality */\nexport enum Quality {\n    Common = 1,\n    Rare = 2,\n}\n";
+        assert_eq!(
+            enums(quality),
+            enums(&quality.replace("/** Quality */", "// Item quality"))
+        );
+        assert_ne!(
+            enums(quality),
+            enums(&quality.replace("Rare = 2", "Rare = 3"))
+        );
+    }
+
+    #[test]
+    fn test_schema_versions_ts() {
+        let classes = TsParser::new().parse_source("role.ts", ROLE).unwrap();
+        let refs: Vec<&ClassInfo> = classes.iter().collect();
+        let versions = schema_versions(
+            &refs,
+            &TypeMapper::new(&HashMap::new()),
+            &BeanKeyFormat::default(),
+            "role",
+        );
+        let hash = &versions["role.RoleConfig"];
+        assert_eq!(
+            generate_schema_versions_ts(&versions),
+            format!(
+                "/** Structural hash of each bean (fields, Luban types, order, parent), by Beans key */\nexport const SchemaVersions = {{\n    \"role.RoleConfig\": \"{}\",\n}} as const;\n",
+                hash
+            )
+        );
+    }
+}
@@ -1,8 +1,23 @@
 use crate::config::TableConfig;
+use serde::Serialize;
 use std::collections::HashMap;
 
+/// Table modes Luban understands; others are rejected unless defaults.allow_custom_modes
+/// passes them through
+pub const TABLE_MODES: [&str; 4] = ["map", "list", "one", "singleton"];
+
+/// Error for a [tables] entry whose mode is not in `TABLE_MODES`
+pub fn unknown_mode_error(key: &str, mode: &str) -> String {
+    format!(
+        "\"{}\": unknown mode \"{}\" (expected {}; set defaults.allow_custom_modes = true to pass custom modes through)",
+        key,
+        mode,
+        TABLE_MODES.join(", ")
+    )
+}
+
 /// Resolved table configuration with all defaults applied
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ResolvedTableConfig {
     /// Full bean name with module prefix (e.g., "role.RoleConfig")
     pub bean: String,
@@ -14,14 +29,44 @@ pub struct ResolvedTableConfig {
     pub mode: String,
     /// Index field (e.g., "id")
     pub index: String,
+    /// Whether `index` was set in the [tables] entry (as opposed to the "id" default)
+    pub index_explicit: bool,
     /// Index field type (e.g., "int", "string") - used by @Ref/@RefKey
     pub index_type: Option<String>,
+    /// Lookup fields of a list table, resolved to schema names by `set_index_types`
+    pub secondary_indexes: Vec<String>,
+    /// Row transform module from the [tables] entry, as written in the config
+    pub transform: Option<String>,
+    /// One/singleton tables: whether the getter throws on an empty table (`required = false`
+    /// makes it return undefined)
+    pub required: bool,
+    /// Luban groups of the table, mapped through [groups] by `resolve_groups`; empty means
+    /// every group
+    pub groups: Vec<String>,
     /// Module name (e.g., "role")
     pub module: String,
     /// Class name without module prefix (e.g., "RoleConfig")
     pub class_name: String,
 }
 
+impl ResolvedTableConfig {
+    /// Whether the mode is outside `TABLE_MODES` (only kept with defaults.allow_custom_modes)
+    pub fn has_custom_mode(&self) -> bool {
+        !TABLE_MODES.contains(&self.mode.as_str())
+    }
+
+    /// Whether the table belongs to any of these Luban groups (tables without groups belong
+    /// to all of them)
+    pub fn in_groups(&self, selected: &[String]) -> bool {
+        self.groups.is_empty() || self.groups.iter().any(|group| selected.contains(group))
+    }
+}
+
+/// Luban group of a table group name: its [groups] entry, or the name itself when unmapped
+pub fn luban_group<'a>(group: &'a str, groups: &'a HashMap<String, String>) -> &'a str {
+    groups.get(group).map(String::as_str).unwrap_or(group)
+}
+
 #[derive(Debug, Clone)]
 pub struct TableEntry {
     pub namespace: String,
@@ -83,7 +128,12 @@ impl TableRegistry {
                 input: config.input().to_string(),
                 mode: config.mode().to_string(),
                 index: config.index().to_string(),
+                index_explicit: matches!(config, TableConfig::Full { index: Some(_), .. }),
                 index_type: None, // Will be set later by set_index_types
+                secondary_indexes: config.secondary_indexes().to_vec(),
+                transform: config.transform().map(str::to_string),
+                required: config.required(),
+                groups: config.groups().to_vec(),
                 module: module.clone(),
                 class_name: class_name.clone(),
             };
@@ -132,34 +182,130 @@ impl TableRegistry {
         );
     }
 
-    /// Set index types for all registered tables based on parsed class information
+    /// Tables whose [tables] `index` overrides a different @RefKey on their class, one
+    /// warning each, sorted by table. An index declared by @LubanTable is left to
+    /// `validator::reconcile_tables`, which reports the same drift
+    pub fn ignored_ref_keys(&self, classes: &[crate::parser::ClassInfo]) -> Vec<String> {
+        let mut tables: Vec<&ResolvedTableConfig> =
+            self.tables.values().filter(|t| t.index_explicit).collect();
+        tables.sort_by(|a, b| a.bean.cmp(&b.bean));
+        tables
+            .into_iter()
+            .filter_map(|table| {
+                let class = classes.iter().find(|c| c.name == table.class_name)?;
+                let ref_key = class.ref_key.as_ref().filter(|key| **key != table.index)?;
+                if class
+                    .luban_table
+                    .as_ref()
+                    .is_some_and(|declared| declared.index == *ref_key)
+                {
+                    return None;
+                }
+                Some(format!(
+                    "{}: {} - table '{}' uses index '{}' from [tables], ignoring @RefKey(\"{}\")",
+                    class.source_file, class.name, table.bean, table.index, ref_key
+                ))
+            })
+            .collect()
+    }
+
+    /// Resolve the index field of every registered table and record its type.
+    /// Index precedence: [tables] `index` > class @RefKey > default "id".
+    /// Returns one error per table whose declared index field is missing or not scalar,
+    /// and per invalid secondary index (missing, not scalar, or on a table that isn't a list).
     /// This should be called after parsing all TypeScript files
-    pub fn set_index_types(&mut self, classes: &[crate::parser::ClassInfo], type_mapper: &crate::type_mapper::TypeMapper) {
+    pub fn set_index_types(
+        &mut self,
+        classes: &[crate::parser::ClassInfo],
+        type_mapper: &crate::type_mapper::TypeMapper,
+    ) -> Vec<String> {
         use std::collections::HashMap as StdHashMap;
-        
-        // Build a map from class name to its fields
-        let class_fields: StdHashMap<&str, &[crate::parser::FieldInfo]> = classes
-            .iter()
-            .map(|c| (c.name.as_str(), c.fields.as_slice()))
-            .collect();
+
+        let mut errors = Vec::new();
+
+        // Build a map from class name to its info
+        let class_by_name: StdHashMap<&str, &crate::parser::ClassInfo> =
+            classes.iter().map(|c| (c.name.as_str(), c)).collect();
 
         // Update index_type for each table
-        for (_full_name, config) in &mut self.tables {
+        for (full_name, config) in &mut self.tables {
             let class_name = &config.class_name;
-            if let Some(fields) = class_fields.get(class_name.as_str()) {
-                // Find the index field
-                if let Some(field) = fields.iter().find(|f| f.name == config.index) {
-                    // Map the TypeScript type to Luban type
-                    let mapped_type = type_mapper.map_full_type(&field.field_type);
-                    config.index_type = Some(mapped_type.clone());
-                    
-                    // Also update the entry
-                    if let Some(entry) = self.entries.get_mut(class_name) {
-                        entry.index_type = Some(mapped_type);
-                    }
+            let Some(class) = class_by_name.get(class_name.as_str()) else {
+                continue;
+            };
+
+            // Apply @RefKey unless the config sets the index explicitly (see `ignored_ref_keys`)
+            if let Some(ref_key) = &class.ref_key {
+                if !config.index_explicit {
+                    config.index = ref_key.clone();
+                }
+            }
+            let declared = config.index_explicit || class.ref_key.is_some();
+
+            // Secondary indexes: scalar fields of list tables, stored by schema name
+            if !config.secondary_indexes.is_empty() && config.mode != "list" {
+                errors.push(format!(
+                    "{}: table '{}' sets secondary_indexes, but only mode = \"list\" tables support them (mode is '{}')",
+                    class.source_file, full_name, config.mode
+                ));
+            }
+            for secondary in &mut config.secondary_indexes {
+                let Some(field) = class.fields.iter().find(|f| {
+                    f.name == *secondary || f.xml_name.as_deref() == Some(secondary.as_str())
+                }) else {
+                    errors.push(format!(
+                        "{}: table '{}' secondary index '{}' does not exist on {}",
+                        class.source_file, full_name, secondary, class_name
+                    ));
+                    continue;
+                };
+                let mapped_type = type_mapper.map_full_type(&field.field_type);
+                if crate::validator::is_container_type(&mapped_type) {
+                    errors.push(format!(
+                        "{}: table '{}' secondary index '{}' must be a scalar, found {}",
+                        class.source_file, full_name, secondary, mapped_type
+                    ));
+                    continue;
+                }
+                *secondary = field.schema_name().to_string();
+            }
+
+            // Find the index field (by property name or @Name schema name)
+            let Some(field) = class.fields.iter().find(|f| {
+                f.name == config.index || f.xml_name.as_deref() == Some(config.index.as_str())
+            }) else {
+                // The implicit "id" default may legitimately be absent (e.g. one/singleton tables)
+                if declared {
+                    errors.push(format!(
+                        "{}: table '{}' index field '{}' does not exist on {}",
+                        class.source_file, full_name, config.index, class_name
+                    ));
                 }
+                continue;
+            };
+
+            // Map the TypeScript type to Luban type
+            let mapped_type = type_mapper.map_full_type(&field.field_type);
+            if crate::validator::is_container_type(&mapped_type) {
+                errors.push(format!(
+                    "{}: table '{}' index field '{}' must be a scalar, found {}",
+                    class.source_file, full_name, config.index, mapped_type
+                ));
+                continue;
+            }
+
+            // Luban indexes by column name, so use the renamed schema name
+            config.index = field.schema_name().to_string();
+            config.index_type = Some(mapped_type.clone());
+
+            // Also update the entry
+            if let Some(entry) = self.entries.get_mut(class_name) {
+                entry.index_type = Some(mapped_type);
             }
         }
+
+        errors.sort();
+        errors
     }
 
     /// Get index type for a class (for @Ref resolution)
@@ -167,6 +313,16 @@ impl TableRegistry {
         self.entries.get(class_name).and_then(|e| e.index_type.as_deref())
     }
 
+    /// Map every table's groups to Luban groups through [groups], so the XML `group`
+    /// attribute and the `--group` selection of TypeScript outputs compare the same names
+    pub fn resolve_groups(&mut self, groups: &HashMap<String, String>) {
+        for table in self.tables.values_mut() {
+            for group in &mut table.groups {
+                *group = luban_group(group, groups).to_string();
+            }
+        }
+    }
+
     /// Get table entry by class name (for @Ref resolution)
     pub fn get(&self, class_name: &str) -> Option<&TableEntry> {
         self.entries.get(class_name)
@@ -263,6 +419,10 @@ mod tests {
                 name: Some("TbBattle".to_string()),
                 mode: Some("one".to_string()),
                 index: Some("battleId".to_string()),
+                secondary_indexes: vec![],
+                transform: None,
+                required: None,
+                groups: vec![],
             },
         );
 
@@ -278,6 +438,40 @@ mod tests {
         assert_eq!(ref_target, "battle.TbBattle");
     }
 
+    #[test]
+    fn test_resolve_groups() {
+        let table = |groups: &[&str]| TableConfig::Full {
+            input: "../datas/item".to_string(),
+            name: None,
+            mode: None,
+            index: None,
+            secondary_indexes: vec![],
+            transform: None,
+            required: None,
+            groups: groups.iter().map(|g| g.to_string()).collect(),
+        };
+        let mut config = HashMap::new();
+        config.insert("item.Item".to_string(), table(&["client", "server"]));
+        config.insert("item.Drop".to_string(), table(&["server", "e"]));
+        config.insert("item.Shop".to_string(), table(&[]));
+        let mut registry = TableRegistry::from_config(&config);
+        let groups: HashMap<String, String> = [("client", "c"), ("server", "s")]
+            .into_iter()
+            .map(|(name, letter)| (name.to_string(), letter.to_string()))
+            .collect();
+        registry.resolve_groups(&groups);
+
+        let item = registry.get_table("item.Item").unwrap();
+        assert_eq!(item.groups, vec!["c", "s"]);
+        let drop = registry.get_table("item.Drop").unwrap();
+        assert_eq!(drop.groups, vec!["s", "e"]);
+
+        let client = vec!["c".to_string()];
+        assert!(item.in_groups(&client));
+        assert!(!drop.in_groups(&client));
+        assert!(registry.get_table("item.Shop").unwrap().in_groups(&client));
+    }
+
     #[test]
     fn test_from_config_no_module() {
         let mut config = HashMap::new();
@@ -423,4 +617,198 @@ mod tests {
         assert_eq!(missing.len(), 1);
         assert!(missing.contains(&"GlobalConfig"));
     }
+
+    fn make_class(name: &str, ref_key: Option<&str>, fields: &[(&str, &str)]) -> crate::parser::ClassInfo {
+        crate::parser::ClassInfo {
+            name: name.to_string(),
+            comment: None,
+            alias: None,
+            fields: fields
+                .iter()
+                .map(|(field_name, field_type)| crate::parser::FieldInfo {
+                    name: field_name.to_string(),
+                    field_type: field_type.to_string(),
+                    original_type: field_type.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            implements: vec![],
+            extends: None,
+            source_file: "item.ts".to_string(),
+            source_path: Default::default(),
+            file_hash: String::new(),
+            is_interface: false,
+            output_path: None,
+            module_name: None,
+            type_params: HashMap::new(),
+            luban_table: None,
+            table_config: None,
+            input_path: None,
+            imports: HashMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: ref_key.map(|s| s.to_string()),
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_ref_key_sets_index_when_config_omits_it() {
+        let mut config = HashMap::new();
+        config.insert(
+            "item.Item".to_string(),
+            TableConfig::Simple("../datas/item".to_string()),
+        );
+        let mut registry = TableRegistry::from_config(&config);
+
+        let class = make_class("Item", Some("itemId"), &[("itemId", "string"), ("count", "number")]);
+        let type_mapper = crate::type_mapper::TypeMapper::new(&HashMap::new());
+        let errors = registry.set_index_types(&[class], &type_mapper);
+
+        assert!(errors.is_empty());
+        let table = registry.get_table("item.Item").unwrap();
+        assert_eq!(table.index, "itemId");
+        assert_eq!(table.index_type.as_deref(), Some("string"));
+        assert_eq!(registry.get_index_type("Item"), Some("string"));
+    }
+
+    #[test]
+    fn test_config_index_overrides_ref_key() {
+        let mut config = HashMap::new();
+        config.insert(
+            "item.Item".to_string(),
+            TableConfig::Full {
+                input: "../datas/item".to_string(),
+                name: None,
+                mode: None,
+                index: Some("id".to_string()),
+                secondary_indexes: vec![],
+                transform: None,
+                required: None,
+                groups: vec![],
+            },
+        );
+        let mut registry = TableRegistry::from_config(&config);
+
+        let class = make_class(
+            "Item",
+            Some("itemId"),
+            &[("id", "int"), ("itemId", "string")],
+        );
+        let type_mapper = crate::type_mapper::TypeMapper::new(&HashMap::new());
+        let errors = registry.set_index_types(std::slice::from_ref(&class), &type_mapper);
+
+        assert!(errors.is_empty());
+        let table = registry.get_table("item.Item").unwrap();
+        assert_eq!(table.index, "id");
+        assert_eq!(table.index_type.as_deref(), Some("int"));
+        assert_eq!(
+            registry.ignored_ref_keys(std::slice::from_ref(&class)),
+            vec![
+                "item.ts: Item - table 'item.Item' uses index 'id' from [tables], ignoring @RefKey(\"itemId\")"
+            ]
+        );
+
+        // An @LubanTable index is reported by validator::reconcile_tables instead
+        let class = crate::parser::ClassInfo {
+            luban_table: Some(crate::parser::LubanTableConfig {
+                mode: "map".to_string(),
+                index: "itemId".to_string(),
+                ..Default::default()
+            }),
+            ..class
+        };
+        assert!(registry.ignored_ref_keys(&[class]).is_empty());
+    }
+
+    #[test]
+    fn test_ref_key_invalid_field_errors() {
+        let mut config = HashMap::new();
+        config.insert(
+            "item.Item".to_string(),
+            TableConfig::Simple("../datas/item".to_string()),
+        );
+        config.insert(
+            "item.Bag".to_string(),
+            TableConfig::Simple("../datas/bag".to_string()),
+        );
+        let mut registry = TableRegistry::from_config(&config);
+
+        let item = make_class("Item", Some("missing"), &[("id", "int")]);
+        let bag = make_class("Bag", Some("slots"), &[("slots", "list,number")]);
+        let type_mapper = crate::type_mapper::TypeMapper::new(&HashMap::new());
+        let errors = registry.set_index_types(&[item, bag], &type_mapper);
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("'item.Bag' index field 'slots' must be a scalar"));
+        assert!(errors[1].contains("'item.Item' index field 'missing' does not exist"));
+    }
+
+    #[test]
+    fn test_secondary_index_errors() {
+        let table = |mode: &str, secondary_indexes: &[&str]| TableConfig::Full {
+            input: "../datas/skill".to_string(),
+            name: None,
+            mode: Some(mode.to_string()),
+            index: None,
+            secondary_indexes: secondary_indexes.iter().map(|s| s.to_string()).collect(),
+            transform: None,
+            required: None,
+            groups: vec![],
+        };
+        let mut config = HashMap::new();
+        config.insert(
+            "skill.SkillBinding".to_string(),
+            table("list", &["skillId", "missing", "tags"]),
+        );
+        config.insert("skill.SkillConfig".to_string(), table("map", &["ownerId"]));
+        let mut registry = TableRegistry::from_config(&config);
+
+        let mut binding = make_class(
+            "SkillBinding",
+            None,
+            &[("skillId", "int"), ("tags", "list,string")],
+        );
+        binding.fields[0].xml_name = Some("skill_id".to_string());
+        let skill = make_class("SkillConfig", None, &[("id", "int"), ("ownerId", "int")]);
+        let type_mapper = crate::type_mapper::TypeMapper::new(&HashMap::new());
+        let errors = registry.set_index_types(&[binding, skill], &type_mapper);
+
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].contains("'skill.SkillBinding' secondary index 'missing' does not exist"));
+        assert!(errors[1].contains("'skill.SkillBinding' secondary index 'tags' must be a scalar"));
+        assert!(errors[2]
+            .contains("'skill.SkillConfig' sets secondary_indexes, but only mode = \"list\""));
+
+        let binding = registry.get_table("skill.SkillBinding").unwrap();
+        assert_eq!(
+            binding.secondary_indexes,
+            vec!["skill_id", "missing", "tags"]
+        );
+    }
+
+    #[test]
+    fn test_missing_default_id_is_not_an_error() {
+        let mut config = HashMap::new();
+        config.insert(
+            "GlobalConfig".to_string(),
+            TableConfig::Simple("../datas/global".to_string()),
+        );
+        let mut registry = TableRegistry::from_config(&config);
+
+        let class = make_class("GlobalConfig", None, &[("maxLevel", "number")]);
+        let type_mapper = crate::type_mapper::TypeMapper::new(&HashMap::new());
+        let errors = registry.set_index_types(&[class], &type_mapper);
+
+        assert!(errors.is_empty());
+        assert_eq!(registry.get_table("GlobalConfig").unwrap().index_type, None);
+    }
 }
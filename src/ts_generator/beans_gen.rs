@@ -1,11 +1,113 @@
+use crate::config::BeansExport;
+use crate::error::GenError;
 use crate::parser::ClassInfo;
 use crate::ts_generator::import_resolver::ImportResolver;
+use anyhow::Result;
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
-/// Maximum number of imports per file to stay under Luau's 200 register limit
+/// Default maximum number of imports per file to stay under Luau's 200 register limit
+/// (output.max_imports_per_file)
 const MAX_IMPORTS_PER_FILE: usize = 100;
 
+/// Template for Beans keys (output.bean_key_format): `{module}`, `{name}` and literal
+/// text, plus `{module?sep}` which expands to the module followed by `sep`, or to nothing
+/// when the module is empty. The default `{module?.}{name}` gives `module.Name` / `Name`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeanKeyFormat {
+    template: String,
+    segments: Vec<KeySegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum KeySegment {
+    Literal(String),
+    Module,
+    /// Module followed by the separator, omitted entirely when the module is empty
+    ModuleWithSeparator(String),
+    Name,
+}
+
+pub const DEFAULT_BEAN_KEY_FORMAT: &str = "{module?.}{name}";
+
+impl BeanKeyFormat {
+    pub fn parse(template: &str) -> Result<Self> {
+        let invalid = |reason: String| {
+            GenError::Validation(format!(
+                "Invalid output.bean_key_format \"{}\": {}",
+                template, reason
+            ))
+        };
+        if template.contains(['"', '\\']) {
+            anyhow::bail!(invalid(
+                "quotes and backslashes are not allowed".to_string()
+            ));
+        }
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while !rest.is_empty() {
+            let Some(open) = rest.find(['{', '}']) else {
+                segments.push(KeySegment::Literal(rest.to_string()));
+                break;
+            };
+            if open > 0 {
+                segments.push(KeySegment::Literal(rest[..open].to_string()));
+            }
+            if rest[open..].starts_with('}') {
+                anyhow::bail!(invalid("unmatched '}'".to_string()));
+            }
+            let Some(close) = rest[open..].find('}').map(|i| open + i) else {
+                anyhow::bail!(invalid("unclosed '{'".to_string()));
+            };
+            let placeholder = &rest[open + 1..close];
+            segments.push(match placeholder {
+                "module" => KeySegment::Module,
+                "name" => KeySegment::Name,
+                _ => match placeholder.strip_prefix("module?") {
+                    Some(separator) => KeySegment::ModuleWithSeparator(separator.to_string()),
+                    None => anyhow::bail!(invalid(format!(
+                        "unknown placeholder '{{{}}}' (expected {{module}}, {{name}} or {{module?sep}})",
+                        placeholder
+                    ))),
+                },
+            });
+            rest = &rest[close + 1..];
+        }
+        Ok(Self {
+            template: template.to_string(),
+            segments,
+        })
+    }
+
+    pub fn format(&self, module: &str, name: &str) -> String {
+        let mut key = String::new();
+        for segment in &self.segments {
+            match segment {
+                KeySegment::Literal(text) => key.push_str(text),
+                KeySegment::Module => key.push_str(module),
+                KeySegment::ModuleWithSeparator(separator) => {
+                    if !module.is_empty() {
+                        key.push_str(module);
+                        key.push_str(separator);
+                    }
+                }
+                KeySegment::Name => key.push_str(name),
+            }
+        }
+        key
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.template == DEFAULT_BEAN_KEY_FORMAT
+    }
+}
+
+impl Default for BeanKeyFormat {
+    fn default() -> Self {
+        Self::parse(DEFAULT_BEAN_KEY_FORMAT).expect("default bean key format is valid")
+    }
+}
+
 /// Represents a generated beans file
 pub struct BeansFile {
     /// File name (e.g., "beans.ts", "beans_1.ts")
@@ -18,34 +120,70 @@ pub struct BeansFile {
 /// Splits into multiple files to avoid Luau's 200 register limit
 pub struct BeansGenerator<'a> {
     import_resolver: &'a ImportResolver,
+    max_imports_per_file: usize,
+    export: BeansExport,
+    key_format: BeanKeyFormat,
 }
 
 impl<'a> BeansGenerator<'a> {
     pub fn new(import_resolver: &'a ImportResolver) -> Self {
-        Self { import_resolver }
+        Self {
+            import_resolver,
+            max_imports_per_file: MAX_IMPORTS_PER_FILE,
+            export: BeansExport::Object,
+            key_format: BeanKeyFormat::default(),
+        }
+    }
+
+    /// Build Beans keys from this template instead of `module.Name`
+    pub fn with_key_format(mut self, key_format: BeanKeyFormat) -> Self {
+        self.key_format = key_format;
+        self
+    }
+
+    /// Export `Beans` (object), `BeansMap` or both from beans.ts
+    pub fn with_export(mut self, export: BeansExport) -> Self {
+        self.export = export;
+        self
+    }
+
+    /// Split into beans_N.ts chunks of at most this many imports
+    pub fn with_max_imports_per_file(mut self, max_imports_per_file: usize) -> Self {
+        self.max_imports_per_file = max_imports_per_file.max(1);
+        self
     }
 
     /// Generate beans files - returns multiple files if needed to avoid register limit
-    /// Returns: Vec of (filename, content) pairs
+    /// Returns: Vec of (filename, content) pairs. Fails when two different classes
+    /// (from different source files) share a Beans key
     pub fn generate(
         &self,
         all_classes: &[&ClassInfo],
         output_path: &Path,
         default_module: &str,
-    ) -> Vec<BeansFile> {
-        // Only include classes (not interfaces), deduplicate by name
-        let mut seen = std::collections::HashSet::new();
-        let classes: Vec<_> = all_classes
+    ) -> Result<Vec<BeansFile>> {
+        // Only include classes with a runtime constructor (not interfaces, ambient
+        // `declare class`es or config-only virtual beans) that TypeScript constructs
+        // (not excluded by @NoRuntime / [beans] exclude)
+        let runtime_classes: Vec<&ClassInfo> = all_classes
             .iter()
-            .filter(|c| !c.is_interface && seen.insert(c.name.clone()))
+            .filter(|c| !c.is_interface && !c.is_ambient && !c.is_virtual && !c.no_runtime)
             .copied()
             .collect();
+        self.check_key_collisions(&runtime_classes, default_module)?;
+
+        // Deduplicate by name
+        let mut seen = std::collections::HashSet::new();
+        let classes: Vec<_> = runtime_classes
+            .into_iter()
+            .filter(|c| seen.insert(c.name.clone()))
+            .collect();
 
         // Collect imports grouped by source file, and count total imports
         let mut imports_by_file: HashMap<String, Vec<&str>> = HashMap::new();
         for class in &classes {
-            let source_path = PathBuf::from(&class.source_file);
-            let import_path = self.import_resolver.resolve(output_path, &source_path);
+            let source_path = class.file_path();
+            let import_path = self.import_resolver.resolve(output_path, source_path);
 
             imports_by_file
                 .entry(import_path)
@@ -57,16 +195,55 @@ impl<'a> BeansGenerator<'a> {
         let total_imports: usize = imports_by_file.values().map(|v| v.len()).sum();
 
         // If under limit, generate single file (backward compatible)
-        if total_imports <= MAX_IMPORTS_PER_FILE {
+        if total_imports <= self.max_imports_per_file {
             let content = self.generate_single_file(&classes, &imports_by_file, default_module);
-            return vec![BeansFile {
+            return Ok(vec![BeansFile {
                 filename: "beans.ts".to_string(),
                 content,
-            }];
+            }]);
         }
 
         // Need to split into multiple files
-        self.generate_split_files(&classes, output_path, default_module)
+        Ok(self.generate_split_files(&classes, output_path, default_module))
+    }
+
+    /// Fail when different classes map to the same Beans key (e.g. two sources assigning
+    /// the same module + name); TypeScript would silently keep only the last entry.
+    /// The same class listed twice (same source file) is not a collision
+    fn check_key_collisions(&self, classes: &[&ClassInfo], default_module: &str) -> Result<()> {
+        let mut by_key: HashMap<String, &ClassInfo> = HashMap::new();
+        let mut errors = Vec::new();
+        for class in classes {
+            let key = self.get_bean_key(class, default_module);
+            match by_key.get(&key) {
+                Some(first) if first.source_file != class.source_file => {
+                    errors.push(format!(
+                        "\"{}\": {} ({}) and {} ({})",
+                        key, first.name, first.source_file, class.name, class.source_file
+                    ));
+                }
+                Some(_) => {}
+                None => {
+                    by_key.insert(key, class);
+                }
+            }
+        }
+        if !errors.is_empty() {
+            let format_note = if self.key_format.is_default() {
+                String::new()
+            } else {
+                format!(
+                    " (keys built with output.bean_key_format = \"{}\")",
+                    self.key_format.template
+                )
+            };
+            anyhow::bail!(GenError::Validation(format!(
+                "Duplicate Beans keys in beans.ts{}:\n  {}",
+                format_note,
+                errors.join("\n  ")
+            )));
+        }
+        Ok(())
     }
 
     /// Generate a single beans.ts file (original behavior)
@@ -96,8 +273,12 @@ impl<'a> BeansGenerator<'a> {
             lines.push(String::new());
         }
 
-        // Generate Beans const object
-        lines.push("export const Beans = {".to_string());
+        // Generate Beans const object (module-private when only the map is exported)
+        if self.export == BeansExport::Map {
+            lines.push("const Beans = {".to_string());
+        } else {
+            lines.push("export const Beans = {".to_string());
+        }
 
         // Collect bean entries and sort them
         let mut bean_entries: Vec<_> = classes
@@ -118,6 +299,11 @@ impl<'a> BeansGenerator<'a> {
 
         lines.push("} as const;".to_string());
 
+        if self.export != BeansExport::Object {
+            lines.push(String::new());
+            lines.extend(map_export_lines(&["Beans".to_string()]));
+        }
+
         lines.join("\n")
     }
 
@@ -179,10 +365,10 @@ impl<'a> BeansGenerator<'a> {
         let mut current_import_count: usize = 0;
 
         for class in classes {
-            let source_path = PathBuf::from(&class.source_file);
-            let import_path = self.import_resolver.resolve(output_path, &source_path);
+            let source_path = class.file_path();
+            let import_path = self.import_resolver.resolve(output_path, source_path);
 
-            if !current_chunk.is_empty() && current_import_count + 1 > MAX_IMPORTS_PER_FILE {
+            if !current_chunk.is_empty() && current_import_count + 1 > self.max_imports_per_file {
                 // Start a new chunk
                 chunks.push(current_chunk);
                 current_chunk = Vec::new();
@@ -217,8 +403,8 @@ impl<'a> BeansGenerator<'a> {
         // Collect imports grouped by source file
         let mut imports_by_file: HashMap<String, Vec<&str>> = HashMap::new();
         for class in classes {
-            let source_path = PathBuf::from(&class.source_file);
-            let import_path = self.import_resolver.resolve(output_path, &source_path);
+            let source_path = class.file_path();
+            let import_path = self.import_resolver.resolve(output_path, source_path);
 
             imports_by_file
                 .entry(import_path)
@@ -283,12 +469,23 @@ impl<'a> BeansGenerator<'a> {
 
         lines.push(String::new());
 
-        // Merge all chunks into Beans
-        lines.push("export const Beans = {".to_string());
-        for i in 1..=chunk_count {
-            lines.push(format!("    ...Beans_{},", i));
+        // Merge all chunks into Beans (skipped in map mode to avoid one giant literal)
+        if self.export != BeansExport::Map {
+            lines.push("export const Beans = {".to_string());
+            for i in 1..=chunk_count {
+                lines.push(format!("    ...Beans_{},", i));
+            }
+            lines.push("} as const;".to_string());
+        }
+
+        // The map reuses the chunk objects, so no import is duplicated
+        if self.export != BeansExport::Object {
+            if self.export == BeansExport::Both {
+                lines.push(String::new());
+            }
+            let chunks: Vec<String> = (1..=chunk_count).map(|i| format!("Beans_{}", i)).collect();
+            lines.extend(map_export_lines(&chunks));
         }
-        lines.push("} as const;".to_string());
 
         lines.join("\n")
     }
@@ -302,17 +499,41 @@ impl<'a> BeansGenerator<'a> {
             .to_string()
     }
 
-    /// Get bean key with module prefix
+    /// Get bean key from the key format (module prefix by default)
     fn get_bean_key(&self, class: &ClassInfo, default_module: &str) -> String {
         let module = self.get_module_name(class, default_module);
-        if module.is_empty() {
-            class.name.clone()
-        } else {
-            format!("{}.{}", module, class.name)
-        }
+        self.key_format.format(&module, &class.name)
     }
 }
 
+/// `BeanKey` / `BeanConstructor` types and a `BeansMap` filled by iterating the given
+/// chunk objects at runtime, one `set` per entry instead of a literal of every bean
+fn map_export_lines(chunks: &[String]) -> Vec<String> {
+    let keys: Vec<String> = chunks
+        .iter()
+        .map(|c| format!("keyof typeof {}", c))
+        .collect();
+    let values: Vec<String> = chunks
+        .iter()
+        .map(|c| format!("(typeof {0})[keyof typeof {0}]", c))
+        .collect();
+    vec![
+        format!("export type BeanKey = {};", keys.join(" | ")),
+        format!("export type BeanConstructor = {};", values.join(" | ")),
+        String::new(),
+        "const beansMap = new Map<BeanKey, BeanConstructor>();".to_string(),
+        format!(
+            "for (const chunk of [{}] as ReadonlyArray<Record<string, BeanConstructor>>) {{",
+            chunks.join(", ")
+        ),
+        "    for (const [key, bean] of pairs(chunk)) {".to_string(),
+        "        beansMap.set(key as BeanKey, bean);".to_string(),
+        "    }".to_string(),
+        "}".to_string(),
+        "export const BeansMap: ReadonlyMap<BeanKey, BeanConstructor> = beansMap;".to_string(),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -331,6 +552,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: source_file.to_string(),
+            source_path: Default::default(),
             file_hash: "".to_string(),
             is_interface,
             output_path: None,
@@ -340,6 +562,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: HashMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         }
     }
 
@@ -353,7 +587,9 @@ mod tests {
         let class2 = make_class("AnotherClass", false);
 
         let all_classes: Vec<&ClassInfo> = vec![&class1, &interface1, &class2];
-        let files = generator.generate(&all_classes, Path::new("out/beans.ts"), "test");
+        let files = generator
+            .generate(&all_classes, Path::new("out/beans.ts"), "test")
+            .unwrap();
 
         // Should generate single file for small number of classes
         assert_eq!(files.len(), 1);
@@ -372,6 +608,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_beans_generator_excludes_virtual_beans() {
+        let import_resolver = ImportResolver::default();
+        let generator = BeansGenerator::new(&import_resolver);
+
+        let class1 = make_class("MyClass", false);
+        let mut virtual_bean = make_class("TScalingStat", false);
+        virtual_bean.is_virtual = true;
+
+        let all_classes: Vec<&ClassInfo> = vec![&class1, &virtual_bean];
+        let files = generator
+            .generate(&all_classes, Path::new("out/beans.ts"), "test")
+            .unwrap();
+
+        let output = &files[0].content;
+        assert!(output.contains("MyClass"));
+        assert!(!output.contains("TScalingStat"), "Virtual beans have no constructor");
+    }
+
     #[test]
     fn test_beans_generator_only_interfaces_produces_empty_beans() {
         let import_resolver = ImportResolver::default();
@@ -381,7 +636,9 @@ mod tests {
         let interface2 = make_class("Interface2", true);
 
         let all_classes: Vec<&ClassInfo> = vec![&interface1, &interface2];
-        let files = generator.generate(&all_classes, Path::new("out/beans.ts"), "test");
+        let files = generator
+            .generate(&all_classes, Path::new("out/beans.ts"), "test")
+            .unwrap();
 
         assert_eq!(files.len(), 1);
         let output = &files[0].content;
@@ -406,7 +663,9 @@ mod tests {
             .collect();
 
         let all_classes: Vec<&ClassInfo> = classes.iter().collect();
-        let files = generator.generate(&all_classes, Path::new("out/beans.ts"), "test");
+        let files = generator
+            .generate(&all_classes, Path::new("out/beans.ts"), "test")
+            .unwrap();
 
         // Should generate multiple files
         assert!(files.len() > 1, "Should generate multiple files for 200 classes");
@@ -437,7 +696,9 @@ mod tests {
             .collect();
 
         let all_classes: Vec<&ClassInfo> = classes.iter().collect();
-        let files = generator.generate(&all_classes, Path::new("out/beans.ts"), "test");
+        let files = generator
+            .generate(&all_classes, Path::new("out/beans.ts"), "test")
+            .unwrap();
 
         // Should generate single file
         assert_eq!(files.len(), 1, "Should generate single file for 50 classes");
@@ -446,4 +707,272 @@ mod tests {
         // Should NOT have chunk imports
         assert!(!files[0].content.contains("Beans_1"), "Should not have chunk references");
     }
+
+    #[test]
+    fn test_beans_export_both_shares_chunks() {
+        let import_resolver = ImportResolver::default();
+        let classes: Vec<ClassInfo> = (0..250)
+            .map(|i| {
+                make_class_with_source(&format!("Class{}", i), false, &format!("file{}.ts", i))
+            })
+            .collect();
+        let all_classes: Vec<&ClassInfo> = classes.iter().collect();
+        let generate = |export: BeansExport| {
+            BeansGenerator::new(&import_resolver)
+                .with_export(export)
+                .generate(&all_classes, Path::new("out/beans.ts"), "test")
+                .unwrap()
+        };
+
+        let object = generate(BeansExport::Object);
+        let both = generate(BeansExport::Both);
+        let map = generate(BeansExport::Map);
+
+        // Chunk files are identical in every mode and stay under the import limit
+        assert_eq!(both.len(), 4);
+        for (i, chunk) in both[..3].iter().enumerate() {
+            assert_eq!(chunk.content, object[i].content);
+            assert_eq!(chunk.content, map[i].content);
+            let imports = chunk
+                .content
+                .lines()
+                .filter(|l| l.starts_with("import "))
+                .count();
+            assert!(imports <= MAX_IMPORTS_PER_FILE);
+        }
+
+        let main = &both[3].content;
+        assert!(main.contains("export const Beans = {\n    ...Beans_1,\n    ...Beans_2,\n    ...Beans_3,\n} as const;"));
+        assert!(main.contains("for (const chunk of [Beans_1, Beans_2, Beans_3] as ReadonlyArray<Record<string, BeanConstructor>>) {"));
+        assert!(main.contains("export type BeanKey = keyof typeof Beans_1 | keyof typeof Beans_2 | keyof typeof Beans_3;"));
+        assert!(main
+            .contains("export const BeansMap: ReadonlyMap<BeanKey, BeanConstructor> = beansMap;"));
+        // Each chunk is imported once and feeds both exports
+        assert_eq!(main.matches("import { Beans_1 }").count(), 1);
+
+        let main = &map[3].content;
+        assert!(!main.contains("export const Beans ="));
+        assert!(!main.contains("...Beans_1"));
+        assert!(main.contains("export const BeansMap"));
+        assert_eq!(
+            object[3].content,
+            object[3].content.split("\n\nexport type").next().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_beans_export_single_file() {
+        let import_resolver = ImportResolver::default();
+        let class = make_class("MyClass", false);
+        let generate = |export: BeansExport| {
+            BeansGenerator::new(&import_resolver)
+                .with_export(export)
+                .generate(&[&class], Path::new("out/beans.ts"), "test")
+                .unwrap()
+                .remove(0)
+                .content
+        };
+
+        let both = generate(BeansExport::Both);
+        assert!(
+            both.contains("export const Beans = {\n    \"test.MyClass\": MyClass,\n} as const;")
+        );
+        assert!(both.contains("for (const chunk of [Beans] as ReadonlyArray"));
+        assert!(both
+            .ends_with("export const BeansMap: ReadonlyMap<BeanKey, BeanConstructor> = beansMap;"));
+
+        let map = generate(BeansExport::Map);
+        assert!(map.contains("\nconst Beans = {"));
+        assert!(map.contains("export const BeansMap"));
+
+        let object = generate(BeansExport::Object);
+        assert!(!object.contains("BeansMap"));
+    }
+
+    #[test]
+    fn test_beans_generator_custom_max_imports() {
+        let import_resolver = ImportResolver::default();
+        let generator = BeansGenerator::new(&import_resolver).with_max_imports_per_file(20);
+
+        let classes: Vec<ClassInfo> = (0..50)
+            .map(|i| {
+                make_class_with_source(&format!("Class{}", i), false, &format!("file{}.ts", i))
+            })
+            .collect();
+
+        let all_classes: Vec<&ClassInfo> = classes.iter().collect();
+        let files = generator
+            .generate(&all_classes, Path::new("out/beans.ts"), "test")
+            .unwrap();
+
+        let filenames: Vec<_> = files.iter().map(|f| f.filename.as_str()).collect();
+        assert_eq!(
+            filenames,
+            vec!["beans_1.ts", "beans_2.ts", "beans_3.ts", "beans.ts"]
+        );
+    }
+
+    #[test]
+    fn test_beans_generator_rejects_key_collisions() {
+        let import_resolver = ImportResolver::default();
+        let generator = BeansGenerator::new(&import_resolver);
+
+        // Same module + name from two different sources
+        let first = make_class_with_source("SkillConfig", false, "a/skill.ts");
+        let second = make_class_with_source("SkillConfig", false, "b/skill.ts");
+        let err = generator
+            .generate(&[&first, &second], Path::new("out/beans.ts"), "test")
+            .err()
+            .expect("colliding keys must fail");
+        assert!(
+            err.to_string().contains(
+                "\"test.SkillConfig\": SkillConfig (a/skill.ts) and SkillConfig (b/skill.ts)"
+            ),
+            "{}",
+            err
+        );
+
+        // The chunked path is checked too
+        let mut classes: Vec<ClassInfo> = (0..200)
+            .map(|i| make_class_with_source(&format!("Class{}", i), false, &format!("file{}.ts", i)))
+            .collect();
+        classes.push(make_class_with_source("Class7", false, "other.ts"));
+        let all_classes: Vec<&ClassInfo> = classes.iter().collect();
+        assert!(generator
+            .generate(&all_classes, Path::new("out/beans.ts"), "test")
+            .is_err());
+
+        // The same class listed twice is not a collision
+        let files = generator
+            .generate(&[&first, &first], Path::new("out/beans.ts"), "test")
+            .unwrap();
+        assert_eq!(files[0].content.matches("SkillConfig,").count(), 1);
+    }
+
+    #[test]
+    fn test_bean_key_format() {
+        let legacy = BeanKeyFormat::parse("{module?__}{name}").unwrap();
+        assert_eq!(legacy.format("role", "RoleConfig"), "role__RoleConfig");
+        assert_eq!(legacy.format("", "RoleConfig"), "RoleConfig");
+
+        let plain = BeanKeyFormat::parse("{module}.{name}").unwrap();
+        assert_eq!(plain.format("", "RoleConfig"), ".RoleConfig");
+
+        let default = BeanKeyFormat::default();
+        assert_eq!(default.format("role", "RoleConfig"), "role.RoleConfig");
+        assert_eq!(default.format("", "RoleConfig"), "RoleConfig");
+
+        for bad in ["{modul}.{name}", "{name", "{module}}{name}", "\"{name}"] {
+            assert!(
+                BeanKeyFormat::parse(bad).is_err(),
+                "{} should be rejected",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_beans_generator_custom_key_format() {
+        let import_resolver = ImportResolver::default();
+        let generator = BeansGenerator::new(&import_resolver)
+            .with_key_format(BeanKeyFormat::parse("{module?__}{name}").unwrap());
+
+        let role = make_class_with_source("RoleConfig", false, "role.ts");
+        let mut global = make_class_with_source("GlobalConfig", false, "global.ts");
+        global.module_name = Some(String::new());
+        let files = generator
+            .generate(&[&role, &global], Path::new("out/beans.ts"), "test")
+            .unwrap();
+        assert!(files[0]
+            .content
+            .contains("    \"test__RoleConfig\": RoleConfig,"));
+        assert!(files[0]
+            .content
+            .contains("    \"GlobalConfig\": GlobalConfig,"));
+
+        // Chunk files use the same keys
+        let classes: Vec<ClassInfo> = (0..150)
+            .map(|i| {
+                make_class_with_source(&format!("Class{}", i), false, &format!("file{}.ts", i))
+            })
+            .collect();
+        let all_classes: Vec<&ClassInfo> = classes.iter().collect();
+        let files = generator
+            .generate(&all_classes, Path::new("out/beans.ts"), "test")
+            .unwrap();
+        assert_eq!(files[0].filename, "beans_1.ts");
+        assert!(files[0].content.contains("    \"test__Class0\": Class0,"));
+    }
+
+    #[test]
+    fn test_beans_generator_lossy_key_format_collision() {
+        let import_resolver = ImportResolver::default();
+        let generator = BeansGenerator::new(&import_resolver)
+            .with_key_format(BeanKeyFormat::parse("{name}").unwrap());
+
+        // Distinct under the default format, the same key once the module is dropped
+        let mut role = make_class_with_source("Config", false, "role/config.ts");
+        role.module_name = Some("role".to_string());
+        let mut item = make_class_with_source("Config", false, "item/config.ts");
+        item.module_name = Some("item".to_string());
+        assert!(BeansGenerator::new(&import_resolver)
+            .generate(&[&role, &item], Path::new("out/beans.ts"), "test")
+            .is_ok());
+
+        let err = generator
+            .generate(&[&role, &item], Path::new("out/beans.ts"), "test")
+            .err()
+            .expect("colliding keys must fail");
+        let message = err.to_string();
+        assert!(
+            message.contains("output.bean_key_format = \"{name}\""),
+            "{}",
+            message
+        );
+        assert!(
+            message.contains("\"Config\": Config (role/config.ts) and Config (item/config.ts)"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_beans_generator_excludes_ambient_classes() {
+        let import_resolver = ImportResolver::default();
+        let generator = BeansGenerator::new(&import_resolver);
+
+        let class1 = make_class("MyClass", false);
+        let mut legacy = make_class("LegacyConfig", false);
+        legacy.is_ambient = true;
+
+        let all_classes: Vec<&ClassInfo> = vec![&class1, &legacy];
+        let files = generator
+            .generate(&all_classes, Path::new("out/beans.ts"), "test")
+            .unwrap();
+
+        let output = &files[0].content;
+        assert!(output.contains("MyClass"));
+        assert!(!output.contains("LegacyConfig"), "Ambient classes have no constructor");
+    }
+
+    #[test]
+    fn test_beans_generator_excludes_no_runtime_classes() {
+        let import_resolver = ImportResolver::default();
+        let generator = BeansGenerator::new(&import_resolver).with_max_imports_per_file(1);
+
+        let class1 = make_class("MyClass", false);
+        let mut drop_entry = make_class("DropEntry", false);
+        drop_entry.no_runtime = true;
+
+        // Excluded classes don't count towards the import limit either
+        let all_classes: Vec<&ClassInfo> = vec![&class1, &drop_entry];
+        let files = generator
+            .generate(&all_classes, Path::new("out/beans.ts"), "test")
+            .unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].filename, "beans.ts");
+        assert!(files[0].content.contains("MyClass"));
+        assert!(!files[0].content.contains("DropEntry"));
+    }
 }
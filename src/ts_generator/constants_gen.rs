@@ -0,0 +1,105 @@
+use crate::parser::ClassInfo;
+
+/// Generate constants.ts (output.constants_format = "ts"):
+/// one `export const {Class}Constants = { ... } as const;` per class with constants
+pub fn generate_constants_ts(classes: &[&ClassInfo]) -> String {
+    let mut lines = Vec::new();
+
+    for class in classes.iter().filter(|c| !c.constants.is_empty()) {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        if let Some(comment) = &class.comment {
+            lines.push(format!("/** {} */", comment));
+        }
+        lines.push(format!("export const {}Constants = {{", class.name));
+        for constant in &class.constants {
+            if let Some(comment) = &constant.comment {
+                lines.push(format!("    /** {} */", comment));
+            }
+            let value = if constant.const_type == "string" {
+                format!(
+                    "\"{}\"",
+                    constant.value.replace('\\', "\\\\").replace('"', "\\\"")
+                )
+            } else {
+                constant.value.clone()
+            };
+            lines.push(format!("    {}: {},", constant.name, value));
+        }
+        lines.push("} as const;".to_string());
+    }
+
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{ClassConstant, ImportMap};
+    use std::collections::HashMap;
+
+    fn make_class(name: &str, constants: Vec<ClassConstant>) -> ClassInfo {
+        ClassInfo {
+            name: name.to_string(),
+            comment: None,
+            alias: None,
+            fields: vec![],
+            implements: vec![],
+            extends: None,
+            source_file: "test.ts".to_string(),
+            source_path: Default::default(),
+            file_hash: String::new(),
+            is_interface: false,
+            output_path: None,
+            module_name: None,
+            type_params: HashMap::new(),
+            luban_table: None,
+            table_config: None,
+            input_path: None,
+            imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants,
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            explicit_parent: None,
+            explicit_module: false,
+            tags: Vec::new(),
+            examples: Vec::new(),
+        }
+    }
+
+    fn constant(name: &str, const_type: &str, value: &str) -> ClassConstant {
+        ClassConstant {
+            name: name.to_string(),
+            const_type: const_type.to_string(),
+            value: value.to_string(),
+            comment: None,
+        }
+    }
+
+    #[test]
+    fn test_generate_constants_ts() {
+        let mut max_level = constant("MAX_LEVEL", "int", "80");
+        max_level.comment = Some("Level cap".to_string());
+        let role = make_class(
+            "RoleConfig",
+            vec![
+                max_level,
+                constant("DEFAULT_NAME", "string", "say \"hi\""),
+                constant("PVP", "bool", "true"),
+            ],
+        );
+        let empty = make_class("ItemConfig", vec![]);
+
+        let output = generate_constants_ts(&[&role, &empty]);
+        assert_eq!(
+            output,
+            "export const RoleConfigConstants = {\n    /** Level cap */\n    MAX_LEVEL: 80,\n    DEFAULT_NAME: \"say \\\"hi\\\"\",\n    PVP: true,\n} as const;\n"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Circular requires between the generated TypeScript and the class modules it imports.
+//! beans.ts (and its chunks) require every class module at runtime; when one of those
+//! modules imports back into the output directory, directly or through other modules,
+//! Roblox fails with a circular require that is hard to trace back.
+//!
+//! The graph is module-specifier level: an edge is a runtime import or re-export of a
+//! relative module. Type-only imports are erased by the compiler and never form edges.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// A runtime require cycle: the generated file, the modules it goes through and the
+/// output file the last module imports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportCycle {
+    pub path: Vec<PathBuf>,
+}
+
+impl ImportCycle {
+    /// `a -> b -> c`, with paths relative to `root` where possible
+    pub fn render(&self, root: &Path) -> String {
+        self.path
+            .iter()
+            .map(|p| {
+                p.strip_prefix(root)
+                    .unwrap_or(p)
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    }
+}
+
+/// Follow the runtime imports of each generated file into the sources and report every
+/// way back into `output_dir`. `imports_of` returns a module's runtime imports and is
+/// called at most once per module. One cycle is reported per import that closes it
+/// (the shortest path to it from the first generated file that reaches it)
+pub fn find_cycles(
+    generated: &[(PathBuf, Vec<PathBuf>)],
+    output_dir: &Path,
+    mut imports_of: impl FnMut(&Path) -> Vec<PathBuf>,
+) -> Vec<ImportCycle> {
+    let mut edges: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    let mut closing: HashSet<(PathBuf, PathBuf)> = HashSet::new();
+    let mut cycles = Vec::new();
+
+    for (file, imports) in generated {
+        // Breadth-first from the generated file, so each reported path is a shortest one
+        let mut parents: HashMap<PathBuf, PathBuf> = HashMap::new();
+        let mut queue: VecDeque<PathBuf> = imports
+            .iter()
+            .filter(|import| !import.starts_with(output_dir))
+            .cloned()
+            .collect();
+        for import in &queue {
+            parents.insert(import.clone(), file.clone());
+        }
+        while let Some(module) = queue.pop_front() {
+            let targets = edges
+                .entry(module.clone())
+                .or_insert_with(|| imports_of(&module))
+                .clone();
+            for target in targets {
+                if target.starts_with(output_dir) {
+                    if closing.insert((module.clone(), target.clone())) {
+                        let mut path = vec![target, module.clone()];
+                        let mut current = &module;
+                        while let Some(parent) = parents.get(current) {
+                            path.push(parent.clone());
+                            current = parent;
+                        }
+                        path.reverse();
+                        cycles.push(ImportCycle { path });
+                    }
+                } else if !parents.contains_key(&target) {
+                    parents.insert(target.clone(), module.clone());
+                    queue.push_back(target);
+                }
+            }
+        }
+    }
+
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &[&str])]) -> HashMap<PathBuf, Vec<PathBuf>> {
+        edges
+            .iter()
+            .map(|(from, to)| {
+                (
+                    PathBuf::from(from),
+                    to.iter().map(PathBuf::from).collect::<Vec<_>>(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_cycles() {
+        let sources = graph(&[
+            ("/p/src/item.ts", &["/p/src/helper.ts"]),
+            ("/p/src/helper.ts", &["/p/out/tables.ts", "/p/src/item.ts"]),
+            ("/p/src/role.ts", &["/p/src/helper.ts"]),
+            ("/p/src/skill.ts", &["/p/out/beans.ts"]),
+            ("/p/src/clean.ts", &["/p/src/util.ts"]),
+        ]);
+        let generated = vec![
+            (
+                PathBuf::from("/p/out/beans_1.ts"),
+                vec![
+                    PathBuf::from("/p/src/item.ts"),
+                    PathBuf::from("/p/src/role.ts"),
+                    PathBuf::from("/p/src/clean.ts"),
+                ],
+            ),
+            (
+                PathBuf::from("/p/out/beans_2.ts"),
+                vec![PathBuf::from("/p/src/skill.ts")],
+            ),
+        ];
+        let mut calls = 0;
+        let cycles = find_cycles(&generated, Path::new("/p/out"), |module| {
+            calls += 1;
+            sources.get(module).cloned().unwrap_or_default()
+        });
+
+        let rendered: Vec<String> = cycles.iter().map(|c| c.render(Path::new("/p"))).collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "out/beans_1.ts -> src/item.ts -> src/helper.ts -> out/tables.ts",
+                "out/beans_2.ts -> src/skill.ts -> out/beans.ts",
+            ]
+        );
+        // item, role, clean, helper, util, skill
+        assert_eq!(calls, 6);
+    }
+
+    #[test]
+    fn test_no_cycles_without_imports_into_output() {
+        let generated = vec![(
+            PathBuf::from("/p/out/beans.ts"),
+            vec![PathBuf::from("/p/src/item.ts")],
+        )];
+        let cycles = find_cycles(&generated, Path::new("/p/out"), |_| {
+            vec![PathBuf::from("/p/src/shared.ts")]
+        });
+        assert!(cycles.is_empty());
+    }
+}
@@ -1,6 +1,7 @@
+use crate::parser::slash_path;
 use crate::tsconfig::TsConfig;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub struct ImportResolver {
     /// tsconfig paths mapping
@@ -57,10 +58,14 @@ impl ImportResolver {
     }
 
     fn calculate_relative_path(&self, from: &Path, to: &Path) -> String {
+        // Compare forward-slash forms so backslash-separated input gives the same import on
+        // every platform
+        let from = PathBuf::from(slash_path(from));
+        let to = PathBuf::from(slash_path(to));
         // Get the directory containing the 'from' file
         let from_dir = from.parent().unwrap_or(Path::new("."));
 
-        pathdiff::diff_paths(to, from_dir)
+        pathdiff::diff_paths(&to, from_dir)
             .map(|p| {
                 // Normalize to forward slashes for TypeScript imports
                 let s = p.to_string_lossy().replace('\\', "/");
@@ -73,7 +78,7 @@ impl ImportResolver {
                     format!("./{}", s)
                 }
             })
-            .unwrap_or_else(|| to.to_string_lossy().to_string())
+            .unwrap_or_else(|| slash_path(&to))
     }
 }
 
@@ -1,14 +1,22 @@
 mod beans_gen;
+mod constants_gen;
+pub mod import_cycles;
 mod import_resolver;
+pub mod register_estimate;
 mod tables_simple_gen;
 
-pub use beans_gen::BeansGenerator;
+pub use beans_gen::{BeanKeyFormat, BeansGenerator};
+pub use constants_gen::generate_constants_ts;
 pub use import_resolver::ImportResolver;
 pub use tables_simple_gen::TablesSimpleGenerator;
 
+use crate::config::{BeansExport, Newline, TsTypeOverride};
+use crate::output::write_if_changed;
 use crate::parser::ClassInfo;
 use crate::table_registry::TableRegistry;
 use crate::tsconfig::TsConfig;
+use crate::type_mapper::TypeMapper;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// Main TypeScript code generator
@@ -18,6 +26,15 @@ pub struct TsCodeGenerator<'a> {
     import_resolver: ImportResolver,
     default_module_name: String,
     table_registry: &'a TableRegistry,
+    newline: Newline,
+    /// Directory that relative [tables] `transform` modules are resolved against
+    config_dir: Option<PathBuf>,
+    max_imports_per_file: Option<usize>,
+    beans_export: BeansExport,
+    bean_key_format: BeanKeyFormat,
+    ts_type_overrides: Option<(&'a HashMap<String, TsTypeOverride>, &'a TypeMapper)>,
+    /// Only tables in one of these Luban groups get declarations and getters (`--group`)
+    groups: Option<Vec<String>>,
 }
 
 impl<'a> TsCodeGenerator<'a> {
@@ -35,9 +52,63 @@ impl<'a> TsCodeGenerator<'a> {
             import_resolver: ImportResolver::new(tsconfig),
             default_module_name,
             table_registry,
+            newline: Newline::default(),
+            config_dir: None,
+            max_imports_per_file: None,
+            beans_export: BeansExport::default(),
+            bean_key_format: BeanKeyFormat::default(),
+            ts_type_overrides: None,
+            groups: None,
         }
     }
 
+    /// Set the line ending used for generated TypeScript files
+    pub fn with_newline(mut self, newline: Newline) -> Self {
+        self.newline = newline;
+        self
+    }
+
+    /// Resolve relative [tables] `transform` modules against the config file's directory
+    pub fn with_config_dir(mut self, config_dir: PathBuf) -> Self {
+        self.config_dir = Some(config_dir);
+        self
+    }
+
+    /// Split beans.ts into chunks of at most this many imports (output.max_imports_per_file)
+    pub fn with_max_imports_per_file(mut self, max_imports_per_file: usize) -> Self {
+        self.max_imports_per_file = Some(max_imports_per_file);
+        self
+    }
+
+    /// Shape of the Beans export (output.beans_export)
+    pub fn with_beans_export(mut self, beans_export: BeansExport) -> Self {
+        self.beans_export = beans_export;
+        self
+    }
+
+    /// Template for Beans keys (output.bean_key_format)
+    pub fn with_bean_key_format(mut self, bean_key_format: BeanKeyFormat) -> Self {
+        self.bean_key_format = bean_key_format;
+        self
+    }
+
+    /// TypeScript types for Luban types in tables.d.ts ([ts_type_overrides])
+    pub fn with_ts_type_overrides(
+        mut self,
+        ts_type_overrides: &'a HashMap<String, TsTypeOverride>,
+        type_mapper: &'a TypeMapper,
+    ) -> Self {
+        self.ts_type_overrides = Some((ts_type_overrides, type_mapper));
+        self
+    }
+
+    /// Leave out tables outside these Luban groups (already mapped through [groups]);
+    /// beans.ts still lists every class
+    pub fn with_groups(mut self, groups: Vec<String>) -> Self {
+        self.groups = Some(groups);
+        self
+    }
+
     fn get_default_module_name(&self) -> &str {
         &self.default_module_name
     }
@@ -46,11 +117,28 @@ impl<'a> TsCodeGenerator<'a> {
         // Create output directory
         std::fs::create_dir_all(&self.output_path)?;
 
+        for (file_path, content) in self.render()? {
+            write_if_changed(&file_path, &content, self.newline)?;
+        }
+
+        Ok(())
+    }
+
+    /// Render tables.d.ts, singletons.ts (with one/singleton tables) and beans.ts (plus
+    /// beans_N.ts chunks) without writing them.
+    /// Returns (output file path, content) pairs
+    pub fn render(&self) -> anyhow::Result<Vec<(PathBuf, String)>> {
+        let mut files = Vec::new();
+
         // Get table classes from [tables] config
         let table_classes: Vec<_> = self
             .classes
             .iter()
             .filter(|c| {
+                // Virtual beans have no TypeScript declaration to import
+                if c.is_virtual {
+                    return false;
+                }
                 // Build full name: module.ClassName
                 // Use class's module_name, or fall back to default_module_name
                 let module = c
@@ -62,34 +150,50 @@ impl<'a> TsCodeGenerator<'a> {
                 } else {
                     format!("{}.{}", module, c.name)
                 };
-                // Check if this class is in the [tables] config
-                self.table_registry.has_table(&full_name)
+                // Check if this class is in the [tables] config (and the selected groups)
+                match (self.table_registry.get_table(&full_name), &self.groups) {
+                    (Some(table), Some(groups)) => table.in_groups(groups),
+                    (table, _) => table.is_some(),
+                }
             })
             .collect();
 
         // Generate tables.d.ts with simple type definitions
-        let tables_gen =
+        let mut tables_gen =
             TablesSimpleGenerator::new(&self.import_resolver, self.table_registry, &self.default_module_name);
+        if let Some(config_dir) = &self.config_dir {
+            tables_gen = tables_gen.with_project_root(config_dir);
+        }
+        if let Some((ts_type_overrides, type_mapper)) = self.ts_type_overrides {
+            tables_gen = tables_gen.with_ts_type_overrides(ts_type_overrides, type_mapper);
+        }
         let tables_path = self.output_path.join("tables.d.ts");
         let content = tables_gen.generate(&table_classes, &tables_path);
-        std::fs::write(&tables_path, content)?;
+        files.push((tables_path, content));
+        if let Some(content) = tables_gen.generate_singletons(&table_classes) {
+            files.push((self.output_path.join("singletons.ts"), content));
+        }
 
         // Generate beans.ts (and beans_N.ts if needed) with all classes
         let all_class_refs: Vec<_> = self.classes.iter().collect();
-        let beans_gen = BeansGenerator::new(&self.import_resolver);
+        let mut beans_gen = BeansGenerator::new(&self.import_resolver)
+            .with_export(self.beans_export)
+            .with_key_format(self.bean_key_format.clone());
+        if let Some(max_imports_per_file) = self.max_imports_per_file {
+            beans_gen = beans_gen.with_max_imports_per_file(max_imports_per_file);
+        }
         let beans_path = self.output_path.join("beans.ts");
         let beans_files = beans_gen.generate(
             &all_class_refs,
             &beans_path,
             self.get_default_module_name(),
-        );
-        
-        // Write all beans files
+        )?;
+
         for beans_file in beans_files {
             let file_path = self.output_path.join(&beans_file.filename);
-            std::fs::write(&file_path, &beans_file.content)?;
+            files.push((file_path, beans_file.content));
         }
 
-        Ok(())
+        Ok(files)
     }
 }
@@ -0,0 +1,285 @@
+//! Conservative estimate of the Luau registers a generated TypeScript module needs once
+//! compiled by roblox-ts. Luau allows at most 200 registers per function, and a module's
+//! top level is one function, so every import binding and top-level declaration holds a
+//! register for the whole chunk, and a table constructor keeps its pending values in
+//! registers until they are flushed.
+//!
+//! The model is line based (the generators emit one import statement and one object
+//! property per line) and has two parts:
+//! - definite: runtime overhead + import bindings + top-level declarations, which really
+//!   are held for the whole chunk; over the limit is an error
+//! - total: definite + the most properties of any single object literal, a deliberately
+//!   pessimistic bound used to warn before the limit
+
+/// Registers available to a Luau function
+pub const LUAU_REGISTER_LIMIT: usize = 200;
+
+/// Registers taken before any user code: the roblox-ts runtime import and the exports table
+const MODULE_OVERHEAD: usize = 2;
+
+/// Estimated register pressure of one generated file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegisterEstimate {
+    /// Imported bindings (`import { A, B }` counts 2); type-only imports are erased
+    pub imports: usize,
+    /// Top-level `const`/`let`/`var`/`function`/`class`/`enum` declarations
+    pub locals: usize,
+    /// Largest number of properties (including spreads) in one object literal
+    pub max_object_properties: usize,
+}
+
+impl RegisterEstimate {
+    /// Registers certainly held for the whole chunk
+    pub fn definite(&self) -> usize {
+        MODULE_OVERHEAD + self.imports + self.locals
+    }
+
+    /// Pessimistic bound including the largest object literal
+    pub fn total(&self) -> usize {
+        self.definite() + self.max_object_properties
+    }
+}
+
+impl std::fmt::Display for RegisterEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "~{} registers: {} imports, {} top-level locals, {} properties in one object literal",
+            self.total(),
+            self.imports,
+            self.locals,
+            self.max_object_properties
+        )
+    }
+}
+
+/// How close a file's estimate comes to the register limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterPressure {
+    Ok,
+    /// The pessimistic total is within `margin` registers of the limit (or over it)
+    NearLimit(RegisterEstimate),
+    /// The definite registers alone exceed the limit
+    OverLimit(RegisterEstimate),
+}
+
+/// Check a generated module against the limit, warning `margin` registers before it
+pub fn check(content: &str, margin: usize) -> RegisterPressure {
+    let estimate = estimate(content);
+    if estimate.definite() > LUAU_REGISTER_LIMIT {
+        RegisterPressure::OverLimit(estimate)
+    } else if estimate.total() + margin > LUAU_REGISTER_LIMIT {
+        RegisterPressure::NearLimit(estimate)
+    } else {
+        RegisterPressure::Ok
+    }
+}
+
+/// Estimate the register pressure of a generated TypeScript module
+pub fn estimate(content: &str) -> RegisterEstimate {
+    let mut estimate = RegisterEstimate::default();
+    // Property count of each object literal that is still open
+    let mut open_objects: Vec<usize> = Vec::new();
+    // Multi-line import statement being collected
+    let mut pending_import: Option<String> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") || trimmed.starts_with("/*") {
+            continue;
+        }
+        if trimmed.starts_with('*') && !trimmed.starts_with("*/") {
+            continue;
+        }
+
+        if let Some(mut statement) = pending_import.take() {
+            statement.push(' ');
+            statement.push_str(trimmed);
+            if trimmed.contains(" from ") || trimmed.starts_with("from ") {
+                estimate.imports += import_bindings(&statement);
+            } else {
+                pending_import = Some(statement);
+            }
+            continue;
+        }
+        if trimmed.starts_with("import ") {
+            if trimmed.contains(" from ") {
+                estimate.imports += import_bindings(trimmed);
+            } else {
+                pending_import = Some(trimmed.to_string());
+            }
+            continue;
+        }
+
+        if open_objects.is_empty() && is_top_level_declaration(line) {
+            estimate.locals += 1;
+        }
+
+        // A property line (`key: value,` / `...Spread,`) belongs to the innermost object;
+        // a nested object literal opening on it counts as one property of its parent
+        if let Some(count) = open_objects.last_mut() {
+            if !trimmed.starts_with('}') {
+                *count += 1;
+            }
+        }
+        if trimmed.starts_with('}') {
+            if let Some(count) = open_objects.pop() {
+                estimate.max_object_properties = estimate.max_object_properties.max(count);
+            }
+        }
+        if opens_object_literal(trimmed) {
+            open_objects.push(0);
+        }
+    }
+
+    for count in open_objects {
+        estimate.max_object_properties = estimate.max_object_properties.max(count);
+    }
+    estimate
+}
+
+/// Number of local bindings an import statement creates
+fn import_bindings(statement: &str) -> usize {
+    let clause = statement
+        .trim_start_matches("import")
+        .split(" from ")
+        .next()
+        .unwrap_or("")
+        .trim();
+    if clause.starts_with("type ") {
+        return 0;
+    }
+    let mut bindings = 0;
+    let (default_part, named_part) = match clause.find('{') {
+        Some(open) => (&clause[..open], Some(&clause[open..])),
+        None => (clause, None),
+    };
+    // `Default`, `* as Ns` or `Default, `
+    if !default_part.trim().trim_end_matches(',').trim().is_empty() {
+        bindings += 1;
+    }
+    if let Some(named) = named_part {
+        bindings += named
+            .trim_matches(|c| c == '{' || c == '}' || c == ' ')
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty() && !name.starts_with("type "))
+            .count();
+    }
+    bindings
+}
+
+/// A declaration at column 0 that becomes a local of the module function
+fn is_top_level_declaration(line: &str) -> bool {
+    if line.starts_with(char::is_whitespace) {
+        return false;
+    }
+    let declaration = line.strip_prefix("export ").unwrap_or(line);
+    let declaration = declaration
+        .strip_prefix("declare ")
+        .map_or(declaration, |_| "");
+    ["const ", "let ", "var ", "function ", "class ", "enum "]
+        .iter()
+        .any(|keyword| declaration.starts_with(keyword))
+}
+
+/// A line ending in `{` that starts an object literal (`= {`, `: {`, `({`, `[{`),
+/// as opposed to a block, class, interface or type body
+fn opens_object_literal(trimmed: &str) -> bool {
+    let Some(before) = trimmed.strip_suffix('{') else {
+        return false;
+    };
+    let before = before.trim_end();
+    before.ends_with('=')
+        || before.ends_with(':')
+        || before.ends_with('(')
+        || before.ends_with('[')
+        || before.ends_with(',')
+        || before.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_chunk_file() {
+        let mut content = String::new();
+        for i in 0..95 {
+            content.push_str(&format!(
+                "import {{ Bean{0} }} from \"./src/bean{0}\";\n",
+                i
+            ));
+        }
+        content.push_str("\nexport const Beans_1 = {\n");
+        for i in 0..95 {
+            content.push_str(&format!("    \"mod.Bean{0}\": Bean{0},\n", i));
+        }
+        content.push_str("} as const;");
+
+        let estimate = estimate(&content);
+        assert_eq!(
+            estimate,
+            RegisterEstimate {
+                imports: 95,
+                locals: 1,
+                max_object_properties: 95,
+            }
+        );
+        assert_eq!(estimate.total(), 193);
+    }
+
+    #[test]
+    fn test_check_margin() {
+        let file = |imports: usize| {
+            let mut content = String::new();
+            for i in 0..imports {
+                content.push_str(&format!("import {{ Bean{0} }} from \"./bean{0}\";\n", i));
+            }
+            content
+        };
+        assert_eq!(check(&file(150), 20), RegisterPressure::Ok);
+        assert!(matches!(
+            check(&file(185), 20),
+            RegisterPressure::NearLimit(_)
+        ));
+        assert!(matches!(check(&file(185), 0), RegisterPressure::Ok));
+        match check(&file(199), 20) {
+            RegisterPressure::OverLimit(estimate) => assert_eq!(estimate.definite(), 201),
+            other => panic!("expected OverLimit, got {:?}", other),
+        }
+
+        // A full default chunk: 100 imports plus a 100-entry object literal
+        let mut chunk = file(100);
+        chunk.push_str("export const Beans_1 = {\n");
+        for i in 0..100 {
+            chunk.push_str(&format!("    \"mod.Bean{0}\": Bean{0},\n", i));
+        }
+        chunk.push_str("} as const;");
+        match check(&chunk, 0) {
+            RegisterPressure::NearLimit(estimate) => {
+                assert_eq!((estimate.definite(), estimate.total()), (103, 203))
+            }
+            other => panic!("expected NearLimit, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_import_bindings() {
+        assert_eq!(import_bindings("import { A, B, C } from \"./a\";"), 3);
+        assert_eq!(import_bindings("import { A, type B } from \"./a\";"), 1);
+        assert_eq!(import_bindings("import type { A, B } from \"./a\";"), 0);
+        assert_eq!(import_bindings("import Default, { A } from \"./a\";"), 2);
+        assert_eq!(import_bindings("import * as Ns from \"./a\";"), 1);
+    }
+
+    #[test]
+    fn test_multiline_imports_and_nested_objects() {
+        let content = "import {\n    A,\n    B,\n} from \"./a\";\n\n/** Doc */\nexport const RoleConstants = {\n    /** Comment */\n    MAX: 1,\n    nested: {\n        x: 1,\n        y: 2,\n        z: 3,\n    },\n} as const;\n\nexport const ItemConstants = {\n    A: 1,\n} as const;\n\nexport interface Shape {\n    a: number;\n}\n";
+        let estimate = estimate(content);
+        assert_eq!(estimate.imports, 2);
+        assert_eq!(estimate.locals, 2);
+        // `nested` has 3 properties, RoleConstants 2 (MAX and nested)
+        assert_eq!(estimate.max_object_properties, 3);
+    }
+}
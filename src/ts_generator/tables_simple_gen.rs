@@ -1,8 +1,10 @@
-use crate::parser::ClassInfo;
-use crate::table_registry::TableRegistry;
+use crate::config::TsTypeOverride;
+use crate::parser::{normalize_path, ClassInfo, FieldInfo};
+use crate::table_registry::{ResolvedTableConfig, TableRegistry};
 use crate::ts_generator::import_resolver::ImportResolver;
+use crate::type_mapper::TypeMapper;
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 /// Simple tables generator - only generates type definitions
 /// Uses [[tables]] config from TableRegistry, ignores @LubanTable decorators
@@ -10,6 +12,10 @@ pub struct TablesSimpleGenerator<'a> {
     import_resolver: &'a ImportResolver,
     table_registry: &'a TableRegistry,
     default_module_name: &'a str,
+    /// Directory that relative `transform` modules are resolved against
+    project_root: Option<&'a Path>,
+    /// [ts_type_overrides], with the mapper that gives each field's Luban type
+    ts_type_overrides: Option<(&'a HashMap<String, TsTypeOverride>, &'a TypeMapper)>,
 }
 
 impl<'a> TablesSimpleGenerator<'a> {
@@ -22,19 +28,41 @@ impl<'a> TablesSimpleGenerator<'a> {
             import_resolver,
             table_registry,
             default_module_name,
+            project_root: None,
+            ts_type_overrides: None,
         }
     }
 
+    /// Type fields whose Luban type has an override (`text`, `datetime`) with the
+    /// override's TypeScript type, through a `{Class}Row` type per table
+    pub fn with_ts_type_overrides(
+        mut self,
+        ts_type_overrides: &'a HashMap<String, TsTypeOverride>,
+        type_mapper: &'a TypeMapper,
+    ) -> Self {
+        if !ts_type_overrides.is_empty() {
+            self.ts_type_overrides = Some((ts_type_overrides, type_mapper));
+        }
+        self
+    }
+
+    /// Resolve relative table `transform` modules against this directory
+    /// (otherwise against the current directory)
+    pub fn with_project_root(mut self, project_root: &'a Path) -> Self {
+        self.project_root = Some(project_root);
+        self
+    }
+
     /// Generate tables.ts with type definitions only
     /// Uses [[tables]] config from TableRegistry
     pub fn generate(&self, table_classes: &[&ClassInfo], output_path: &Path) -> String {
         let mut lines = Vec::new();
 
         // Collect imports
-        let mut imports = HashMap::new();
+        let mut imports: HashMap<String, Vec<&str>> = HashMap::new();
         for class in table_classes {
-            let source_path = PathBuf::from(&class.source_file);
-            let import_path = self.import_resolver.resolve(output_path, &source_path);
+            let source_path = class.file_path();
+            let import_path = self.import_resolver.resolve(output_path, source_path);
 
             imports
                 .entry(import_path)
@@ -42,8 +70,63 @@ impl<'a> TablesSimpleGenerator<'a> {
                 .push(class.name.as_str());
         }
 
-        // Generate import statements
-        for (import_path, class_names) in imports {
+        // Collect table entries, adding imports for enum key types
+        let mut table_entries = Vec::new();
+        let mut index_entries = Vec::new();
+        let mut transform_entries = Vec::new();
+        let mut row_entries = Vec::new();
+        let mut singleton_entries = Vec::new();
+        for &class in table_classes {
+            // Get config from TableRegistry
+            if let Some(config) = self.table_config(class) {
+                // Rows with overridden field types are typed as `{Class}Row`
+                let overridden = self.overridden_fields(class, output_path, &mut imports);
+                let row_type = if overridden.is_empty() {
+                    class.name.clone()
+                } else {
+                    let row_type = format!("{}Row", class.name);
+                    row_entries.push((row_type.clone(), class.name.as_str(), overridden));
+                    row_type
+                };
+
+                let type_def = self.generate_table_type(
+                    class,
+                    &row_type,
+                    &config.mode,
+                    &config.index,
+                    output_path,
+                    &mut imports,
+                );
+                table_entries.push((config.name.clone(), type_def));
+                if config.mode == "list" && !config.secondary_indexes.is_empty() {
+                    let indexes = self.generate_table_indexes(
+                        class,
+                        &row_type,
+                        &config.secondary_indexes,
+                        output_path,
+                        &mut imports,
+                    );
+                    index_entries.push((config.name.clone(), indexes));
+                }
+                if is_singleton(config) {
+                    singleton_entries.push((
+                        class.name.as_str(),
+                        row_type.clone(),
+                        config.required,
+                    ));
+                }
+                if let Some(transform) = &config.transform {
+                    let import_path = self.module_import_path(transform, output_path);
+                    transform_entries.push((config.name.clone(), row_type, import_path));
+                }
+            }
+        }
+
+        // Generate import statements (sorted for deterministic output)
+        let mut sorted_imports: Vec<_> = imports.into_iter().collect();
+        sorted_imports.sort_by(|a, b| a.0.cmp(&b.0));
+        for (import_path, mut class_names) in sorted_imports {
+            class_names.sort();
             lines.push(format!(
                 "import {{ {} }} from \"{}\";",
                 class_names.join(", "),
@@ -55,33 +138,31 @@ impl<'a> TablesSimpleGenerator<'a> {
             lines.push(String::new());
         }
 
+        // Row types: the class with overridden fields retyped as they are at runtime
+        if !row_entries.is_empty() {
+            row_entries.sort_by(|a, b| a.0.cmp(&b.0));
+            for (row_type, class_name, fields) in &row_entries {
+                let names: Vec<String> = fields
+                    .iter()
+                    .map(|(name, _)| format!("\"{}\"", name))
+                    .collect();
+                lines.push(format!(
+                    "export type {} = Omit<{}, {}> & {{",
+                    row_type,
+                    class_name,
+                    names.join(" | ")
+                ));
+                for (name, ts_type) in fields {
+                    lines.push(format!("    {}: {};", name, ts_type));
+                }
+                lines.push("};".to_string());
+                lines.push(String::new());
+            }
+        }
+
         // Generate AllTables interface - use TableRegistry config
         lines.push("export interface AllTables {".to_string());
 
-        // Collect and sort table entries
-        let mut table_entries: Vec<_> = table_classes
-            .iter()
-            .filter_map(|class| {
-                // Build full name to look up in registry
-                // Use class's module_name, or fall back to default_module_name
-                let module = class
-                    .module_name
-                    .as_deref()
-                    .unwrap_or(self.default_module_name);
-                let full_name = if module.is_empty() {
-                    class.name.clone()
-                } else {
-                    format!("{}.{}", module, class.name)
-                };
-
-                // Get config from TableRegistry
-                self.table_registry.get_table(&full_name).map(|config| {
-                    let type_def = self.generate_table_type(class, &config.mode, &config.index);
-                    (config.name.clone(), type_def)
-                })
-            })
-            .collect();
-
         // Sort by table name for consistent output
         table_entries.sort_by(|a, b| a.0.cmp(&b.0));
 
@@ -90,16 +171,220 @@ impl<'a> TablesSimpleGenerator<'a> {
         }
         lines.push("}".to_string());
 
+        // Getters of one/singleton tables (implemented by singletons.ts): required tables
+        // always return their row
+        if !singleton_entries.is_empty() {
+            singleton_entries.sort_by(|a, b| a.0.cmp(b.0));
+            lines.push(String::new());
+            lines.push("export interface AllTableSingletons {".to_string());
+            for (class_name, row_type, required) in &singleton_entries {
+                let return_type = if *required {
+                    row_type.clone()
+                } else {
+                    format!("{} | undefined", row_type)
+                };
+                lines.push(format!("    get{}(): {};", class_name, return_type));
+            }
+            lines.push("}".to_string());
+        }
+
+        // Lookup maps over the secondary indexes of list tables
+        if !index_entries.is_empty() {
+            index_entries.sort_by(|a, b| a.0.cmp(&b.0));
+            for (table_name, members) in &index_entries {
+                lines.push(String::new());
+                lines.push(format!("export interface {}Indexes {{", table_name));
+                lines.extend(members.iter().map(|m| format!("    {}", m)));
+                lines.push("}".to_string());
+            }
+            lines.push(String::new());
+            lines.push("export interface AllTableIndexes {".to_string());
+            for (table_name, _) in &index_entries {
+                lines.push(format!("    {0}: {0}Indexes;", table_name));
+            }
+            lines.push("}".to_string());
+        }
+
+        // Row transforms: the signature each table's transform module must default-export.
+        // `CheckedTransform` makes tsc report a transform whose export doesn't match
+        if !transform_entries.is_empty() {
+            transform_entries.sort_by(|a, b| a.0.cmp(&b.0));
+            lines.push(String::new());
+            lines.push("type CheckedTransform<Signature, T extends Signature> = T;".to_string());
+            for (table_name, row_type, import_path) in &transform_entries {
+                lines.push(String::new());
+                lines.push(format!(
+                    "export type {}Transform = (rows: {}[]) => {}[];",
+                    table_name, row_type, row_type
+                ));
+                lines.push(format!(
+                    "export type {0}TransformModule = CheckedTransform<{0}Transform, typeof import(\"{1}\").default>;",
+                    table_name, import_path
+                ));
+            }
+        }
+
         lines.join("\n")
     }
 
+    /// singletons.ts: `createTableSingletons`, implementing `AllTableSingletons` over the
+    /// loaded rows. A getter throws, naming the table and its input, when the data holds
+    /// more than one row, or no row for a required table. None without one/singleton tables
+    pub fn generate_singletons(&self, table_classes: &[&ClassInfo]) -> Option<String> {
+        let mut singletons: Vec<(&ClassInfo, &ResolvedTableConfig)> = table_classes
+            .iter()
+            .filter_map(|&class| Some((class, self.table_config(class)?)))
+            .filter(|(_, config)| is_singleton(config))
+            .collect();
+        if singletons.is_empty() {
+            return None;
+        }
+        singletons.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+
+        let mut lines = vec![
+            "import type { AllTables, AllTableSingletons } from \"./tables\";".to_string(),
+            String::new(),
+            "function singleRow<T>(".to_string(),
+            "    rows: readonly T[] | undefined,".to_string(),
+            "    table: string,".to_string(),
+            "    input: string,".to_string(),
+            "    required: boolean,".to_string(),
+            "): T | undefined {".to_string(),
+            "    const count = rows === undefined ? 0 : rows.size();".to_string(),
+            "    if (count > 1 || (count === 0 && required)) {".to_string(),
+            "        throw `${table} (input \"${input}\") must hold exactly one row, but the loaded data has ${count}`;".to_string(),
+            "    }".to_string(),
+            "    return rows?.[0];".to_string(),
+            "}".to_string(),
+            String::new(),
+            "/** `rows(table)` returns the loaded rows of a table */".to_string(),
+            "export function createTableSingletons(".to_string(),
+            "    rows: <K extends keyof AllTables>(table: K) => readonly AllTables[K][] | undefined,".to_string(),
+            "): AllTableSingletons {".to_string(),
+            "    return {".to_string(),
+        ];
+        for (class, config) in singletons {
+            let call = format!(
+                "singleRow(rows(\"{0}\"), \"{0}\", {1:?}, {2})",
+                config.name, config.input, config.required
+            );
+            lines.push(if config.required {
+                format!("        get{}: () => {}!,", class.name, call)
+            } else {
+                format!("        get{}: () => {},", class.name, call)
+            });
+        }
+        lines.push("    };".to_string());
+        lines.push("}".to_string());
+        Some(lines.join("\n"))
+    }
+
+    /// Resolved [tables] entry of a class (class module, else the default module)
+    fn table_config(&self, class: &ClassInfo) -> Option<&'a ResolvedTableConfig> {
+        let module = class
+            .module_name
+            .as_deref()
+            .unwrap_or(self.default_module_name);
+        let full_name = if module.is_empty() {
+            class.name.clone()
+        } else {
+            format!("{}.{}", module, class.name)
+        };
+        self.table_registry.get_table(&full_name)
+    }
+
+    /// Import path of a configured module (a transform or an override type's import) from
+    /// the generated file. Relative paths are resolved against the project root; bare
+    /// specifiers (packages) are kept as written
+    fn module_import_path(&self, module: &str, output_path: &Path) -> String {
+        if !module.starts_with('.') && !Path::new(module).is_absolute() {
+            return module.to_string();
+        }
+        let module_path = match self.project_root {
+            Some(root) => normalize_path(&root.join(module)),
+            None => normalize_path(Path::new(module)),
+        };
+        self.import_resolver.resolve(output_path, &module_path)
+    }
+
+    /// TypeScript type of a field whose Luban type (`@type`, or the mapped TypeScript
+    /// type) has an override; list, array and set elements with one become `T[]`.
+    /// The override's import is added when it has one
+    fn field_override_type<'c>(
+        &self,
+        field: &FieldInfo,
+        output_path: &Path,
+        imports: &mut HashMap<String, Vec<&'c str>>,
+    ) -> Option<String>
+    where
+        'a: 'c,
+    {
+        let (overrides, type_mapper) = self.ts_type_overrides?;
+        let luban_type = match &field.type_override {
+            Some(type_override) => type_override.clone(),
+            None => type_mapper.map_full_type(&field.field_type),
+        };
+        let luban_type = luban_type
+            .split('#')
+            .next()
+            .unwrap_or("")
+            .trim_end_matches('?');
+        let element = ["list,", "array,", "set,"]
+            .iter()
+            .find_map(|prefix| luban_type.strip_prefix(prefix));
+        let ts_override = overrides.get(element.unwrap_or(luban_type))?;
+
+        if let Some(module) = ts_override.import() {
+            let import_path = self.module_import_path(module, output_path);
+            let name = ts_override.ts_type().split('<').next().unwrap_or("").trim();
+            let names = imports.entry(import_path).or_default();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        Some(match element {
+            Some(_) => format!("{}[]", ts_override.ts_type()),
+            None => ts_override.ts_type().to_string(),
+        })
+    }
+
+    /// (property name, TypeScript type) of every field with an overridden type
+    fn overridden_fields<'c>(
+        &self,
+        class: &ClassInfo,
+        output_path: &Path,
+        imports: &mut HashMap<String, Vec<&'c str>>,
+    ) -> Vec<(String, String)>
+    where
+        'a: 'c,
+    {
+        class
+            .ordered_fields()
+            .into_iter()
+            .filter_map(|field| {
+                let ts_type = self.field_override_type(field, output_path, imports)?;
+                Some((field.name.clone(), ts_type))
+            })
+            .collect()
+    }
+
     /// Generate table type based on mode
-    fn generate_table_type(&self, class: &ClassInfo, mode: &str, index: &str) -> String {
-        let class_name = &class.name;
+    fn generate_table_type<'c>(
+        &self,
+        class: &'c ClassInfo,
+        class_name: &str,
+        mode: &str,
+        index: &str,
+        output_path: &Path,
+        imports: &mut HashMap<String, Vec<&'c str>>,
+    ) -> String
+    where
+        'a: 'c,
+    {
         match mode {
             "map" => {
                 // Determine key type from index field
-                let key_type = self.get_index_field_ts_type(class, index);
+                let key_type = self.key_type_with_import(class, index, output_path, imports);
                 format!("Map<{}, {}>", key_type, class_name)
             }
             "list" => {
@@ -109,20 +394,97 @@ impl<'a> TablesSimpleGenerator<'a> {
                 class_name.to_string()
             }
             _ => {
-                // Default to map with number key
-                format!("Map<number, {}>", class_name)
+                // Custom mode passed through with defaults.allow_custom_modes: the loaded
+                // shape is up to the Luban fork
+                "unknown".to_string()
+            }
+        }
+    }
+
+    /// Members of the `{Table}Indexes` interface of a list table:
+    /// a readonly map and a lookup method per secondary index
+    fn generate_table_indexes<'c>(
+        &self,
+        class: &'c ClassInfo,
+        row_type: &str,
+        secondary_indexes: &[String],
+        output_path: &Path,
+        imports: &mut HashMap<String, Vec<&'c str>>,
+    ) -> Vec<String>
+    where
+        'a: 'c,
+    {
+        let mut members = Vec::new();
+        for index in secondary_indexes {
+            // Indexes are stored by schema name; accessors use the property name
+            let property = class
+                .fields
+                .iter()
+                .find(|f| f.schema_name() == index)
+                .map_or(index.as_str(), |f| f.name.as_str());
+            let key_type = self.key_type_with_import(class, index, output_path, imports);
+            let suffix = capitalize(property);
+            members.push(format!(
+                "readonly by{}: ReadonlyMap<{}, {}[]>;",
+                suffix, key_type, row_type
+            ));
+            members.push(format!(
+                "getBy{}(id: {}): {}[];",
+                suffix, key_type, row_type
+            ));
+        }
+        members
+    }
+
+    /// TypeScript type of an index field; enum (or other imported) key types
+    /// and overridden types get an import of their own
+    fn key_type_with_import<'c>(
+        &self,
+        class: &'c ClassInfo,
+        index: &str,
+        output_path: &Path,
+        imports: &mut HashMap<String, Vec<&'c str>>,
+    ) -> String
+    where
+        'a: 'c,
+    {
+        let index_field = class
+            .fields
+            .iter()
+            .find(|f| f.name == index || f.xml_name.as_deref() == Some(index));
+        if let Some(ts_type) =
+            index_field.and_then(|field| self.field_override_type(field, output_path, imports))
+        {
+            return ts_type;
+        }
+
+        let key_type = self.get_index_field_ts_type(class, index);
+        if let Some(source) = class.imports.get(key_type) {
+            let import_path = self.import_resolver.resolve(output_path, source);
+            let names = imports.entry(import_path).or_default();
+            if !names.contains(&key_type) {
+                names.push(key_type);
             }
         }
+        key_type.to_string()
     }
 
-    /// Get TypeScript type for the index field
-    fn get_index_field_ts_type(&self, class: &ClassInfo, index: &str) -> &'static str {
+    /// Get TypeScript type for the index field: number/string, a number-literal union
+    /// (`1 | 2 | 3`) or an imported enum
+    fn get_index_field_ts_type<'c>(&self, class: &'c ClassInfo, index: &str) -> &'c str {
         // Find the index field in the class fields
-        if let Some(field) = class.fields.iter().find(|f| f.name == index) {
+        // The index may be the property name or the @Name schema name
+        if let Some(field) = class
+            .fields
+            .iter()
+            .find(|f| f.name == index || f.xml_name.as_deref() == Some(index))
+        {
             // Use original_type which is the TypeScript type before mapping
             match field.original_type.as_str() {
                 "string" => "string",
                 "number" | "int" | "float" | "double" | "long" => "number",
+                union if !field.validators.set_values.is_empty() && union.contains(" | ") => union,
+                imported if class.imports.contains_key(imported) => imported,
                 _ => "number", // Default to number for unknown types
             }
         } else {
@@ -132,6 +494,19 @@ impl<'a> TablesSimpleGenerator<'a> {
     }
 }
 
+/// Tables holding a single row
+fn is_singleton(config: &ResolvedTableConfig) -> bool {
+    matches!(config.mode.as_str(), "one" | "singleton")
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,6 +531,10 @@ mod tests {
                 name: name.map(|s| s.to_string()),
                 mode: mode.map(|s| s.to_string()),
                 index: index.map(|s| s.to_string()),
+                secondary_indexes: vec![],
+                transform: None,
+                required: None,
+                groups: vec![],
             },
         );
         TableRegistry::from_config(&tables)
@@ -181,6 +560,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "hash".to_string(),
             is_interface: true,
             output_path: None,
@@ -190,6 +570,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let output_path = PathBuf::from("out/tables.d.ts");
@@ -219,6 +611,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "hash".to_string(),
             is_interface: false,
             output_path: None,
@@ -228,6 +621,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let output_path = PathBuf::from("out/tables.d.ts");
@@ -264,6 +669,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "hash".to_string(),
             is_interface: false,
             output_path: None,
@@ -273,6 +679,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let output_path = PathBuf::from("out/tables.d.ts");
@@ -314,6 +732,7 @@ mod tests {
             implements: vec![],
             extends: None,
             source_file: "test.ts".to_string(),
+            source_path: Default::default(),
             file_hash: "hash".to_string(),
             is_interface: false,
             output_path: None,
@@ -323,6 +742,18 @@ mod tests {
             table_config: None,
             input_path: None,
             imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
         };
 
         let output_path = PathBuf::from("out/tables.d.ts");
@@ -335,4 +766,356 @@ mod tests {
             content
         );
     }
+
+    #[test]
+    fn test_list_table_secondary_indexes() {
+        let resolver = ImportResolver::new(&TsConfig::default());
+        let mut tables = HashMap::new();
+        tables.insert(
+            "SkillBinding".to_string(),
+            TableConfig::Full {
+                input: "../datas/skill-binding".to_string(),
+                name: None,
+                mode: Some("list".to_string()),
+                index: None,
+                secondary_indexes: vec!["skillId".to_string(), "owner".to_string()],
+                transform: None,
+                required: None,
+                groups: vec![],
+            },
+        );
+        let registry = TableRegistry::from_config(&tables);
+        let gen = TablesSimpleGenerator::new(&resolver, &registry, "");
+
+        let field = |name: &str, original_type: &str| FieldInfo {
+            name: name.to_string(),
+            field_type: original_type.to_string(),
+            original_type: original_type.to_string(),
+            ..Default::default()
+        };
+        let mut owner = field("ownerId", "string");
+        owner.xml_name = Some("owner".to_string());
+        let class = ClassInfo {
+            name: "SkillBinding".to_string(),
+            comment: None,
+            alias: None,
+            fields: vec![field("skillId", "number"), owner],
+            implements: vec![],
+            extends: None,
+            source_file: "test.ts".to_string(),
+            source_path: Default::default(),
+            file_hash: "hash".to_string(),
+            is_interface: false,
+            output_path: None,
+            module_name: None,
+            type_params: Default::default(),
+            luban_table: None,
+            table_config: None,
+            input_path: None,
+            imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
+        };
+
+        let output_path = PathBuf::from("out/tables.d.ts");
+        let content = gen.generate(&[&class], &output_path);
+
+        assert!(content.contains("    SkillBindingTable: SkillBinding[];"));
+        assert!(content.ends_with(
+            "export interface SkillBindingTableIndexes {
+    readonly bySkillId: ReadonlyMap<number, SkillBinding[]>;
+    getBySkillId(id: number): SkillBinding[];
+    readonly byOwnerId: ReadonlyMap<string, SkillBinding[]>;
+    getByOwnerId(id: string): SkillBinding[];
+}
+
+export interface AllTableIndexes {
+    SkillBindingTable: SkillBindingTableIndexes;
+}"
+        ));
+    }
+
+    #[test]
+    fn test_table_transform_signatures() {
+        let resolver = ImportResolver::new(&TsConfig::default());
+        let table = |input: &str, transform: Option<&str>| TableConfig::Full {
+            input: input.to_string(),
+            name: None,
+            mode: None,
+            index: None,
+            secondary_indexes: vec![],
+            transform: transform.map(|s| s.to_string()),
+            required: None,
+            groups: vec![],
+        };
+        let mut tables = HashMap::new();
+        tables.insert(
+            "RoleConfig".to_string(),
+            table("../datas/role", Some("./transforms/role-config")),
+        );
+        tables.insert(
+            "DropConfig".to_string(),
+            table("../datas/drop", Some("@game/drop-patches")),
+        );
+        tables.insert("ItemConfig".to_string(), table("../datas/item", None));
+        let registry = TableRegistry::from_config(&tables);
+        let project_root = PathBuf::from("/proj");
+        let gen =
+            TablesSimpleGenerator::new(&resolver, &registry, "").with_project_root(&project_root);
+
+        let class = |name: &str| ClassInfo {
+            name: name.to_string(),
+            comment: None,
+            alias: None,
+            fields: vec![],
+            implements: vec![],
+            extends: None,
+            source_file: format!("/proj/src/{}.ts", name),
+            source_path: Default::default(),
+            file_hash: "hash".to_string(),
+            is_interface: false,
+            output_path: None,
+            module_name: None,
+            type_params: Default::default(),
+            luban_table: None,
+            table_config: None,
+            input_path: None,
+            imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
+        };
+        let role = class("RoleConfig");
+        let drops = class("DropConfig");
+        let item = class("ItemConfig");
+
+        let output_path = PathBuf::from("/proj/out/config/tables.d.ts");
+        let content = gen.generate(&[&role, &drops, &item], &output_path);
+
+        assert!(content.ends_with(
+            "type CheckedTransform<Signature, T extends Signature> = T;
+
+export type DropConfigTableTransform = (rows: DropConfig[]) => DropConfig[];
+export type DropConfigTableTransformModule = CheckedTransform<DropConfigTableTransform, typeof import(\"@game/drop-patches\").default>;
+
+export type RoleConfigTableTransform = (rows: RoleConfig[]) => RoleConfig[];
+export type RoleConfigTableTransformModule = CheckedTransform<RoleConfigTableTransform, typeof import(\"../../transforms/role-config\").default>;"
+        ));
+        assert!(!content.contains("ItemConfigTableTransform"));
+
+        // Without any transform the file ends with AllTables
+        let content = gen.generate(&[&item], &output_path);
+        assert!(content.ends_with("    ItemConfigTable: Map<number, ItemConfig>;\n}"));
+        assert!(!content.contains("CheckedTransform"));
+    }
+
+    #[test]
+    fn test_singleton_table_getters() {
+        let resolver = ImportResolver::new(&TsConfig::default());
+        let table = |input: &str, mode: &str, required: Option<bool>| TableConfig::Full {
+            input: input.to_string(),
+            name: None,
+            mode: Some(mode.to_string()),
+            index: None,
+            secondary_indexes: vec![],
+            transform: None,
+            required,
+            groups: vec![],
+        };
+        let mut tables = HashMap::new();
+        tables.insert(
+            "GlobalConfig".to_string(),
+            table("../datas/global", "one", None),
+        );
+        tables.insert(
+            "EventConfig".to_string(),
+            table("../datas/event", "singleton", Some(false)),
+        );
+        tables.insert(
+            "ItemConfig".to_string(),
+            table("../datas/item", "map", None),
+        );
+        let registry = TableRegistry::from_config(&tables);
+        let gen = TablesSimpleGenerator::new(&resolver, &registry, "");
+
+        let class = |name: &str| ClassInfo {
+            name: name.to_string(),
+            comment: None,
+            alias: None,
+            fields: vec![],
+            implements: vec![],
+            extends: None,
+            source_file: format!("/proj/src/{}.ts", name),
+            source_path: Default::default(),
+            file_hash: "hash".to_string(),
+            is_interface: false,
+            output_path: None,
+            module_name: None,
+            type_params: Default::default(),
+            luban_table: None,
+            table_config: None,
+            input_path: None,
+            imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
+        };
+        let global = class("GlobalConfig");
+        let event = class("EventConfig");
+        let item = class("ItemConfig");
+
+        let output_path = PathBuf::from("/proj/out/tables.d.ts");
+        let content = gen.generate(&[&global, &event, &item], &output_path);
+        assert!(
+            content.ends_with(
+                "export interface AllTableSingletons {
+    getEventConfig(): EventConfig | undefined;
+    getGlobalConfig(): GlobalConfig;
+}"
+            ),
+            "{}",
+            content
+        );
+
+        let singletons = gen.generate_singletons(&[&global, &event, &item]).unwrap();
+        assert!(singletons
+            .starts_with("import type { AllTables, AllTableSingletons } from \"./tables\";"));
+        assert!(singletons.contains(
+            "        throw `${table} (input \"${input}\") must hold exactly one row, but the loaded data has ${count}`;"
+        ));
+        assert!(singletons.ends_with(
+            "): AllTableSingletons {
+    return {
+        getEventConfig: () => singleRow(rows(\"EventConfigTable\"), \"EventConfigTable\", \"../datas/event\", false),
+        getGlobalConfig: () => singleRow(rows(\"GlobalConfigTable\"), \"GlobalConfigTable\", \"../datas/global\", true)!,
+    };
+}"
+        ));
+
+        // No one/singleton tables: no getters, no singletons.ts
+        let content = gen.generate(&[&item], &output_path);
+        assert!(!content.contains("AllTableSingletons"));
+        assert!(gen.generate_singletons(&[&item]).is_none());
+    }
+
+    #[test]
+    fn test_ts_type_overrides_row_type() {
+        let resolver = ImportResolver::new(&TsConfig::default());
+        let registry = create_registry_with_table(
+            "MailConfig",
+            "../datas/mail",
+            None,
+            Some("map"),
+            Some("id"),
+        );
+        let project_root = PathBuf::from("/proj");
+        let type_mapper = TypeMapper::new(&HashMap::new());
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            "text".to_string(),
+            TsTypeOverride::Full {
+                ts_type: "LocalizedText".to_string(),
+                import: Some("./shared/localization".to_string()),
+            },
+        );
+        overrides.insert(
+            "datetime".to_string(),
+            TsTypeOverride::Simple("number".to_string()),
+        );
+        let gen = TablesSimpleGenerator::new(&resolver, &registry, "")
+            .with_project_root(&project_root)
+            .with_ts_type_overrides(&overrides, &type_mapper);
+
+        let field = |name: &str, field_type: &str, type_override: Option<&str>| FieldInfo {
+            name: name.to_string(),
+            field_type: field_type.to_string(),
+            original_type: field_type.to_string(),
+            type_override: type_override.map(|s| s.to_string()),
+            ..Default::default()
+        };
+        let class = ClassInfo {
+            name: "MailConfig".to_string(),
+            comment: None,
+            alias: None,
+            fields: vec![
+                field("id", "number", None),
+                field("title", "string", Some("text")),
+                field("sentAt", "string", Some("datetime")),
+            ],
+            implements: vec![],
+            extends: None,
+            source_file: "/proj/src/mail.ts".to_string(),
+            source_path: Default::default(),
+            file_hash: "hash".to_string(),
+            is_interface: false,
+            output_path: None,
+            module_name: None,
+            type_params: Default::default(),
+            luban_table: None,
+            table_config: None,
+            input_path: None,
+            imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
+        };
+
+        let output_path = PathBuf::from("/proj/out/tables.d.ts");
+        let content = gen.generate(&[&class], &output_path);
+
+        assert!(content.contains("import { LocalizedText } from \"../shared/localization\";"));
+        assert!(content.ends_with(
+            "export type MailConfigRow = Omit<MailConfig, \"title\" | \"sentAt\"> & {
+    title: LocalizedText;
+    sentAt: number;
+};
+
+export interface AllTables {
+    MailConfigTable: Map<number, MailConfigRow>;
+}"
+        ));
+
+        // Without overrides rows keep the class type
+        let plain = TablesSimpleGenerator::new(&resolver, &registry, "");
+        let content = plain.generate(&[&class], &output_path);
+        assert!(!content.contains("MailConfigRow"));
+        assert!(!content.contains("LocalizedText"));
+    }
 }
@@ -22,13 +22,126 @@ pub struct CompilerOptions {
 }
 
 impl TsConfig {
+    /// Load a tsconfig, following its `extends` chain. Compiler options of the extended
+    /// configs are merged option by option, the extending config winning (as tsc does, an
+    /// option such as `paths` replaces the inherited value as a whole). An inherited
+    /// `baseUrl` stays relative to the config that declared it. `references` is ignored
     pub fn load(path: &Path) -> anyhow::Result<Self> {
-        let content = std::fs::read_to_string(path)?;
-        // Remove comments (tsconfig allows them)
-        let cleaned = remove_json_comments(&content);
-        let config: TsConfig = serde_json::from_str(&cleaned)?;
-        Ok(config)
+        let mut options = load_compiler_options(path, &mut Vec::new())?;
+        // Inherited baseUrls were made absolute; express them relative to this config again
+        if let Some(serde_json::Value::String(base_url)) = options.get_mut("baseUrl") {
+            let dir = config_dir(path);
+            if let Some(relative) = pathdiff::diff_paths(&*base_url, &dir) {
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                *base_url = if relative.is_empty() {
+                    ".".to_string()
+                } else {
+                    relative
+                };
+            }
+        }
+        let compiler_options = serde_json::from_value(serde_json::Value::Object(options))?;
+        Ok(Self { compiler_options })
+    }
+}
+
+/// The parts of a tsconfig file read while following `extends`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTsConfig {
+    #[serde(default)]
+    extends: Option<Extends>,
+    #[serde(default)]
+    compiler_options: serde_json::Map<String, serde_json::Value>,
+}
+
+/// `"extends": "./base.json"` or, since TypeScript 5.0, a list applied in order
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Extends {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Compiler options of `path` merged over those of the configs it extends, with `baseUrl`
+/// made absolute. `chain` holds the configs being loaded, to report cycles
+fn load_compiler_options(
+    path: &Path,
+    chain: &mut Vec<PathBuf>,
+) -> anyhow::Result<serde_json::Map<String, serde_json::Value>> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if let Some(start) = chain.iter().position(|p| *p == canonical) {
+        let cycle: Vec<String> = chain[start..]
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect();
+        anyhow::bail!("Circular tsconfig extends: {}", cycle.join(" -> "));
+    }
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+    // tsconfig is JSONC: comments and trailing commas are allowed
+    let cleaned = remove_trailing_commas(&remove_json_comments(&content));
+    let raw: RawTsConfig = serde_json::from_str(&cleaned)
+        .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))?;
+
+    let dir = config_dir(path);
+    let bases = match raw.extends {
+        None => Vec::new(),
+        Some(Extends::One(base)) => vec![base],
+        Some(Extends::Many(bases)) => bases,
+    };
+    chain.push(canonical);
+    let mut options = serde_json::Map::new();
+    for base in &bases {
+        let base_path = resolve_extends(base, &dir).ok_or_else(|| {
+            anyhow::anyhow!(
+                "{}: can't find the config \"{}\" it extends",
+                path.display(),
+                base
+            )
+        })?;
+        options.extend(load_compiler_options(&base_path, chain)?);
+    }
+    chain.pop();
+
+    let mut own = raw.compiler_options;
+    if let Some(serde_json::Value::String(base_url)) = own.get_mut("baseUrl") {
+        *base_url = dir.join(&*base_url).to_string_lossy().to_string();
     }
+    options.extend(own);
+    Ok(options)
+}
+
+/// Directory a config's relative paths are resolved against
+fn config_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// File an `extends` specifier points at: a path relative to the extending config, or a
+/// package specifier looked up in `node_modules` of `dir` and its ancestors. A missing
+/// `.json` extension is added, and a package directory means its tsconfig.json
+fn resolve_extends(specifier: &str, dir: &Path) -> Option<PathBuf> {
+    let candidates = |base: PathBuf| {
+        let with_json = PathBuf::from(format!("{}.json", base.display()));
+        let in_dir = base.join("tsconfig.json");
+        [base, with_json, in_dir]
+    };
+    let is_path = specifier.starts_with('.') || Path::new(specifier).is_absolute();
+    if is_path {
+        return candidates(dir.join(specifier))
+            .into_iter()
+            .find(|p| p.is_file());
+    }
+    dir.ancestors().find_map(|ancestor| {
+        candidates(ancestor.join("node_modules").join(specifier))
+            .into_iter()
+            .find(|p| p.is_file())
+    })
 }
 
 impl Default for TsConfig {
@@ -87,13 +200,43 @@ fn remove_json_comments(input: &str) -> String {
     result
 }
 
-#[allow(dead_code)]
+/// Drop commas directly followed (after whitespace) by `}` or `]`. Expects comments to be
+/// removed already; commas inside strings are kept
+fn remove_trailing_commas(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut chars = input.char_indices().peekable();
+    let mut in_string = false;
+
+    while let Some((i, c)) = chars.next() {
+        if in_string {
+            result.push(c);
+            if c == '"' {
+                in_string = false;
+            } else if c == '\\' {
+                if let Some((_, next)) = chars.next() {
+                    result.push(next);
+                }
+            }
+        } else if c == '"' {
+            in_string = true;
+            result.push(c);
+        } else if c == ',' {
+            let rest = input[i + 1..].trim_start();
+            if !(rest.starts_with('}') || rest.starts_with(']')) {
+                result.push(c);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
 pub struct PathResolver {
     base_url: PathBuf,
     paths: Vec<(String, String)>,
 }
 
-#[allow(dead_code)]
 impl PathResolver {
     pub fn new(tsconfig: &TsConfig, project_root: &Path) -> Self {
         let base_url = tsconfig
@@ -129,6 +272,13 @@ impl PathResolver {
         self.base_url.join(import_path)
     }
 
+    /// Whether `import_path` matches one of the `paths` aliases
+    pub fn is_alias(&self, import_path: &str) -> bool {
+        self.paths
+            .iter()
+            .any(|(pattern, _)| self.match_pattern(pattern, import_path).is_some())
+    }
+
     fn match_pattern(&self, pattern: &str, input: &str) -> Option<String> {
         if let Some(prefix) = pattern.strip_suffix("/*") {
             if let Some(suffix) = input.strip_prefix(&format!("{}/", prefix)) {
@@ -178,6 +328,155 @@ mod tests {
 
         let resolved = resolver.resolve("@types/models");
         assert_eq!(resolved, PathBuf::from("/project/src/types/models"));
+        assert!(resolver.is_alias("@types/models"));
+        assert!(!resolver.is_alias("@rbxts/services"));
+    }
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_single_extends() {
+        let temp = tempfile::tempdir().unwrap();
+        write(
+            temp.path(),
+            "tsconfig.base.json",
+            r#"{
+                // Shared options
+                "compilerOptions": {
+                    "baseUrl": "./src",
+                    "paths": { "shared/*": ["shared/*"] },
+                    "strict": true
+                }
+            }"#,
+        );
+        let child = write(
+            temp.path(),
+            "packages/game/tsconfig.json",
+            r#"{ "extends": "../../tsconfig.base.json", "references": [{ "path": "../shared" }] }"#,
+        );
+
+        let tsconfig = TsConfig::load(&child).unwrap();
+        assert_eq!(
+            tsconfig.compiler_options.base_url.as_deref(),
+            Some("../../src")
+        );
+        assert_eq!(
+            tsconfig.compiler_options.paths["shared/*"],
+            vec!["shared/*".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_load_two_level_chain() {
+        let temp = tempfile::tempdir().unwrap();
+        write(
+            temp.path(),
+            "base.json",
+            r#"{ "compilerOptions": { "baseUrl": ".", "paths": { "a/*": ["a/*"] } } }"#,
+        );
+        write(
+            temp.path(),
+            "configs/middle.json",
+            r#"{ "extends": "../base", "compilerOptions": { "paths": { "b/*": ["b/*"] } } }"#,
+        );
+        let child = write(
+            temp.path(),
+            "tsconfig.json",
+            r#"{ "extends": ["./configs/middle.json"], "compilerOptions": { "baseUrl": "./src" } }"#,
+        );
+
+        let tsconfig = TsConfig::load(&child).unwrap();
+        // The child's baseUrl wins; `paths` from the middle config replaces the base's
+        assert_eq!(tsconfig.compiler_options.base_url.as_deref(), Some("src"));
+        assert_eq!(
+            tsconfig.compiler_options.paths.keys().collect::<Vec<_>>(),
+            vec!["b/*"]
+        );
+    }
+
+    #[test]
+    fn test_load_extends_package() {
+        let temp = tempfile::tempdir().unwrap();
+        write(
+            temp.path(),
+            "node_modules/@company/tsconfig/roblox.json",
+            r#"{ "compilerOptions": { "paths": { "@shared/*": ["shared/*"] } } }"#,
+        );
+        write(
+            temp.path(),
+            "node_modules/@company/tsconfig/tsconfig.json",
+            r#"{ "compilerOptions": { "paths": { "@default/*": ["default/*"] } } }"#,
+        );
+        let child = write(
+            temp.path(),
+            "game/tsconfig.json",
+            r#"{ "extends": "@company/tsconfig/roblox" }"#,
+        );
+        let tsconfig = TsConfig::load(&child).unwrap();
+        assert!(tsconfig.compiler_options.paths.contains_key("@shared/*"));
+
+        // A bare package name means the package's tsconfig.json
+        write(
+            temp.path(),
+            "game/tsconfig.json",
+            r#"{ "extends": "@company/tsconfig" }"#,
+        );
+        let tsconfig = TsConfig::load(&child).unwrap();
+        assert!(tsconfig.compiler_options.paths.contains_key("@default/*"));
+    }
+
+    #[test]
+    fn test_load_extends_cycle() {
+        let temp = tempfile::tempdir().unwrap();
+        write(temp.path(), "a.json", r#"{ "extends": "./b.json" }"#);
+        write(temp.path(), "b.json", r#"{ "extends": "./a.json" }"#);
+        let child = write(temp.path(), "tsconfig.json", r#"{ "extends": "./a.json" }"#);
+
+        let err = TsConfig::load(&child).unwrap_err().to_string();
+        assert!(err.starts_with("Circular tsconfig extends:"), "{}", err);
+        assert!(err.contains("a.json -> "), "{}", err);
+        assert!(err.ends_with("a.json"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_jsonc() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = write(
+            temp.path(),
+            "tsconfig.json",
+            r#"{
+                "compilerOptions": {
+                    "baseUrl": ".", // trailing comment
+                    "paths": {
+                        "http://*": ["vendor/http/*", ],
+                        "quoted\"//*": ["quoted/*"], /* "not a string */
+                        "end\\": ["end/*",],
+                    },
+                    "lib": [
+                        "ES2020",
+                    ],
+                },
+            }"#,
+        );
+
+        let tsconfig = TsConfig::load(&path).unwrap();
+        let paths = &tsconfig.compiler_options.paths;
+        assert_eq!(paths["http://*"], vec!["vendor/http/*".to_string()]);
+        assert_eq!(paths["quoted\"//*"], vec!["quoted/*".to_string()]);
+        assert_eq!(paths["end\\"], vec!["end/*".to_string()]);
+    }
+
+    #[test]
+    fn test_remove_trailing_commas() {
+        assert_eq!(
+            remove_trailing_commas(r#"{"a": [1, 2, ], "b": ",}", "c": {"d": "\",]",},}"#),
+            r#"{"a": [1, 2 ], "b": ",}", "c": {"d": "\",]"}}"#
+        );
     }
 
     #[test]
@@ -1,7 +1,9 @@
+use crate::config::NestedContainers;
 use std::collections::HashMap;
 
 pub struct TypeMapper {
     mappings: HashMap<String, String>,
+    nested_containers: NestedContainers,
 }
 
 impl TypeMapper {
@@ -13,7 +15,16 @@ impl TypeMapper {
             mappings.insert(key.to_lowercase(), value.clone());
         }
 
-        Self { mappings }
+        Self {
+            mappings,
+            nested_containers: NestedContainers::default(),
+        }
+    }
+
+    /// How a container inside another is written in mapped types
+    pub fn with_nested_containers(mut self, style: NestedContainers) -> Self {
+        self.nested_containers = style;
+        self
     }
 
     fn builtin_mappings() -> HashMap<String, String> {
@@ -53,36 +64,138 @@ impl TypeMapper {
     }
 
     pub fn map(&self, ts_type: &str) -> String {
+        self.map_within(ts_type, &mut Vec::new())
+    }
+
+    /// `map`, re-parsing container targets (`IntPair = "list,int"`) through `map_full_type`
+    /// so their elements are mapped too. `expanding` holds the mappings being expanded;
+    /// a cyclic one is returned as written
+    fn map_within(&self, ts_type: &str, expanding: &mut Vec<String>) -> String {
+        // Nullable element (`ItemDrop?`): map the type, keep the marker
+        if let Some(inner) = ts_type.strip_suffix('?') {
+            return format!("{}?", self.map_within(inner, expanding));
+        }
+
         // Check case-insensitive match
-        if let Some(mapped) = self.mappings.get(&ts_type.to_lowercase()) {
+        let key = ts_type.to_lowercase();
+        let Some(mapped) = self.mappings.get(&key) else {
+            // Return original type if no mapping found
+            return ts_type.to_string();
+        };
+        if container_of(mapped).is_none() || expanding.contains(&key) {
             return mapped.clone();
         }
+        expanding.push(key);
+        let resolved = self.map_full_type_within(mapped, expanding);
+        expanding.pop();
+        resolved
+    }
 
-        // Return original type if no mapping found
-        ts_type.to_string()
+    /// Luban types that some mapping resolves to (e.g. `Vector3`, `long`)
+    pub fn mapped_types(&self) -> impl Iterator<Item = &str> {
+        self.mappings.values().map(|v| v.as_str())
     }
 
     pub fn map_full_type(&self, field_type: &str) -> String {
-        // Handle list,T and map,K,V and set,T types
-        if field_type.starts_with("list,") {
-            let element = &field_type[5..];
-            return format!("list,{}", self.map(element));
-        }
+        self.map_full_type_within(field_type, &mut Vec::new())
+    }
 
-        if field_type.starts_with("set,") {
-            let element = &field_type[4..];
-            return format!("set,{}", self.map(element));
+    fn map_full_type_within(&self, field_type: &str, expanding: &mut Vec<String>) -> String {
+        if let Some(inner) = strip_nesting(field_type) {
+            return self.map_full_type_within(inner, expanding);
         }
 
-        if field_type.starts_with("map,") {
-            let parts: Vec<&str> = field_type[4..].splitn(2, ',').collect();
-            if parts.len() == 2 {
-                return format!("map,{},{}", self.map(parts[0]), self.map(parts[1]));
+        // Handle list,T and map,K,V and set,T types; elements may be containers themselves
+        for container in ["list", "set", "array"] {
+            if let Some(element) = field_type
+                .strip_prefix(container)
+                .and_then(|rest| rest.strip_prefix(','))
+            {
+                return format!("{},{}", container, self.map_component(element, expanding));
             }
         }
 
-        self.map(field_type)
+        if let Some((key, value)) = field_type
+            .strip_prefix("map,")
+            .and_then(|rest| rest.split_once(','))
+        {
+            return format!(
+                "map,{},{}",
+                self.map_component(key, expanding),
+                self.map_component(value, expanding)
+            );
+        }
+
+        self.map_within(field_type, expanding)
+    }
+
+    /// Map an element, key or value type; a container result is parenthesized unless
+    /// output.nested_containers is "flat"
+    fn map_component(&self, component: &str, expanding: &mut Vec<String>) -> String {
+        let mapped = self.map_full_type_within(component, expanding);
+        if self.nested_containers == NestedContainers::Parenthesized
+            && container_of(&mapped).is_some()
+        {
+            format!("({})", mapped)
+        } else {
+            mapped
+        }
+    }
+}
+
+/// The container (`list`, `set`, `array`, `map`) a Luban type starts with, if any
+pub(crate) fn container_of(luban_type: &str) -> Option<&str> {
+    let head = luban_type.trim_start_matches('(');
+    let head = head.split([',', '#']).next().unwrap_or(head);
+    ["list", "set", "array", "map"]
+        .into_iter()
+        .find(|container| *container == head)
+}
+
+/// The container type inside a nested element like `(list,int)`, if the parentheses
+/// enclose the whole type. Modifier groups like `(list#sep=|),int` aren't nesting
+pub fn strip_nesting(luban_type: &str) -> Option<&str> {
+    let inner = luban_type.strip_prefix('(')?.strip_suffix(')')?;
+    let mut depth = 0usize;
+    for c in inner.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1)?,
+            _ => {}
+        }
+    }
+    (depth == 0 && container_of(inner).is_some()).then_some(inner)
+}
+
+/// Check a [type_mappings] target: a container target must be `list,T`, `set,T`, `array,T`
+/// or `map,K,V` with non-empty, non-container element types. Returns what is wrong
+pub fn check_mapping_target(target: &str) -> Result<(), String> {
+    let Some(container) = container_of(target) else {
+        return Ok(());
+    };
+    if target.starts_with('(') {
+        return Err(
+            "container modifiers aren't supported here; put @Size/@sep on the field instead"
+                .to_string(),
+        );
+    }
+    let elements: Vec<&str> = target[container.len()..]
+        .strip_prefix(',')
+        .map(|rest| rest.split(',').collect())
+        .unwrap_or_default();
+    let arity = if container == "map" { 2 } else { 1 };
+    if elements.len() != arity || elements.iter().any(|e| e.trim().is_empty()) {
+        let expected = if container == "map" {
+            "map,K,V".to_string()
+        } else {
+            format!("{},T", container)
+        };
+        return Err(format!("expected {}", expected));
+    }
+    if let Some(nested) = elements.iter().find(|e| container_of(e).is_some()) {
+        return Err(format!("nested container '{}' is not supported", nested));
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -125,4 +238,98 @@ mod tests {
             "map,string,double"
         );
     }
+
+    #[test]
+    fn test_container_mapping_targets_are_reparsed() {
+        let mut custom = HashMap::new();
+        custom.insert("IntPair".to_string(), "list,int".to_string());
+        custom.insert("Weights".to_string(), "map,ItemId,number".to_string());
+        custom.insert("ItemId".to_string(), "int".to_string());
+        custom.insert("Loop".to_string(), "list,Loop".to_string());
+        let mapper = TypeMapper::new(&custom);
+        assert_eq!(mapper.map_full_type("IntPair"), "list,int");
+        assert_eq!(mapper.map_full_type("Weights"), "map,int,double");
+        // A cyclic mapping is expanded once
+        assert_eq!(mapper.map_full_type("Loop"), "list,(list,Loop)");
+    }
+
+    #[test]
+    fn test_check_mapping_target() {
+        assert!(check_mapping_target("long").is_ok());
+        assert!(check_mapping_target("list,int").is_ok());
+        assert!(check_mapping_target("map,string,int").is_ok());
+        assert!(check_mapping_target("listing").is_ok());
+        assert_eq!(
+            check_mapping_target("list,"),
+            Err("expected list,T".to_string())
+        );
+        assert_eq!(
+            check_mapping_target("list"),
+            Err("expected list,T".to_string())
+        );
+        assert_eq!(
+            check_mapping_target("map,int"),
+            Err("expected map,K,V".to_string())
+        );
+        assert_eq!(
+            check_mapping_target("list,list,int"),
+            Err("expected list,T".to_string())
+        );
+        assert_eq!(
+            check_mapping_target("map,int,list"),
+            Err("nested container 'list' is not supported".to_string())
+        );
+        assert!(check_mapping_target("(list#sep=|),int").is_err());
+    }
+
+    #[test]
+    fn test_map_nested_containers() {
+        let mut custom = HashMap::new();
+        custom.insert("ItemId".to_string(), "int".to_string());
+        let mapper = TypeMapper::new(&custom);
+        assert_eq!(
+            mapper.map_full_type("list,(list,(list,number))"),
+            "list,(list,(list,double))"
+        );
+        assert_eq!(
+            mapper.map_full_type("map,string,(map,ItemId,(list,Item))"),
+            "map,string,(map,int,(list,Item))"
+        );
+        // Flat input is parenthesized on the way through
+        assert_eq!(mapper.map_full_type("set,list,number"), "set,(list,double)");
+        assert_eq!(
+            mapper.map_full_type("(list#sep=|),number"),
+            "(list#sep=|),number"
+        );
+
+        let flat = TypeMapper::new(&custom).with_nested_containers(NestedContainers::Flat);
+        assert_eq!(
+            flat.map_full_type("map,string,(map,ItemId,(list,Item))"),
+            "map,string,map,int,list,Item"
+        );
+    }
+
+    #[test]
+    fn test_strip_nesting() {
+        assert_eq!(strip_nesting("(list,int)"), Some("list,int"));
+        assert_eq!(
+            strip_nesting("(map,int,(list,Item))"),
+            Some("map,int,(list,Item)")
+        );
+        assert_eq!(strip_nesting("(list#sep=|),int"), None);
+        assert_eq!(strip_nesting("(a),(b)"), None);
+        assert_eq!(strip_nesting("list,int"), None);
+    }
+
+    #[test]
+    fn test_map_keeps_nullable_marker() {
+        let mut custom = HashMap::new();
+        custom.insert("ItemDrop".to_string(), "DropInfo".to_string());
+        let mapper = TypeMapper::new(&custom);
+        assert_eq!(mapper.map_full_type("list,number?"), "list,double?");
+        assert_eq!(
+            mapper.map_full_type("map,string,ItemDrop?"),
+            "map,string,DropInfo?"
+        );
+    }
 }
@@ -1,14 +1,33 @@
-use crate::parser::field_info::{FieldValidators, SizeConstraint};
+use crate::config::{OpenRangeFormat, TableConfig};
+use crate::generator::bean_parent;
+use crate::parser::field_info::{FieldValidators, RangeConstraint, SizeConstraint};
+use crate::parser::{ClassInfo, EnumInfo, FieldInfo};
 use crate::table_registry::TableRegistry;
+use crate::type_mapper::TypeMapper;
+use crate::virtual_fields::nearest_name;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
 
 pub struct ValidatorGenerator<'a> {
     #[allow(dead_code)]
     registry: &'a TableRegistry,
+    /// How one-sided ranges are written (output.open_range_format)
+    open_range_format: OpenRangeFormat,
 }
 
 impl<'a> ValidatorGenerator<'a> {
     pub fn new(registry: &'a TableRegistry) -> Self {
-        Self { registry }
+        Self {
+            registry,
+            open_range_format: OpenRangeFormat::default(),
+        }
+    }
+
+    /// Set how one-sided ranges are written (output.open_range_format)
+    pub fn with_open_range_format(mut self, open_range_format: OpenRangeFormat) -> Self {
+        self.open_range_format = open_range_format;
+        self
     }
 
     /// Generate Luban type string with validators
@@ -23,8 +42,11 @@ impl<'a> ValidatorGenerator<'a> {
         }
 
         // Handle range
-        if let Some((min, max)) = &validators.range {
-            result.push_str(&format!("#range=[{},{}]", min, max));
+        if let Some(range) = &validators.range {
+            result.push_str(&format!(
+                "#range={}",
+                range_spec(range, base_type, self.open_range_format)
+            ));
         }
 
         // Handle set
@@ -71,3 +93,1452 @@ impl<'a> ValidatorGenerator<'a> {
         }
     }
 }
+
+/// Luban interval for a range validator on `base_type`: `[1,100]`, and for one-sided
+/// ranges `[0,]` / `[,59]`, or the type's limit in place of the missing bound
+pub fn range_spec(range: &RangeConstraint, base_type: &str, format: OpenRangeFormat) -> String {
+    let (low, high) = match format {
+        OpenRangeFormat::Open => (String::new(), String::new()),
+        OpenRangeFormat::Bounded => numeric_limits(base_type),
+    };
+    format!(
+        "[{},{}]",
+        range.min().map_or(low, format_number),
+        range.max().map_or(high, format_number)
+    )
+}
+
+/// Smallest and largest value of a Luban numeric type; unknown types use double's
+fn numeric_limits(luban_type: &str) -> (String, String) {
+    match luban_type {
+        "byte" => ("0".to_string(), u8::MAX.to_string()),
+        "short" => (i16::MIN.to_string(), i16::MAX.to_string()),
+        "int" => (i32::MIN.to_string(), i32::MAX.to_string()),
+        "long" => (i64::MIN.to_string(), i64::MAX.to_string()),
+        "float" => (format!("{:e}", f32::MIN), format!("{:e}", f32::MAX)),
+        _ => (format!("{:e}", f64::MIN), format!("{:e}", f64::MAX)),
+    }
+}
+
+/// Format a number, removing unnecessary decimal points
+/// e.g., 1.0 -> "1", 1.5 -> "1.5"
+fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 {
+        format!("{}", n as i64)
+    } else {
+        format!("{}", n)
+    }
+}
+
+/// Whether a mapped Luban type is a container (list/map/set/array)
+pub fn is_container_type(luban_type: &str) -> bool {
+    ["list,", "map,", "set,", "array,"]
+        .iter()
+        .any(|p| luban_type.starts_with(p))
+}
+
+/// Check that field default values (@Default decorator / @default JSDoc) match the field type.
+/// Returns one error message per invalid field, including its location.
+pub fn validate_default_values(classes: &[ClassInfo], type_mapper: &TypeMapper) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    for class in classes {
+        for field in &class.fields {
+            let Some(default) = &field.default_value else {
+                continue;
+            };
+            let luban_type = field
+                .type_override
+                .clone()
+                .unwrap_or_else(|| type_mapper.map_full_type(&field.field_type));
+            let location = format!("{}: {}.{}", class.source_file, class.name, field.name);
+
+            if is_container_type(&luban_type) {
+                errors.push(format!(
+                    "{} - default values are not supported on container fields (type {})",
+                    location, luban_type
+                ));
+                continue;
+            }
+
+            let valid = match luban_type.as_str() {
+                // Whole numbers only, like @Range bounds on the same types
+                "byte" | "short" | "int" | "long" => default.trim().parse::<i64>().is_ok(),
+                "float" | "double" => default
+                    .trim()
+                    .parse::<f64>()
+                    .is_ok_and(|value| value.is_finite()),
+                "bool" => matches!(default.trim(), "true" | "false" | "1" | "0"),
+                _ => true,
+            };
+            if !valid {
+                errors.push(format!(
+                    "{} - default value \"{}\" is not a valid {}",
+                    location, default, luban_type
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+/// Check that @Range bounds on integer fields (or integer list elements / map values) are
+/// whole numbers. Returns one error message per invalid field, including its location.
+pub fn validate_range_bounds(classes: &[ClassInfo], type_mapper: &TypeMapper) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    for class in classes {
+        for field in &class.fields {
+            let Some(range) = &field.validators.range else {
+                continue;
+            };
+            let luban_type = field
+                .type_override
+                .clone()
+                .unwrap_or_else(|| type_mapper.map_full_type(&field.field_type));
+            // The range applies to the element of a container
+            let value_type = luban_type.rsplit(',').next().unwrap_or(&luban_type);
+            let value_type = value_type.trim_end_matches('?');
+            if !matches!(value_type, "byte" | "short" | "int" | "long") {
+                continue;
+            }
+            let fractional: Vec<String> = [range.min(), range.max()]
+                .into_iter()
+                .flatten()
+                .filter(|bound| bound.fract() != 0.0)
+                .map(|bound| bound.to_string())
+                .collect();
+            if !fractional.is_empty() {
+                let location = format!(
+                    "{}: {}.{}",
+                    class.field_location(field),
+                    class.name,
+                    field.name
+                );
+                errors.push(format!(
+                    "{} - @Range bound {} must be a whole number for type {}",
+                    location,
+                    fractional.join(", "),
+                    value_type
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+/// Check that @Converter is not combined with a @type override on the same field.
+/// Returns one error message per conflicting field, including its location.
+pub fn validate_converters(classes: &[ClassInfo]) -> Vec<String> {
+    classes
+        .iter()
+        .flat_map(|class| {
+            class
+                .fields
+                .iter()
+                .filter(|f| f.converter.is_some() && f.type_override.is_some())
+                .map(move |f| {
+                    format!(
+                        "{}: {}.{} - @Converter and @type cannot be used together",
+                        class.source_file, class.name, f.name
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Check map key types: number-literal key unions must not mix in other types, and a key
+/// can't be a bean (use a scalar, an enum or a number-literal union).
+/// Returns one error message per invalid field, including its location.
+pub fn validate_map_keys(classes: &[ClassInfo], type_mapper: &TypeMapper) -> Vec<String> {
+    let bean_names: HashSet<&str> = classes.iter().map(|c| c.name.as_str()).collect();
+    let mut errors = Vec::new();
+
+    for class in classes {
+        for field in &class.fields {
+            let location = format!(
+                "{}: {}.{}",
+                class.field_location(field),
+                class.name,
+                field.name
+            );
+            if let Some(type_error) = &field.type_error {
+                errors.push(format!("{} - {}", location, type_error));
+                continue;
+            }
+            if field.converter.is_some() || field.validators.has_ref_key {
+                continue;
+            }
+
+            let luban_type = field
+                .type_override
+                .clone()
+                .unwrap_or_else(|| type_mapper.map_full_type(&field.field_type));
+            let Some(key_type) = luban_type
+                .strip_prefix("map,")
+                .and_then(|rest| rest.split(',').next())
+            else {
+                continue;
+            };
+            if bean_names.contains(key_type) {
+                errors.push(format!(
+                    "{} - map key type {} is a bean; use a scalar, an enum or a number-literal union",
+                    location, key_type
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+/// Luban numeric scalars; TypeScript `number` maps to one of them, so any of them can hold
+/// a numeric table key
+const NUMERIC_TYPES: [&str; 6] = ["byte", "short", "int", "long", "float", "double"];
+
+/// Check @Ref(Target) / @RefKey(Target) fields against the target tables: the target must
+/// have a [tables] entry, @RefKey only applies to maps, and the slot the ref lands on (the
+/// scalar, the list/array/set element, or the map value / key) must be able to hold the
+/// table's index type (`get_index_type`). Must run after `set_index_types`.
+/// Returns one error message per invalid field, including its location.
+pub fn validate_refs(
+    classes: &[ClassInfo],
+    type_mapper: &TypeMapper,
+    table_registry: &TableRegistry,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    for class in classes {
+        for field in &class.fields {
+            let validators = &field.validators;
+            if validators.ref_target.is_none() && validators.ref_key_target.is_none() {
+                continue;
+            }
+            let location = format!(
+                "{}: {}.{}",
+                class.field_location(field),
+                class.name,
+                field.name
+            );
+            let luban_type = field
+                .type_override
+                .clone()
+                .unwrap_or_else(|| type_mapper.map_full_type(&field.field_type));
+            let (key_slot, value_slot) = ref_slots(&luban_type);
+
+            let refs = [
+                ("Ref", &validators.ref_target, Some(value_slot)),
+                ("RefKey", &validators.ref_key_target, key_slot),
+            ];
+            for (decorator, target, slot) in refs {
+                let Some(target) = target else {
+                    continue;
+                };
+                let Some((slot, slot_type)) = slot else {
+                    errors.push(format!(
+                        "{} - @{}({}) only applies to map fields, the field is {}",
+                        location, decorator, target, luban_type
+                    ));
+                    continue;
+                };
+                if table_registry.resolve_ref(target).is_none() {
+                    errors.push(format!(
+                        "{} - @{}({}) target has no [tables] entry",
+                        location, decorator, target
+                    ));
+                    continue;
+                }
+                let Some(index_type) = table_registry.get_index_type(target) else {
+                    continue;
+                };
+                let compatible = slot_type == index_type
+                    || (NUMERIC_TYPES.contains(&slot_type) && NUMERIC_TYPES.contains(&index_type));
+                if !compatible {
+                    errors.push(format!(
+                        "{} - @{}({}) refers to a table indexed by {}, but the {} is {}",
+                        location, decorator, target, index_type, slot, slot_type
+                    ));
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+/// Where @RefKey and @Ref land in a mapped Luban type, as (description, type): the key and
+/// value of a map, otherwise no key and the list/array/set element or the scalar itself
+fn ref_slots(luban_type: &str) -> (Option<(String, &str)>, (String, &str)) {
+    if let Some(rest) = luban_type.strip_prefix("map,") {
+        let (key, value) = rest.split_once(',').unwrap_or((rest, ""));
+        return (
+            Some(("map key".to_string(), key.trim_end_matches('?'))),
+            ("map value".to_string(), value.trim_end_matches('?')),
+        );
+    }
+    for container in ["list", "array", "set"] {
+        if let Some(element) = luban_type
+            .strip_prefix(container)
+            .and_then(|rest| rest.strip_prefix(','))
+        {
+            let slot = format!("{} element", container);
+            return (None, (slot, element.trim_end_matches('?')));
+        }
+    }
+    (
+        None,
+        ("field".to_string(), luban_type.trim_end_matches('?')),
+    )
+}
+
+/// `unsupported_type` values of fields typed `any` / `unknown` (see `find_untyped_fields`)
+const UNTYPED_KEYWORDS: [&str; 2] = ["any", "unknown"];
+
+/// Find fields typed with `keyof`, `typeof` or conditional types, which are converted to string.
+/// A @type override on the field suppresses the warning. Returns one message per field
+pub fn find_unsupported_types(classes: &[ClassInfo]) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    for class in classes {
+        for field in &class.fields {
+            let Some(construct) = &field.unsupported_type else {
+                continue;
+            };
+            if UNTYPED_KEYWORDS.contains(&construct.as_str()) {
+                continue;
+            }
+            if field.type_override.is_some() || field.converter.is_some() {
+                continue;
+            }
+            let location = class.field_location(field);
+            warnings.push(format!(
+                "{}: unsupported type construct `{}` on {}.{}, falling back to string",
+                location, construct, class.name, field.name
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Find fields typed `any` or `unknown` (also as an element or type argument), which have no
+/// schema type. A @type or @Converter override suppresses the message. Returns one message
+/// per field
+pub fn find_untyped_fields(classes: &[ClassInfo]) -> Vec<String> {
+    let mut messages = Vec::new();
+
+    for class in classes {
+        for field in &class.fields {
+            let Some(keyword) = field
+                .unsupported_type
+                .as_deref()
+                .filter(|construct| UNTYPED_KEYWORDS.contains(construct))
+            else {
+                continue;
+            };
+            if field.type_override.is_some() || field.converter.is_some() {
+                continue;
+            }
+            let location = class.field_location(field);
+            messages.push(format!(
+                "{}: {}.{} - type `{}` has no schema type and falls back to string; declare a concrete type or add a @type override (or allow it with defaults.any_type)",
+                location, class.name, field.name, keyword
+            ));
+        }
+    }
+
+    messages
+}
+
+/// Check a module name against Luban's identifier rule: empty (no module) or dot-separated
+/// `[A-Za-z_][A-Za-z0-9_]*` segments. On failure returns a sanitized suggestion
+pub fn check_module_name(module: &str) -> Result<(), String> {
+    let is_identifier = |segment: &str| {
+        let mut chars = segment.chars();
+        chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    if module.is_empty() || module.split('.').all(is_identifier) {
+        return Ok(());
+    }
+    let suggestion: Vec<String> = module
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let sanitized: String = segment
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+                format!("_{}", sanitized)
+            } else {
+                sanitized
+            }
+        })
+        .collect();
+    Err(suggestion.join("."))
+}
+
+/// Find module names Luban would reject, from @Module / `@module` on a class (one message per
+/// class) or a [[sources]] module_name (one message per module, at the first file using it)
+pub fn find_invalid_modules(classes: &[ClassInfo], enums: &[EnumInfo]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut reported: HashSet<&str> = HashSet::new();
+    let sources = classes
+        .iter()
+        .map(|c| (&c.source_file, &c.name, &c.module_name, c.explicit_module))
+        .chain(
+            enums
+                .iter()
+                .map(|e| (&e.source_file, &e.name, &e.module_name, false)),
+        );
+    for (source_file, name, module, explicit) in sources {
+        let Some(module) = module.as_deref() else {
+            continue;
+        };
+        let Err(suggestion) = check_module_name(module) else {
+            continue;
+        };
+        if explicit {
+            errors.push(format!(
+                "{}: {} - @Module '{}' is not a valid Luban module name, use '{}'",
+                source_file, name, module, suggestion
+            ));
+        } else if reported.insert(module) {
+            errors.push(format!(
+                "{}: {} - module '{}' (module_name of its [[sources]] entry) is not a valid Luban module name, use '{}'",
+                source_file, name, module, suggestion
+            ));
+        }
+    }
+    errors
+}
+
+/// Find beans that reference a bean routed to a different named output (@Output / [[output_routes]]),
+/// e.g. a shipped bean whose field or parent is an editor-only bean. References into the
+/// default output are fine. Returns one message per reference
+pub fn find_cross_route_references(classes: &[ClassInfo]) -> Vec<String> {
+    let routes: HashMap<&str, Option<&str>> = classes
+        .iter()
+        .map(|c| (c.name.as_str(), c.output_route.as_deref()))
+        .collect();
+    let route_label = |route: Option<&str>| match route {
+        Some(route) => format!("output '{}'", route),
+        None => "the default output".to_string(),
+    };
+
+    let mut warnings = Vec::new();
+    for class in classes {
+        let route = class.output_route.as_deref();
+        let mut check = |target: &str, via: String| {
+            if let Some(Some(target_route)) = routes.get(target) {
+                if route != Some(*target_route) {
+                    warnings.push(format!(
+                        "{}: {} ({}) references {} ({}) {}",
+                        class.source_file,
+                        class.name,
+                        route_label(route),
+                        target,
+                        route_label(Some(*target_route)),
+                        via
+                    ));
+                }
+            }
+        };
+
+        let parent = class
+            .explicit_parent
+            .as_deref()
+            .or(class.extends.as_deref())
+            .filter(|p| !p.is_empty());
+        if let Some(parent) = parent {
+            check(parent, "as its parent".to_string());
+        }
+        for field in &class.fields {
+            let mut referenced: Vec<&str> = type_names(&field.field_type).collect();
+            referenced.extend(field.factory_inner_type.as_deref());
+            referenced.extend(field.constructor_inner_type.as_deref());
+            referenced.sort_unstable();
+            referenced.dedup();
+            for target in referenced {
+                check(target, format!("through field '{}'", field.name));
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Compare tables declared in code (@LubanTable, `@table` JSDoc) with [tables] entries keyed
+/// `module.ClassName`: declarations without an entry, entries whose class declares no table,
+/// and mode/index disagreements. Entries without a declaration are only reported when some
+/// class declares a table in code, so projects configuring tables only in [tables] aren't flagged
+pub fn reconcile_tables(
+    classes: &[ClassInfo],
+    tables: &HashMap<String, TableConfig>,
+) -> Vec<String> {
+    /// (mode, index, what declared it)
+    fn declaration(class: &ClassInfo) -> Option<(&str, Option<&str>, &'static str)> {
+        match (&class.luban_table, &class.table_config) {
+            (Some(table), _) => Some((
+                table.mode.as_str(),
+                Some(table.index.as_str()).filter(|index| !index.is_empty()),
+                "@LubanTable",
+            )),
+            (None, Some(table)) => Some((table.mode.as_str(), table.index.as_deref(), "@table")),
+            (None, None) => None,
+        }
+    }
+    let uses_declarations = classes.iter().any(|c| declaration(c).is_some());
+
+    let mut messages = Vec::new();
+    for class in classes {
+        let key = match &class.module_name {
+            Some(module) if !module.is_empty() => format!("{}.{}", module, class.name),
+            _ => class.name.clone(),
+        };
+        match (declaration(class), tables.get(&key)) {
+            (Some((_, _, source)), None) => messages.push(format!(
+                "{}: {} - declared as a table by {} but [tables] has no \"{}\" entry, no <table> is generated",
+                class.source_file, class.name, source, key
+            )),
+            (None, Some(_)) if uses_declarations => messages.push(format!(
+                "{}: {} - [tables] \"{}\" configures a table but the class has no @LubanTable",
+                class.source_file, class.name, key
+            )),
+            (Some((mode, index, source)), Some(config)) => {
+                if mode != config.mode() {
+                    messages.push(format!(
+                        "{}: {} - {} mode '{}' conflicts with [tables] \"{}\" mode '{}', the config wins",
+                        class.source_file, class.name, source, mode, key, config.mode()
+                    ));
+                }
+                let config_index = match config {
+                    TableConfig::Full {
+                        index: Some(index), ..
+                    } => Some(index.as_str()),
+                    _ => None,
+                };
+                if let (Some(index), Some(config_index)) = (index, config_index) {
+                    if index != config_index {
+                        messages.push(format!(
+                            "{}: {} - {} index '{}' conflicts with [tables] \"{}\" index '{}', the config wins",
+                            class.source_file, class.name, source, index, key, config_index
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    messages
+}
+
+/// Identifiers in a type string such as `map,string,SkillConfig`
+pub(crate) fn type_names(field_type: &str) -> impl Iterator<Item = &str> {
+    field_type
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|s| !s.is_empty())
+}
+
+/// Find fields referencing runtime-only types (`FieldInfo::forbidden_type`). Names that a
+/// parsed class/enum defines or a type mapping covers are not runtime types, and @type or
+/// @Converter overrides are trusted. Returns one message per field with a hint
+pub fn find_forbidden_types(
+    classes: &[ClassInfo],
+    enums: &[EnumInfo],
+    type_mapper: &TypeMapper,
+) -> Vec<String> {
+    let defined: HashSet<&str> = classes
+        .iter()
+        .map(|c| c.name.as_str())
+        .chain(enums.iter().map(|e| e.name.as_str()))
+        .collect();
+    let mut errors = Vec::new();
+
+    for class in classes {
+        for field in &class.fields {
+            let Some(type_name) = &field.forbidden_type else {
+                continue;
+            };
+            if field.type_override.is_some()
+                || field.converter.is_some()
+                || defined.contains(type_name.as_str())
+                || type_mapper.map(type_name) != *type_name
+            {
+                continue;
+            }
+            let location = class.field_location(field);
+            errors.push(format!(
+                "{}: {}.{} - type '{}' only exists at runtime; {}",
+                location,
+                class.name,
+                field.name,
+                type_name,
+                forbidden_type_hint(type_name)
+            ));
+        }
+    }
+
+    errors
+}
+
+fn forbidden_type_hint(type_name: &str) -> &'static str {
+    match type_name {
+        "Promise" | "PromiseLike" => {
+            "configs are loaded synchronously; use the resolved type instead"
+        }
+        "RBXScriptSignal" | "RBXScriptConnection" => {
+            "configs cannot hold event connections; store the event name as a string instead"
+        }
+        _ => "configs cannot reference runtime Instances; store an asset id string instead",
+    }
+}
+
+/// Luban's own scalar and container type names
+const LUBAN_BUILTIN_TYPES: &[&str] = &[
+    "bool", "byte", "short", "int", "long", "float", "double", "string", "text", "datetime",
+    "list", "set", "map", "array",
+];
+
+/// How a field type that isn't a parsed bean, enum or builtin was classified
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnknownTypeKind {
+    /// Listed in [external_beans] (defined in hand-written XML)
+    External,
+    /// Close to a known bean or external name
+    SuspectedTypo,
+    /// Nothing similar is known; an error with defaults.strict_types
+    Unknown,
+}
+
+/// A field referencing a type that no parsed source defines
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnknownType {
+    pub class: String,
+    pub field: String,
+    pub type_name: String,
+    pub kind: UnknownTypeKind,
+    /// Nearest known bean/external name (suspected typos only)
+    pub suggestion: Option<String>,
+    /// `file:line` of the field declaration
+    pub location: String,
+}
+
+impl fmt::Display for UnknownType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}.{} - unknown type '{}'",
+            self.location, self.class, self.field, self.type_name
+        )?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean '{}'?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Classify every field type name that isn't a parsed bean/enum, a Luban builtin or the
+/// target of a type mapping. Names in `external_beans` are `External`; names close to a known
+/// bean or external name are `SuspectedTypo` with a suggestion; the rest are `Unknown`
+pub fn classify_unknown_types(
+    classes: &[ClassInfo],
+    enums: &[EnumInfo],
+    type_mapper: &TypeMapper,
+    external_beans: &[String],
+) -> Vec<UnknownType> {
+    let known: HashSet<&str> = classes
+        .iter()
+        .map(|c| c.name.as_str())
+        .chain(enums.iter().map(|e| e.name.as_str()))
+        .chain(LUBAN_BUILTIN_TYPES.iter().copied())
+        .chain(type_mapper.mapped_types())
+        .collect();
+    let external: HashSet<&str> = external_beans.iter().map(|s| short_name(s)).collect();
+    let candidates: Vec<&str> = classes
+        .iter()
+        .map(|c| c.name.as_str())
+        .chain(external.iter().copied())
+        .collect();
+
+    let mut unknown = Vec::new();
+    for class in classes {
+        for field in class.fields.iter().filter(|f| f.converter.is_none()) {
+            // Runtime-only types are reported by find_forbidden_types instead
+            let luban_type = field
+                .type_override
+                .clone()
+                .unwrap_or_else(|| type_mapper.map_full_type(&field.field_type));
+            let mut seen = HashSet::new();
+            for name in luban_type_names(&luban_type) {
+                if known.contains(name)
+                    || field.forbidden_type.as_deref() == Some(name)
+                    || !seen.insert(name)
+                {
+                    continue;
+                }
+                let (kind, suggestion) = if external.contains(name) {
+                    (UnknownTypeKind::External, None)
+                } else {
+                    match nearest_name(name, candidates.iter().copied()) {
+                        Some(s) => (UnknownTypeKind::SuspectedTypo, Some(s)),
+                        None => (UnknownTypeKind::Unknown, None),
+                    }
+                };
+                unknown.push(UnknownType {
+                    class: class.name.clone(),
+                    field: field.name.clone(),
+                    type_name: name.to_string(),
+                    kind,
+                    suggestion,
+                    location: class.field_location(field),
+                });
+            }
+        }
+    }
+
+    unknown
+}
+
+/// Type names in a Luban type such as `map,(int#set=1,2),mod.SkillConfig`, without module
+/// prefixes; type tags and numbers are skipped
+fn luban_type_names(luban_type: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = luban_type;
+    while !rest.is_empty() {
+        // A tag section (`#set=1,2`) runs until the closing parenthesis
+        let end = rest.find([',', '#']).unwrap_or(rest.len());
+        let name = short_name(rest[..end].trim_matches(|c| c == '(' || c == ')' || c == '?'));
+        if name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            names.push(name);
+        }
+        rest = &rest[end..];
+        if rest.starts_with('#') {
+            rest = rest.find(')').map_or("", |close| &rest[close..]);
+        }
+        rest = rest.trim_start_matches([')', ',']);
+    }
+    names
+}
+
+fn short_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// Find optional (`?`) or nullable (`| null` / `| undefined`) list/map/set fields, whose
+/// optionality is dropped unless defaults.optional_containers = "nullable". Returns one
+/// message per field, including its location.
+pub fn find_optional_containers(classes: &[ClassInfo], type_mapper: &TypeMapper) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    for class in classes {
+        for field in class.fields.iter().filter(|f| f.may_be_empty() && f.converter.is_none()) {
+            let luban_type = field
+                .type_override
+                .clone()
+                .unwrap_or_else(|| type_mapper.map_full_type(&field.field_type));
+            if is_container_type(&luban_type) {
+                warnings.push(format!(
+                    "{}: {}.{} - optional container field is emitted as required (type {}); set defaults.optional_containers = \"nullable\" or \"drop\"",
+                    class.source_file, class.name, field.name, luban_type
+                ));
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Find inheritance cycles (A extends B, B extends A, or a class that is its own parent),
+/// following the parent each bean is emitted with. Luban rejects such beans, so they fail
+/// validation. Returns one message per cycle, from its alphabetically first class.
+pub fn find_inheritance_cycles(classes: &[ClassInfo]) -> Vec<String> {
+    let by_name: HashMap<&str, &ClassInfo> = classes.iter().map(|c| (c.name.as_str(), c)).collect();
+    let mut names: Vec<&str> = by_name.keys().copied().collect();
+    names.sort();
+    let mut errors = Vec::new();
+    for &start in &names {
+        let mut path = vec![start];
+        let mut current = by_name[start];
+        loop {
+            let parent = bean_parent(current);
+            let Some(&next) = by_name.get(parent.as_str()) else {
+                break;
+            };
+            if next.name == start {
+                path.push(start);
+                errors.push(format!(
+                    "{}: {} - inheritance cycle {}",
+                    by_name[start].source_file,
+                    start,
+                    path.join(" -> ")
+                ));
+                break;
+            }
+            // A cycle not through `start` is reported from its own first class
+            if path.contains(&next.name.as_str()) || next.name.as_str() < start {
+                break;
+            }
+            path.push(next.name.as_str());
+            current = next;
+        }
+    }
+    errors
+}
+
+/// Find beans that contain themselves through required fields alone (`next: Chain` in
+/// Chain, or `b: B` in A and `a: A` in B): their data could never end. Recursion through a
+/// container or an optional/nullable field is how recursive beans are written and is not
+/// reported. Fields are followed to the bean they name, not its subclasses, since any
+/// subclass may end the chain. Returns one message per cycle, from its alphabetically
+/// first bean.
+pub fn find_unbounded_recursion(classes: &[ClassInfo], type_mapper: &TypeMapper) -> Vec<String> {
+    let by_name: HashMap<&str, &ClassInfo> = classes.iter().map(|c| (c.name.as_str(), c)).collect();
+    // Required fields holding exactly one bean
+    let mut edges: HashMap<&str, Vec<(&str, &FieldInfo)>> = HashMap::new();
+    for class in classes {
+        for field in &class.fields {
+            if field.may_be_empty() || field.converter.is_some() {
+                continue;
+            }
+            let luban_type = field
+                .type_override
+                .clone()
+                .unwrap_or_else(|| type_mapper.map_full_type(&field.field_type));
+            if luban_type.contains([',', '?', '#', '(']) {
+                continue;
+            }
+            if let Some((target, _)) = by_name.get_key_value(short_name(&luban_type)) {
+                edges
+                    .entry(class.name.as_str())
+                    .or_default()
+                    .push((*target, field));
+            }
+        }
+    }
+
+    let mut names: Vec<&str> = by_name.keys().copied().collect();
+    names.sort();
+    let mut warnings = Vec::new();
+    for &start in &names {
+        // Breadth-first through beans ordered after `start`, for the shortest way back to it
+        let mut parents: HashMap<&str, (&str, &FieldInfo)> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        let mut closing = None;
+        'search: while let Some(name) = queue.pop_front() {
+            for &(target, field) in edges.get(name).into_iter().flatten() {
+                if target == start {
+                    closing = Some((name, field));
+                    break 'search;
+                }
+                if target > start && !parents.contains_key(target) {
+                    parents.insert(target, (name, field));
+                    queue.push_back(target);
+                }
+            }
+        }
+        let Some(last) = closing else {
+            continue;
+        };
+        let mut steps = vec![last];
+        while steps[steps.len() - 1].0 != start {
+            steps.push(parents[steps[steps.len() - 1].0]);
+        }
+        steps.reverse();
+        let path: Vec<String> = steps
+            .iter()
+            .map(|(class, field)| format!("{}.{}", class, field.name))
+            .chain([start.to_string()])
+            .collect();
+        let (_, first) = steps[0];
+        warnings.push(format!(
+            "{}: {}.{} - recursion through required fields never ends ({}); make one of these fields optional or a list",
+            by_name[start].field_location(first),
+            start,
+            first.name,
+            path.join(" -> ")
+        ));
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{FieldInfo, FieldOrigin, ImportMap};
+    use std::collections::HashMap;
+
+    /// Origin of a field declared at `line` of weapon.ts
+    fn at_line(line: usize) -> Option<FieldOrigin> {
+        Some(FieldOrigin::Source {
+            file: "weapon.ts".to_string(),
+            line,
+        })
+    }
+
+    fn make_class(fields: Vec<FieldInfo>) -> ClassInfo {
+        ClassInfo {
+            name: "WeaponConfig".to_string(),
+            comment: None,
+            alias: None,
+            fields,
+            implements: vec![],
+            extends: None,
+            source_file: "weapon.ts".to_string(),
+            source_path: Default::default(),
+            file_hash: String::new(),
+            is_interface: false,
+            output_path: None,
+            module_name: None,
+            type_params: HashMap::new(),
+            luban_table: None,
+            table_config: None,
+            input_path: None,
+            imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            explicit_parent: None,
+            explicit_module: false,
+            tags: Vec::new(),
+            examples: Vec::new(),
+        }
+    }
+
+    fn make_field(name: &str, field_type: &str, default: &str) -> FieldInfo {
+        FieldInfo {
+            name: name.to_string(),
+            field_type: field_type.to_string(),
+            original_type: field_type.to_string(),
+            default_value: Some(default.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_valid_defaults() {
+        let type_mapper = TypeMapper::new(&HashMap::new());
+        let class = make_class(vec![
+            make_field("count", "double", "5"),
+            make_field("kind", "string", "melee"),
+            make_field("enabled", "bool", "true"),
+        ]);
+        assert!(validate_default_values(&[class], &type_mapper).is_empty());
+    }
+
+    #[test]
+    fn test_string_default_on_number_field_errors() {
+        let type_mapper = TypeMapper::new(&HashMap::new());
+        let mut field = make_field("count", "double", "melee");
+        field.type_override = Some("int".to_string());
+        let errors = validate_default_values(&[make_class(vec![field])], &type_mapper);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("weapon.ts: WeaponConfig.count"));
+        assert!(errors[0].contains("not a valid int"));
+    }
+
+    #[test]
+    fn test_fractional_or_non_finite_number_defaults_error() {
+        let type_mapper = TypeMapper::new(&HashMap::new());
+        let int_field = |name: &str, default: &str| FieldInfo {
+            type_override: Some("int".to_string()),
+            ..make_field(name, "double", default)
+        };
+        let class = make_class(vec![
+            int_field("level", "1.5"),
+            int_field("count", "-3"),
+            int_field("limit", "inf"),
+            make_field("ratio", "double", "NaN"),
+            make_field("speed", "double", "2.5"),
+        ]);
+        let errors = validate_default_values(&[class], &type_mapper);
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors[0].contains("WeaponConfig.level - default value \"1.5\" is not a valid int"));
+        assert!(errors[1].contains("WeaponConfig.limit - default value \"inf\" is not a valid int"));
+        assert!(
+            errors[2].contains("WeaponConfig.ratio - default value \"NaN\" is not a valid double")
+        );
+    }
+
+    #[test]
+    fn test_container_default_rejected() {
+        let type_mapper = TypeMapper::new(&HashMap::new());
+        let errors = validate_default_values(
+            &[make_class(vec![make_field("ids", "list,int", "1")])],
+            &type_mapper,
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("not supported on container fields"));
+    }
+
+    #[test]
+    fn test_generate_type_range_bounds() {
+        let registry = TableRegistry::new();
+        let generator = ValidatorGenerator::new(&registry);
+        let range = |range| FieldValidators {
+            range: Some(range),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            generator.generate_type("int", &range(RangeConstraint::Between(1.0, 100.0))),
+            "int#range=[1,100]"
+        );
+        assert_eq!(
+            generator.generate_type("int", &range(RangeConstraint::Min(0.0))),
+            "int#range=[0,]"
+        );
+        assert_eq!(
+            generator.generate_type("double", &range(RangeConstraint::Max(59.5))),
+            "double#range=[,59.5]"
+        );
+
+        let bounded =
+            ValidatorGenerator::new(&registry).with_open_range_format(OpenRangeFormat::Bounded);
+        assert_eq!(
+            bounded.generate_type("int", &range(RangeConstraint::Min(0.0))),
+            "int#range=[0,2147483647]"
+        );
+        assert_eq!(
+            bounded.generate_type("short", &range(RangeConstraint::Max(59.0))),
+            "short#range=[-32768,59]"
+        );
+        assert_eq!(
+            bounded.generate_type("int", &range(RangeConstraint::Between(1.0, 100.0))),
+            "int#range=[1,100]"
+        );
+    }
+
+    #[test]
+    fn test_fractional_range_on_int_field_rejected() {
+        let type_mapper = TypeMapper::new(&HashMap::new());
+        let ranged = |name: &str, field_type: &str, range| {
+            let mut field = make_field(name, field_type, "");
+            field.default_value = None;
+            field.validators.range = Some(range);
+            field
+        };
+        let mut level = ranged("level", "int", RangeConstraint::Min(0.5));
+        level.origin = at_line(7);
+        let class = make_class(vec![
+            level,
+            ranged("ratio", "double", RangeConstraint::Between(0.5, 1.5)),
+            ranged("counts", "list,int", RangeConstraint::Max(9.9)),
+            ranged("ids", "list,int", RangeConstraint::Between(1.0, 10.0)),
+        ]);
+
+        assert_eq!(
+            validate_range_bounds(&[class], &type_mapper),
+            vec![
+                "weapon.ts:7: WeaponConfig.level - @Range bound 0.5 must be a whole number for type int",
+                "weapon.ts: WeaponConfig.counts - @Range bound 9.9 must be a whole number for type int",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_converter_with_type_override_rejected() {
+        let mut field = make_field("curve", "string", "");
+        field.default_value = None;
+        field.converter = Some(crate::parser::field_info::FieldConverter {
+            luban_type: "CurveRef".to_string(),
+            tags: vec![],
+        });
+        assert!(validate_converters(&[make_class(vec![field.clone()])]).is_empty());
+
+        field.type_override = Some("int".to_string());
+        let errors = validate_converters(&[make_class(vec![field])]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("weapon.ts: WeaponConfig.curve"));
+    }
+
+    #[test]
+    fn test_find_optional_containers() {
+        let type_mapper = TypeMapper::new(&HashMap::new());
+        let optional = |name: &str, field_type: &str| FieldInfo {
+            name: name.to_string(),
+            field_type: field_type.to_string(),
+            is_optional: true,
+            ..Default::default()
+        };
+        let mut required_list = optional("required", "list,int");
+        required_list.is_optional = false;
+        let class = make_class(vec![
+            optional("items", "list,string"),
+            optional("weights", "map,string,number"),
+            optional("ids", "set,int"),
+            optional("name", "string"),
+            required_list,
+        ]);
+
+        let warnings = find_optional_containers(&[class], &type_mapper);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].starts_with("weapon.ts: WeaponConfig.items"));
+        assert!(warnings[1].contains("WeaponConfig.weights"));
+        assert!(warnings[1].contains("map,string,double"));
+        assert!(warnings[2].contains("WeaponConfig.ids"));
+    }
+
+    #[test]
+    fn test_find_inheritance_cycles() {
+        let class = |name: &str, extends: Option<&str>| ClassInfo {
+            name: name.to_string(),
+            extends: extends.map(str::to_string),
+            ..make_class(vec![])
+        };
+        let mut via_parent = class("Gamma", None);
+        via_parent.explicit_parent = Some("Gamma".to_string());
+        let classes = vec![
+            class("Beta", Some("Alpha")),
+            class("Alpha", Some("Beta")),
+            via_parent,
+            class("Leaf", Some("Alpha")),
+            class("Base", Some("ExternalBean")),
+        ];
+        let errors = find_inheritance_cycles(&classes);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].ends_with("Alpha - inheritance cycle Alpha -> Beta -> Alpha"));
+        assert!(errors[1].ends_with("Gamma - inheritance cycle Gamma -> Gamma"));
+    }
+
+    #[test]
+    fn test_find_unbounded_recursion() {
+        let type_mapper = TypeMapper::new(&HashMap::new());
+        let field = |name: &str, field_type: &str, optional: bool| FieldInfo {
+            name: name.to_string(),
+            field_type: field_type.to_string(),
+            is_optional: optional,
+            origin: at_line(3),
+            ..Default::default()
+        };
+        let class = |name: &str, fields: Vec<FieldInfo>| ClassInfo {
+            name: name.to_string(),
+            ..make_class(fields)
+        };
+        // A field typed as a parent bean can hold a leaf subclass
+        let mut not = class("Not", vec![field("operand", "Expr", false)]);
+        not.extends = Some("Expr".to_string());
+        let mut literal = class("Literal", vec![]);
+        literal.extends = Some("Expr".to_string());
+        let classes = vec![
+            // Recursion through a list, an optional field or a map ends
+            class(
+                "UiNode",
+                vec![
+                    field("children", "list,UiNode", false),
+                    field("parent", "UiNode", true),
+                    field("slots", "map,string,UiNode", false),
+                ],
+            ),
+            // Self and mutual recursion through required fields doesn't
+            class("Chain", vec![field("next", "Chain", false)]),
+            class("Left", vec![field("right", "Right", false)]),
+            class("Right", vec![field("left", "Left", false)]),
+            class("Expr", vec![]),
+            not,
+            literal,
+        ];
+
+        let warnings = find_unbounded_recursion(&classes, &type_mapper);
+        assert_eq!(
+            warnings,
+            vec![
+                "weapon.ts:3: Chain.next - recursion through required fields never ends (Chain.next -> Chain); make one of these fields optional or a list",
+                "weapon.ts:3: Left.right - recursion through required fields never ends (Left.right -> Right.left -> Left); make one of these fields optional or a list",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_key_errors() {
+        let type_mapper = TypeMapper::new(&HashMap::new());
+        let field = |name: &str, field_type: &str| FieldInfo {
+            name: name.to_string(),
+            field_type: field_type.to_string(),
+            ..Default::default()
+        };
+        let mut mixed = field("mixed", "map,int,string");
+        mixed.origin = at_line(7);
+        mixed.type_error = Some("union `1 | \"a\"` is not a valid map key".to_string());
+        let class = make_class(vec![
+            field("tiers", "map,int,string"),
+            field("byWeapon", "map,WeaponConfig,int"),
+            mixed,
+        ]);
+
+        let errors = validate_map_keys(&[class], &type_mapper);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("WeaponConfig.byWeapon"));
+        assert!(errors[0].contains("map key type WeaponConfig is a bean"));
+        assert!(errors[1].starts_with("weapon.ts:7: WeaponConfig.mixed"));
+    }
+
+    #[test]
+    fn test_validate_refs() {
+        let type_mapper = TypeMapper::new(&HashMap::new());
+        let mut item = make_class(vec![FieldInfo {
+            name: "id".to_string(),
+            field_type: "int".to_string(),
+            ..Default::default()
+        }]);
+        item.name = "ItemConfig".to_string();
+        let field = |name: &str, field_type: &str, target: &str, key: bool| {
+            let mut field = FieldInfo {
+                name: name.to_string(),
+                field_type: field_type.to_string(),
+                ..Default::default()
+            };
+            if key {
+                field.validators.ref_key_target = Some(target.to_string());
+            } else {
+                field.validators.ref_target = Some(target.to_string());
+            }
+            field
+        };
+        let weapon = make_class(vec![
+            field("itemIds", "list,double", "ItemConfig", false),
+            field("prices", "map,string,double", "ItemConfig", false),
+            field("itemNames", "list,string", "ItemConfig", false),
+            field("names", "map,string,int", "ItemConfig", true),
+            field("owner", "int", "ItemConfig", true),
+            field("skillId", "int", "SkillConfig", false),
+        ]);
+        let classes = vec![item, weapon];
+        let tables: HashMap<String, TableConfig> = [(
+            "shop.ItemConfig".to_string(),
+            TableConfig::Simple("../datas/item".to_string()),
+        )]
+        .into_iter()
+        .collect();
+        let mut registry = TableRegistry::from_config(&tables);
+        assert!(registry.set_index_types(&classes, &type_mapper).is_empty());
+
+        assert_eq!(
+            validate_refs(&classes, &type_mapper, &registry),
+            vec![
+                "weapon.ts: WeaponConfig.itemNames - @Ref(ItemConfig) refers to a table indexed by int, but the list element is string",
+                "weapon.ts: WeaponConfig.names - @RefKey(ItemConfig) refers to a table indexed by int, but the map key is string",
+                "weapon.ts: WeaponConfig.owner - @RefKey(ItemConfig) only applies to map fields, the field is int",
+                "weapon.ts: WeaponConfig.skillId - @Ref(SkillConfig) target has no [tables] entry",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_cross_route_references() {
+        let mut debug = make_class(vec![]);
+        debug.name = "DebugOverlay".to_string();
+        debug.output_route = Some("editor".to_string());
+
+        let mut editor = make_class(vec![make_field("overlay", "DebugOverlay", "")]);
+        editor.name = "EditorConfig".to_string();
+        editor.output_route = Some("editor".to_string());
+
+        let mut shipped = make_class(vec![
+            make_field("overlays", "list,DebugOverlay", ""),
+            make_field("count", "double", ""),
+        ]);
+        shipped.extends = Some("EditorConfig".to_string());
+
+        let warnings = find_cross_route_references(&[debug, editor, shipped]);
+        assert_eq!(
+            warnings,
+            vec![
+                "weapon.ts: WeaponConfig (the default output) references EditorConfig (output 'editor') as its parent",
+                "weapon.ts: WeaponConfig (the default output) references DebugOverlay (output 'editor') through field 'overlays'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_classify_unknown_types() {
+        let type_mapper = TypeMapper::new(&HashMap::new());
+        let mut skill = make_class(vec![]);
+        skill.name = "SkillConfig".to_string();
+        let mut weapon = make_class(vec![
+            make_field("skill", "SkillConfig", ""),
+            make_field("buff", "LegacyBuff", ""),
+            make_field("effects", "list,SkilConfig", ""),
+            make_field("drops", "map,int,QuantumLoot", ""),
+            make_field("pos", "Vector3", ""),
+        ]);
+        weapon.fields[2].origin = at_line(7);
+        let external = vec!["legacy.LegacyBuff".to_string()];
+
+        let unknown = classify_unknown_types(&[skill, weapon], &[], &type_mapper, &external);
+        let kinds: Vec<(&str, UnknownTypeKind)> = unknown
+            .iter()
+            .map(|u| (u.type_name.as_str(), u.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("LegacyBuff", UnknownTypeKind::External),
+                ("SkilConfig", UnknownTypeKind::SuspectedTypo),
+                ("QuantumLoot", UnknownTypeKind::Unknown),
+            ]
+        );
+        assert_eq!(
+            unknown[1].to_string(),
+            "weapon.ts:7: WeaponConfig.effects - unknown type 'SkilConfig', did you mean 'SkillConfig'?"
+        );
+        assert_eq!(unknown[2].suggestion, None);
+    }
+
+    #[test]
+    fn test_luban_type_names_skip_tags() {
+        assert_eq!(
+            luban_type_names("map,(int#set=1,2,3),mod.RewardTier"),
+            vec!["map", "int", "RewardTier"]
+        );
+        assert_eq!(
+            luban_type_names("(list#sep=|),(string#set=a,b)"),
+            vec!["list", "string"]
+        );
+        assert_eq!(luban_type_names("list?,string"), vec!["list", "string"]);
+    }
+
+    #[test]
+    fn test_find_unsupported_types() {
+        let field = |name: &str, construct: &str| FieldInfo {
+            name: name.to_string(),
+            field_type: "string".to_string(),
+            origin: at_line(12),
+            unsupported_type: Some(construct.to_string()),
+            ..Default::default()
+        };
+        let mut overridden = field("defaults", "typeof");
+        overridden.type_override = Some("string".to_string());
+        let class = make_class(vec![field("statKey", "keyof"), overridden]);
+
+        let warnings = find_unsupported_types(&[class]);
+        assert_eq!(
+            warnings,
+            vec![
+                "weapon.ts:12: unsupported type construct `keyof` on WeaponConfig.statKey, falling back to string"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_untyped_fields() {
+        let field = |name: &str, keyword: &str| FieldInfo {
+            name: name.to_string(),
+            field_type: "string".to_string(),
+            origin: at_line(12),
+            unsupported_type: Some(keyword.to_string()),
+            ..Default::default()
+        };
+        let mut overridden = field("payload", "unknown");
+        overridden.type_override = Some("json".to_string());
+        let mut converted = field("curve", "any");
+        converted.converter = Some(crate::parser::field_info::FieldConverter {
+            luban_type: "CurveRef".to_string(),
+            tags: Vec::new(),
+        });
+        let class = make_class(vec![
+            field("extra", "any"),
+            field("statKey", "keyof"),
+            overridden,
+            converted,
+        ]);
+
+        let classes = [class];
+        assert_eq!(
+            find_untyped_fields(&classes),
+            vec!["weapon.ts:12: WeaponConfig.extra - type `any` has no schema type and falls back to string; declare a concrete type or add a @type override (or allow it with defaults.any_type)"]
+        );
+        // any/unknown have their own report
+        assert_eq!(find_unsupported_types(&classes).len(), 1);
+    }
+
+    #[test]
+    fn test_check_module_name() {
+        assert_eq!(check_module_name(""), Ok(()));
+        assert_eq!(check_module_name("game"), Ok(()));
+        assert_eq!(check_module_name("ui_widgets.hud2"), Ok(()));
+        assert_eq!(
+            check_module_name("ui-widgets"),
+            Err("ui_widgets".to_string())
+        );
+        assert_eq!(check_module_name("2d"), Err("_2d".to_string()));
+        assert_eq!(check_module_name("ui..hud"), Err("ui.hud".to_string()));
+    }
+
+    #[test]
+    fn test_find_invalid_modules() {
+        let mut hud = make_class(vec![]);
+        hud.name = "HudConfig".to_string();
+        hud.source_file = "ui/hud.ts".to_string();
+        hud.module_name = Some("ui-widgets".to_string());
+        let mut menu = hud.clone();
+        menu.name = "MenuConfig".to_string();
+        let mut weapon = make_class(vec![]);
+        weapon.module_name = Some("combat stats".to_string());
+        weapon.explicit_module = true;
+        let mut valid = make_class(vec![]);
+        valid.module_name = Some("item".to_string());
+
+        assert_eq!(
+            find_invalid_modules(&[hud, menu, weapon, valid], &[]),
+            vec![
+                "ui/hud.ts: HudConfig - module 'ui-widgets' (module_name of its [[sources]] entry) is not a valid Luban module name, use 'ui_widgets'",
+                "weapon.ts: WeaponConfig - @Module 'combat stats' is not a valid Luban module name, use 'combat_stats'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_forbidden_types() {
+        let type_mapper = TypeMapper::new(&HashMap::new());
+        let field = |name: &str, field_type: &str, forbidden: &str| FieldInfo {
+            name: name.to_string(),
+            field_type: field_type.to_string(),
+            origin: at_line(7),
+            forbidden_type: Some(forbidden.to_string()),
+            ..Default::default()
+        };
+        let mut overridden = field("handle", "string", "Instance");
+        overridden.type_override = Some("string".to_string());
+        let weapon = make_class(vec![
+            field("model", "Instance", "Instance"),
+            field("loaded", "Promise", "Promise"),
+            field("spawn", "Model", "Model"),
+            overridden,
+        ]);
+        // A parsed class named Model is a regular bean
+        let model = ClassInfo {
+            name: "Model".to_string(),
+            ..make_class(Vec::new())
+        };
+
+        let errors = find_forbidden_types(&[weapon.clone()], &[], &type_mapper);
+        assert_eq!(errors.len(), 3);
+        assert_eq!(
+            errors[0],
+            "weapon.ts:7: WeaponConfig.model - type 'Instance' only exists at runtime; configs cannot reference runtime Instances; store an asset id string instead"
+        );
+        assert!(
+            errors[1].ends_with("configs are loaded synchronously; use the resolved type instead")
+        );
+
+        let errors = find_forbidden_types(&[weapon.clone(), model], &[], &type_mapper);
+        assert_eq!(errors.len(), 2);
+        assert!(!errors.iter().any(|e| e.contains("'Model'")));
+
+        // Not double-reported as an unknown type
+        assert!(classify_unknown_types(&[weapon], &[], &type_mapper, &[]).is_empty());
+    }
+}
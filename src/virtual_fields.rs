@@ -0,0 +1,744 @@
+use crate::config::{VirtualBeanConfig, VirtualField, VirtualFieldMode, VirtualFieldsConfig};
+use crate::parser::{
+    ClassInfo, FieldInfo, FieldOrigin, FieldValidators, FlavorCondition, ImportMap,
+    RangeConstraint, SizeConstraint,
+};
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+
+/// Where a virtual field is inserted into the target class
+#[derive(Debug, Clone, PartialEq)]
+pub enum VirtualFieldPosition {
+    Start,
+    End,
+    Before(String),
+    After(String),
+}
+
+impl VirtualFieldPosition {
+    /// Parse a position string: "start", "end", "before:<field>" or "after:<field>"
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s == "start" {
+            return Ok(Self::Start);
+        }
+        if s == "end" {
+            return Ok(Self::End);
+        }
+        if let Some(anchor) = s.strip_prefix("before:") {
+            if !anchor.trim().is_empty() {
+                return Ok(Self::Before(anchor.trim().to_string()));
+            }
+        }
+        if let Some(anchor) = s.strip_prefix("after:") {
+            if !anchor.trim().is_empty() {
+                return Ok(Self::After(anchor.trim().to_string()));
+            }
+        }
+        bail!(
+            "Invalid virtual field position {:?}, expected \"start\", \"end\", \"before:<field>\" or \"after:<field>\"",
+            s
+        )
+    }
+}
+
+/// Convert a config-defined virtual field into a FieldInfo
+pub fn virtual_field_to_field_info(field: &VirtualField) -> FieldInfo {
+    let v = &field.validators;
+    let validators = FieldValidators {
+        has_ref: v.has_ref,
+        range: v.range.map(|(min, max)| RangeConstraint::Between(min, max)),
+        required: v.required,
+        size: v.size.map(SizeConstraint::Exact),
+        set_values: v.set.clone(),
+        index_field: v.index.clone(),
+        ..Default::default()
+    };
+
+    let custom_tags = field.relocate_to.as_ref().map(|r| {
+        let mut tags = format!("relocateTo={},prefix={}", r.target, r.prefix);
+        if let Some(target_bean) = &r.target_bean {
+            tags.push_str(&format!(",targetBean={}", target_bean));
+        }
+        tags
+    });
+
+    FieldInfo {
+        name: field.name.clone(),
+        field_type: field.field_type.clone(),
+        original_type: field.field_type.clone(),
+        comment: field.comment.clone(),
+        alias: field.alias.clone(),
+        is_optional: field.is_optional,
+        validators,
+        custom_tags,
+        ..Default::default()
+    }
+}
+
+/// A [[virtual_fields]] block whose `class` matched no parsed class
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnmatchedVirtualClass {
+    pub class: String,
+    /// Closest known class name, if any is similar enough
+    pub suggestion: Option<String>,
+}
+
+impl std::fmt::Display for UnmatchedVirtualClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[[virtual_fields]] targets unknown class '{}', fields were not injected",
+            self.class
+        )?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (did you mean '{}'?)", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Inject [[virtual_fields]] into the matching parsed classes.
+///
+/// Blocks targeting a class that doesn't exist are skipped rather than treated
+/// as errors; they are returned so the caller can warn about them. Fields are
+/// applied in config order; several fields anchored to the same position keep
+/// that order.
+pub fn inject_virtual_fields(
+    classes: &mut [ClassInfo],
+    virtual_fields: &[VirtualFieldsConfig],
+) -> Result<Vec<UnmatchedVirtualClass>> {
+    let mut unmatched: Vec<UnmatchedVirtualClass> = Vec::new();
+
+    for (block_index, block) in virtual_fields.iter().enumerate() {
+        if !classes.iter().any(|c| c.name == block.class) {
+            if !unmatched.iter().any(|u| u.class == block.class) {
+                unmatched.push(UnmatchedVirtualClass {
+                    class: block.class.clone(),
+                    suggestion: nearest_name(&block.class, classes.iter().map(|c| c.name.as_str())),
+                });
+            }
+            continue;
+        }
+
+        for class in classes.iter_mut().filter(|c| c.name == block.class) {
+            // Anchors refer to declaration order, whatever order earlier passes left
+            class.fields.sort_by_key(|f| f.order);
+
+            // Number of fields already inserted at a given anchor, so that
+            // "start"/"after:x" insertions keep config order instead of reversing it
+            let mut start_offset = 0;
+            let mut after_offsets: HashMap<String, usize> = HashMap::new();
+
+            for field in &block.fields {
+                match field.mode {
+                    VirtualFieldMode::Add => {
+                        add_field(class, field, &mut start_offset, &mut after_offsets)?
+                    }
+                    VirtualFieldMode::Override => override_field(class, field)?,
+                    VirtualFieldMode::Remove => remove_field(class, field)?,
+                }
+                // Remember where the field came from for diagnostics and the model
+                if field.mode != VirtualFieldMode::Remove {
+                    if let Some(injected) = class.fields.iter_mut().find(|f| f.name == field.name) {
+                        injected.origin = Some(FieldOrigin::VirtualConfig { index: block_index });
+                    }
+                }
+            }
+            // Injected fields have no source offset: renumber so `order` keeps their position
+            for (index, field) in class.fields.iter_mut().enumerate() {
+                field.order = index as u32;
+            }
+        }
+    }
+    Ok(unmatched)
+}
+
+/// Find the candidate closest to `name` by edit distance (case-insensitive).
+/// Returns None when nothing is reasonably close.
+pub fn nearest_name<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    let target = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(2);
+
+    candidates
+        .map(|c| (levenshtein(&target, &c.to_lowercase()), c))
+        .filter(|(d, _)| *d <= max_distance)
+        .min_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)))
+        .map(|(_, c)| c.to_string())
+}
+
+/// Up to `limit` candidates close to `name` by edit distance, or containing it
+/// (case-insensitive), closest first
+pub fn nearest_names<'a>(
+    name: &str,
+    candidates: impl Iterator<Item = &'a str>,
+    limit: usize,
+) -> Vec<String> {
+    let target = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(2);
+
+    let mut scored: Vec<(usize, &str)> = candidates
+        .filter_map(|c| {
+            let lower = c.to_lowercase();
+            let distance = levenshtein(&target, &lower);
+            (distance <= max_distance || lower.contains(&target)).then_some((distance, c))
+        })
+        .collect();
+    scored.sort();
+    scored.dedup();
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, c)| c.to_string())
+        .collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut curr = vec![0; b_chars.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b_chars.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b_chars.len()]
+}
+
+/// Build [[virtual_beans]] into ClassInfo instances.
+///
+/// Virtual beans take part in XML generation like parsed classes (as field
+/// types, parents and table/ref targets) but are marked `is_virtual` so the
+/// TypeScript generators skip them. A name colliding with a parsed class or
+/// another virtual bean is an error.
+pub fn build_virtual_beans(
+    virtual_beans: &[VirtualBeanConfig],
+    parsed_classes: &[ClassInfo],
+) -> Result<Vec<ClassInfo>> {
+    let mut beans: Vec<ClassInfo> = Vec::new();
+
+    for (bean_index, bean) in virtual_beans.iter().enumerate() {
+        if let Some(existing) = parsed_classes.iter().find(|c| c.name == bean.name) {
+            bail!(
+                "Virtual bean '{}' collides with class '{}' parsed from {}",
+                bean.name,
+                existing.name,
+                existing.source_file
+            );
+        }
+        if beans.iter().any(|b| b.name == bean.name) {
+            bail!("Virtual bean '{}' is defined more than once", bean.name);
+        }
+
+        let mut class = ClassInfo {
+            name: bean.name.clone(),
+            comment: bean.comment.clone(),
+            alias: bean.alias.clone(),
+            fields: vec![],
+            implements: vec![],
+            extends: bean.parent.clone(),
+            source_file: String::new(),
+            source_path: Default::default(),
+            file_hash: String::new(),
+            is_interface: false,
+            output_path: bean.output_path.clone(),
+            module_name: bean.module.clone(),
+            type_params: HashMap::new(),
+            luban_table: None,
+            table_config: None,
+            input_path: None,
+            imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: true,
+            no_runtime: false,
+            flavors: FlavorCondition::default(),
+            examples: Vec::new(),
+        };
+
+        let mut start_offset = 0;
+        let mut after_offsets: HashMap<String, usize> = HashMap::new();
+        for field in &bean.fields {
+            if field.mode != VirtualFieldMode::Add {
+                bail!(
+                    "Field '{}' of virtual bean '{}' must use mode = \"add\"",
+                    field.name,
+                    bean.name
+                );
+            }
+            add_field(&mut class, field, &mut start_offset, &mut after_offsets)?;
+        }
+        for field in &mut class.fields {
+            field.origin = Some(FieldOrigin::VirtualBean { index: bean_index });
+        }
+
+        beans.push(class);
+    }
+
+    Ok(beans)
+}
+
+fn add_field(
+    class: &mut ClassInfo,
+    field: &VirtualField,
+    start_offset: &mut usize,
+    after_offsets: &mut HashMap<String, usize>,
+) -> Result<()> {
+    if let Some(existing) = class.fields.iter().find(|f| f.name == field.name) {
+        bail!(
+            "Virtual field '{}' already exists on class '{}' ({}; use mode = \"override\" to replace it)",
+            field.name,
+            class.name,
+            class.field_location(existing)
+        );
+    }
+    if field.field_type.is_empty() {
+        bail!(
+            "Virtual field '{}' on class '{}' is missing a type",
+            field.name,
+            class.name
+        );
+    }
+
+    let position = match &field.position {
+        Some(p) => VirtualFieldPosition::parse(p)?,
+        None => VirtualFieldPosition::End,
+    };
+    let field_info = virtual_field_to_field_info(field);
+
+    match position {
+        VirtualFieldPosition::Start => {
+            class.fields.insert(*start_offset, field_info);
+            *start_offset += 1;
+        }
+        VirtualFieldPosition::End => class.fields.push(field_info),
+        VirtualFieldPosition::Before(anchor) => {
+            let idx = find_anchor(class, &anchor, &field.name)?;
+            class.fields.insert(idx, field_info);
+        }
+        VirtualFieldPosition::After(anchor) => {
+            let idx = find_anchor(class, &anchor, &field.name)?;
+            let offset = after_offsets.entry(anchor).or_insert(0);
+            class.fields.insert(idx + 1 + *offset, field_info);
+            *offset += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Replace an existing field's type and validators in place; comment and alias are kept unless given
+fn override_field(class: &mut ClassInfo, field: &VirtualField) -> Result<()> {
+    if field.field_type.is_empty() {
+        bail!(
+            "Virtual field '{}' on class '{}' is missing a type",
+            field.name,
+            class.name
+        );
+    }
+    let Some(existing) = class.fields.iter_mut().find(|f| f.name == field.name) else {
+        bail!(
+            "Cannot override field '{}': class '{}' has no such field",
+            field.name,
+            class.name
+        );
+    };
+
+    let mut replacement = virtual_field_to_field_info(field);
+    if replacement.comment.is_none() {
+        replacement.comment = existing.comment.take();
+    }
+    if replacement.alias.is_none() {
+        replacement.alias = existing.alias.take();
+    }
+    replacement.order = existing.order;
+    *existing = replacement;
+    Ok(())
+}
+
+fn remove_field(class: &mut ClassInfo, field: &VirtualField) -> Result<()> {
+    let before = class.fields.len();
+    class.fields.retain(|f| f.name != field.name);
+    if class.fields.len() == before {
+        bail!(
+            "Cannot remove field '{}': class '{}' has no such field",
+            field.name,
+            class.name
+        );
+    }
+    Ok(())
+}
+
+fn find_anchor(class: &ClassInfo, anchor: &str, field_name: &str) -> Result<usize> {
+    match class.fields.iter().position(|f| f.name == anchor) {
+        Some(idx) => Ok(idx),
+        None => bail!(
+            "Virtual field '{}' is anchored to '{}', but class '{}' has no field named '{}'",
+            field_name,
+            anchor,
+            class.name,
+            anchor
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{RelocateTo, VirtualFieldValidators};
+
+    fn make_class(name: &str, fields: &[&str]) -> ClassInfo {
+        ClassInfo {
+            name: name.to_string(),
+            comment: None,
+            alias: None,
+            fields: fields
+                .iter()
+                .map(|f| FieldInfo {
+                    name: f.to_string(),
+                    field_type: "double".to_string(),
+                    original_type: "number".to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            implements: vec![],
+            extends: None,
+            source_file: "test.ts".to_string(),
+            source_path: Default::default(),
+            file_hash: "abc".to_string(),
+            is_interface: false,
+            output_path: None,
+            module_name: None,
+            type_params: HashMap::new(),
+            luban_table: None,
+            table_config: None,
+            input_path: None,
+            imports: HashMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
+        }
+    }
+
+    fn make_virtual(name: &str, position: Option<&str>) -> VirtualField {
+        VirtualField {
+            name: name.to_string(),
+            field_type: "ScalingStat".to_string(),
+            comment: None,
+            alias: None,
+            is_optional: false,
+            relocate_to: None,
+            validators: VirtualFieldValidators::default(),
+            position: position.map(|p| p.to_string()),
+            mode: VirtualFieldMode::Add,
+        }
+    }
+
+    fn field_names(class: &ClassInfo) -> Vec<&str> {
+        class.fields.iter().map(|f| f.name.as_str()).collect()
+    }
+
+    fn inject(fields: Vec<VirtualField>) -> Result<ClassInfo> {
+        let mut classes = vec![make_class("WeaponConfig", &["id", "name", "damage"])];
+        let config = vec![VirtualFieldsConfig {
+            class: "WeaponConfig".to_string(),
+            fields,
+        }];
+        let unmatched = inject_virtual_fields(&mut classes, &config)?;
+        assert!(unmatched.is_empty());
+        Ok(classes.remove(0))
+    }
+
+    #[test]
+    fn test_parse_position() {
+        assert_eq!(
+            VirtualFieldPosition::parse("start").unwrap(),
+            VirtualFieldPosition::Start
+        );
+        assert_eq!(
+            VirtualFieldPosition::parse("end").unwrap(),
+            VirtualFieldPosition::End
+        );
+        assert_eq!(
+            VirtualFieldPosition::parse("before:id").unwrap(),
+            VirtualFieldPosition::Before("id".to_string())
+        );
+        assert_eq!(
+            VirtualFieldPosition::parse("after:name").unwrap(),
+            VirtualFieldPosition::After("name".to_string())
+        );
+        assert!(VirtualFieldPosition::parse("middle").is_err());
+        assert!(VirtualFieldPosition::parse("after:").is_err());
+    }
+
+    #[test]
+    fn test_default_position_appends() {
+        let class = inject(vec![make_virtual("a", None), make_virtual("b", None)]).unwrap();
+        assert_eq!(field_names(&class), vec!["id", "name", "damage", "a", "b"]);
+        assert_eq!(class.fields[0].virtual_block(), None);
+        assert_eq!(class.fields[3].virtual_block(), Some(0));
+    }
+
+    #[test]
+    fn test_position_end() {
+        let class = inject(vec![make_virtual("a", Some("end"))]).unwrap();
+        assert_eq!(field_names(&class), vec!["id", "name", "damage", "a"]);
+    }
+
+    #[test]
+    fn test_position_start_preserves_order() {
+        let class = inject(vec![
+            make_virtual("a", Some("start")),
+            make_virtual("b", Some("start")),
+        ])
+        .unwrap();
+        assert_eq!(field_names(&class), vec!["a", "b", "id", "name", "damage"]);
+    }
+
+    #[test]
+    fn test_position_before_preserves_order() {
+        let class = inject(vec![
+            make_virtual("a", Some("before:damage")),
+            make_virtual("b", Some("before:damage")),
+        ])
+        .unwrap();
+        assert_eq!(field_names(&class), vec!["id", "name", "a", "b", "damage"]);
+    }
+
+    #[test]
+    fn test_position_after_preserves_order() {
+        let class = inject(vec![
+            make_virtual("a", Some("after:id")),
+            make_virtual("b", Some("after:id")),
+        ])
+        .unwrap();
+        assert_eq!(field_names(&class), vec!["id", "a", "b", "name", "damage"]);
+    }
+
+    #[test]
+    fn test_anchors_follow_declaration_order() {
+        let mut classes = vec![make_class("WeaponConfig", &["name", "id", "damage"])];
+        // Declared as id, name, damage but stored out of order
+        for (field, order) in classes[0].fields.iter_mut().zip([20, 10, 30]) {
+            field.order = order;
+        }
+        let config = vec![VirtualFieldsConfig {
+            class: "WeaponConfig".to_string(),
+            fields: vec![make_virtual("a", Some("after:id"))],
+        }];
+        inject_virtual_fields(&mut classes, &config).unwrap();
+
+        assert_eq!(field_names(&classes[0]), vec!["id", "a", "name", "damage"]);
+        let orders: Vec<u32> = classes[0].fields.iter().map(|f| f.order).collect();
+        assert_eq!(orders, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_missing_anchor_errors() {
+        let err = inject(vec![make_virtual("a", Some("after:missing"))]).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("WeaponConfig"));
+        assert!(msg.contains("missing"));
+    }
+
+    #[test]
+    fn test_nonexistent_class_is_reported() {
+        let mut classes = vec![make_class("WeaponConfig", &["id"])];
+        let config = vec![
+            VirtualFieldsConfig {
+                class: "WeaponConfg".to_string(),
+                fields: vec![make_virtual("a", None)],
+            },
+            VirtualFieldsConfig {
+                class: "ArmorConfig".to_string(),
+                fields: vec![make_virtual("b", None)],
+            },
+        ];
+        let unmatched = inject_virtual_fields(&mut classes, &config).unwrap();
+        assert_eq!(field_names(&classes[0]), vec!["id"]);
+
+        assert_eq!(unmatched.len(), 2);
+        assert_eq!(unmatched[0].class, "WeaponConfg");
+        assert_eq!(unmatched[0].suggestion.as_deref(), Some("WeaponConfig"));
+        assert!(unmatched[0]
+            .to_string()
+            .contains("did you mean 'WeaponConfig'"));
+        assert_eq!(unmatched[1].class, "ArmorConfig");
+        assert!(unmatched[1].suggestion.is_none());
+    }
+
+    #[test]
+    fn test_nearest_name() {
+        let names = ["WeaponConfig", "ArmorConfig", "RoleConfig"];
+        assert_eq!(
+            nearest_name("weaponconfig", names.iter().copied()),
+            Some("WeaponConfig".to_string())
+        );
+        assert_eq!(
+            nearest_name("RolConfig", names.iter().copied()),
+            Some("RoleConfig".to_string())
+        );
+        assert_eq!(nearest_name("Totally", names.iter().copied()), None);
+    }
+
+    #[test]
+    fn test_relocate_to_becomes_tags() {
+        let mut field = make_virtual("mainStat", None);
+        field.relocate_to = Some(RelocateTo {
+            target: "TScalingStat".to_string(),
+            prefix: "_main".to_string(),
+            target_bean: None,
+        });
+        let class = inject(vec![field]).unwrap();
+        let injected = class.fields.last().unwrap();
+        assert_eq!(
+            injected.custom_tags.as_deref(),
+            Some("relocateTo=TScalingStat,prefix=_main")
+        );
+        assert_eq!(injected.field_type, "ScalingStat");
+    }
+
+    #[test]
+    fn test_add_duplicate_field_errors() {
+        let err = inject(vec![make_virtual("name", None)]).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        assert!(err.to_string().contains("(test.ts; use mode"));
+    }
+
+    #[test]
+    fn test_override_keeps_position() {
+        let mut field = make_virtual("name", None);
+        field.field_type = "CostBundle".to_string();
+        field.comment = Some("消耗".to_string());
+        field.validators.required = true;
+        field.mode = VirtualFieldMode::Override;
+        let class = inject(vec![field]).unwrap();
+
+        assert_eq!(field_names(&class), vec!["id", "name", "damage"]);
+        let overridden = &class.fields[1];
+        assert_eq!(overridden.field_type, "CostBundle");
+        assert_eq!(overridden.comment.as_deref(), Some("消耗"));
+        assert!(overridden.validators.required);
+        assert_eq!(
+            overridden.origin,
+            Some(FieldOrigin::VirtualConfig { index: 0 })
+        );
+    }
+
+    #[test]
+    fn test_alias_on_virtual_fields() {
+        let mut added = make_virtual("cost", None);
+        added.alias = Some("消耗".to_string());
+        let mut overridden = make_virtual("name", None);
+        overridden.alias = Some("名称".to_string());
+        overridden.mode = VirtualFieldMode::Override;
+        let class = inject(vec![added, overridden]).unwrap();
+        assert_eq!(class.fields[1].alias.as_deref(), Some("名称"));
+        assert_eq!(class.fields[3].alias.as_deref(), Some("消耗"));
+
+        let mut bean = make_bean("TScalingStat", vec![make_virtual("id", None)]);
+        bean.alias = Some("成长属性".to_string());
+        let beans = build_virtual_beans(&[bean], &[]).unwrap();
+        assert_eq!(beans[0].alias.as_deref(), Some("成长属性"));
+        assert_eq!(
+            beans[0].fields[0].origin,
+            Some(FieldOrigin::VirtualBean { index: 0 })
+        );
+    }
+
+    #[test]
+    fn test_override_missing_field_errors() {
+        let mut field = make_virtual("cost", None);
+        field.mode = VirtualFieldMode::Override;
+        assert!(inject(vec![field]).is_err());
+    }
+
+    #[test]
+    fn test_remove_field() {
+        let mut field = make_virtual("name", None);
+        field.field_type = String::new();
+        field.mode = VirtualFieldMode::Remove;
+        let class = inject(vec![field]).unwrap();
+        assert_eq!(field_names(&class), vec!["id", "damage"]);
+    }
+
+    #[test]
+    fn test_remove_missing_field_errors() {
+        let mut field = make_virtual("cost", None);
+        field.mode = VirtualFieldMode::Remove;
+        assert!(inject(vec![field]).is_err());
+    }
+
+    fn make_bean(name: &str, fields: Vec<VirtualField>) -> VirtualBeanConfig {
+        VirtualBeanConfig {
+            name: name.to_string(),
+            module: Some("stats".to_string()),
+            parent: None,
+            comment: None,
+            alias: None,
+            output_path: None,
+            fields,
+        }
+    }
+
+    #[test]
+    fn test_build_virtual_beans() {
+        let mut value = make_virtual("value", None);
+        value.field_type = "double".to_string();
+        value.validators.range = Some((0.0, 1.0));
+        let beans = build_virtual_beans(
+            &[make_bean(
+                "TScalingStat",
+                vec![make_virtual("id", None), value],
+            )],
+            &[make_class("WeaponConfig", &["id"])],
+        )
+        .unwrap();
+
+        assert_eq!(beans.len(), 1);
+        let bean = &beans[0];
+        assert!(bean.is_virtual);
+        assert!(!bean.is_interface);
+        assert_eq!(bean.module_name.as_deref(), Some("stats"));
+        assert_eq!(field_names(bean), vec!["id", "value"]);
+        assert_eq!(
+            bean.fields[1].validators.range,
+            Some(RangeConstraint::Between(0.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn test_virtual_bean_collision_errors() {
+        let err = build_virtual_beans(
+            &[make_bean("WeaponConfig", vec![])],
+            &[make_class("WeaponConfig", &["id"])],
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("collides"));
+
+        let err = build_virtual_beans(
+            &[make_bean("TStat", vec![]), make_bean("TStat", vec![])],
+            &[],
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("more than once"));
+    }
+}
@@ -0,0 +1,858 @@
+//! Checks over the rendered Luban XML before it is written. Each rule mirrors a schema
+//! rule the Luban toolchain would otherwise only report minutes later, against the XML
+//! rather than the TypeScript. Violations point at the originating TypeScript source when
+//! the bean or field can be traced back to a parsed class.
+
+use crate::parser::ClassInfo;
+use crate::table_registry::TABLE_MODES;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Keys allowed after `#` in a var type, e.g. `int#range=[1,10]`
+const VALIDATOR_KEYS: &[&str] = &[
+    "ref",
+    "range",
+    "set",
+    "size",
+    "index",
+    "sep",
+    "default",
+    "path",
+    "regex",
+    "constructor",
+];
+
+/// Container types that must not be nullable unless optional containers are allowed
+const CONTAINER_TYPES: &[&str] = &["list", "array", "set", "map"];
+
+/// What the rules allow beyond the Luban defaults
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LintOptions {
+    /// Nullable containers (`list?,string`) are emitted on purpose
+    /// (defaults.optional_containers = "nullable")
+    pub nullable_containers: bool,
+    /// Table modes outside map/list/one/singleton are passed through on purpose
+    /// (defaults.allow_custom_modes)
+    pub custom_table_modes: bool,
+}
+
+/// A `<var>` of a bean
+#[derive(Debug, Clone)]
+struct Var {
+    name: String,
+    ty: String,
+    line: usize,
+}
+
+/// A `<bean>`, nested beans included
+#[derive(Debug, Clone)]
+struct Bean {
+    name: String,
+    /// The `parent` attribute, or the enclosing bean for nested beans
+    parent: Option<String>,
+    /// Whether `parent` came from an attribute (and so must resolve)
+    explicit_parent: bool,
+    vars: Vec<Var>,
+    line: usize,
+}
+
+/// A `<table>`
+#[derive(Debug, Clone)]
+struct Table {
+    name: String,
+    value: String,
+    mode: Option<String>,
+    index: Option<String>,
+    line: usize,
+}
+
+/// The parts of one rendered XML file the rules look at
+#[derive(Debug, Default)]
+struct Document {
+    path: PathBuf,
+    module: String,
+    beans: Vec<Bean>,
+    tables: Vec<Table>,
+}
+
+/// Everything the rules need: the parsed documents and where their beans came from
+struct Context<'a> {
+    documents: Vec<Document>,
+    /// `module.Bean` (or `Bean` in the root module) -> bean, across all documents
+    beans: HashMap<String, (usize, usize)>,
+    classes: &'a [ClassInfo],
+    default_module: &'a str,
+    options: LintOptions,
+}
+
+/// Lint rendered XML files (path, content). `classes` are the parsed classes, used to point
+/// violations at their TypeScript source; classes without a module are in `default_module`.
+/// Returns one message per violation
+pub fn lint<'p>(
+    files: impl IntoIterator<Item = (&'p Path, &'p str)>,
+    classes: &[ClassInfo],
+    default_module: &str,
+    options: LintOptions,
+) -> Vec<String> {
+    let mut violations = Vec::new();
+    let documents: Vec<Document> = files
+        .into_iter()
+        .map(|(path, content)| parse_document(path, content, &mut violations))
+        .collect();
+    let context = Context::new(documents, classes, default_module, options);
+
+    for rule in RULES {
+        violations.extend(rule(&context));
+    }
+    violations
+}
+
+type Rule = fn(&Context) -> Vec<String>;
+
+/// Every rule, in reporting order
+const RULES: &[Rule] = &[
+    check_names,
+    check_table_modes,
+    check_table_indexes,
+    check_table_values,
+    check_parents,
+    check_validator_keys,
+    check_nullable_containers,
+];
+
+impl<'a> Context<'a> {
+    fn new(
+        documents: Vec<Document>,
+        classes: &'a [ClassInfo],
+        default_module: &'a str,
+        options: LintOptions,
+    ) -> Self {
+        let mut beans = HashMap::new();
+        for (doc_index, document) in documents.iter().enumerate() {
+            for (bean_index, bean) in document.beans.iter().enumerate() {
+                beans.insert(
+                    qualified(&document.module, &bean.name),
+                    (doc_index, bean_index),
+                );
+            }
+        }
+        Self {
+            documents,
+            beans,
+            classes,
+            default_module,
+            options,
+        }
+    }
+
+    fn bean(&self, key: &str) -> Option<&Bean> {
+        self.beans
+            .get(key)
+            .map(|&(doc, bean)| &self.documents[doc].beans[bean])
+    }
+
+    /// Resolve a type name the way Luban does: relative to `module` first, then absolute
+    fn resolve(&self, module: &str, name: &str) -> Option<(String, &Bean)> {
+        [qualified(module, name), name.to_string()]
+            .into_iter()
+            .find_map(|key| self.bean(&key).map(|bean| (key, bean)))
+    }
+
+    /// Modules this run generates
+    fn modules(&self) -> HashSet<&str> {
+        self.documents.iter().map(|d| d.module.as_str()).collect()
+    }
+
+    /// Var names of a bean and its ancestors within the generated documents
+    fn all_var_names(&self, module: &str, bean: &Bean) -> HashSet<String> {
+        let mut names = HashSet::new();
+        let mut seen = HashSet::new();
+        let mut current = Some((module.to_string(), bean));
+        while let Some((module, bean)) = current {
+            if !seen.insert(qualified(&module, &bean.name)) {
+                break;
+            }
+            names.extend(bean.vars.iter().map(|v| v.name.clone()));
+            current = bean.parent.as_deref().and_then(|parent| {
+                let (key, parent) = self.resolve(&module, parent)?;
+                let parent_module = key.rsplit_once('.').map_or("", |(m, _)| m).to_string();
+                Some((parent_module, parent))
+            });
+        }
+        names
+    }
+
+    /// Whether a parsed class named `name` belongs to `module`
+    fn is_parsed(&self, module: &str, name: &str) -> bool {
+        self.classes.iter().any(|c| {
+            c.name == name && c.module_name.as_deref().unwrap_or(self.default_module) == module
+        })
+    }
+
+    /// Where a bean (or one of its vars) came from: `file:line` of the TypeScript source
+    /// when the bean traces back to a parsed class, else the XML file and line
+    fn location(
+        &self,
+        document: &Document,
+        bean: Option<&Bean>,
+        var: Option<&Var>,
+        line: usize,
+    ) -> String {
+        let class = bean.and_then(|bean| {
+            let mut candidates = self.classes.iter().filter(|c| c.name == bean.name);
+            let first = candidates.next()?;
+            let module_of = |c: &ClassInfo| {
+                c.module_name
+                    .clone()
+                    .unwrap_or_else(|| self.default_module.to_string())
+            };
+            if module_of(first) == document.module {
+                return Some(first);
+            }
+            candidates
+                .find(|c| module_of(c) == document.module)
+                .or(Some(first))
+        });
+        match class {
+            Some(class) => {
+                let field =
+                    var.and_then(|var| class.fields.iter().find(|f| f.schema_name() == var.name));
+                match field {
+                    Some(field) => class.field_location(field),
+                    None => class.source_file.clone(),
+                }
+            }
+            None => format!("{}:{}", document.path.display(), line),
+        }
+    }
+}
+
+fn qualified(module: &str, name: &str) -> String {
+    if module.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", module, name)
+    }
+}
+
+/// Read the beans and tables of one file. Malformed XML and attribute values that aren't
+/// XML-safe are reported directly
+fn parse_document(path: &Path, content: &str, violations: &mut Vec<String>) -> Document {
+    let mut document = Document {
+        path: path.to_path_buf(),
+        ..Default::default()
+    };
+    let line_at = |pos: u64| {
+        content[..(pos as usize).min(content.len())]
+            .matches('\n')
+            .count()
+            + 1
+    };
+
+    let mut reader = Reader::from_str(content);
+    // Open elements, with the index of the bean each open <bean> became
+    let mut open: Vec<(String, Option<usize>)> = Vec::new();
+    loop {
+        let position = reader.buffer_position();
+        let event = match reader.read_event() {
+            Ok(event) => event,
+            Err(e) => {
+                violations.push(format!(
+                    "{}:{}: generated XML is not well-formed: {}",
+                    path.display(),
+                    line_at(reader.error_position()),
+                    e
+                ));
+                break;
+            }
+        };
+        let line = line_at(position);
+        let (element, is_empty) = match &event {
+            Event::Start(element) => (element, false),
+            Event::Empty(element) => (element, true),
+            Event::End(_) => {
+                open.pop();
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+        let tag = String::from_utf8_lossy(element.name().as_ref()).to_string();
+        let attrs = read_attributes(element, path, line, violations);
+        let attr = |key: &str| attrs.get(key).cloned();
+        let enclosing_bean = open.iter().rev().find_map(|(_, bean)| *bean);
+        let in_enum = open.iter().any(|(tag, _)| tag == "enum");
+
+        let mut bean_index = None;
+        match tag.as_str() {
+            "module" => document.module = attr("name").unwrap_or_default(),
+            "bean" => {
+                let explicit_parent = attr("parent");
+                let parent = explicit_parent
+                    .clone()
+                    .or_else(|| enclosing_bean.map(|i| document.beans[i].name.clone()));
+                bean_index = Some(document.beans.len());
+                document.beans.push(Bean {
+                    name: attr("name").unwrap_or_default(),
+                    explicit_parent: explicit_parent.is_some(),
+                    parent,
+                    vars: Vec::new(),
+                    line,
+                });
+            }
+            "var" if !in_enum => {
+                if let Some(index) = enclosing_bean {
+                    document.beans[index].vars.push(Var {
+                        name: attr("name").unwrap_or_default(),
+                        ty: attr("type").unwrap_or_default(),
+                        line,
+                    });
+                }
+            }
+            "table" => document.tables.push(Table {
+                name: attr("name").unwrap_or_default(),
+                value: attr("value").unwrap_or_default(),
+                mode: attr("mode"),
+                index: attr("index"),
+                line,
+            }),
+            _ => {}
+        }
+        if !is_empty {
+            open.push((tag, bean_index));
+        }
+    }
+    document
+}
+
+/// Unescaped attribute values. Values that fail to unescape or contain control characters
+/// are reported and skipped
+fn read_attributes(
+    element: &BytesStart,
+    path: &Path,
+    line: usize,
+    violations: &mut Vec<String>,
+) -> HashMap<String, String> {
+    let mut attrs = HashMap::new();
+    for attr in element.attributes() {
+        let attr = match attr {
+            Ok(attr) => attr,
+            Err(e) => {
+                violations.push(format!(
+                    "{}:{}: malformed attribute: {}",
+                    path.display(),
+                    line,
+                    e
+                ));
+                continue;
+            }
+        };
+        let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+        match attr.unescape_value() {
+            Ok(value) if value.chars().any(|c| c.is_control() && c != '\t') => {
+                violations.push(format!(
+                    "{}:{}: {} - attribute value contains a control character",
+                    path.display(),
+                    line,
+                    key
+                ));
+            }
+            Ok(value) => {
+                attrs.insert(key, value.into_owned());
+            }
+            Err(e) => violations.push(format!(
+                "{}:{}: {} - attribute value is not XML-safe: {}",
+                path.display(),
+                line,
+                key,
+                e
+            )),
+        }
+    }
+    attrs
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Bean and var names must be non-empty identifiers
+fn check_names(context: &Context) -> Vec<String> {
+    let mut violations = Vec::new();
+    for document in &context.documents {
+        for bean in &document.beans {
+            if !is_identifier(&bean.name) {
+                violations.push(format!(
+                    "{}: bean '{}' - bean name must be a non-empty identifier",
+                    context.location(document, Some(bean), None, bean.line),
+                    bean.name
+                ));
+            }
+            for var in &bean.vars {
+                if !is_identifier(&var.name) {
+                    violations.push(format!(
+                        "{}: {}.{} - var name must be a non-empty identifier",
+                        context.location(document, Some(bean), Some(var), var.line),
+                        bean.name,
+                        var.name
+                    ));
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// Table modes must be one Luban knows (an absent mode means `map`)
+fn check_table_modes(context: &Context) -> Vec<String> {
+    let mut violations = Vec::new();
+    if context.options.custom_table_modes {
+        return violations;
+    }
+    for document in &context.documents {
+        for table in &document.tables {
+            if let Some(mode) = &table.mode {
+                if !TABLE_MODES.contains(&mode.as_str()) {
+                    violations.push(format!(
+                        "{}: table '{}' - unknown mode '{}' (expected one of {})",
+                        table_location(context, document, table),
+                        table.name,
+                        mode,
+                        TABLE_MODES.join(", ")
+                    ));
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// `one`/`singleton` tables have no index; map tables need one, and every index names a
+/// field of the value bean (or its ancestors)
+fn check_table_indexes(context: &Context) -> Vec<String> {
+    let mut violations = Vec::new();
+    for document in &context.documents {
+        for table in &document.tables {
+            let location = table_location(context, document, table);
+            let mode = table.mode.as_deref().unwrap_or("map");
+            match (mode, &table.index) {
+                ("one" | "singleton", Some(index)) => violations.push(format!(
+                    "{}: table '{}' - a {} table has no index, but index '{}' is set",
+                    location, table.name, mode, index
+                )),
+                ("map", None) => violations.push(format!(
+                    "{}: table '{}' - a map table needs an index",
+                    location, table.name
+                )),
+                _ => {}
+            }
+
+            let (Some(index), Some((_, bean))) = (
+                &table.index,
+                context.resolve(&document.module, &table.value),
+            ) else {
+                continue;
+            };
+            let fields = context.all_var_names(&document.module, bean);
+            for key in index.split(['+', ',']).map(str::trim) {
+                if !fields.contains(key) {
+                    violations.push(format!(
+                        "{}: table '{}' - index '{}' is not a field of bean '{}'",
+                        location, table.name, key, table.value
+                    ));
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// Table values must name a generated bean
+fn check_table_values(context: &Context) -> Vec<String> {
+    let mut violations = Vec::new();
+    for document in &context.documents {
+        for table in &document.tables {
+            if context.resolve(&document.module, &table.value).is_none() {
+                violations.push(format!(
+                    "{}: table '{}' - value bean '{}' is not defined",
+                    table_location(context, document, table),
+                    table.name,
+                    table.value
+                ));
+            }
+        }
+    }
+    violations
+}
+
+/// Parent references must not be empty or the bean itself, and a parent in a generated
+/// module must exist there (or be a parsed class left out of this run). Unqualified
+/// parents that aren't generated are assumed to be hand-written beans
+fn check_parents(context: &Context) -> Vec<String> {
+    let modules = context.modules();
+    let mut violations = Vec::new();
+    for document in &context.documents {
+        for bean in document.beans.iter().filter(|b| b.explicit_parent) {
+            let parent = bean.parent.as_deref().unwrap_or("");
+            let location = context.location(document, Some(bean), None, bean.line);
+            let problem = if parent.is_empty() {
+                Some("parent is empty".to_string())
+            } else if parent == bean.name || parent == qualified(&document.module, &bean.name) {
+                Some("bean is its own parent".to_string())
+            } else {
+                match parent.rsplit_once('.') {
+                    Some((module, name))
+                        if modules.contains(module)
+                            && context.resolve(&document.module, parent).is_none()
+                            && !context.is_parsed(module, name) =>
+                    {
+                        Some(format!(
+                            "parent '{}' is not defined in module '{}'",
+                            parent, module
+                        ))
+                    }
+                    _ => None,
+                }
+            };
+            if let Some(problem) = problem {
+                violations.push(format!("{}: bean '{}' - {}", location, bean.name, problem));
+            }
+        }
+    }
+    violations
+}
+
+/// `#key=value` suffixes in var types must use a known key
+fn check_validator_keys(context: &Context) -> Vec<String> {
+    let mut violations = Vec::new();
+    for document in &context.documents {
+        for bean in &document.beans {
+            for var in &bean.vars {
+                for key in validator_keys(&var.ty) {
+                    if !VALIDATOR_KEYS.contains(&key) {
+                        violations.push(format!(
+                            "{}: {}.{} - unknown validator key '{}' in type '{}'",
+                            context.location(document, Some(bean), Some(var), var.line),
+                            bean.name,
+                            var.name,
+                            key,
+                            var.ty
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// Keys of the `#key=value` / `#key` suffixes in a var type. Text inside `[...]` (range
+/// bounds) is skipped
+fn validator_keys(ty: &str) -> Vec<&str> {
+    let mut keys = Vec::new();
+    let mut depth = 0;
+    for (i, c) in ty.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            '#' if depth == 0 => {
+                let rest = &ty[i + 1..];
+                let end = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                    .unwrap_or(rest.len());
+                if end > 0 {
+                    keys.push(&rest[..end]);
+                }
+            }
+            _ => {}
+        }
+    }
+    keys
+}
+
+/// `?` on a container type (`list?,string`) only with optional containers allowed
+fn check_nullable_containers(context: &Context) -> Vec<String> {
+    if context.options.nullable_containers {
+        return Vec::new();
+    }
+    let mut violations = Vec::new();
+    for document in &context.documents {
+        for bean in &document.beans {
+            for var in &bean.vars {
+                let head = var.ty.trim_start_matches('(');
+                let container = head.split([',', '#', ')']).next().unwrap_or("");
+                if let Some(name) = container.strip_suffix('?') {
+                    if CONTAINER_TYPES.contains(&name) {
+                        violations.push(format!(
+                            "{}: {}.{} - container type '{}' can't be nullable in Luban",
+                            context.location(document, Some(bean), Some(var), var.line),
+                            bean.name,
+                            var.name,
+                            var.ty
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    violations
+}
+
+fn table_location(context: &Context, document: &Document, table: &Table) -> String {
+    let bean = context
+        .resolve(&document.module, &table.value)
+        .map(|(_, bean)| bean);
+    context.location(document, bean, None, table.line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{FieldInfo, FieldOrigin, ImportMap};
+
+    fn module(body: &str) -> String {
+        format!(
+            "<module name=\"game\" comment=\"x\">\n{}\n</module>\n",
+            body
+        )
+    }
+
+    fn lint_one(content: &str) -> Vec<String> {
+        lint(
+            [(Path::new("out/game.xml"), content)],
+            &[],
+            "game",
+            LintOptions::default(),
+        )
+    }
+
+    #[test]
+    fn test_valid_module() {
+        let content = module(
+            r#"    <bean name="Base">
+        <var name="id" type="int"/>
+    </bean>
+    <bean name="Item" parent="Base">
+        <var name="count" type="int!#range=[1,10]#default=1"/>
+        <var name="tags" type="(list#sep=|),string"/>
+    </bean>
+    <table name="ItemTable" value="Item" index="id" input="../datas/item" />
+    <table name="ItemConstants" value="Item" mode="one" input="constants/item" />"#,
+        );
+        assert!(lint_one(&content).is_empty());
+    }
+
+    #[test]
+    fn test_names_rule() {
+        let content = module(
+            r#"    <bean name="">
+        <var name="" type="int"/>
+    </bean>"#,
+        );
+        assert_eq!(
+            lint_one(&content),
+            vec![
+                "out/game.xml:2: bean '' - bean name must be a non-empty identifier",
+                "out/game.xml:3: . - var name must be a non-empty identifier",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_table_modes_rule() {
+        let content = module(
+            r#"    <bean name="Item"><var name="id" type="int"/></bean>
+    <table name="ItemTable" value="Item" mode="dict" input="x" />"#,
+        );
+        assert_eq!(
+            lint_one(&content),
+            vec!["out/game.xml:3: table 'ItemTable' - unknown mode 'dict' (expected one of map, list, one, singleton)"]
+        );
+        let allowed = LintOptions {
+            custom_table_modes: true,
+            ..Default::default()
+        };
+        assert!(lint(
+            [(Path::new("out/game.xml"), content.as_str())],
+            &[],
+            "game",
+            allowed
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_table_indexes_rule() {
+        let content = module(
+            r#"    <bean name="Item"><var name="id" type="int"/></bean>
+    <table name="A" value="Item" mode="one" index="id" input="x" />
+    <table name="B" value="Item" input="x" />
+    <table name="C" value="Item" index="uid" input="x" />
+    <table name="D" value="Item" mode="list" index="id,kind" input="x" />"#,
+        );
+        assert_eq!(
+            lint_one(&content),
+            vec![
+                "out/game.xml:3: table 'A' - a one table has no index, but index 'id' is set",
+                "out/game.xml:4: table 'B' - a map table needs an index",
+                "out/game.xml:5: table 'C' - index 'uid' is not a field of bean 'Item'",
+                "out/game.xml:6: table 'D' - index 'kind' is not a field of bean 'Item'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_table_values_rule() {
+        let content =
+            module(r#"    <table name="GhostTable" value="Ghost" index="id" input="x" />"#);
+        assert_eq!(
+            lint_one(&content),
+            vec!["out/game.xml:2: table 'GhostTable' - value bean 'Ghost' is not defined"]
+        );
+    }
+
+    #[test]
+    fn test_parents_rule() {
+        let other =
+            "<module name=\"shared\" comment=\"x\">\n    <bean name=\"Vector\"/>\n</module>\n";
+        let content = module(
+            r#"    <bean name="A" parent=""/>
+    <bean name="B" parent="B"/>
+    <bean name="C" parent="shared.Missing"/>
+    <bean name="D" parent="shared.Vector"/>
+    <bean name="E" parent="TsTriggerClass"/>"#,
+        );
+        let violations = lint(
+            [
+                (Path::new("out/shared.xml"), other),
+                (Path::new("out/game.xml"), content.as_str()),
+            ],
+            &[],
+            "game",
+            LintOptions::default(),
+        );
+        assert_eq!(
+            violations,
+            vec![
+                "out/game.xml:2: bean 'A' - parent is empty",
+                "out/game.xml:3: bean 'B' - bean is its own parent",
+                "out/game.xml:4: bean 'C' - parent 'shared.Missing' is not defined in module 'shared'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validator_keys_rule() {
+        assert_eq!(
+            validator_keys("(map#sep=,|#size=[1,3]),string,int#ref=game.TbItem"),
+            vec!["sep", "size", "ref"]
+        );
+        let content = module(
+            r#"    <bean name="Item">
+        <var name="level" type="int#rnage=[1,10]"/>
+    </bean>"#,
+        );
+        assert_eq!(
+            lint_one(&content),
+            vec!["out/game.xml:3: Item.level - unknown validator key 'rnage' in type 'int#rnage=[1,10]'"]
+        );
+    }
+
+    #[test]
+    fn test_nullable_containers_rule() {
+        let content = module(
+            r#"    <bean name="Item">
+        <var name="tags" type="list?,string"/>
+        <var name="drops" type="(list?#sep=|),int"/>
+        <var name="values" type="list,double?"/>
+    </bean>"#,
+        );
+        assert_eq!(lint_one(&content).len(), 2);
+        let allowed = LintOptions {
+            nullable_containers: true,
+            ..Default::default()
+        };
+        assert!(lint(
+            [(Path::new("out/game.xml"), content.as_str())],
+            &[],
+            "game",
+            allowed
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_xml_safety_rule() {
+        let content = module(
+            r#"    <bean name="Item" comment="Tom & Jerry">
+        <var name="id" type="int"/>
+    </bean>"#,
+        );
+        let violations = lint_one(&content);
+        assert_eq!(violations.len(), 1);
+        assert!(
+            violations[0].starts_with("out/game.xml:2: comment - attribute value is not XML-safe")
+        );
+    }
+
+    #[test]
+    fn test_violations_point_at_typescript_source() {
+        let content = module(
+            r#"    <bean name="Item">
+        <var name="level" type="int#rnage=[1,10]"/>
+    </bean>"#,
+        );
+        let class = ClassInfo {
+            name: "Item".to_string(),
+            comment: None,
+            alias: None,
+            fields: vec![FieldInfo {
+                name: "level".to_string(),
+                origin: Some(FieldOrigin::Source {
+                    file: "src/item.ts".to_string(),
+                    line: 7,
+                }),
+                ..Default::default()
+            }],
+            implements: vec![],
+            extends: None,
+            source_file: "src/item.ts".to_string(),
+            source_path: Default::default(),
+            file_hash: "hash".to_string(),
+            is_interface: false,
+            output_path: None,
+            module_name: None,
+            type_params: Default::default(),
+            luban_table: None,
+            table_config: None,
+            input_path: None,
+            imports: ImportMap::new(),
+            output_route: None,
+            is_ambient: false,
+            constants: Vec::new(),
+            ignored_statics: Vec::new(),
+            ref_key: None,
+            tags: Vec::new(),
+            explicit_parent: None,
+            explicit_module: false,
+            is_virtual: false,
+            no_runtime: false,
+            flavors: Default::default(),
+            examples: Vec::new(),
+        };
+        let violations = lint(
+            [(Path::new("out/game.xml"), content.as_str())],
+            &[class],
+            "game",
+            LintOptions::default(),
+        );
+        assert_eq!(
+            violations,
+            vec!["src/item.ts:7: Item.level - unknown validator key 'rnage' in type 'int#rnage=[1,10]'"]
+        );
+    }
+}
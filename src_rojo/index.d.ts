@@ -56,10 +56,19 @@ export declare function Index(field: string): PropertyDecorator;
  */
 export declare function Nominal(): PropertyDecorator;
 /**
- * 引用验证器（已废弃，请使用 JSDoc @ref 代替）
- * @deprecated Use JSDoc @ref tag instead
+ * 引用验证器：字段保存 target 表的 key，标量、list/array/set 元素或 map value 使用目标表的索引类型
+ * @example
+ * @Ref(ItemConfig)
+ * itemIds: number[];  // -> list,(int#ref=shop.ItemConfigTable)
  */
 export declare function Ref(_target: any): PropertyDecorator;
+/**
+ * map key 引用验证器
+ * @example
+ * @RefKey(ItemConfig)
+ * stock: Map<number, number>;  // -> map,(int#ref=shop.ItemConfigTable),int
+ */
+export declare function RefKey(_target: any): PropertyDecorator;
 /**
  * 引用替换装饰器
  * 1. 提供 luban 引用
@@ -0,0 +1,4 @@
+export class CurveConfig {
+    @Converter("CurveRef", { tag: "converter=CurveParser" })
+    public curve?: string;
+}
@@ -0,0 +1,17 @@
+/** A UI tree: each node lists its children */
+export class UiNode {
+    public name: string;
+    public children: UiNode[];
+    public parent?: UiNode;
+}
+
+/** A quest made of steps, each of which may start a sub-quest */
+export class QuestConfig {
+    public id: number;
+    public steps: QuestStep[];
+}
+
+export class QuestStep {
+    public description: string;
+    public subQuest?: QuestConfig;
+}
@@ -74,7 +74,7 @@ path = "{}"
 
     // Check for EntityTrigger (interface, should not have parent)
     assert!(
-        output.contains(r#"<bean name="EntityTrigger">"#)
+        output.contains(r#"<bean name="EntityTrigger"/>"#)
             && !output.contains(r#"<bean name="EntityTrigger" parent="#),
         "Missing EntityTrigger interface bean (should have no parent)"
     );
@@ -102,6 +102,17 @@ path = "{}"
         output.contains(r#"tags="ObjectFactory=true""#),
         "Missing ObjectFactory=true tag for ObjectFactory field"
     );
+
+    // Check @Converter field: verbatim type with optional suffix and converter tag
+    assert!(
+        output.contains(r#"<var name="curve" type="CurveRef?" tags="converter=CurveParser"/>"#),
+        "Missing @Converter field with verbatim type and converter tag"
+    );
+
+    // Recursive beans reference themselves and each other by name
+    assert!(output.contains(r#"<var name="children" type="list,UiNode"/>"#));
+    assert!(output.contains(r#"<var name="parent" type="UiNode?"/>"#));
+    assert!(output.contains(r#"<var name="subQuest" type="QuestConfig?"/>"#));
 }
 
 #[test]
@@ -154,3 +165,3016 @@ path = "{}"
         .expect("Failed to run luban-gen with --force");
     assert!(status.success());
 }
+
+#[test]
+fn test_nested_children_generation() {
+    let fixtures = project_root().join("tests/fixtures");
+    let temp = TempDir::new().unwrap();
+    let output_path = temp.path().join("output.xml");
+    let cache_path = temp.path().join(".cache.json");
+
+    let config = format!(
+        r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "{}"
+cache_file = "{}"
+nested_children = true
+
+[[sources]]
+type = "directory"
+path = "{}"
+"#,
+        output_path.display().to_string().replace('\\', "/"),
+        cache_path.display().to_string().replace('\\', "/"),
+        fixtures.display().to_string().replace('\\', "/"),
+    );
+
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(&config_path, &config).unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_luban-gen"))
+        .arg("-c")
+        .arg(&config_path)
+        .status()
+        .expect("Failed to run luban-gen");
+    assert!(status.success(), "luban-gen failed");
+
+    let output = fs::read_to_string(&output_path).unwrap();
+
+    // DamageTrigger is nested inside EntityTrigger and has no parent= attribute
+    let expected = [
+        r#"    <bean name="EntityTrigger">"#,
+        r#"        <bean name="DamageTrigger">"#,
+        r#"            <var name="damage" type="double"/>"#,
+        r#"            <var name="radius" type="double"/>"#,
+        r#"        </bean>"#,
+        r#"    </bean>"#,
+    ]
+    .join("\n");
+    assert!(
+        output.contains(&expected),
+        "DamageTrigger should be nested inside EntityTrigger. Got:\n{}",
+        output
+    );
+    assert!(!output.contains(r#"parent="EntityTrigger""#));
+
+    // Beans without a generated parent stay at the top level
+    assert!(output.contains(r#"    <bean name="SimpleClass">"#));
+    // Field references are not nesting: recursive beans stay at the top level too
+    assert!(output.contains(r#"    <bean name="UiNode""#));
+}
+
+/// Write a config + tsconfig into `temp` and load it through the library
+fn library_pipeline(temp: &TempDir, force: bool) -> luban_gen::Pipeline {
+    let fixtures = project_root().join("tests/fixtures");
+    let config = format!(
+        r#"
+[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+cache_file = ".cache.json"
+
+[[sources]]
+type = "directory"
+path = "{}"
+"#,
+        fixtures.display().to_string().replace('\\', "/"),
+    );
+
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(&config_path, &config).unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .force(force)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_library_pipeline_returns_values_without_writing() {
+    let temp = TempDir::new().unwrap();
+    let pipeline = library_pipeline(&temp, false);
+
+    let classes = pipeline.parsed_classes().unwrap();
+    assert!(classes.iter().any(|c| c.name == "SimpleClass"));
+    assert!(classes.iter().any(|c| c.name == "DamageTrigger"));
+
+    let files = pipeline.generate_xml().unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].path, temp.path().join("output.xml"));
+    assert!(files[0]
+        .content
+        .contains(r#"<bean name="DamageTrigger" parent="EntityTrigger">"#));
+
+    // No table_output_path configured
+    assert!(pipeline.generate_ts().unwrap().is_empty());
+
+    // Nothing is written by the accessors
+    assert!(!temp.path().join("output.xml").exists());
+    assert!(!temp.path().join(".cache.json").exists());
+}
+
+#[test]
+fn test_library_pipeline_run_report() {
+    let temp = TempDir::new().unwrap();
+
+    let report = library_pipeline(&temp, false).run().unwrap();
+    assert!(report.beans > 0);
+    assert_eq!(report.updated, report.beans + report.enums);
+    assert_eq!(report.written, vec![temp.path().join("output.xml")]);
+    assert!(temp.path().join("output.xml").exists());
+    assert!(temp.path().join(".cache.json").exists());
+
+    // Second run hits the cache and leaves the output untouched
+    let report = library_pipeline(&temp, false).run().unwrap();
+    assert_eq!(report.updated, 0);
+    assert!(report.written.is_empty());
+    assert_eq!(report.unchanged, vec![temp.path().join("output.xml")]);
+
+    // Forcing ignores the cache
+    let report = library_pipeline(&temp, true).run().unwrap();
+    assert_eq!(report.cached, 0);
+}
+
+#[test]
+fn test_library_pipeline_cache_fallback_warning() {
+    let temp = TempDir::new().unwrap();
+    library_pipeline(&temp, false).run().unwrap();
+    // The second save keeps the first cache as the backup
+    library_pipeline(&temp, false).run().unwrap();
+
+    // Left empty by a crash mid-write
+    fs::write(temp.path().join(".cache.json"), "").unwrap();
+    let report = library_pipeline(&temp, false).run().unwrap();
+    assert_eq!(report.updated, 0);
+    let fallback: Vec<&String> = report
+        .warnings
+        .iter()
+        .filter(|w| w.contains("cache is unreadable"))
+        .collect();
+    assert_eq!(fallback.len(), 1, "{:?}", report.warnings);
+    assert!(fallback[0].ends_with(".cache.json.bak"), "{}", fallback[0]);
+
+    // The cache was rewritten: no fallback next time
+    let report = library_pipeline(&temp, false).run().unwrap();
+    assert!(!report
+        .warnings
+        .iter()
+        .any(|w| w.contains("cache is unreadable")));
+}
+
+#[test]
+fn test_library_pipeline_formatting_only_changes() {
+    let temp = TempDir::new().unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ncache_file = \".cache.json\"\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+
+    let run = |source: &str| {
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("item.ts", source)])
+            .build()
+            .unwrap()
+            .run()
+            .unwrap()
+    };
+
+    let report = run("export class ItemConfig {\n    id: number;\n    count: number;\n}\n");
+    assert_eq!(report.updated, 1);
+
+    // Comment and whitespace edits change the file hash but not the structure
+    let report = run(
+        "// Item table\nexport class ItemConfig {\n    id: number;\n\n    count: number; // stack size\n}\n",
+    );
+    assert_eq!(report.updated, 0);
+    assert_eq!(report.formatting_only, 1);
+    assert!(report.field_changes.is_empty());
+    assert!(!report.has_structural_changes());
+
+    // A type change is structural
+    let report = run(
+        "// Item table\nexport class ItemConfig {\n    id: number;\n\n    count: string; // stack size\n}\n",
+    );
+    assert_eq!(report.updated, 1);
+    assert_eq!(report.formatting_only, 0);
+    assert_eq!(report.field_changes.len(), 1);
+    assert!(report.has_structural_changes());
+}
+
+#[test]
+fn test_library_pipeline_output_path_is_directory() {
+    let temp = TempDir::new().unwrap();
+    fs::create_dir(temp.path().join("output.xml")).unwrap();
+
+    let error = library_pipeline(&temp, false)
+        .run()
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.starts_with("Cannot write generated files:"),
+        "{}",
+        error
+    );
+    assert!(
+        error.contains(&format!(
+            "module XML ({}): is a directory",
+            temp.path().join("output.xml").display()
+        )),
+        "{}",
+        error
+    );
+    // Checked before parsing, so the cache was never written
+    assert!(!temp.path().join(".cache.json").exists());
+}
+
+#[test]
+fn test_library_pipeline_report() {
+    let temp = TempDir::new().unwrap();
+    // Writes the config and tsconfig
+    library_pipeline(&temp, false);
+    let report_path = temp.path().join("report.json");
+    let pipeline = |report_path: &PathBuf| {
+        let config =
+            luban_gen::config::Config::load_with_refs(&temp.path().join("luban.config.toml"))
+                .unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .report(report_path)
+            .build()
+            .unwrap()
+    };
+
+    let report = pipeline(&report_path).run().unwrap();
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+    assert_eq!(json["beans"], report.beans);
+
+    // Checked up front with the other outputs
+    let report_dir = temp.path().join("reports");
+    fs::create_dir(&report_dir).unwrap();
+    let error = pipeline(&report_dir).run().unwrap_err().to_string();
+    assert!(
+        error.contains(&format!(
+            "report ({}): is a directory",
+            report_dir.display()
+        )),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_library_pipeline_filter() {
+    let temp = TempDir::new().unwrap();
+    let fixtures = project_root().join("tests/fixtures");
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[[sources]]\ntype = \"directory\"\npath = \"{}\"\n",
+            fixtures.display().to_string().replace('\\', "/"),
+        ),
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .filter("^DamageTrigger$")
+        .build()
+        .unwrap();
+
+    let files = pipeline.generate_xml().unwrap();
+    let output = &files[0].content;
+    assert!(output.contains(r#"<bean name="DamageTrigger" parent="EntityTrigger">"#));
+    assert!(!output.contains(r#"<bean name="SimpleClass">"#));
+    assert_eq!(files[0].beans, 1);
+
+    assert!(
+        luban_gen::Pipeline::builder(luban_gen::config::Config::load(&config_path).unwrap())
+            .project_root(temp.path())
+            .filter("(")
+            .build()
+            .is_err()
+    );
+}
+
+#[test]
+fn test_library_pipeline_registration_source() {
+    let temp = TempDir::new().unwrap();
+    let src = temp.path().join("src");
+    let write = |name: &str, content: &str| {
+        let path = src.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+    write(
+        "registration.ts",
+        "export { RoleConfig } from \"./role\";\nexport * from \"./items\";\nimport \"./missing\";\n",
+    );
+    write("more.ts", "export { RoleConfig } from \"./role\";\n");
+    write(
+        "role.ts",
+        "import type { Reward } from \"@shared/reward\";\nimport { Players } from \"@rbxts/services\";\n\nexport class RoleConfig {\n    id: number;\n    reward: Reward;\n}\n",
+    );
+    write(
+        "shared/reward.ts",
+        "export class Reward {\n    count: number;\n}\n",
+    );
+    write("items/index.ts", "export { ItemConfig } from \"./item\";\n");
+    write(
+        "items/item.ts",
+        "export class ItemConfig {\n    id: number;\n}\n",
+    );
+    write("unused.ts", "export class Unused {\n    id: number;\n}\n");
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {"baseUrl": "src", "paths": {"@shared/*": ["shared/*"]}}}"#,
+    )
+    .unwrap();
+
+    let pipeline = |sources: &str, filter: Option<&str>| {
+        let config_path = temp.path().join("luban.config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n{}",
+                sources
+            ),
+        )
+        .unwrap();
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        let mut builder = luban_gen::Pipeline::builder(config).project_root(temp.path());
+        if let Some(filter) = filter {
+            builder = builder.filter(filter);
+        }
+        builder.build().unwrap()
+    };
+    let sorted_lines = |content: &str| {
+        let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+        lines.sort();
+        lines
+    };
+
+    let registration = pipeline(
+        "[[sources]]\ntype = \"registration\"\npath = \"src/registration.ts\"\n\n[[sources]]\ntype = \"registration\"\npath = \"src/more.ts\"\n",
+        None,
+    );
+    let files = registration.generate_xml().unwrap();
+    // RoleConfig is reached from both registration files but parsed once
+    assert_eq!(files[0].beans, 3);
+    assert!(!files[0].content.contains("Unused"));
+
+    let directory = pipeline(
+        "[[sources]]\ntype = \"directory\"\npath = \"src\"\n",
+        Some("^(RoleConfig|Reward|ItemConfig)$"),
+    );
+    assert_eq!(
+        sorted_lines(&files[0].content),
+        sorted_lines(&directory.generate_xml().unwrap()[0].content)
+    );
+
+    // Relative and aliased imports that resolve to nothing are reported, packages are not
+    let report = registration.run().unwrap();
+    assert_eq!(report.warnings.len(), 1);
+    assert!(report.warnings[0].ends_with(
+        "registration.ts:3: import './missing' doesn't resolve to a source file, not followed (registration src/registration.ts)"
+    ));
+}
+
+#[test]
+fn test_library_pipeline_virtual_sources() {
+    let temp = TempDir::new().unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .virtual_sources([
+            (
+                "src/triggers/base.ts",
+                "export interface Trigger {\n    delay: number;\n}\n",
+            ),
+            (
+                "src/triggers/damage.ts",
+                "import { Trigger } from \"./base\";\n\nexport class DamageTrigger implements Trigger {\n    delay: number;\n    damage: number;\n}\n",
+            ),
+        ])
+        .build()
+        .unwrap();
+
+    let classes = pipeline.parsed_classes().unwrap();
+    let damage = classes.iter().find(|c| c.name == "DamageTrigger").unwrap();
+    let expected_source = temp.path().join("src/triggers/damage.ts");
+    assert_eq!(damage.source_file, "src/triggers/damage.ts");
+    assert_eq!(damage.source_path, expected_source);
+    assert_eq!(
+        damage.imports.get("Trigger"),
+        Some(&temp.path().join("src/triggers/base.ts"))
+    );
+
+    let files = pipeline.generate_xml().unwrap();
+    assert!(files[0]
+        .content
+        .contains(r#"<bean name="DamageTrigger" parent="Trigger">"#));
+    assert!(!temp.path().join("src").exists());
+}
+
+#[test]
+fn test_library_pipeline_constants_output() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let source =
+        "export class RoleConfig {\n    static readonly MAX_LEVEL = 80;\n    level: number;\n}\n";
+
+    for (format, path) in [("xml", "constants.xml"), ("ts", "constants.ts")] {
+        let config_path = temp.path().join("luban.config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\nconstants_path = \"{}\"\nconstants_format = \"{}\"\n",
+                path, format
+            ),
+        )
+        .unwrap();
+
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        let pipeline = luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("role.ts", source)])
+            .build()
+            .unwrap();
+
+        let xml = pipeline.generate_xml().unwrap();
+        let ts = pipeline.generate_ts().unwrap();
+        assert!(!xml[0].content.contains("MAX_LEVEL"));
+        let (constants, other) = if format == "xml" {
+            (&xml, &ts)
+        } else {
+            (&ts, &xml)
+        };
+        let file = constants
+            .iter()
+            .find(|f| f.path == temp.path().join(path))
+            .unwrap();
+        assert!(file.content.contains("MAX_LEVEL"));
+        assert!(!other.iter().any(|f| f.path == temp.path().join(path)));
+    }
+}
+
+#[test]
+fn test_library_pipeline_ambient_classes() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let source = "export declare class LegacyConfig {\n    id: number;\n}\n\nexport class ItemConfig {\n    id: number;\n}\n";
+
+    for skip_ambient in [false, true] {
+        let config_path = temp.path().join("luban.config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ntable_output_path = \"out\"\n\n[defaults]\nskip_ambient = {}\n",
+                skip_ambient
+            ),
+        )
+        .unwrap();
+
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        let pipeline = luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("legacy.ts", source)])
+            .build()
+            .unwrap();
+
+        let xml = &pipeline.generate_xml().unwrap()[0].content;
+        assert!(xml.contains(r#"<bean name="ItemConfig">"#));
+        assert_eq!(xml.contains(r#"<bean name="LegacyConfig">"#), !skip_ambient);
+
+        let ts = pipeline.generate_ts().unwrap();
+        let beans = ts.iter().find(|f| f.path.ends_with("beans.ts")).unwrap();
+        assert!(beans.content.contains("ItemConfig"));
+        assert!(!beans.content.contains("LegacyConfig"));
+    }
+}
+
+#[test]
+fn test_custom_table_modes() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    let write_config = |defaults: &str, mode: &str| {
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\nmodule_name = \"item\"\ntable_output_path = \"out\"\n\n[defaults]\n{}\n\n[tables]\n\"item.ItemConfig\" = {{ input = \"../datas/item\", mode = \"{}\" }}\n",
+                defaults, mode
+            ),
+        )
+        .unwrap();
+    };
+
+    // A typo is rejected at load, naming the entry and the accepted modes
+    write_config("", "lsit");
+    let err = luban_gen::config::Config::load_with_refs(&config_path)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains(
+        "\"item.ItemConfig\": unknown mode \"lsit\" (expected map, list, one, singleton;"
+    ));
+
+    // Opted in, the mode goes to the XML as is and tables.d.ts doesn't guess its shape
+    write_config("allow_custom_modes = true", "sharded");
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .virtual_sources([("item.ts", "export class ItemConfig {\n    id: number;\n}\n")])
+        .build()
+        .unwrap();
+    let report = pipeline.run().unwrap();
+    assert_eq!(
+        report.warnings,
+        vec!["[tables] \"item.ItemConfig\": custom mode \"sharded\" is passed through to the XML as is; tables.d.ts types ItemConfigTable as unknown"]
+    );
+    let xml = fs::read_to_string(temp.path().join("output.xml")).unwrap();
+    assert!(xml.contains(r#"<table name="ItemConfigTable" value="ItemConfig" mode="sharded""#));
+    let tables = fs::read_to_string(temp.path().join("out/tables.d.ts")).unwrap();
+    assert!(tables.contains("ItemConfigTable: unknown;"));
+}
+
+#[test]
+fn test_library_pipeline_deny_warnings() {
+    let temp = TempDir::new().unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let source = "export class RoleConfig {\n    statKey: keyof StatTable;\n}\n";
+
+    let pipeline = |deny_warnings: bool| {
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("role.ts", source)])
+            .deny_warnings(deny_warnings)
+            .build()
+            .unwrap()
+    };
+
+    let report = pipeline(false).run().unwrap();
+    assert_eq!(report.warnings.len(), 1);
+    assert!(report.warnings[0].ends_with(
+        "role.ts:2: unsupported type construct `keyof` on RoleConfig.statKey, falling back to string"
+    ));
+
+    let err = pipeline(true).generate_xml().unwrap_err().to_string();
+    assert!(err.contains("1 warning(s)"));
+}
+
+#[test]
+fn test_library_pipeline_inheritance_cycle() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("luban.config.toml"),
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n",
+    )
+    .unwrap();
+    let source = "export class A extends B {\n    id: number;\n}\n\nexport class B extends A {\n    name: string;\n}\n";
+    let config =
+        luban_gen::config::Config::load_with_refs(&temp.path().join("luban.config.toml")).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .virtual_sources([("cycle.ts", source)])
+        .build()
+        .unwrap();
+
+    let err = format!("{:#}", pipeline.run().unwrap_err());
+    assert!(err.contains("Inheritance cycles:"), "{}", err);
+    assert!(
+        err.contains("cycle.ts: A - inheritance cycle A -> B -> A"),
+        "{}",
+        err
+    );
+    assert!(!temp.path().join("output.xml").exists());
+}
+
+#[test]
+fn test_library_pipeline_lint() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let source = "export class RoleConfig {\n    id: number;\n    max_hp: number;\n}\n\nexport class skill_config {\n    id: number;\n}\n";
+    let pipeline = |lint: &str, deny_warnings: bool| {
+        let config_path = temp.path().join("luban.config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[tables]\n\"RoleConfig\" = {{ input = \"../datas/role\", name = \"Roles\" }}\n\"skill_config\" = \"../datas/skill\"\n\n[lint]\n{}",
+                lint
+            ),
+        )
+        .unwrap();
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("role.ts", source)])
+            .deny_warnings(deny_warnings)
+            .build()
+            .unwrap()
+    };
+
+    // Without a preset or rules nothing is checked
+    let report = pipeline("", false).run().unwrap();
+    assert!(report.lint.is_empty());
+
+    let report = pipeline("preset = \"default\"\n", false).run().unwrap();
+    let lint: Vec<&str> = report.lint.iter().map(|v| v.message.as_str()).collect();
+    assert_eq!(lint.len(), 3);
+    assert!(lint[0].ends_with(
+        "role.ts: skill_config - bean name doesn't match the [lint] beans pattern \"^[A-Z][A-Za-z0-9]*$\""
+    ));
+    assert!(lint[1].ends_with(
+        "role.ts:3: RoleConfig.max_hp - field name doesn't match the [lint] fields pattern \"^[a-z][A-Za-z0-9]*$\""
+    ));
+    assert_eq!(
+        lint[2],
+        "[tables] \"RoleConfig\": Roles - table name doesn't match the [lint] tables pattern \"^Tb[A-Z][A-Za-z0-9]*$\""
+    );
+    assert_eq!(report.warnings, lint);
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["lint"][0]["rule"], "beans");
+    assert_eq!(json["lint"][0]["severity"], "warn");
+
+    // Lint warnings fail the run with deny_warnings
+    let err = pipeline("preset = \"default\"\n", true)
+        .generate_xml()
+        .unwrap_err();
+    assert!(err.to_string().contains("3 warning(s)"), "{}", err);
+
+    // Error rules fail validation on their own
+    let err = pipeline(
+        "preset = \"default\"\nfields = { pattern = \"^[a-z][A-Za-z0-9]*$\", severity = \"error\" }\n",
+        false,
+    )
+    .generate_xml()
+    .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<luban_gen::GenError>(),
+        Some(luban_gen::GenError::Validation(_))
+    ));
+    let err = err.to_string();
+    assert!(err.starts_with("Naming convention violations ([lint]):\n  "));
+    assert!(err.ends_with(
+        "role.ts:3: RoleConfig.max_hp - field name doesn't match the [lint] fields pattern \"^[a-z][A-Za-z0-9]*$\""
+    ));
+}
+
+#[test]
+fn test_library_pipeline_preserves_field_order() {
+    let temp = TempDir::new().unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[[virtual_fields]]\nclass = \"RoleConfig\"\nfields = [{ name = \"bonus\", type = \"int\", position = \"after:zeta\" }]\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let source =
+        "export class RoleConfig {\n    zeta: number;\n    alpha: string;\n    middle: boolean;\n}\n";
+
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .virtual_sources([("role.ts", source)])
+        .build()
+        .unwrap();
+
+    let xml = &pipeline.generate_xml().unwrap()[0].content;
+    let positions: Vec<usize> = ["zeta", "bonus", "alpha", "middle"]
+        .iter()
+        .map(|name| xml.find(&format!(r#"<var name="{}""#, name)).unwrap())
+        .collect();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]), "{}", xml);
+}
+
+#[test]
+fn test_library_pipeline_output_routes() {
+    let temp = TempDir::new().unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[[output_routes]]\nname = \"editor\"\npath = \"editor.xml\"\npatterns = [\"^Debug\"]\n\n[[output_routes]]\nname = \"server\"\npath = \"server.xml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let source = "export class DebugOverlay {\n    color: string;\n}\n\n@Output(\"server\")\nexport class MatchRules {\n    rounds: number;\n}\n\nexport class ItemConfig {\n    id: number;\n    overlay: DebugOverlay;\n}\n";
+
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .virtual_sources([("items.ts", source)])
+        .build()
+        .unwrap();
+
+    let files = pipeline.generate_xml().unwrap();
+    let content_of = |name: &str| {
+        &files
+            .iter()
+            .find(|f| f.path == temp.path().join(name))
+            .unwrap()
+            .content
+    };
+    assert!(content_of("output.xml").contains(r#"<bean name="ItemConfig">"#));
+    assert!(!content_of("output.xml").contains(r#"<bean name="DebugOverlay">"#));
+    assert!(content_of("editor.xml").contains(r#"<bean name="DebugOverlay">"#));
+    assert!(content_of("server.xml").contains(r#"<bean name="MatchRules">"#));
+
+    let report = pipeline.run().unwrap();
+    assert_eq!(report.written.len(), 3);
+    assert_eq!(report.warnings.len(), 1);
+    assert!(report.warnings[0].ends_with(
+        "ItemConfig (the default output) references DebugOverlay (output 'editor') through field 'overlay'"
+    ));
+}
+
+#[test]
+fn test_library_pipeline_unknown_types() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let source = "export class SkillConfig {\n    id: number;\n}\n\nexport class WeaponConfig {\n    skill: SkillConfig;\n    buff: LegacyBuff;\n    effect: SkilConfig;\n    loot: QuantumLoot;\n}\n";
+
+    let pipeline = |strict_types: bool| {
+        let config_path = temp.path().join("luban.config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[defaults]\nstrict_types = {}\n\n[external_beans]\nnames = [\"LegacyBuff\"]\n",
+                strict_types
+            ),
+        )
+        .unwrap();
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("weapon.ts", source)])
+            .build()
+            .unwrap()
+    };
+
+    let report = pipeline(false).run().unwrap();
+    assert_eq!(report.warnings.len(), 2, "{:?}", report.warnings);
+    assert!(report.warnings[0].ends_with(
+        "weapon.ts:8: WeaponConfig.effect - unknown type 'SkilConfig', did you mean 'SkillConfig'?"
+    ));
+    assert!(
+        report.warnings[1].ends_with("weapon.ts:9: WeaponConfig.loot - unknown type 'QuantumLoot'")
+    );
+
+    let json = serde_json::to_value(&report).unwrap();
+    let kinds: Vec<(&str, &str)> = json["unknown_types"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|u| {
+            (
+                u["type_name"].as_str().unwrap(),
+                u["kind"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ("LegacyBuff", "external"),
+            ("SkilConfig", "suspected_typo"),
+            ("QuantumLoot", "unknown"),
+        ]
+    );
+
+    // Every name but the external one fails, near misses included
+    let err = pipeline(true).generate_xml().unwrap_err().to_string();
+    assert!(err.contains("WeaponConfig.loot - unknown type 'QuantumLoot'"));
+    assert!(err
+        .contains("WeaponConfig.effect - unknown type 'SkilConfig', did you mean 'SkillConfig'?"));
+    assert!(!err.contains("LegacyBuff"));
+}
+
+#[test]
+fn test_library_pipeline_forbidden_types() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let source = "export class ItemConfig {\n    id: number;\n}\n\nexport class WeaponConfig {\n    part: BasePart;\n    loaded: Promise<ItemConfig>;\n    cleanup: Janitor;\n}\n";
+
+    let pipeline = |strict_types: bool| {
+        let config_path = temp.path().join("luban.config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[defaults]\nstrict_types = {}\n\n[forbidden_types]\nnames = [\"Janitor\"]\n",
+                strict_types
+            ),
+        )
+        .unwrap();
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("weapon.ts", source)])
+            .build()
+            .unwrap()
+    };
+
+    let report = pipeline(false).run().unwrap();
+    assert_eq!(report.warnings.len(), 3);
+    assert!(report.warnings[0].ends_with(
+        "weapon.ts:6: WeaponConfig.part - type 'BasePart' only exists at runtime; configs cannot reference runtime Instances; store an asset id string instead"
+    ));
+    assert!(report.warnings[1].contains("WeaponConfig.loaded - type 'Promise'"));
+    assert!(report.warnings[2].contains("WeaponConfig.cleanup - type 'Janitor'"));
+    assert!(report.unknown_types.is_empty());
+
+    let err = pipeline(true).generate_xml().unwrap_err().to_string();
+    assert!(err.contains("Runtime-only field types"));
+    assert!(err.contains("WeaponConfig.part - type 'BasePart'"));
+}
+
+#[test]
+fn test_library_pipeline_untyped_fields() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let source = "export class EventConfig {\n    id: number;\n    payload: any;\n}\n";
+
+    let pipeline = |defaults: &str| {
+        let config_path = temp.path().join("luban.config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[defaults]\n{}\n",
+                defaults
+            ),
+        )
+        .unwrap();
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("event.ts", source)])
+            .build()
+            .unwrap()
+    };
+
+    let report = pipeline("").run().unwrap();
+    assert_eq!(report.warnings.len(), 1);
+    assert!(report.warnings[0].ends_with(
+        "event.ts:3: EventConfig.payload - type `any` has no schema type and falls back to string; declare a concrete type or add a @type override (or allow it with defaults.any_type)"
+    ));
+
+    let err = pipeline("strict_types = true")
+        .generate_xml()
+        .unwrap_err()
+        .to_string();
+    assert!(err.starts_with("Fields typed any/unknown (defaults.strict_types):"));
+    assert!(err.contains("EventConfig.payload - type `any`"));
+
+    // Explicitly allowed: no report, even in strict mode
+    let report = pipeline("strict_types = true\nany_type = \"string\"")
+        .run()
+        .unwrap();
+    assert!(report.warnings.is_empty());
+    let xml = fs::read_to_string(temp.path().join("output.xml")).unwrap();
+    assert!(xml.contains(r#"<var name="payload" type="string"/>"#));
+}
+
+#[test]
+fn test_library_pipeline_default_module() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let item = "export class ItemConfig {\n    id: number;\n}\n";
+
+    let builder = |defaults: &str, tables: &str, source: &str| {
+        let config_path = temp.path().join("luban.config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\nmodule_name = \"base\"\n\n[defaults]\n{}\n\n[tables]\n{}\n",
+                defaults, tables
+            ),
+        )
+        .unwrap();
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("item.ts", source.to_string())])
+    };
+
+    // --module > defaults.module > output.module_name
+    let default_module = |defaults: &str, module: Option<&str>| {
+        let mut builder = builder(defaults, "", item);
+        if let Some(module) = module {
+            builder = builder.module(module);
+        }
+        builder.build().unwrap().default_module().to_string()
+    };
+    assert_eq!(default_module("", None), "base");
+    assert_eq!(default_module("module = \"game\"", None), "game");
+    assert_eq!(default_module("module = \"game\"", Some("cli")), "cli");
+    assert_eq!(default_module("", Some("cli")), "cli");
+
+    // XML module and table reference use the resolved module
+    builder(
+        "module = \"game\"",
+        "\"game.ItemConfig\" = \"../datas/item\"",
+        item,
+    )
+    .build()
+    .unwrap()
+    .run()
+    .unwrap();
+    let xml = fs::read_to_string(temp.path().join("output.xml")).unwrap();
+    assert!(xml.contains(r#"<module name="game""#), "{}", xml);
+    assert!(
+        xml.contains(r#"<table name="ItemConfigTable" value="ItemConfig""#),
+        "{}",
+        xml
+    );
+
+    let err = match builder("module = \"ui-widgets\"", "", item).build() {
+        Ok(_) => panic!("expected an invalid module error"),
+        Err(err) => err,
+    };
+    assert!(matches!(
+        err.downcast_ref::<luban_gen::GenError>(),
+        Some(luban_gen::GenError::Validation(_))
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Default module 'ui-widgets' (defaults.module) is not a valid Luban module name, use 'ui_widgets'"
+    );
+    assert!(builder("", "", item).module("2d").build().is_err());
+
+    let err = builder(
+        "",
+        "",
+        "@Module(\"ui-widgets\")\nexport class HudConfig {\n    id: number;\n}\n",
+    )
+    .build()
+    .unwrap()
+    .generate_xml()
+    .unwrap_err()
+    .to_string();
+    assert!(err.starts_with("Invalid module names:"), "{}", err);
+    assert!(err.ends_with(
+        "item.ts: HudConfig - @Module 'ui-widgets' is not a valid Luban module name, use 'ui_widgets'"
+    ));
+}
+
+#[test]
+fn test_library_pipeline_import_cycle_warning() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::create_dir_all(temp.path().join("src")).unwrap();
+    // item.ts -> util.ts -> out/beans.ts, which requires item.ts again
+    fs::write(
+        temp.path().join("src/item.ts"),
+        "import { formatId } from \"./util\";\n\nexport class ItemConfig {\n    id: number;\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("src/util.ts"),
+        "import { Beans } from \"../out/beans\";\n\nexport function formatId(id: number) {\n    return `${id}`;\n}\n",
+    )
+    .unwrap();
+    // Type-only imports of the output are erased and never form a cycle
+    fs::write(
+        temp.path().join("src/role.ts"),
+        "import type { Beans } from \"../out/beans\";\nimport { type Beans as B } from \"../out/beans\";\n\nexport class RoleConfig {\n    id: number;\n}\n",
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ntable_output_path = \"out\"\n\n[[sources]]\ntype = \"directory\"\npath = \"src\"\n",
+    )
+    .unwrap();
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let report = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .build()
+        .unwrap()
+        .run()
+        .unwrap();
+
+    assert_eq!(
+        report.warnings,
+        vec!["circular require at runtime: out/beans.ts -> src/item.ts -> src/util.ts -> out/beans.ts; use `import type` where only types are needed, or move the import out of the config modules"]
+    );
+}
+
+#[test]
+fn test_library_pipeline_nullable_elements() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let source = "export class ItemDrop {\n    id: number;\n}\n\nexport class DropConfig {\n    rewards: Map<string, ItemDrop | undefined>;\n    weights: Array<number | null>;\n}\n";
+
+    let pipeline = |nullable_elements: bool| {
+        let config_path = temp.path().join("luban.config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[defaults]\nnullable_elements = {}\n",
+                nullable_elements
+            ),
+        )
+        .unwrap();
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("drop.ts", source)])
+            .build()
+            .unwrap()
+    };
+
+    // Stripped by default, with a warning per field
+    let xml = &pipeline(false).generate_xml().unwrap()[0].content;
+    assert!(xml.contains(r#"<var name="rewards" type="map,string,ItemDrop"/>"#));
+    assert!(xml.contains(r#"<var name="weights" type="list,double"/>"#));
+    let report = pipeline(false).run().unwrap();
+    assert_eq!(report.warnings.len(), 2);
+    assert!(report.warnings[0].contains(
+        "DropConfig.rewards - nullable elements are emitted as required (type map,string,ItemDrop?)"
+    ));
+
+    let xml = &pipeline(true).generate_xml().unwrap()[0].content;
+    assert!(xml.contains(r#"<var name="rewards" type="map,string,ItemDrop?"/>"#));
+    assert!(xml.contains(r#"<var name="weights" type="list,double?"/>"#));
+}
+
+#[test]
+fn test_library_pipeline_file_stats() {
+    let temp = TempDir::new().unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+
+    let pipeline = || {
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([
+                (
+                    "item.ts",
+                    "export class ItemConfig {\n    id: number;\n}\n\nexport class DropConfig {\n    id: number;\n}\n",
+                ),
+                (
+                    "role.ts",
+                    "export enum RoleKind {\n    Melee = 1,\n}\n\nexport class RoleConfig {\n    kind: RoleKind;\n}\n",
+                ),
+            ])
+            .stats(true)
+            .build()
+            .unwrap()
+    };
+
+    let report = pipeline().run().unwrap();
+    assert_eq!(report.file_stats.len(), 2);
+    let classes: usize = report.file_stats.iter().map(|f| f.classes).sum();
+    let enums: usize = report.file_stats.iter().map(|f| f.enums).sum();
+    assert_eq!((classes, enums), (report.beans, report.enums));
+    assert!(report.file_stats.iter().all(|f| !f.cached));
+
+    let report = pipeline().run().unwrap();
+    assert!(report.file_stats.iter().all(|f| f.cached));
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["file_stats"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_library_pipeline_stats_by_module() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::create_dir_all(temp.path().join("src/item")).unwrap();
+    fs::create_dir_all(temp.path().join("src/role")).unwrap();
+    fs::write(
+        temp.path().join("src/item/item.ts"),
+        "export class ItemConfig {\n    id: number;\n    name: string;\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("src/role/role.ts"),
+        "export enum RoleKind {\n    Melee = 1,\n}\n\nexport class RoleConfig {\n    id: number;\n    kind: RoleKind;\n    level: number;\n}\n\nexport class RoleSkill {\n    skillId: number;\n}\n",
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[[sources]]\ntype = \"directory\"\npath = \"src/item\"\noutput_path = \"item.xml\"\nmodule_name = \"item\"\n\n[[sources]]\ntype = \"directory\"\npath = \"src/role\"\noutput_path = \"role.xml\"\nmodule_name = \"role\"\n\n[tables]\n\"role.RoleConfig\" = \"../datas/role\"\n",
+    )
+    .unwrap();
+    let run = || {
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .stats_by_module(true)
+            .build()
+            .unwrap()
+            .run()
+            .unwrap()
+    };
+
+    // The first run has no baseline to compare with
+    let report = run();
+    let modules: Vec<&str> = report
+        .module_stats
+        .iter()
+        .map(|m| m.module.as_str())
+        .collect();
+    assert_eq!(modules, vec!["role", "item"]);
+    let role = &report.module_stats[0];
+    assert_eq!(
+        (
+            role.counts.beans,
+            role.counts.fields,
+            role.counts.enums,
+            role.counts.tables
+        ),
+        (2, 4, 1, 1)
+    );
+    assert_eq!(
+        role.counts.xml_bytes,
+        fs::read_to_string(temp.path().join("role.xml"))
+            .unwrap()
+            .len()
+    );
+    let item = &report.module_stats[1];
+    assert_eq!(
+        (
+            item.counts.beans,
+            item.counts.fields,
+            item.counts.enums,
+            item.counts.tables
+        ),
+        (1, 2, 0, 0)
+    );
+    assert!(report.module_stats.iter().all(|m| m.delta.is_none()));
+
+    fs::write(
+        temp.path().join("src/item/drop.ts"),
+        "export class DropConfig {\n    id: number;\n}\n",
+    )
+    .unwrap();
+    let report = run();
+    let item = report
+        .module_stats
+        .iter()
+        .find(|m| m.module == "item")
+        .unwrap();
+    let delta = item.delta.unwrap();
+    assert_eq!((delta.beans, delta.fields, delta.enums), (1, 1, 0));
+    assert!(delta.xml_bytes > 0);
+    let role = report
+        .module_stats
+        .iter()
+        .find(|m| m.module == "role")
+        .unwrap();
+    assert_eq!(role.delta.unwrap(), Default::default());
+    // Modules with as many beans are sorted by name
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["module_stats"][0]["module"], "item");
+    assert_eq!(json["module_stats"][0]["beans"], 2);
+    assert_eq!(json["module_stats"][0]["delta"]["beans"], 1);
+}
+
+#[test]
+fn test_library_pipeline_recursive_beans() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ntable_output_path = \"out\"\n\n[defaults]\nstrict_types = true\n\n[[sources]]\ntype = \"file\"\npath = \"{}\"\nmodule_name = \"ui\"\n\n[tables]\n\"ui.QuestConfig\" = \"../datas/quest\"\n",
+            project_root()
+                .join("tests/fixtures/recursive.ts")
+                .display()
+                .to_string()
+                .replace('\\', "/"),
+        ),
+    )
+    .unwrap();
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .build()
+        .unwrap();
+
+    // Self and mutual references are known types, not unbounded recursion
+    let report = pipeline.run().unwrap();
+    assert_eq!(report.beans, 3);
+    assert!(report.unknown_types.is_empty());
+    assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+
+    let xml = fs::read_to_string(temp.path().join("output.xml")).unwrap();
+    assert!(
+        xml.contains(r#"<var name="children" type="list,UiNode"/>"#),
+        "{}",
+        xml
+    );
+    assert!(
+        xml.contains(r#"<var name="steps" type="list,QuestStep"/>"#),
+        "{}",
+        xml
+    );
+    assert!(
+        xml.contains(r#"<var name="subQuest" type="QuestConfig?"/>"#),
+        "{}",
+        xml
+    );
+
+    let tables = fs::read_to_string(temp.path().join("out/tables.d.ts")).unwrap();
+    assert!(tables.contains("QuestConfigTable"), "{}", tables);
+
+    let model: serde_json::Value = serde_json::from_str(&pipeline.model_json().unwrap()).unwrap();
+    let ui_node = model["classes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == "UiNode")
+        .unwrap();
+    assert_eq!(ui_node["fields"][1]["field_type"], "list,UiNode");
+}
+
+#[test]
+fn test_library_pipeline_report_keeps_duplicate_warnings() {
+    let temp = TempDir::new().unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+
+    let mut sources = vec![(
+        "skill.ts".to_string(),
+        "export class SkillConfig {\n    id: number;\n}\n".to_string(),
+    )];
+    for i in 0..30 {
+        sources.push((
+            format!("legacy/gen_{}.ts", i),
+            format!("export class Legacy{} {{\n    effect: SkilConfig;\n}}\n", i),
+        ));
+    }
+
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let report = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .virtual_sources(sources)
+        .max_warnings(1)
+        .build()
+        .unwrap()
+        .run()
+        .unwrap();
+
+    // The console shows one grouped line; the report keeps every occurrence
+    assert_eq!(report.warnings.len(), 30);
+    assert!(report
+        .warnings
+        .iter()
+        .all(|w| w.ends_with("unknown type 'SkilConfig', did you mean 'SkillConfig'?")));
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["warnings"].as_array().unwrap().len(), 30);
+}
+
+#[test]
+fn test_library_pipeline_beans_key_collision() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::create_dir_all(temp.path().join("src/a")).unwrap();
+    fs::create_dir_all(temp.path().join("src/b")).unwrap();
+    fs::write(
+        temp.path().join("src/a/skill.ts"),
+        "export class SkillConfig {\n    id: number;\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("src/b/skill.ts"),
+        "export class SkillConfig {\n    name: string;\n}\n",
+    )
+    .unwrap();
+
+    // Both sources put SkillConfig into module "shared"
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ntable_output_path = \"out\"\n\n[[sources]]\ntype = \"file\"\npath = \"src/a/skill.ts\"\nmodule_name = \"shared\"\n\n[[sources]]\ntype = \"file\"\npath = \"src/b/skill.ts\"\nmodule_name = \"shared\"\n",
+    )
+    .unwrap();
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .build()
+        .unwrap();
+
+    let err = pipeline.run().unwrap_err().to_string();
+    assert!(err.contains("Duplicate Beans keys"), "{}", err);
+    assert!(err.contains("\"shared.SkillConfig\""), "{}", err);
+    assert!(!temp.path().join("output.xml").exists());
+    assert!(!temp.path().join("out").exists());
+}
+
+#[test]
+fn test_library_pipeline_register_pressure() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let sources: Vec<(String, String)> = (0..200)
+        .map(|i| {
+            (
+                format!("beans/bean{}.ts", i),
+                format!("export class Bean{} {{\n    id: number;\n}}\n", i),
+            )
+        })
+        .collect();
+
+    let pipeline = |classes: usize, max_imports: usize| {
+        let config_path = temp.path().join("luban.config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ntable_output_path = \"out\"\nmax_imports_per_file = {}\n",
+                max_imports
+            ),
+        )
+        .unwrap();
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources(sources[..classes].to_vec())
+            .build()
+            .unwrap()
+    };
+
+    // A full chunk of 100 imports plus 100 entries is within the default margin of 20
+    let report = pipeline(150, 100).run().unwrap();
+    assert_eq!(report.warnings.len(), 1, "{:?}", report.warnings);
+    assert!(report.warnings[0].contains("beans_1.ts: ~203 registers"));
+    assert!(report.warnings[0]
+        .ends_with("consider lowering output.max_imports_per_file from 100 to 88"));
+
+    let report = pipeline(150, 80).run().unwrap();
+    assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+
+    // 200 imports in one file can't fit, whatever the object literal needs
+    let err = pipeline(200, 300).run().unwrap_err().to_string();
+    assert!(err.contains("Generated TypeScript would exceed Luau's register limit"));
+    assert!(err.contains("beans.ts"));
+}
+
+#[test]
+fn test_library_pipeline_beans_exclude() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ntable_output_path = \"out\"\nmodule_name = \"game\"\n\n[beans]\nexclude = [\"^Drop\"]\n\n[tables]\n\"game.DropEntry\" = \"../datas/drop\"\n",
+    )
+    .unwrap();
+    let source = "export class ItemConfig {\n    id: number;\n}\n\nexport class DropEntry {\n    id: number;\n}\n\n@NoRuntime()\nexport class LootRoll {\n    id: number;\n}\n";
+
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .virtual_sources([("items.ts", source)])
+        .build()
+        .unwrap();
+
+    // Excluded classes keep their XML beans and table
+    let xml = &pipeline.generate_xml().unwrap()[0].content;
+    assert!(xml.contains(r#"<bean name="DropEntry">"#), "{}", xml);
+    assert!(xml.contains(r#"<bean name="LootRoll">"#), "{}", xml);
+    assert!(
+        xml.contains(r#"<table name="DropEntryTable" value="DropEntry""#),
+        "{}",
+        xml
+    );
+
+    let ts = pipeline.generate_ts().unwrap();
+    let ts_of = |name: &str| {
+        ts.iter()
+            .find(|f| f.path.ends_with(name))
+            .map(|f| f.content.clone())
+            .unwrap()
+    };
+    let beans = ts_of("beans.ts");
+    assert!(
+        beans.contains("\"game.ItemConfig\": ItemConfig,"),
+        "{}",
+        beans
+    );
+    assert!(!beans.contains("DropEntry"), "{}", beans);
+    assert!(!beans.contains("LootRoll"), "{}", beans);
+
+    // tables.d.ts still imports and types the excluded table bean
+    let tables = ts_of("tables.d.ts");
+    assert!(tables.contains("import { DropEntry }"), "{}", tables);
+    assert!(
+        tables.contains("DropEntryTable: Map<number, DropEntry>;"),
+        "{}",
+        tables
+    );
+}
+
+#[test]
+fn test_library_pipeline_schema_versions() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ntable_output_path = \"out\"\nmodule_name = \"game\"\nschema_versions_path = \"out/schemaVersions.ts\"\n",
+    )
+    .unwrap();
+    let schema_versions = |source: &str| {
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        let pipeline = luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("items.ts", source)])
+            .build()
+            .unwrap();
+        pipeline
+            .generate_ts()
+            .unwrap()
+            .into_iter()
+            .find(|f| f.path.ends_with("schemaVersions.ts"))
+            .unwrap()
+            .content
+    };
+
+    let original =
+        schema_versions("export class ItemConfig {\n    id: number;\n    name: string;\n}\n");
+    assert!(original.starts_with("/** Structural hash"), "{}", original);
+    assert!(original.contains("\"game.ItemConfig\": \""), "{}", original);
+
+    let commented = schema_versions(
+        "/** Item */\nexport class ItemConfig {\n    /** Id */\n    id: number;\n\n    name: string; // display\n}\n",
+    );
+    assert_eq!(commented, original);
+
+    let retyped =
+        schema_versions("export class ItemConfig {\n    id: string;\n    name: string;\n}\n");
+    assert_ne!(retyped, original);
+}
+
+#[test]
+fn test_library_pipeline_flavors() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::create_dir_all(temp.path().join("src")).unwrap();
+    fs::write(
+        temp.path().join("src/item.ts"),
+        "@OnlyIn(\"full\")\nexport class DebugConfig {\n    id: number;\n    note: string;\n}\n\nexport class ItemConfig {\n    id: number;\n\n    @ExcludeFrom(\"lite\")\n    bigIcon: string;\n\n    name: string;\n}\n",
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ntable_output_path = \"out\"\n\n[[sources]]\ntype = \"directory\"\npath = \"src\"\noutput_path = \"game.xml\"\nmodule_name = \"game\"\n\n[tables]\n\"game.ItemConfig\" = \"../datas/item\"\n\"game.DebugConfig\" = \"../datas/debug\"\n",
+    )
+    .unwrap();
+    let pipeline = |flavor: &str| {
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .flavor(flavor)
+            .build()
+            .unwrap()
+    };
+    let outputs = |flavor: &str| {
+        let pipeline = pipeline(flavor);
+        let xml = pipeline
+            .generate_xml()
+            .unwrap()
+            .into_iter()
+            .find(|f| f.path.ends_with("game.xml"))
+            .unwrap()
+            .content;
+        let ts: String = pipeline
+            .generate_ts()
+            .unwrap()
+            .into_iter()
+            .map(|f| f.content)
+            .collect();
+        (xml, ts)
+    };
+
+    let (xml, ts) = outputs("full");
+    assert!(xml.contains("<bean name=\"DebugConfig\""), "{}", xml);
+    assert!(xml.contains("<var name=\"bigIcon\""), "{}", xml);
+    assert!(ts.contains("DebugConfig"), "{}", ts);
+
+    let (xml, ts) = outputs("lite");
+    assert!(!xml.contains("DebugConfig"), "{}", xml);
+    assert!(!xml.contains("bigIcon"), "{}", xml);
+    assert!(xml.contains("<var name=\"name\""), "{}", xml);
+    assert!(!ts.contains("DebugConfig"), "{}", ts);
+    assert!(!ts.contains("bigIcon"), "{}", ts);
+    assert!(ts.contains("ItemConfig"), "{}", ts);
+
+    let report = pipeline("lite").run().unwrap();
+    assert_eq!(
+        report.omitted,
+        vec!["game.DebugConfig", "game.ItemConfig.bigIcon"]
+    );
+
+    // A kept field can't depend on an excluded class
+    fs::write(
+        temp.path().join("src/shop.ts"),
+        "import { DebugConfig } from \"./item\";\n\nexport class ShopConfig {\n    id: number;\n    debug: DebugConfig;\n}\n",
+    )
+    .unwrap();
+    let err = pipeline("lite").parsed_classes().unwrap_err().to_string();
+    assert!(
+        err.contains("ShopConfig.debug - type 'DebugConfig' is excluded from flavor 'lite'"),
+        "{}",
+        err
+    );
+    assert!(pipeline("full").parsed_classes().is_ok());
+}
+
+#[test]
+fn test_library_pipeline_table_groups() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::create_dir_all(temp.path().join("src")).unwrap();
+    fs::write(
+        temp.path().join("src/item.ts"),
+        "export class ItemConfig {\n    id: number;\n}\n\nexport class DropConfig {\n    id: number;\n}\n\nexport class ShopConfig {\n    id: number;\n}\n",
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ntable_output_path = \"out\"\n\n[[sources]]\ntype = \"directory\"\npath = \"src\"\noutput_path = \"game.xml\"\nmodule_name = \"game\"\n\n[groups]\nclient = \"c\"\nserver = \"s\"\n\n[tables]\n\"game.ItemConfig\" = { input = \"../datas/item\", groups = [\"client\", \"server\"] }\n\"game.DropConfig\" = { input = \"../datas/drop\", groups = [\"server\"] }\n\"game.ShopConfig\" = \"../datas/shop\"\n",
+    )
+    .unwrap();
+    let pipeline = |groups: &[&str]| {
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        groups
+            .iter()
+            .fold(
+                luban_gen::Pipeline::builder(config).project_root(temp.path()),
+                |builder, group| builder.group(*group),
+            )
+            .build()
+    };
+    let outputs = |groups: &[&str]| {
+        let pipeline = pipeline(groups).unwrap();
+        let xml = pipeline
+            .generate_xml()
+            .unwrap()
+            .into_iter()
+            .find(|f| f.path.ends_with("game.xml"))
+            .unwrap()
+            .content;
+        let tables = pipeline
+            .generate_ts()
+            .unwrap()
+            .into_iter()
+            .find(|f| f.path.ends_with("tables.d.ts"))
+            .unwrap()
+            .content;
+        (xml, tables)
+    };
+
+    let (xml, tables) = outputs(&[]);
+    assert!(tables.contains("DropConfigTable"), "{}", tables);
+    assert!(
+        xml.contains(r#"<table name="ItemConfigTable" value="ItemConfig" index="id" input="../datas/item" group="c,s" />"#),
+        "{}",
+        xml
+    );
+
+    // Client build: the server-only table leaves the declarations, the XML keeps it
+    let (client_xml, tables) = outputs(&["client"]);
+    assert!(tables.contains("ItemConfigTable"), "{}", tables);
+    assert!(tables.contains("ShopConfigTable"), "{}", tables);
+    assert!(!tables.contains("DropConfig"), "{}", tables);
+    assert_eq!(client_xml, xml);
+    assert!(
+        xml.contains(r#"<table name="DropConfigTable" value="DropConfig" index="id" input="../datas/drop" group="s" />"#),
+        "{}",
+        xml
+    );
+    assert!(xml.contains(r#"input="../datas/shop" />"#), "{}", xml);
+
+    // The Luban group selects the same tables as its [groups] name
+    assert_eq!(outputs(&["c"]).1, tables);
+
+    let err = pipeline(&["clinet"]).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Unknown group 'clinet' (--group), expected one of: client, server"
+    );
+}
+
+#[test]
+fn test_library_pipeline_table_reconcile() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::create_dir_all(temp.path().join("src")).unwrap();
+    fs::write(
+        temp.path().join("src/tables.ts"),
+        "@LubanTable({ mode: \"map\", index: \"id\" })\nexport class ItemConfig {\n    id: number;\n}\n\nexport class ShopConfig {\n    id: number;\n}\n\n@LubanTable({ mode: \"list\", index: \"id\" })\nexport class SkillConfig {\n    id: number;\n    skillId: number;\n}\n",
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    let write_config = |defaults: &str| {
+        fs::write(
+            &config_path,
+            format!("[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[defaults]\n{}\n\n[[sources]]\ntype = \"directory\"\npath = \"src\"\noutput_path = \"game.xml\"\nmodule_name = \"game\"\n\n[tables]\n\"game.ShopConfig\" = \"../datas/shop\"\n\"game.SkillConfig\" = {{ input = \"../datas/skill\", index = \"skillId\" }}\n", defaults),
+        )
+        .unwrap();
+    };
+    let pipeline = || {
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .build()
+            .unwrap()
+    };
+
+    write_config("");
+    // Drop the location prefix (`.../src/tables.ts: `)
+    let warnings: Vec<String> = pipeline()
+        .run()
+        .unwrap()
+        .warnings
+        .iter()
+        .filter_map(|w| {
+            w.split_once("tables.ts: ")
+                .map(|(_, rest)| rest.to_string())
+        })
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![
+            "ItemConfig - declared as a table by @LubanTable but [tables] has no \"game.ItemConfig\" entry, no <table> is generated",
+            "ShopConfig - [tables] \"game.ShopConfig\" configures a table but the class has no @LubanTable",
+            "SkillConfig - @LubanTable mode 'list' conflicts with [tables] \"game.SkillConfig\" mode 'map', the config wins",
+            "SkillConfig - @LubanTable index 'id' conflicts with [tables] \"game.SkillConfig\" index 'skillId', the config wins",
+        ]
+    );
+
+    write_config("table_reconcile = \"error\"");
+    let err = pipeline().parsed_classes().unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("Tables declared in code and [tables] disagree"),
+        "{}",
+        err
+    );
+
+    write_config("table_reconcile = \"off\"");
+    assert!(pipeline().parsed_classes().is_ok());
+}
+
+#[test]
+fn test_library_pipeline_unexported_referenced_class() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    let source = "class DropEntry {\n    itemId: number;\n}\n\nexport class DropConfig {\n    id: number;\n    entries: DropEntry[];\n}\n";
+    let pipeline = |defaults: &str| {
+        fs::write(
+            &config_path,
+            format!("[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ntable_output_path = \"out\"\n\n[defaults]\n{}\n", defaults),
+        )
+        .unwrap();
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("drop.ts", source)])
+            .build()
+            .unwrap()
+    };
+
+    let err = pipeline("").generate_xml().unwrap_err().to_string();
+    assert!(
+        err.contains("DropConfig.entries - type 'DropEntry' is declared but not exported in the same file (line 1); add `export`"),
+        "{}",
+        err
+    );
+
+    let pipeline = pipeline("include_private_referenced = true");
+    let xml = pipeline.generate_xml().unwrap()[0].content.clone();
+    assert!(xml.contains("<bean name=\"DropEntry\""), "{}", xml);
+    let beans = pipeline
+        .generate_ts()
+        .unwrap()
+        .into_iter()
+        .find(|f| f.path.ends_with("beans.ts"))
+        .unwrap()
+        .content;
+    assert!(beans.contains("DropConfig"), "{}", beans);
+    assert!(!beans.contains("DropEntry"), "{}", beans);
+}
+
+#[test]
+fn test_library_pipeline_skips_oversized_files() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::create_dir_all(temp.path().join("src")).unwrap();
+    fs::write(
+        temp.path().join("src/item.ts"),
+        "export class ItemConfig {\n    id: number;\n}\n",
+    )
+    .unwrap();
+    // A generated asset manifest of ~3 MB, over the default 2 MB limit
+    let mut manifest = String::from("export class AssetManifest {\n");
+    for i in 0..60_000 {
+        manifest.push_str(&format!("    asset{:06}: string; // rbxassetid\n", i));
+    }
+    manifest.push_str("}\n");
+    assert!(manifest.len() > 2 * 1024 * 1024);
+    fs::write(temp.path().join("src/manifest.ts"), &manifest).unwrap();
+
+    let config_path = temp.path().join("luban.config.toml");
+    let pipeline = |limits: &str| {
+        fs::write(
+            &config_path,
+            format!("[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[[sources]]\ntype = \"directory\"\npath = \"src\"\n\n[limits]\n{}\n", limits),
+        )
+        .unwrap();
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .build()
+            .unwrap()
+    };
+
+    let report = pipeline("").run().unwrap();
+    assert_eq!(report.beans, 1);
+    assert_eq!(report.file_stats.len(), 1);
+    let skipped: Vec<&String> = report
+        .warnings
+        .iter()
+        .filter(|w| w.contains("over limits.max_file_size (2048 KB); skipped"))
+        .collect();
+    assert_eq!(skipped.len(), 1, "{:?}", report.warnings);
+    assert!(
+        skipped[0].contains("manifest.ts: file is"),
+        "{}",
+        skipped[0]
+    );
+
+    // 0 disables the limit
+    let classes = pipeline("max_file_size = 0").parsed_classes().unwrap();
+    assert!(classes.iter().any(|c| c.name == "AssetManifest"));
+}
+
+#[test]
+fn test_library_pipeline_module_decorator() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::create_dir_all(temp.path().join("src/role")).unwrap();
+    fs::create_dir_all(temp.path().join("src/shared")).unwrap();
+    fs::write(
+        temp.path().join("src/role/role.ts"),
+        "import { StatBlock } from \"./stat\";\n\nexport class RoleConfig {\n    id: number;\n    stat: StatBlock;\n}\n",
+    )
+    .unwrap();
+    // Lives in the role source dir but belongs to the shared module
+    fs::write(
+        temp.path().join("src/role/stat.ts"),
+        "@Module(\"shared\")\nexport class StatBlock {\n    id: number;\n    hp: number;\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("src/shared/item.ts"),
+        "export class ItemBase {\n    id: number;\n}\n",
+    )
+    .unwrap();
+
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ntable_output_path = \"out\"\n\n[[sources]]\ntype = \"directory\"\npath = \"src/role\"\noutput_path = \"role.xml\"\nmodule_name = \"role\"\n\n[[sources]]\ntype = \"directory\"\npath = \"src/shared\"\noutput_path = \"shared.xml\"\nmodule_name = \"shared\"\n\n[tables]\n\"shared.StatBlock\" = \"../datas/stat\"\n",
+    )
+    .unwrap();
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .build()
+        .unwrap();
+
+    let classes = pipeline.parsed_classes().unwrap();
+    let stat = classes.iter().find(|c| c.name == "StatBlock").unwrap();
+    assert_eq!(stat.module_name.as_deref(), Some("shared"));
+
+    // XML: written with the shared module, referenced with its module prefix
+    let files = pipeline.generate_xml().unwrap();
+    let xml_of = |name: &str| {
+        let path = temp.path().join(name);
+        files
+            .iter()
+            .find(|f| f.path == path)
+            .map(|f| f.content.clone())
+            .unwrap()
+    };
+    let shared = xml_of("shared.xml");
+    let role = xml_of("role.xml");
+    assert!(shared.contains(r#"<module name="shared""#), "{}", shared);
+    assert!(shared.contains(r#"<bean name="StatBlock""#), "{}", shared);
+    assert!(
+        shared.contains(r#"<table name="StatBlockTable" value="StatBlock""#),
+        "{}",
+        shared
+    );
+    assert!(!role.contains(r#"<bean name="StatBlock""#), "{}", role);
+    assert!(
+        role.contains(r#"<var name="stat" type="shared.StatBlock"/>"#),
+        "{}",
+        role
+    );
+
+    // TypeScript: the Beans key and the [tables] lookup use the shared module
+    let ts_files = pipeline.generate_ts().unwrap();
+    let ts_of = |name: &str| {
+        ts_files
+            .iter()
+            .find(|f| f.path.ends_with(name))
+            .map(|f| f.content.clone())
+            .unwrap()
+    };
+    let beans = ts_of("beans.ts");
+    assert!(
+        beans.contains("\"shared.StatBlock\": StatBlock,"),
+        "{}",
+        beans
+    );
+    assert!(
+        beans.contains("\"role.RoleConfig\": RoleConfig,"),
+        "{}",
+        beans
+    );
+    let tables = ts_of("tables.d.ts");
+    assert!(
+        tables.contains("StatBlockTable: Map<number, StatBlock>;"),
+        "{}",
+        tables
+    );
+}
+
+#[test]
+fn test_library_pipeline_preview() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::create_dir_all(temp.path().join("src/role")).unwrap();
+    fs::create_dir_all(temp.path().join("src/shared")).unwrap();
+    fs::write(
+        temp.path().join("src/role/role.ts"),
+        "import { StatBlock } from \"../shared/stat\";\n\n/** Role config */\nexport class RoleConfig {\n    id: number;\n    level: number;\n    stat: StatBlock;\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("src/role/skill.ts"),
+        "export class SkillConfig {\n    id: number;\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("src/shared/stat.ts"),
+        "export class StatBlock {\n    hp: number;\n}\n",
+    )
+    .unwrap();
+
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ntable_output_path = \"out\"\n\n[[sources]]\ntype = \"directory\"\npath = \"src/role\"\noutput_path = \"role.xml\"\nmodule_name = \"role\"\n\n[[sources]]\ntype = \"directory\"\npath = \"src/shared\"\noutput_path = \"shared.xml\"\nmodule_name = \"shared\"\n\n[tables]\n\"role.RoleConfig\" = \"../datas/role\"\n\"role.SkillConfig\" = \"../datas/skill\"\n",
+    )
+    .unwrap();
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .build()
+        .unwrap();
+
+    let preview = pipeline.preview("role.RoleConfig").unwrap();
+    assert_eq!(preview.name, "role.RoleConfig");
+    // The declaring file and its import, not the unrelated skill.ts
+    let parsed: Vec<String> = preview
+        .parsed_files
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(parsed, vec!["role.ts", "stat.ts"]);
+    assert!(
+        preview
+            .xml
+            .contains(r#"<var name="stat" type="shared.StatBlock"/>"#),
+        "{}",
+        preview.xml
+    );
+
+    // Every fragment is what a full run generates
+    let role_xml = pipeline
+        .generate_xml()
+        .unwrap()
+        .into_iter()
+        .find(|f| f.path == temp.path().join("role.xml"))
+        .unwrap()
+        .content;
+    for line in preview.xml.lines() {
+        assert!(role_xml.contains(line), "{}\n---\n{}", line, role_xml);
+    }
+    assert!(preview.xml.contains("<table name=\"RoleConfigTable\""));
+    let ts = pipeline.generate_ts().unwrap();
+    let ts_of = |name: &str| {
+        ts.iter()
+            .find(|f| f.path.ends_with(name))
+            .map(|f| f.content.clone())
+            .unwrap()
+    };
+    let beans_entry = preview.beans_entry.as_deref().unwrap();
+    assert_eq!(beans_entry, "\"role.RoleConfig\": RoleConfig,");
+    assert!(ts_of("beans.ts").contains(beans_entry));
+    assert!(!preview.tables_lines.is_empty());
+    for line in &preview.tables_lines {
+        assert!(ts_of("tables.d.ts").contains(line.as_str()), "{}", line);
+    }
+    assert!(!preview
+        .tables_lines
+        .iter()
+        .any(|l| l.contains("SkillConfig")));
+
+    // Nothing was written
+    assert!(!temp.path().join("role.xml").exists());
+    assert!(!temp.path().join(".luban-cache.json").exists());
+
+    let err = pipeline.preview("RoleConfg").unwrap_err().to_string();
+    assert_eq!(err, "Unknown class 'RoleConfg'; close matches: RoleConfig");
+}
+
+#[test]
+fn test_library_pipeline_changelog() {
+    let temp = TempDir::new().unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let changelog_path = temp.path().join("SCHEMA_CHANGES.md");
+
+    let run = |source: &str| {
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("item.ts", source)])
+            .changelog(&changelog_path)
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+    };
+
+    // The first run only records the schema
+    run("export class ItemConfig {\n    id: number;\n    name: string;\n}\n");
+    assert!(!changelog_path.exists());
+
+    run("export class ItemConfig {\n    id: number;\n    count: number;\n}\n\nexport class DropConfig {\n    id: number;\n}\n");
+    let changelog = fs::read_to_string(&changelog_path).unwrap();
+    assert!(changelog.starts_with("## "));
+    assert!(changelog.ends_with(
+        "### Beans
+
+- Added `DropConfig`
+
+### Fields
+
+- `ItemConfig`: added `count` (`double`)
+- `ItemConfig`: removed `name` (`string`)
+"
+    ));
+
+    // Nothing changed: nothing is appended
+    run("export class ItemConfig {\n    id: number;\n    count: number;\n}\n\nexport class DropConfig {\n    id: number;\n}\n");
+    assert_eq!(fs::read_to_string(&changelog_path).unwrap(), changelog);
+}
+
+#[test]
+fn test_library_pipeline_model_json_golden() {
+    let temp = TempDir::new().unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\nmodule_name = \"item\"\n\n[tables]\n\"item.ItemConfig\" = \"../datas/item\"\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let model_path = temp.path().join("model.json");
+
+    let source = "export enum Quality {\n    Common,\n    Rare = 5,\n}\n\n/** Item config */\nexport class ItemConfig {\n    id: number;\n    /** Display name */\n    name?: string;\n}\n";
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .virtual_sources([("item.ts", source)])
+        .emit_model(&model_path)
+        .build()
+        .unwrap();
+    pipeline.run().unwrap();
+    let json = fs::read_to_string(&model_path).unwrap();
+    assert_eq!(json, pipeline.model_json().unwrap());
+
+    // An unchanged model is not rewritten
+    let modified = fs::metadata(&model_path).unwrap().modified().unwrap();
+    pipeline.run().unwrap();
+    assert_eq!(
+        fs::metadata(&model_path).unwrap().modified().unwrap(),
+        modified
+    );
+
+    // Regenerate with UPDATE_GOLDEN=1 and review the diff
+    let golden_path = project_root().join("tests/golden/model.json");
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&golden_path, format!("{}\n", json)).unwrap();
+    }
+    let golden = fs::read_to_string(&golden_path).unwrap();
+    assert_eq!(json, golden.trim_end());
+}
+
+#[test]
+fn test_model_json_field_origins() {
+    let temp = TempDir::new().unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        r#"[project]
+tsconfig = "tsconfig.json"
+
+[output]
+path = "output.xml"
+module_name = "item"
+
+[[virtual_fields]]
+class = "ItemConfig"
+fields = [
+    { name = "cost", type = "int", comment = "Purchase cost", alias = "价格" }
+]
+"#,
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+
+    let source = "export class ItemConfig {\n    id: number;\n}\n";
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .virtual_sources([("src/item.ts", source)])
+        .build()
+        .unwrap();
+    let model: serde_json::Value = serde_json::from_str(&pipeline.model_json().unwrap()).unwrap();
+    let fields = &model["classes"][0]["fields"];
+
+    assert_eq!(fields[0]["name"], "id");
+    assert_eq!(
+        fields[0]["origin"],
+        serde_json::json!({ "kind": "source", "file": "src/item.ts", "line": 2 })
+    );
+    assert_eq!(fields[1]["name"], "cost");
+    assert_eq!(fields[1]["alias"], "价格");
+    assert_eq!(
+        fields[1]["origin"],
+        serde_json::json!({ "kind": "virtual_config", "index": 0 })
+    );
+
+    let xml = pipeline.generate_xml().unwrap();
+    assert!(xml.iter().any(|file| file
+        .content
+        .contains(r#"<var name="cost" type="int" alias="价格" comment="Purchase cost"/>"#)));
+}
+
+#[test]
+fn test_model_json_examples() {
+    let temp = TempDir::new().unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\nmodule_name = \"skill\"\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+
+    let source = "/**\n * 技能\n * @example\n * ```ts\n * tables.SkillTable.get(1001)\n * ```\n */\nexport class SkillConfig {\n    /**\n     * 冷却时间（秒）\n     * @example 3.5 表示 3.5 秒冷却\n     * @example 0 表示没有冷却\n     */\n    cooldown: number;\n}\n";
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .virtual_sources([("src/skill.ts", source)])
+        .build()
+        .unwrap();
+    let model: serde_json::Value = serde_json::from_str(&pipeline.model_json().unwrap()).unwrap();
+    let class = &model["classes"][0];
+    assert_eq!(
+        class["examples"],
+        serde_json::json!(["```ts\ntables.SkillTable.get(1001)\n```"])
+    );
+    assert_eq!(
+        class["fields"][0]["examples"],
+        serde_json::json!(["3.5 表示 3.5 秒冷却", "0 表示没有冷却"])
+    );
+
+    // Examples stay out of the XML comment attributes
+    let xml: String = pipeline
+        .generate_xml()
+        .unwrap()
+        .into_iter()
+        .map(|file| file.content)
+        .collect();
+    assert!(
+        xml.contains(r#"<bean name="SkillConfig" comment="技能">"#),
+        "{}",
+        xml
+    );
+    assert!(
+        xml.contains(r#"<var name="cooldown" type="double" comment="冷却时间（秒）"/>"#),
+        "{}",
+        xml
+    );
+    assert!(!xml.contains("表示"), "{}", xml);
+}
+
+/// Write a project with one source file and return its config path
+fn exit_code_project(temp: &TempDir, source: &str) -> PathBuf {
+    fs::create_dir_all(temp.path().join("src")).unwrap();
+    fs::write(temp.path().join("src/skill.ts"), source).unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[[sources]]\ntype = \"directory\"\npath = \"src\"\n",
+    )
+    .unwrap();
+    config_path
+}
+
+fn exit_code(config_path: &std::path::Path, args: &[&str]) -> i32 {
+    std::process::Command::new(env!("CARGO_BIN_EXE_luban-gen"))
+        .arg("-c")
+        .arg(config_path)
+        .args(args)
+        .output()
+        .expect("Failed to run luban-gen")
+        .status
+        .code()
+        .unwrap()
+}
+
+#[test]
+fn test_exit_code_success_and_fail_on_change() {
+    let temp = TempDir::new().unwrap();
+    let config_path = exit_code_project(&temp, "export class SkillConfig {\n    id: number;\n}\n");
+
+    // Outputs written: 3 with --fail-on-change, otherwise 0
+    assert_eq!(exit_code(&config_path, &["--fail-on-change"]), 3);
+    fs::remove_file(temp.path().join("output.xml")).unwrap();
+    assert_eq!(exit_code(&config_path, &[]), 0);
+
+    // Nothing changed
+    assert_eq!(exit_code(&config_path, &["--fail-on-change"]), 0);
+}
+
+#[test]
+fn test_exit_code_internal_error() {
+    let temp = TempDir::new().unwrap();
+    let missing = temp.path().join("missing.toml");
+    assert_eq!(exit_code(&missing, &[]), 1);
+}
+
+#[test]
+fn test_exit_code_validation_failure() {
+    let temp = TempDir::new().unwrap();
+    let config_path = exit_code_project(
+        &temp,
+        "export class SkillConfig {\n    id: number;\n    kind: keyof SkillConfig;\n}\n",
+    );
+
+    assert_eq!(exit_code(&config_path, &["--deny-warnings"]), 2);
+    assert!(!temp.path().join("output.xml").exists());
+}
+
+#[test]
+fn test_exit_code_parse_errors() {
+    let temp = TempDir::new().unwrap();
+    let config_path = exit_code_project(&temp, "export class Broken {\n    id: number\n");
+
+    assert_eq!(exit_code(&config_path, &[]), 4);
+    assert_eq!(exit_code(&config_path, &["--deny-warnings"]), 4);
+}
+
+#[test]
+fn test_stdin_filelist_parses_only_listed_files() {
+    use std::io::Write;
+
+    let temp = TempDir::new().unwrap();
+    let src = temp.path().join("src");
+    fs::create_dir(&src).unwrap();
+    for name in ["simple.ts", "complex.ts", "trigger.ts"] {
+        fs::copy(
+            project_root().join("tests/fixtures").join(name),
+            src.join(name),
+        )
+        .unwrap();
+    }
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\nper_file_cache = true\n\n[[sources]]\ntype = \"directory\"\npath = \"src\"\n",
+    )
+    .unwrap();
+    let report_path = temp.path().join("report.json");
+    let run = |stdin: Option<&str>| -> serde_json::Value {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_luban-gen"));
+        command
+            .arg("-c")
+            .arg(&config_path)
+            .arg("--report")
+            .arg(&report_path);
+        if stdin.is_some() {
+            command.arg("--stdin-filelist");
+        }
+        let mut child = command
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .expect("Failed to run luban-gen");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.unwrap_or_default().as_bytes())
+            .unwrap();
+        assert!(child.wait().unwrap().success());
+        serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap()
+    };
+    let parsed = |report: &serde_json::Value| -> Vec<String> {
+        report["file_stats"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| {
+                let path = f["path"].as_str().unwrap().replace('\\', "/");
+                path.rsplit('/').next().unwrap().to_string()
+            })
+            .collect()
+    };
+
+    // A full run fills the per-file cache
+    let full = run(None);
+    assert_eq!(parsed(&full).len(), 3);
+    let xml = fs::read_to_string(temp.path().join("output.xml")).unwrap();
+
+    fs::write(
+        src.join("simple.ts"),
+        fs::read_to_string(src.join("simple.ts"))
+            .unwrap()
+            .replace("export class", "/** Changed */\nexport class"),
+    )
+    .unwrap();
+    let report = run(Some("src/simple.ts\nscripts/build.ts\n"));
+    assert_eq!(parsed(&report), vec!["simple.ts"]);
+    assert_eq!(report["beans"], full["beans"]);
+    assert_eq!(report["enums"], full["enums"]);
+    assert!(report["warnings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|w| w.as_str().unwrap()
+            == "scripts/build.ts: not part of any [[sources]] entry, ignored"));
+    // Only the new comment (XML comment line and bean attribute) differs from the full
+    // run's output
+    let updated = fs::read_to_string(temp.path().join("output.xml")).unwrap();
+    assert_ne!(updated, xml);
+    let restored: Vec<String> = updated
+        .lines()
+        .filter(|line| line.trim() != "<!-- Changed -->")
+        .map(|line| line.replace(r#" comment="Changed""#, ""))
+        .collect();
+    assert_eq!(restored, xml.lines().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_generation_is_deterministic() {
+    let temp = TempDir::new().unwrap();
+    let mut tables = String::new();
+    for (dir, names) in [
+        ("src/shop", ["Goods", "Price", "Stall", "Vendor"]),
+        ("src/role", ["Hero", "Skill", "Buff", "Talent"]),
+        ("src/item", ["Weapon", "Armor", "Potion", "Scroll"]),
+    ] {
+        let dir = temp.path().join(dir);
+        fs::create_dir_all(&dir).unwrap();
+        for name in names {
+            fs::write(
+                dir.join(format!("{}.ts", name.to_lowercase())),
+                format!(
+                    "export class {}Config {{\n    id: number;\n    name: string;\n}}\n",
+                    name
+                ),
+            )
+            .unwrap();
+            tables.push_str(&format!(
+                "\"{}Config\" = \"../datas/{}\"\n",
+                name,
+                name.to_lowercase()
+            ));
+        }
+    }
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\ntable_output_path = \"out\"\nmax_imports_per_file = 5\n\n[[sources]]\ntype = \"directory\"\npath = \"src\"\n\n[tables]\n{}",
+            tables
+        ),
+    )
+    .unwrap();
+    let generate = || {
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        let pipeline = luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .build()
+            .unwrap();
+        let mut files = pipeline.generate_xml().unwrap();
+        files.extend(pipeline.generate_ts().unwrap());
+        files
+            .into_iter()
+            .map(|f| (f.path, f.content))
+            .collect::<Vec<_>>()
+    };
+
+    let first = generate();
+    // Beans are split over several beans_N.ts chunks
+    assert!(first.iter().any(|(path, _)| path.ends_with("beans_3.ts")));
+    for _ in 0..3 {
+        assert_eq!(generate(), first);
+    }
+}
+
+#[test]
+fn test_xml_beans_sorted_by_module_and_name() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("luban.config.toml"),
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n",
+    )
+    .unwrap();
+    // Declared out of order
+    let source = "export class SkillConfig {\n    id: number;\n}\n\nexport class RoleConfig {\n    id: number;\n}\n\nexport class BuffConfig {\n    id: number;\n}\n";
+    let config =
+        luban_gen::config::Config::load_with_refs(&temp.path().join("luban.config.toml")).unwrap();
+    let xml = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .virtual_sources([("configs.ts", source)])
+        .build()
+        .unwrap()
+        .generate_xml()
+        .unwrap()
+        .remove(0)
+        .content;
+
+    let position = |name: &str| xml.find(&format!("<bean name=\"{}\"", name)).unwrap();
+    assert!(position("BuffConfig") < position("RoleConfig"));
+    assert!(position("RoleConfig") < position("SkillConfig"));
+}
+
+#[test]
+fn test_library_pipeline_run_changed() {
+    let temp = TempDir::new().unwrap();
+    let src = temp.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(
+        src.join("role.ts"),
+        "export class RoleConfig {\n    id: number;\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        src.join("item.ts"),
+        "export class ItemConfig {\n    id: number;\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let config_path = temp.path().join("luban.config.toml");
+    fs::write(
+        &config_path,
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\nper_file_cache = true\n\n[[sources]]\ntype = \"directory\"\npath = \"src\"\n",
+    )
+    .unwrap();
+    let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .build()
+        .unwrap();
+
+    // What a scan picks up, whether or not the file exists
+    assert!(pipeline.is_source(&src.join("role.ts")));
+    assert!(pipeline.is_source(&src.join("shop.tsx")));
+    assert!(!pipeline.is_source(&src.join("role.test.ts")));
+    assert!(!pipeline.is_source(&src.join("node_modules/lib.ts")));
+    assert!(!pipeline.is_source(&temp.path().join("scripts/build.ts")));
+
+    assert_eq!(pipeline.run().unwrap().beans, 2);
+
+    fs::write(
+        src.join("role.ts"),
+        "export class RoleConfig {\n    id: number;\n}\n\nexport class SkillConfig {\n    id: number;\n}\n",
+    )
+    .unwrap();
+    let report = pipeline.run_changed(&[src.join("role.ts")]).unwrap();
+    assert_eq!(report.beans, 3);
+    assert_eq!(report.file_stats.len(), 1);
+
+    // A deleted source takes its beans out of the output
+    fs::remove_file(src.join("item.ts")).unwrap();
+    let report = pipeline.run_changed(&[src.join("item.ts")]).unwrap();
+    assert_eq!(report.beans, 2);
+    let xml = fs::read_to_string(temp.path().join("output.xml")).unwrap();
+    assert!(!xml.contains("ItemConfig"));
+}
+
+#[test]
+fn test_library_pipeline_xml_style() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let source = "export interface EntityTrigger {}\n\n@Alias(\"伤害\")\nexport class DamageTrigger implements EntityTrigger {\n    damage: number;\n}\n";
+    let generate = |xml_style: &str| {
+        let config_path = temp.path().join("luban.config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n{}\n",
+                xml_style
+            ),
+        )
+        .unwrap();
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        let pipeline = luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("triggers.ts", source)])
+            .build()
+            .unwrap();
+        pipeline.generate_xml().unwrap()[0].content.clone()
+    };
+
+    let xml = generate("xml_style = { indent = 2 }");
+    for expected in [
+        "\n  <bean name=\"EntityTrigger\"/>\n",
+        "\n  <bean name=\"DamageTrigger\" parent=\"EntityTrigger\" alias=\"伤害\">\n    <var name=\"damage\" type=\"double\"/>\n  </bean>\n",
+    ] {
+        assert!(xml.contains(expected), "missing {:?}\nGot:\n{}", expected, xml);
+    }
+
+    let xml = generate("xml_style = { attribute_order = \"legacy\", self_closing = false }");
+    assert!(
+        xml.contains("\n    <bean name=\"EntityTrigger\">\n    </bean>\n"),
+        "{}",
+        xml
+    );
+    assert!(
+        xml.contains(r#"<bean name="DamageTrigger" alias="伤害" parent="EntityTrigger">"#),
+        "{}",
+        xml
+    );
+}
+
+#[test]
+fn test_library_pipeline_initializer_defaults() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("luban.config.toml"),
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n",
+    )
+    .unwrap();
+    let source = "export enum Mode {\n    Auto,\n    Manual,\n}\n\nexport class SpawnConfig {\n    count: number = 3;\n    label: string = \"a<b & \\\"c\\\"\";\n    mode: Mode = Mode.Manual;\n}\n";
+    let config =
+        luban_gen::config::Config::load_with_refs(&temp.path().join("luban.config.toml")).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .virtual_sources([("spawn.ts", source)])
+        .build()
+        .unwrap();
+    let xml = pipeline.generate_xml().unwrap()[0].content.clone();
+    for expected in [
+        r#"<var name="count" type="double#default=3"/>"#,
+        r#"<var name="label" type="string#default=a&lt;b &amp; &quot;c&quot;"/>"#,
+        r#"<var name="mode" type="Mode#default=Manual"/>"#,
+    ] {
+        assert!(
+            xml.contains(expected),
+            "missing {:?}\nGot:\n{}",
+            expected,
+            xml
+        );
+    }
+}
+
+#[test]
+fn test_library_pipeline_split_by_module() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("luban.config.toml"),
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"configs/output.xml\"\nsplit_by_module = true\n",
+    )
+    .unwrap();
+    let source = "@Module(\"role\")\nexport class RoleConfig {\n    id: number;\n}\n\n@Module(\"battle\")\nexport class SkillConfig {\n    id: number;\n}\n\nexport class CommonConfig {\n    id: number;\n}\n";
+    let config =
+        luban_gen::config::Config::load_with_refs(&temp.path().join("luban.config.toml")).unwrap();
+    let pipeline = luban_gen::Pipeline::builder(config)
+        .project_root(temp.path())
+        .virtual_sources([("configs.ts", source)])
+        .build()
+        .unwrap();
+    let files = pipeline.generate_xml().unwrap();
+    let content = |name: &str| {
+        files
+            .iter()
+            .find(|f| f.path.ends_with(name))
+            .unwrap_or_else(|| panic!("{} not generated", name))
+            .content
+            .as_str()
+    };
+
+    let root = content("configs/output.xml");
+    assert!(root.contains(
+        "    <import name=\"beans_battle.xml\"/>\n    <import name=\"beans_role.xml\"/>\n"
+    ));
+    assert!(root.contains(r#"<bean name="CommonConfig">"#));
+    assert!(!root.contains("RoleConfig"));
+
+    let role = content("configs/beans_role.xml");
+    assert!(role.starts_with(r#"<module name="role""#));
+    assert!(role.contains(r#"<bean name="RoleConfig">"#));
+    assert!(content("configs/beans_battle.xml").contains(r#"<bean name="SkillConfig">"#));
+}
+
+#[test]
+fn test_library_pipeline_generate_without_writing() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("luban.config.toml"),
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n",
+    )
+    .unwrap();
+    let source = "export class RoleConfig {\n    id: number;\n    skill: SkilConfig;\n}\n\nexport class SkillConfig {\n    id: number;\n}\n";
+    let pipeline = || {
+        let config =
+            luban_gen::config::Config::load_with_refs(&temp.path().join("luban.config.toml"))
+                .unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("configs.ts", source)])
+            .build()
+            .unwrap()
+    };
+
+    let generation = pipeline().generate().unwrap();
+    let names: Vec<&str> = generation.classes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["RoleConfig", "SkillConfig"]);
+    assert_eq!(generation.xml.len(), 1);
+    assert!(generation.xml[0]
+        .content
+        .contains(r#"<bean name="RoleConfig">"#));
+    assert!(generation.ts.is_empty());
+    assert_eq!((generation.cached, generation.updated), (0, 2));
+    assert_eq!(generation.warnings.len(), 1);
+    assert_eq!(generation.warnings[0].code, "suspected-typo");
+    assert!(generation.warnings[0].message.contains("SkilConfig"));
+    assert!(!temp.path().join("output.xml").exists());
+    assert!(!temp.path().join(".luban-cache.json").exists());
+
+    // Counted against the cache a run leaves behind
+    pipeline().run().unwrap();
+    let generation = pipeline().generate().unwrap();
+    assert_eq!((generation.cached, generation.updated), (2, 0));
+}
+
+/// Runs `generate` and `run` in a child copy of this test binary (the harness captures
+/// in-process output) and returns its stdout and stderr
+fn library_console_output(progress: bool) -> (String, String) {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("luban.config.toml"),
+        "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n",
+    )
+    .unwrap();
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([
+            "test_library_pipeline_console_child",
+            "--exact",
+            "--nocapture",
+        ])
+        .env("LUBAN_GEN_CONSOLE_PROJECT", temp.path())
+        .env("LUBAN_GEN_CONSOLE_PROGRESS", progress.to_string())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    (
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn test_library_pipeline_console_child() {
+    let Some(project) = std::env::var_os("LUBAN_GEN_CONSOLE_PROJECT") else {
+        return;
+    };
+    let project = PathBuf::from(project);
+    let progress = std::env::var("LUBAN_GEN_CONSOLE_PROGRESS").unwrap() == "true";
+    let source = "export class RoleConfig {\n    id: number;\n    skill: SkilConfig;\n}\n";
+    let pipeline = || {
+        let config =
+            luban_gen::config::Config::load_with_refs(&project.join("luban.config.toml")).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(&project)
+            .virtual_sources([("configs.ts", source)])
+            .verbose(true)
+            .stats(true)
+            .progress(progress)
+            .build()
+            .unwrap()
+    };
+    assert_eq!(pipeline().generate().unwrap().warnings.len(), 1);
+    assert_eq!(pipeline().run().unwrap().warnings.len(), 1);
+}
+
+#[test]
+fn test_library_pipeline_is_silent() {
+    let (stdout, stderr) = library_console_output(false);
+    for line in [
+        "Luban Schema Generator",
+        "[1/4]",
+        "[update] RoleConfig",
+        "Stats:",
+    ] {
+        assert!(!stdout.contains(line), "{}", stdout);
+    }
+    assert!(!stderr.contains("SkilConfig"), "{}", stderr);
+
+    // The CLI's console output
+    let (stdout, stderr) = library_console_output(true);
+    for line in [
+        "Luban Schema Generator",
+        "[1/4]",
+        "[update] RoleConfig",
+        "Stats:",
+    ] {
+        assert!(stdout.contains(line), "{}", stdout);
+    }
+    assert!(stderr.contains("SkilConfig"), "{}", stderr);
+}
+
+#[test]
+fn test_library_pipeline_nested_containers() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let source = "export class Item {\n    id: number;\n}\n\nexport class Loot {\n    grid: Array<Array<number>>;\n    drops: Map<string, Item[]>;\n    table: Map<string, Map<number, Item[]>>;\n}\n";
+    let generate = |output: &str| {
+        let config_path = temp.path().join("luban.config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n{}\n",
+                output
+            ),
+        )
+        .unwrap();
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        let pipeline = luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .virtual_sources([("loot.ts", source)])
+            .build()
+            .unwrap();
+        pipeline.generate_xml().unwrap()[0].content.clone()
+    };
+
+    let xml = generate("");
+    for expected in [
+        r#"<var name="grid" type="list,(list,double)"/>"#,
+        r#"<var name="drops" type="map,string,(list,Item)"/>"#,
+        r#"<var name="table" type="map,string,(map,double,(list,Item))"/>"#,
+    ] {
+        assert!(
+            xml.contains(expected),
+            "missing {:?}\nGot:\n{}",
+            expected,
+            xml
+        );
+    }
+
+    let xml = generate("nested_containers = \"flat\"");
+    for expected in [
+        r#"<var name="grid" type="list,list,double"/>"#,
+        r#"<var name="table" type="map,string,map,double,list,Item"/>"#,
+    ] {
+        assert!(
+            xml.contains(expected),
+            "missing {:?}\nGot:\n{}",
+            expected,
+            xml
+        );
+    }
+}
+
+#[test]
+fn test_library_pipeline_source_encodings() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("tsconfig.json"),
+        r#"{"compilerOptions": {}}"#,
+    )
+    .unwrap();
+    let src = temp.path().join("src");
+    fs::create_dir(&src).unwrap();
+    // Saved as UTF-8 with BOM and CRLF line endings
+    fs::write(
+        src.join("item.ts"),
+        "\u{feff}/** 物品 */\r\nexport class ItemConfig {\r\n    id: number;\r\n}\r\n",
+    )
+    .unwrap();
+    // GBK-encoded legacy file: the comment "物品" is not valid UTF-8
+    fs::write(
+        src.join("legacy.ts"),
+        b"// \xce\xef\xc6\xb7\nexport class LegacyConfig {\n    id: number;\n}\n",
+    )
+    .unwrap();
+
+    let pipeline = |invalid_utf8: &str| {
+        let config_path = temp.path().join("luban.config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[project]\ntsconfig = \"tsconfig.json\"\n\n[output]\npath = \"output.xml\"\n\n[defaults]\ninvalid_utf8 = \"{}\"\n\n[[sources]]\ntype = \"directory\"\npath = \"src\"\n",
+                invalid_utf8
+            ),
+        )
+        .unwrap();
+        let config = luban_gen::config::Config::load_with_refs(&config_path).unwrap();
+        luban_gen::Pipeline::builder(config)
+            .project_root(temp.path())
+            .force(true)
+            .build()
+            .unwrap()
+    };
+
+    let xml = &pipeline("warn").generate_xml().unwrap()[0].content;
+    assert!(
+        xml.contains(r#"<bean name="ItemConfig" comment="物品">"#),
+        "{}",
+        xml
+    );
+    assert!(xml.contains(r#"<bean name="LegacyConfig">"#), "{}", xml);
+
+    let report = pipeline("warn").run().unwrap();
+    assert_eq!(report.warnings.len(), 1, "{:?}", report.warnings);
+    assert!(report.warnings[0].ends_with(
+        "src/legacy.ts: not valid UTF-8, invalid bytes were replaced with U+FFFD; re-save the file as UTF-8"
+    ));
+
+    let err = pipeline("error").run().unwrap_err().to_string();
+    assert!(
+        err.starts_with("Source files are not valid UTF-8"),
+        "{}",
+        err
+    );
+    assert!(err.ends_with("\n  src/legacy.ts"), "{}", err);
+}